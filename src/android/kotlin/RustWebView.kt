@@ -67,6 +67,12 @@ class RustWebView(context: Context, val initScripts: Array<String>): WebView(con
         }
     }
 
+    fun loadHTMLWithBaseURLMainThread(html: String, baseUrl: String) {
+        post {
+          super.loadDataWithBaseURL(baseUrl, html, "text/html", null, null)
+        }
+    }
+
     fun evalScript(id: Int, script: String) {
         post {
             super.evaluateJavascript(script) { result ->
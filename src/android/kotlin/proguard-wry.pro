@@ -25,6 +25,7 @@
 
   void loadUrlMainThread(...);
   void loadHTMLMainThread(...);
+  void loadHTMLWithBaseURLMainThread(...);
   void setAutoPlay(...);
   void setUserAgent(...);
 }
@@ -271,6 +271,24 @@ impl<'a> MainPipe<'a> {
             tx.send(url).unwrap()
           }
         }
+        WebViewMessage::GetTitle(tx) => {
+          if let Some(webview) = &self.webview {
+            let title = self
+              .env
+              .call_method(webview.as_obj(), "getTitle", "()Ljava/lang/String;", &[])
+              .and_then(|v| v.l())
+              .and_then(|s| {
+                let s = JString::from(s);
+                self
+                  .env
+                  .get_string(&s)
+                  .map(|v| v.to_string_lossy().to_string())
+              })
+              .unwrap_or_default();
+
+            tx.send(title).unwrap()
+          }
+        }
         WebViewMessage::Jni(f) => {
           if let Some(w) = &self.webview {
             f(&mut self.env, activity, w.as_obj());
@@ -284,6 +302,34 @@ impl<'a> MainPipe<'a> {
             load_url(&mut self.env, webview.as_obj(), &url, headers, false)?;
           }
         }
+        WebViewMessage::PostUrl(url, body) => {
+          if let Some(webview) = &self.webview {
+            let url = self.env.new_string(url)?;
+            let body = self.env.byte_array_from_slice(&body)?;
+            self.env.call_method(
+              webview,
+              "postUrl",
+              "(Ljava/lang/String;[B)V",
+              &[(&url).into(), (&body).into()],
+            )?;
+          }
+        }
+        WebViewMessage::LoadHtml(html, base_url) => {
+          if let Some(webview) = &self.webview {
+            let html = self.env.new_string(html)?;
+            if let Some(base_url) = base_url {
+              let base_url = self.env.new_string(base_url)?;
+              self.env.call_method(
+                webview,
+                "loadHTMLWithBaseURLMainThread",
+                "(Ljava/lang/String;Ljava/lang/String;)V",
+                &[(&html).into(), (&base_url).into()],
+              )?;
+            } else {
+              load_html(&mut self.env, webview.as_obj(), &html)?;
+            }
+          }
+        }
         WebViewMessage::ClearAllBrowsingData => {
           if let Some(webview) = &self.webview {
             self
@@ -291,6 +337,65 @@ impl<'a> MainPipe<'a> {
               .call_method(webview, "clearAllBrowsingData", "()V", &[])?;
           }
         }
+        WebViewMessage::ClearCache => {
+          if let Some(webview) = &self.webview {
+            // clears the disk cache too, shared by the webviews of the app
+            self
+              .env
+              .call_method(webview, "clearCache", "(Z)V", &[true.into()])?;
+          }
+        }
+        WebViewMessage::SetUserAgent(user_agent) => {
+          if let Some(webview) = &self.webview {
+            let user_agent = self.env.new_string(user_agent)?;
+            self.env.call_method(
+              webview,
+              "setUserAgent",
+              "(Ljava/lang/String;)V",
+              &[(&user_agent).into()],
+            )?;
+          }
+        }
+        WebViewMessage::Reload => {
+          if let Some(webview) = &self.webview {
+            self.env.call_method(webview, "reload", "()V", &[])?;
+          }
+        }
+        WebViewMessage::StopLoading => {
+          if let Some(webview) = &self.webview {
+            self.env.call_method(webview, "stopLoading", "()V", &[])?;
+          }
+        }
+        WebViewMessage::GoBack => {
+          if let Some(webview) = &self.webview {
+            self.env.call_method(webview, "goBack", "()V", &[])?;
+          }
+        }
+        WebViewMessage::GoForward => {
+          if let Some(webview) = &self.webview {
+            self.env.call_method(webview, "goForward", "()V", &[])?;
+          }
+        }
+        WebViewMessage::CanGoBack(tx) => {
+          if let Some(webview) = &self.webview {
+            let can_go_back = self
+              .env
+              .call_method(webview, "canGoBack", "()Z", &[])
+              .and_then(|v| v.z())
+              .unwrap_or_default();
+            tx.send(can_go_back).unwrap()
+          }
+        }
+        WebViewMessage::CanGoForward(tx) => {
+          if let Some(webview) = &self.webview {
+            let can_go_forward = self
+              .env
+              .call_method(webview, "canGoForward", "()Z", &[])
+              .and_then(|v| v.z())
+              .unwrap_or_default();
+            tx.send(can_go_forward).unwrap()
+          }
+        }
       }
     }
     Ok(())
@@ -369,9 +474,20 @@ pub(crate) enum WebViewMessage {
   SetBackgroundColor(RGBA),
   GetWebViewVersion(Sender<Result<String, Error>>),
   GetUrl(Sender<String>),
+  GetTitle(Sender<String>),
   Jni(Box<dyn FnOnce(&mut JNIEnv, &JObject, &JObject) + Send>),
   LoadUrl(String, Option<http::HeaderMap>),
+  LoadHtml(String, Option<String>),
+  PostUrl(String, Vec<u8>),
   ClearAllBrowsingData,
+  ClearCache,
+  SetUserAgent(String),
+  Reload,
+  StopLoading,
+  GoBack,
+  GoForward,
+  CanGoBack(Sender<bool>),
+  CanGoForward(Sender<bool>),
 }
 
 pub(crate) struct CreateWebViewAttributes {
@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: MIT
 
 use super::{PageLoadEvent, WebContext, WebViewAttributes, RGBA};
-use crate::{RequestAsyncResponder, Result};
+use crate::{
+  EditCommand, FrameInfo, HistoryEntry, RequestAsyncResponder, Result, WindowLevel, ZOrder,
+};
 use base64::{engine::general_purpose, Engine};
 use crossbeam_channel::*;
 use html5ever::{interface::QualName, namespace_url, ns, tendril::TendrilSink, LocalName};
@@ -23,6 +25,7 @@ use raw_window_handle::HasWindowHandle;
 use sha2::{Digest, Sha256};
 use std::{
   borrow::Cow,
+  cell::Cell,
   collections::HashMap,
   sync::{atomic::AtomicI32, mpsc::channel, Mutex},
 };
@@ -121,7 +124,10 @@ pub unsafe fn android_setup(
     .unwrap();
 }
 
-pub(crate) struct InnerWebView;
+pub(crate) struct InnerWebView {
+  audio_muted: Cell<bool>,
+  on_audio_muted_change: Option<Box<dyn Fn(bool)>>,
+}
 
 impl InnerWebView {
   pub fn new_as_child(
@@ -142,7 +148,8 @@ impl InnerWebView {
     let WebViewAttributes {
       url,
       html,
-      initialization_scripts,
+      mut initialization_scripts,
+      main_frame_initialization_scripts,
       ipc_handler,
       #[cfg(any(debug_assertions, feature = "devtools"))]
       devtools,
@@ -152,9 +159,14 @@ impl InnerWebView {
       headers,
       autoplay,
       user_agent,
+      on_audio_muted_change,
       ..
     } = attributes;
 
+    initialization_scripts.push(crate::media::MUTE_MEDIA_SCRIPT.to_string());
+    // the scripts are prepended to the HTML of the main frame only
+    initialization_scripts.extend(main_frame_initialization_scripts);
+
     let super::PlatformSpecificWebViewAttributes {
       on_webview_created,
       with_asset_loader,
@@ -274,7 +286,13 @@ impl InnerWebView {
                 tx.send(response).unwrap();
               });
 
-            (custom_protocol.1)(request, RequestAsyncResponder { responder });
+            (custom_protocol.1)(
+              request,
+              RequestAsyncResponder {
+                responder,
+                stream_responder: None,
+              },
+            );
             return Some(rx.recv().unwrap());
           }
           None
@@ -298,11 +316,39 @@ impl InnerWebView {
       ON_LOAD_HANDLER.get_or_init(move || UnsafeOnPageLoadHandler::new(h));
     }
 
-    Ok(Self)
+    Ok(Self {
+      audio_muted: Cell::new(false),
+      on_audio_muted_change,
+    })
   }
 
   pub fn print(&self) {}
 
+  pub fn restore_dom(&self, png: Vec<u8>) -> Result<()> {
+    self.eval(
+      &crate::restore_dom_script(&png),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn resume_rendering(&self) -> Result<()> {
+    self.eval(
+      crate::RESUME_RENDERING_SCRIPT,
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn snapshot(
+    &self,
+    _rect_script: &str,
+    _target: &str,
+    callback: Box<dyn FnOnce(Result<Vec<u8>>)>,
+  ) {
+    callback(Err(crate::Error::SnapshotError(
+      "not supported on Android".into(),
+    )))
+  }
+
   pub fn url(&self) -> Url {
     let (tx, rx) = bounded(1);
     MainPipe::send(WebViewMessage::GetUrl(tx));
@@ -310,6 +356,17 @@ impl InnerWebView {
     Url::parse(uri.as_str()).unwrap()
   }
 
+  pub fn set_user_agent(&self, user_agent: &str) -> Result<()> {
+    MainPipe::send(WebViewMessage::SetUserAgent(user_agent.to_string()));
+    Ok(())
+  }
+
+  pub fn title(&self) -> String {
+    let (tx, rx) = bounded(1);
+    MainPipe::send(WebViewMessage::GetTitle(tx));
+    rx.recv().unwrap_or_default()
+  }
+
   pub fn eval(&self, js: &str, callback: Option<impl Fn(String) + Send + 'static>) -> Result<()> {
     MainPipe::send(WebViewMessage::Eval(
       js.into(),
@@ -318,6 +375,15 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn eval_with_result(
+    &self,
+    js: &str,
+    callback: impl Fn(std::result::Result<String, String>) + Send + 'static,
+  ) -> Result<()> {
+    // the exceptions are not reported by `evaluateJavascript`
+    self.eval(js, Some(move |result| callback(Ok(result))))
+  }
+
   #[cfg(any(debug_assertions, feature = "devtools"))]
   pub fn open_devtools(&self) {}
 
@@ -331,15 +397,46 @@ impl InnerWebView {
 
   pub fn zoom(&self, _scale_factor: f64) {}
 
+  pub fn zoom_factor(&self) -> f64 {
+    1.0
+  }
+
+  pub fn set_zoom_limits(&self, _min: f64, _max: f64) {}
+
   pub fn set_background_color(&self, background_color: RGBA) -> Result<()> {
     MainPipe::send(WebViewMessage::SetBackgroundColor(background_color));
     Ok(())
   }
 
+  pub fn load_html(&self, html: &str, base_url: Option<&str>) {
+    MainPipe::send(WebViewMessage::LoadHtml(
+      html.to_string(),
+      base_url.map(ToString::to_string),
+    ));
+  }
+
   pub fn load_url(&self, url: &str) {
     MainPipe::send(WebViewMessage::LoadUrl(url.to_string(), None));
   }
 
+  pub fn load_request(&self, request: Request<Vec<u8>>) -> Result<()> {
+    let (parts, body) = request.into_parts();
+    let url = parts.uri.to_string();
+    match parts.method {
+      http::Method::GET if body.is_empty() => self.load_url_with_headers(&url, parts.headers),
+      // `WebView.postUrl` only sends `application/x-www-form-urlencoded` bodies without custom headers
+      http::Method::POST if parts.headers.is_empty() => {
+        MainPipe::send(WebViewMessage::PostUrl(url, body))
+      }
+      _ => {
+        return Err(crate::Error::NotSupported(
+          "loading a request with this method, body or headers",
+        ))
+      }
+    }
+    Ok(())
+  }
+
   pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) {
     MainPipe::send(WebViewMessage::LoadUrl(url.to_string(), Some(headers)));
   }
@@ -358,6 +455,20 @@ impl InnerWebView {
     }
   }
 
+  pub fn scale_factor(&self) -> f64 {
+    1.0
+  }
+
+  pub fn set_click_through_regions(&self, _regions: &[crate::Rect]) -> Result<()> {
+    Err(crate::Error::NotSupported(
+      "setting the click-through regions",
+    ))
+  }
+
+  pub fn click_through_regions_setter(&self) -> Box<dyn Fn(&[crate::Rect])> {
+    Box::new(|_| ())
+  }
+
   pub fn set_bounds(&self, _bounds: crate::Rect) {
     // Unsupported
   }
@@ -369,6 +480,231 @@ impl InnerWebView {
   pub fn focus(&self) {
     // Unsupported
   }
+
+  pub fn set_touch_adjust_radius(&self, radius_css_px: f64) {
+    let _ = self.eval(
+      &crate::touch_adjust_script(radius_css_px),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    );
+  }
+
+  pub fn reload(&self) {
+    MainPipe::send(WebViewMessage::Reload);
+  }
+
+  pub fn stop(&self) {
+    MainPipe::send(WebViewMessage::StopLoading);
+  }
+
+  pub fn reload_ignoring_local_cache(&self) {
+    MainPipe::send(WebViewMessage::ClearCache);
+    let url = self.url();
+    self.load_url_with_headers(url.as_str(), crate::no_cache_headers());
+  }
+
+  pub fn set_window_level(&self, _level: WindowLevel) -> Result<()> {
+    Err(crate::Error::NotSupported("setting the window level"))
+  }
+
+  pub fn register_protocol(
+    &self,
+    _name: String,
+    _handler: Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)>,
+  ) -> Result<()> {
+    Err(crate::Error::NotSupported(
+      "registering custom protocols after build",
+    ))
+  }
+
+  pub fn unregister_protocol(&self, _name: &str) -> Result<()> {
+    Err(crate::Error::NotSupported("unregistering custom protocols"))
+  }
+
+  pub fn set_audio_muted(&self, muted: bool) -> Result<()> {
+    if self.audio_muted.replace(muted) == muted {
+      return Ok(());
+    }
+
+    self.eval(
+      &crate::media::set_audio_muted_script(muted),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )?;
+
+    if let Some(on_audio_muted_change) = &self.on_audio_muted_change {
+      on_audio_muted_change(muted);
+    }
+    Ok(())
+  }
+
+  pub fn is_audio_muted(&self) -> bool {
+    self.audio_muted.get()
+  }
+
+  pub fn is_playing_audio(&self) -> Result<bool> {
+    Err(crate::Error::NotSupported("querying the audio playback"))
+  }
+
+  pub fn history(&self, callback: Box<dyn FnOnce(Vec<HistoryEntry>)>) {
+    callback(vec![HistoryEntry::new(self.url(), None, 0)])
+  }
+
+  pub fn go_to_history_index(&self, index: usize, callback: Box<dyn FnOnce(Result<()>)>) {
+    // only the current entry is known
+    let result = match index {
+      0 => self.navigate_to_history_offset(0),
+      _ => Err(crate::Error::HistoryIndexOutOfBounds(index)),
+    };
+    callback(result)
+  }
+
+  pub fn navigate_to_history_offset(&self, offset: i32) -> Result<()> {
+    self.eval(
+      &crate::history_go_script(offset),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn is_visible(&self) -> bool {
+    true
+  }
+
+  pub fn set_parent(&self, _parent: &impl HasWindowHandle) -> Result<()> {
+    Err(crate::Error::NotSupported("reparenting the webview"))
+  }
+
+  pub fn set_z_order(&self, _order: ZOrder) -> Result<()> {
+    Err(crate::Error::NotSupported(
+      "changing the z-order of the webview",
+    ))
+  }
+
+  pub fn go_back(&self) {
+    MainPipe::send(WebViewMessage::GoBack);
+  }
+
+  pub fn go_forward(&self) {
+    MainPipe::send(WebViewMessage::GoForward);
+  }
+
+  pub fn can_go_back(&self) -> bool {
+    let (tx, rx) = bounded(1);
+    MainPipe::send(WebViewMessage::CanGoBack(tx));
+    rx.recv().unwrap_or_default()
+  }
+
+  pub fn can_go_forward(&self) -> bool {
+    let (tx, rx) = bounded(1);
+    MainPipe::send(WebViewMessage::CanGoForward(tx));
+    rx.recv().unwrap_or_default()
+  }
+
+  pub fn execute_edit_command(&self, command: EditCommand) -> Result<()> {
+    let command = match command {
+      EditCommand::Cut => "cut",
+      EditCommand::Copy => "copy",
+      // pages can't read the clipboard with `document.execCommand`
+      EditCommand::Paste => return Err(crate::Error::NotSupported("pasting")),
+      EditCommand::SelectAll => "selectAll",
+      EditCommand::Undo => "undo",
+      EditCommand::Redo => "redo",
+    };
+    self.eval(
+      &format!("document.execCommand('{command}');"),
+      None::<Box<dyn Fn(String) + Send>>,
+    )
+  }
+
+  pub fn set_zoom_hotkeys_enabled(&self, _enabled: bool) -> Result<()> {
+    Err(crate::Error::NotSupported("toggling the zoom hotkeys"))
+  }
+
+  pub fn set_background_throttling(&self, _enabled: bool) -> Result<()> {
+    Err(crate::Error::NotSupported(
+      "changing the background throttling",
+    ))
+  }
+
+  pub fn save_state(&self, callback: Box<dyn FnOnce(Result<Vec<u8>>)>) {
+    callback(Err(crate::Error::NotSupported("saving the session state")))
+  }
+
+  pub fn restore_state(&self, _state: &[u8]) -> Result<()> {
+    Err(crate::Error::NotSupported("restoring the session state"))
+  }
+
+  pub fn add_initialization_script(&self, _js: &str) -> Result<crate::ScriptId> {
+    Err(crate::Error::NotSupported(
+      "adding initialization scripts at runtime",
+    ))
+  }
+
+  pub fn remove_initialization_script(&self, _id: crate::ScriptId) -> Result<()> {
+    Err(crate::Error::NotSupported(
+      "removing initialization scripts at runtime",
+    ))
+  }
+
+  pub fn find(
+    &self,
+    text: &str,
+    options: crate::FindOptions,
+    callback: impl Fn(usize) + Send + 'static,
+  ) -> Result<()> {
+    // the page searches with `window.find()`
+    self.eval(
+      &crate::find::find_script(text, options),
+      Some(move |result: String| {
+        if let Ok(count) = result.parse() {
+          callback(count);
+        }
+      }),
+    )
+  }
+
+  pub fn find_next(&self) -> Result<()> {
+    self.eval(
+      &crate::find::find_next_script(false),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn find_previous(&self) -> Result<()> {
+    self.eval(
+      &crate::find::find_next_script(true),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn clear_matches(&self) -> Result<()> {
+    self.eval(
+      crate::find::CLEAR_MATCHES_SCRIPT,
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn inject_css(&self, _css: &str) -> Result<crate::StyleId> {
+    Err(crate::Error::NotSupported("injecting user stylesheets"))
+  }
+
+  pub fn remove_css(&self, _id: crate::StyleId) -> Result<()> {
+    Err(crate::Error::NotSupported("removing user stylesheets"))
+  }
+
+  pub fn frames(&self) -> Vec<FrameInfo> {
+    vec![FrameInfo {
+      id: crate::MAIN_FRAME_ID,
+      url: Some(self.url()),
+      name: String::new(),
+      is_main_frame: true,
+    }]
+  }
+
+  pub fn eval_in_frame(&self, frame_id: u64, js: &str) -> Result<()> {
+    if frame_id != crate::MAIN_FRAME_ID {
+      return Err(crate::Error::FrameNotFound(frame_id));
+    }
+    self.eval(js, None::<Box<dyn Fn(String) + Send + 'static>>)
+  }
 }
 
 #[derive(Clone, Copy)]
@@ -0,0 +1,215 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  fs::File,
+  path::{Component, Path, PathBuf},
+  time::UNIX_EPOCH,
+};
+
+use http::{
+  header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+  Method, Request, Response, StatusCode,
+};
+
+use crate::RequestAsyncResponder;
+
+/// The custom protocol handler of [`WebViewBuilder::with_asset_dir`](crate::WebViewBuilder::with_asset_dir).
+pub(crate) fn asset_dir_handler(root: PathBuf) -> impl Fn(Request<Vec<u8>>, RequestAsyncResponder) {
+  move |request, responder| {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+      return responder.respond(empty_response(StatusCode::METHOD_NOT_ALLOWED));
+    }
+
+    let Some((path, file)) = resolve_path(&root, request.uri().path())
+      .and_then(|path| File::open(&path).ok().map(|file| (path, file)))
+    else {
+      return responder.respond(empty_response(StatusCode::NOT_FOUND));
+    };
+
+    let etag = file.metadata().ok().and_then(|metadata| {
+      let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+      Some(format!(
+        "W/\"{:x}-{:x}.{:x}\"",
+        metadata.len(),
+        modified.as_secs(),
+        modified.subsec_nanos()
+      ))
+    });
+    if let Some(etag) = &etag {
+      let is_cached = request
+        .headers()
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| {
+          value
+            .split(',')
+            .any(|tag| tag.trim() == etag || tag.trim() == "*")
+        });
+      if is_cached {
+        let mut response = empty_response(StatusCode::NOT_MODIFIED);
+        if let Ok(etag) = etag.parse() {
+          response.headers_mut().insert(ETAG, etag);
+        }
+        return responder.respond(response);
+      }
+    }
+
+    let mut response = match crate::range_response(&request, file) {
+      Ok(response) => response,
+      Err(e) => {
+        log::warn!("Failed to read the asset {}: {e}", path.display());
+        return responder.respond(empty_response(StatusCode::INTERNAL_SERVER_ERROR));
+      }
+    };
+    let headers = response.headers_mut();
+    if let Ok(content_type) = mime_type(&path).parse() {
+      headers.insert(CONTENT_TYPE, content_type);
+    }
+    if let Some(Ok(etag)) = etag.map(|etag| etag.parse()) {
+      headers.insert(ETAG, etag);
+    }
+
+    if request.method() == Method::HEAD {
+      responder.respond(response.map(|_| Vec::new()))
+    } else {
+      responder.respond_with_stream(response)
+    }
+  }
+}
+
+pub(crate) fn empty_response(status: StatusCode) -> Response<Vec<u8>> {
+  let mut response = Response::new(Vec::new());
+  *response.status_mut() = status;
+  response
+}
+
+/// Resolves the file of the URI `path` in `root`, `None` when it is outside of `root`.
+fn resolve_path(root: &Path, path: &str) -> Option<PathBuf> {
+  let path = percent_encoding::percent_decode_str(path)
+    .decode_utf8()
+    .ok()?;
+
+  let mut relative = PathBuf::new();
+  for segment in path.split('/') {
+    // rejects the segments which could be interpreted as another path on Windows
+    if segment.contains(['\\', ':', '\0']) {
+      return None;
+    }
+    for component in Path::new(segment).components() {
+      match component {
+        Component::Normal(segment) => relative.push(segment),
+        Component::CurDir => {}
+        _ => return None,
+      }
+    }
+  }
+
+  let root = root.canonicalize().ok()?;
+  let mut resolved = root.join(relative);
+  if resolved.is_dir() {
+    resolved.push("index.html");
+  }
+
+  // also prevents the symbolic links from escaping the root
+  let resolved = resolved.canonicalize().ok()?;
+  (resolved.starts_with(&root) && resolved.is_file()).then_some(resolved)
+}
+
+/// The MIME type of the file at `path`, guessed from its extension.
+pub(crate) fn mime_type(path: &Path) -> &'static str {
+  let extension = path
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .map(|extension| extension.to_ascii_lowercase())
+    .unwrap_or_default();
+
+  match extension.as_str() {
+    "html" | "htm" => "text/html",
+    "js" | "mjs" => "text/javascript",
+    "css" => "text/css",
+    "json" | "map" => "application/json",
+    "wasm" => "application/wasm",
+    "txt" => "text/plain",
+    "csv" => "text/csv",
+    "xml" => "application/xml",
+    "pdf" => "application/pdf",
+    "svg" => "image/svg+xml",
+    "png" => "image/png",
+    "jpg" | "jpeg" => "image/jpeg",
+    "gif" => "image/gif",
+    "webp" => "image/webp",
+    "avif" => "image/avif",
+    "bmp" => "image/bmp",
+    "ico" => "image/x-icon",
+    "woff" => "font/woff",
+    "woff2" => "font/woff2",
+    "ttf" => "font/ttf",
+    "otf" => "font/otf",
+    "mp4" => "video/mp4",
+    "webm" => "video/webm",
+    "ogg" | "ogv" => "video/ogg",
+    "mp3" => "audio/mpeg",
+    "m4a" => "audio/mp4",
+    "wav" => "audio/wav",
+    "flac" => "audio/flac",
+    "oga" => "audio/ogg",
+    _ => "application/octet-stream",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use super::*;
+
+  /// Creates a root directory with an `index.html`, `app.js` and a `secret.txt` outside of it.
+  fn create_root(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wry-asset-dir-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("root/assets")).unwrap();
+    fs::write(dir.join("root/index.html"), "index").unwrap();
+    fs::write(dir.join("root/assets/app.js"), "app").unwrap();
+    fs::write(dir.join("secret.txt"), "secret").unwrap();
+    dir.join("root")
+  }
+
+  #[test]
+  fn should_resolve_path() {
+    let root = create_root("resolve");
+    let canonical = root.canonicalize().unwrap();
+    assert_eq!(
+      resolve_path(&root, "/assets/app.js"),
+      Some(canonical.join("assets").join("app.js"))
+    );
+    assert_eq!(
+      resolve_path(&root, "/assets/./app%2Ejs"),
+      Some(canonical.join("assets").join("app.js"))
+    );
+    assert_eq!(resolve_path(&root, "/"), Some(canonical.join("index.html")));
+    assert_eq!(resolve_path(&root, "/missing.js"), None);
+    let _ = fs::remove_dir_all(root.parent().unwrap());
+  }
+
+  #[test]
+  fn should_not_resolve_path_outside_of_root() {
+    let root = create_root("traversal");
+    assert_eq!(resolve_path(&root, "/../secret.txt"), None);
+    assert_eq!(resolve_path(&root, "/assets/../../secret.txt"), None);
+    assert_eq!(resolve_path(&root, "/%2E%2E/secret.txt"), None);
+    assert_eq!(resolve_path(&root, "/..%2Fsecret.txt"), None);
+    assert_eq!(resolve_path(&root, "/..%5Csecret.txt"), None);
+    assert_eq!(resolve_path(&root, "/C:%5Csecret.txt"), None);
+    assert_eq!(resolve_path(&root, "/assets/app.js%00"), None);
+    let _ = fs::remove_dir_all(root.parent().unwrap());
+  }
+
+  #[test]
+  fn should_guess_mime_type() {
+    assert_eq!(mime_type(Path::new("index.HTML")), "text/html");
+    assert_eq!(mime_type(Path::new("app.mjs")), "text/javascript");
+    assert_eq!(mime_type(Path::new("data")), "application/octet-stream");
+  }
+}
@@ -0,0 +1,144 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{borrow::Cow, io::Cursor, path::Path};
+
+use http::{header::CONTENT_TYPE, Method, Request, StatusCode};
+
+use crate::{
+  asset_dir::{empty_response, mime_type},
+  RequestAsyncResponder,
+};
+
+/// A provider of the assets served by [`WebViewBuilder::with_asset_provider`](crate::WebViewBuilder::with_asset_provider),
+/// e.g. a frontend bundle compiled into the binary.
+///
+/// It is implemented by the closures returning the content of a path, and with the `rust-embed`
+/// feature by the `PhantomData` of the [`rust_embed::RustEmbed`] types, see
+/// [`WebViewBuilder::with_embedded_assets`](crate::WebViewBuilder::with_embedded_assets).
+pub trait AssetProvider {
+  /// Returns the content of the asset at `path`, relative to the root of the assets and
+  /// without leading slash, e.g. `index.html` or `assets/app.js`.
+  fn get(&self, path: &str) -> Option<Cow<'static, [u8]>>;
+}
+
+impl<F> AssetProvider for F
+where
+  F: Fn(&str) -> Option<Cow<'static, [u8]>>,
+{
+  fn get(&self, path: &str) -> Option<Cow<'static, [u8]>> {
+    self(path)
+  }
+}
+
+#[cfg(feature = "rust-embed")]
+impl<T: rust_embed::RustEmbed> AssetProvider for std::marker::PhantomData<T> {
+  fn get(&self, path: &str) -> Option<Cow<'static, [u8]>> {
+    T::get(path).map(|file| file.data)
+  }
+}
+
+/// Returns the path and the content of the asset of the URL `path`, the directories serve their
+/// `index.html` and the routes of single page applications, which have no file extension, serve
+/// the root `index.html`.
+fn resolve_asset<P: AssetProvider>(
+  provider: &P,
+  path: &str,
+) -> Option<(String, Cow<'static, [u8]>)> {
+  let path = percent_encoding::percent_decode_str(path)
+    .decode_utf8_lossy()
+    .trim_start_matches('/')
+    .to_string();
+  let path = if path.is_empty() || path.ends_with('/') {
+    format!("{path}index.html")
+  } else {
+    path
+  };
+
+  if let Some(content) = provider.get(&path) {
+    return Some((path, content));
+  }
+  let is_route = Path::new(&path).extension().is_none();
+  is_route
+    .then(|| provider.get("index.html"))
+    .flatten()
+    .map(|content| ("index.html".to_string(), content))
+}
+
+/// The custom protocol handler of [`WebViewBuilder::with_asset_provider`](crate::WebViewBuilder::with_asset_provider).
+pub(crate) fn asset_provider_handler<P: AssetProvider>(
+  provider: P,
+) -> impl Fn(Request<Vec<u8>>, RequestAsyncResponder) {
+  move |request, responder| {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+      return responder.respond(empty_response(StatusCode::METHOD_NOT_ALLOWED));
+    }
+
+    let Some((path, content)) = resolve_asset(&provider, request.uri().path()) else {
+      return responder.respond(empty_response(StatusCode::NOT_FOUND));
+    };
+
+    let mut response = match crate::range_response(&request, Cursor::new(content)) {
+      Ok(response) => response,
+      Err(e) => {
+        log::warn!("Failed to read the asset {path}: {e}");
+        return responder.respond(empty_response(StatusCode::INTERNAL_SERVER_ERROR));
+      }
+    };
+    if let Ok(content_type) = mime_type(Path::new(&path)).parse() {
+      response.headers_mut().insert(CONTENT_TYPE, content_type);
+    }
+
+    if request.method() == Method::HEAD {
+      responder.respond(response.map(|_| Vec::new()))
+    } else {
+      responder.respond_with_stream(response)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A provider of an `index.html`, `app.js` and `docs/index.html`.
+  fn provider(path: &str) -> Option<Cow<'static, [u8]>> {
+    match path {
+      "index.html" => Some(Cow::Borrowed(b"index")),
+      "app.js" => Some(Cow::Borrowed(b"app")),
+      "docs/index.html" => Some(Cow::Borrowed(b"docs")),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn should_serve_the_index_of_routes() {
+    assert_eq!(
+      resolve_asset(&provider, "/settings/profile"),
+      Some(("index.html".to_string(), Cow::Borrowed(&b"index"[..])))
+    );
+    assert_eq!(
+      resolve_asset(&provider, "/"),
+      Some(("index.html".to_string(), Cow::Borrowed(&b"index"[..])))
+    );
+    assert_eq!(
+      resolve_asset(&provider, "/app.js"),
+      Some(("app.js".to_string(), Cow::Borrowed(&b"app"[..])))
+    );
+  }
+
+  #[test]
+  fn should_not_serve_the_index_of_missing_files() {
+    assert_eq!(resolve_asset(&provider, "/missing.js"), None);
+    assert_eq!(resolve_asset(&provider, "/assets/logo.png"), None);
+  }
+
+  #[test]
+  fn should_serve_the_index_of_directories() {
+    assert_eq!(
+      resolve_asset(&provider, "/docs/"),
+      Some(("docs/index.html".to_string(), Cow::Borrowed(&b"docs"[..])))
+    );
+  }
+}
@@ -0,0 +1,173 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Internal channel used by the scripts wry injects to report back to Rust.
+//!
+//! Messages are sent through the same `window.ipc.postMessage` channel as the page's own
+//! messages, as internal messages of the [`MESSAGE_KIND`] kind, and are filtered out before
+//! reaching [`WebViewAttributes::ipc_handler`].
+
+use std::{
+  cell::{Cell, RefCell},
+  collections::HashMap,
+  rc::Rc,
+};
+
+use serde::Deserialize;
+
+use crate::{ipc, WebViewAttributes};
+
+/// Kind of the internal IPC messages sent by the bridge scripts.
+const MESSAGE_KIND: &str = "bridge";
+
+#[derive(Deserialize)]
+struct BridgeMessage {
+  name: String,
+  #[serde(default)]
+  payload: serde_json::Value,
+}
+
+/// Name of the handler resolving the [`BridgeCallbacks`].
+const CALLBACK_MESSAGE_NAME: &str = "callback";
+
+#[derive(Deserialize)]
+struct CallbackMessage {
+  id: u64,
+  #[serde(default)]
+  value: serde_json::Value,
+}
+
+/// Collects the scripts and handlers of the features implemented on top of the IPC channel.
+pub(crate) struct Bridge {
+  scripts: Vec<String>,
+  handlers: HashMap<&'static str, Box<dyn Fn(serde_json::Value)>>,
+  callbacks: BridgeCallbacks,
+}
+
+impl Bridge {
+  pub fn new() -> Self {
+    let callbacks = BridgeCallbacks::default();
+    let callbacks_ = callbacks.clone();
+
+    let mut handlers: HashMap<&'static str, Box<dyn Fn(serde_json::Value)>> = HashMap::new();
+    handlers.insert(
+      CALLBACK_MESSAGE_NAME,
+      Box::new(move |payload| {
+        if let Ok(message) = serde_json::from_value::<CallbackMessage>(payload) {
+          callbacks_.resolve(message.id, message.value);
+        }
+      }),
+    );
+
+    Self {
+      scripts: Vec::new(),
+      handlers,
+      callbacks,
+    }
+  }
+
+  /// The callbacks resolved by the messages routed by this bridge.
+  pub fn callbacks(&self) -> BridgeCallbacks {
+    self.callbacks.clone()
+  }
+
+  /// Registers a `handler` for the messages sent with [`post_message`]`(name)`
+  /// and an initialization `script` sending them.
+  pub fn register<F>(&mut self, name: &'static str, script: String, handler: F)
+  where
+    F: Fn(serde_json::Value) + 'static,
+  {
+    self.scripts.push(script);
+    self.handlers.insert(name, Box::new(handler));
+  }
+
+  /// Registers a `handler` like [`Bridge::register`], with an initialization script running
+  /// `body` in the main frame only, where the `post` function sends the messages.
+  pub fn register_main_frame<F>(&mut self, name: &'static str, body: &str, handler: F)
+  where
+    F: Fn(serde_json::Value) + 'static,
+  {
+    let script = format!(
+      "(function (post) {{\n  if (window.top !== window) return;{body}\n}})({});",
+      post_message(name)
+    );
+    self.register(name, script, handler);
+  }
+
+  /// Adds the registered scripts to the initialization scripts and routes
+  /// the bridge messages to their handlers.
+  pub fn install(self, attrs: &mut WebViewAttributes) {
+    attrs.initialization_scripts.extend(self.scripts);
+
+    let handlers = self.handlers;
+    let ipc_handler = attrs.ipc_handler.take();
+    attrs.ipc_handler = Some(Box::new(move |message| {
+      match ipc::open_envelope::<BridgeMessage>(&message, MESSAGE_KIND) {
+        Some(message) => {
+          if let Some(handler) = handlers.get(message.name.as_str()) {
+            handler(message.payload);
+          }
+        }
+        None => {
+          if let Some(ipc_handler) = &ipc_handler {
+            ipc_handler(message);
+          }
+        }
+      }
+    }));
+  }
+}
+
+/// A JavaScript function expression posting its argument to the bridge handler registered as `name`.
+pub(crate) fn post_message(name: &str) -> String {
+  format!(
+    "(function (payload) {{ window.ipc.postMessage({}); }})",
+    ipc::envelope_script(
+      MESSAGE_KIND,
+      &format!(
+        "{{ name: {}, payload: payload }}",
+        serde_json::to_string(name).unwrap()
+      ),
+    ),
+  )
+}
+
+/// One-shot callbacks waiting for a value posted back by an evaluated script.
+#[derive(Clone, Default)]
+pub(crate) struct BridgeCallbacks {
+  next_id: Rc<Cell<u64>>,
+  pending: Rc<RefCell<HashMap<u64, Box<dyn FnOnce(serde_json::Value)>>>>,
+}
+
+impl BridgeCallbacks {
+  /// Registers a `callback` and returns a JavaScript function expression calling it with its argument.
+  pub fn register<F>(&self, callback: F) -> String
+  where
+    F: FnOnce(serde_json::Value) + 'static,
+  {
+    let id = self.next_id.get();
+    self.next_id.set(id.wrapping_add(1));
+    self.pending.borrow_mut().insert(id, Box::new(callback));
+
+    format!(
+      "(function (value) {{ {}({{ id: {id}, value: value }}); }})",
+      post_message(CALLBACK_MESSAGE_NAME)
+    )
+  }
+
+  /// Drops the pending callbacks without calling them.
+  pub fn clear(&self) {
+    // release the borrow before dropping the callbacks
+    let pending = std::mem::take(&mut *self.pending.borrow_mut());
+    drop(pending);
+  }
+
+  fn resolve(&self, id: u64, value: serde_json::Value) {
+    // release the borrow before running the callback, it may register new ones
+    let callback = self.pending.borrow_mut().remove(&id);
+    if let Some(callback) = callback {
+      callback(value);
+    }
+  }
+}
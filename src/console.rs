@@ -0,0 +1,178 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Deserialize;
+
+/// The level of a message of the page console, see [`WebViewAttributes::on_console_message`](crate::WebViewAttributes::on_console_message).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleMessageLevel {
+  /// `console.debug`.
+  Debug,
+  /// `console.log` and the other console methods.
+  Log,
+  /// `console.info`.
+  Info,
+  /// `console.warn`.
+  Warning,
+  /// `console.error` and the failed `console.assert`.
+  Error,
+}
+
+impl ConsoleMessageLevel {
+  fn from_method(method: &str) -> Self {
+    match method {
+      "debug" => Self::Debug,
+      "info" => Self::Info,
+      "warn" | "warning" => Self::Warning,
+      "error" | "assert" => Self::Error,
+      _ => Self::Log,
+    }
+  }
+}
+
+/// A message of the page console: its level, text, source URL and line, when they are known.
+pub(crate) type ConsoleMessage = (ConsoleMessageLevel, String, Option<String>, Option<u32>);
+
+#[cfg(not(target_os = "windows"))]
+#[derive(Deserialize)]
+struct RawConsoleMessage {
+  level: String,
+  message: String,
+  source: Option<String>,
+  line: Option<u32>,
+}
+
+/// Parses the value posted by [`console_script`].
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn parse_console_message(value: serde_json::Value) -> Option<ConsoleMessage> {
+  let message = serde_json::from_value::<RawConsoleMessage>(value).ok()?;
+  Some((
+    ConsoleMessageLevel::from_method(&message.level),
+    message.message,
+    message.source,
+    message.line,
+  ))
+}
+
+/// Initialization script wrapping the `console` methods to post their messages to the `post`
+/// function expression.
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn console_script(post: &str) -> String {
+  format!(
+    r#"(function (post) {{
+  function format(value) {{
+    if (typeof value === 'string') return value;
+    if (value instanceof Error) return value.stack || String(value);
+    try {{
+      var json = JSON.stringify(value);
+      return json === undefined ? String(value) : json;
+    }} catch (e) {{
+      return String(value);
+    }}
+  }}
+
+  function caller() {{
+    // skips the `Error` header of V8 and the frames of this script
+    var frames = String(new Error().stack || '')
+      .split('\n')
+      .filter(function (frame) {{ return /:\d+/.test(frame); }})
+      .slice(2);
+    for (var i = 0; i < frames.length; i++) {{
+      var match = /((?:[a-zA-Z][a-zA-Z0-9+.-]*):\/\/[^\s()]*?):(\d+)(?::\d+)?\)?\s*$/.exec(frames[i]);
+      if (match) return {{ source: match[1], line: Number(match[2]) }};
+    }}
+    return {{ source: null, line: null }};
+  }}
+
+  ['debug', 'log', 'info', 'warn', 'error', 'assert'].forEach(function (method) {{
+    var original = console[method];
+    if (typeof original !== 'function') return;
+    console[method] = function () {{
+      var args = Array.prototype.slice.call(arguments);
+      if (method === 'assert') {{
+        if (args.shift()) return original.apply(this, arguments);
+        args.unshift('Assertion failed:');
+      }}
+      try {{
+        var location = caller();
+        post({{
+          level: method,
+          message: args.map(format).join(' '),
+          source: location.source,
+          line: location.line
+        }});
+      }} catch (e) {{}}
+      return original.apply(this, arguments);
+    }};
+  }});
+}})({post});"#
+  )
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConsoleApiCalled {
+  #[serde(rename = "type")]
+  kind: String,
+  args: Vec<RemoteObject>,
+  stack_trace: Option<StackTrace>,
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteObject {
+  value: Option<serde_json::Value>,
+  unserializable_value: Option<String>,
+  description: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StackTrace {
+  call_frames: Vec<CallFrame>,
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CallFrame {
+  url: String,
+  line_number: u32,
+}
+
+/// Parses the parameters of a `Runtime.consoleAPICalled` DevTools protocol event.
+#[cfg(target_os = "windows")]
+pub(crate) fn parse_console_api_called(json: &str) -> Option<ConsoleMessage> {
+  let event = serde_json::from_str::<ConsoleApiCalled>(json).ok()?;
+  let message = event
+    .args
+    .into_iter()
+    .map(
+      |arg| match (arg.value, arg.unserializable_value, arg.description) {
+        (Some(serde_json::Value::String(value)), _, _) => value,
+        (_, _, Some(description)) => description,
+        (_, Some(value), _) => value,
+        (Some(value), _, _) => value.to_string(),
+        (None, None, None) => "undefined".to_string(),
+      },
+    )
+    .collect::<Vec<_>>()
+    .join(" ");
+  let frame = event
+    .stack_trace
+    .and_then(|stack| stack.call_frames.into_iter().next())
+    .filter(|frame| !frame.url.is_empty());
+
+  Some((
+    ConsoleMessageLevel::from_method(&event.kind),
+    message,
+    frame.as_ref().map(|frame| frame.url.clone()),
+    // the line numbers of the DevTools protocol are zero-based
+    frame.map(|frame| frame.line_number + 1),
+  ))
+}
@@ -58,4 +58,26 @@ pub enum Error {
   UnsupportedWindowHandle,
   #[error(transparent)]
   Utf8Error(#[from] std::str::Utf8Error),
+  #[error("Invalid MIME type: {0}")]
+  InvalidMimeType(String),
+  #[error("Failed to take a snapshot: {0}")]
+  SnapshotError(String),
+  #[error("{0} is not supported on this platform")]
+  NotSupported(&'static str),
+  #[error("The webview was not created as a child")]
+  NotAChildWebView,
+  #[error("Invalid zoom limits: {0} to {1}")]
+  InvalidZoomLimits(f64, f64),
+  #[error("Invalid zoom step: {0}")]
+  InvalidZoomStep(f64),
+  #[error("No history entry at index {0}")]
+  HistoryIndexOutOfBounds(usize),
+  #[error("Invalid session state")]
+  InvalidSessionState,
+  #[error("No frame with id {0}")]
+  FrameNotFound(u64),
+  #[error("The script threw an exception: {0}")]
+  ScriptException(String),
+  #[error("The script was not evaluated")]
+  ScriptNotEvaluated,
 }
@@ -0,0 +1,56 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use base64::{engine::general_purpose, Engine};
+use serde_json::Value;
+
+/// Body of the main frame script posting the favicon of the page, re-encoded as a base64 PNG,
+/// every time the icon `<link>` changes, see [`Bridge::register_main_frame`](crate::bridge::Bridge::register_main_frame).
+///
+/// Falls back to `/favicon.ico` when the page doesn't declare an icon.
+pub(crate) const FAVICON_SCRIPT: &str = r#"
+  var current = null;
+
+  function iconUrl() {
+    var links = document.querySelectorAll('link[rel~="icon" i]');
+    var link = links[links.length - 1];
+    return link && link.href ? link.href : new URL('/favicon.ico', location.href).href;
+  }
+
+  function update() {
+    var url = iconUrl();
+    if (url === current) return;
+    current = url;
+    fetch(url)
+      .then(function (response) {
+        if (!response.ok) throw new Error(response.statusText);
+        return response.blob();
+      })
+      .then(createImageBitmap)
+      .then(function (bitmap) {
+        if (url !== current) return;
+        var canvas = document.createElement('canvas');
+        canvas.width = bitmap.width;
+        canvas.height = bitmap.height;
+        canvas.getContext('2d').drawImage(bitmap, 0, 0);
+        var png = canvas.toDataURL('image/png');
+        post(png.slice(png.indexOf(',') + 1));
+      })
+      .catch(function () {});
+  }
+
+  document.addEventListener('DOMContentLoaded', function () {
+    update();
+    new MutationObserver(update).observe(document.head || document.documentElement, {
+      childList: true,
+      subtree: true,
+      attributes: true,
+      attributeFilter: ['href', 'rel']
+    });
+  });"#;
+
+/// Decodes the base64 PNG posted by [`FAVICON_SCRIPT`].
+pub(crate) fn parse_favicon(payload: Value) -> Option<Vec<u8>> {
+  general_purpose::STANDARD.decode(payload.as_str()?).ok()
+}
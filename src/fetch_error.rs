@@ -0,0 +1,90 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Deserialize;
+use url::Url;
+
+/// A failed `fetch()` or `XMLHttpRequest` of the page, see [`WebViewAttributes::on_fetch_error`](crate::WebViewAttributes::on_fetch_error).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchError {
+  /// The requested URL.
+  pub url: Url,
+  /// The origin of the page that sent the request.
+  pub origin: String,
+  /// The message of the JavaScript error.
+  pub error_message: String,
+  /// Whether the request was likely blocked by the CORS policy.
+  ///
+  /// Browsers do not expose the cause of the network errors to the page scripts, so this is guessed:
+  /// the request was cross-origin and the browser is online.
+  pub is_cors_error: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawFetchError {
+  url: String,
+  origin: String,
+  error_message: String,
+  is_cors_error: bool,
+}
+
+/// Parses the value posted by [`fetch_error_script`].
+pub(crate) fn parse_fetch_error(value: serde_json::Value) -> Option<FetchError> {
+  let error = serde_json::from_value::<RawFetchError>(value).ok()?;
+  Some(FetchError {
+    url: Url::parse(&error.url).ok()?,
+    origin: error.origin,
+    error_message: error.error_message,
+    is_cors_error: error.is_cors_error,
+  })
+}
+
+/// Initialization script posting the failed `fetch()` and `XMLHttpRequest`s to the `post` function expression.
+pub(crate) fn fetch_error_script(post: &str) -> String {
+  format!(
+    r#"(function (post) {{
+  function report(input, message) {{
+    try {{
+      var url = new URL(input, window.location.href);
+      post({{
+        url: url.href,
+        origin: window.location.origin,
+        errorMessage: String(message),
+        isCorsError: url.origin !== window.location.origin && navigator.onLine !== false
+      }});
+    }} catch (e) {{}}
+  }}
+
+  var fetch = window.fetch;
+  if (fetch) {{
+    window.fetch = function (input, init) {{
+      var url = input instanceof Request ? input.url : String(input);
+      return fetch.apply(this, arguments).catch(function (error) {{
+        if (!error || error.name !== 'AbortError') report(url, error && error.message);
+        throw error;
+      }});
+    }};
+  }}
+
+  var open = XMLHttpRequest.prototype.open;
+  var send = XMLHttpRequest.prototype.send;
+  XMLHttpRequest.prototype.open = function (method, url) {{
+    this.__wryUrl = String(url);
+    return open.apply(this, arguments);
+  }};
+  XMLHttpRequest.prototype.send = function () {{
+    var xhr = this;
+    xhr.addEventListener('error', function () {{
+      report(xhr.__wryUrl, 'Network request failed');
+    }});
+    xhr.addEventListener('timeout', function () {{
+      report(xhr.__wryUrl, 'Network request timed out');
+    }});
+    return send.apply(this, arguments);
+  }};
+}})({post});"#
+  )
+}
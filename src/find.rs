@@ -0,0 +1,72 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/// The options of a find-in-page search, see [`WebView::find`](crate::WebView::find).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FindOptions {
+  /// Whether the search is case sensitive, `false` by default.
+  pub case_sensitive: bool,
+  /// Whether the first match is searched from the end of the page, `false` by default.
+  pub backwards: bool,
+  /// Whether the search continues from the other end of the page after the last match, `true` by default.
+  pub wrap_around: bool,
+}
+
+impl Default for FindOptions {
+  fn default() -> Self {
+    Self {
+      case_sensitive: false,
+      backwards: false,
+      wrap_around: true,
+    }
+  }
+}
+
+/// Script selecting the first match of `text` and returning the number of matches in the page.
+///
+/// The search is kept in `window.__WRY_FIND__` for [`find_next_script`].
+#[cfg(not(gtk))]
+pub(crate) fn find_script(text: &str, options: FindOptions) -> String {
+  let text = serde_json::to_string(text).unwrap();
+  let FindOptions {
+    case_sensitive,
+    backwards,
+    wrap_around,
+  } = options;
+
+  format!(
+    r#"(function (text, caseSensitive, backwards, wrap) {{
+  window.getSelection().removeAllRanges();
+  window.__WRY_FIND__ = {{ text: text, caseSensitive: caseSensitive, wrap: wrap }};
+  if (!text) return 0;
+  window.find(text, caseSensitive, backwards, wrap, false, false, false);
+  var haystack = document.body ? document.body.innerText : '';
+  var needle = text;
+  if (!caseSensitive) {{
+    haystack = haystack.toLowerCase();
+    needle = needle.toLowerCase();
+  }}
+  var count = 0;
+  for (var i = haystack.indexOf(needle); i !== -1; i = haystack.indexOf(needle, i + needle.length)) count++;
+  return count;
+}})({text}, {case_sensitive}, {backwards}, {wrap_around});"#
+  )
+}
+
+/// Script selecting the next, or previous, match of the search started by [`find_script`].
+#[cfg(not(gtk))]
+pub(crate) fn find_next_script(backwards: bool) -> String {
+  format!(
+    r#"(function (backwards) {{
+  var search = window.__WRY_FIND__;
+  if (search && search.text) window.find(search.text, search.caseSensitive, backwards, search.wrap, false, false, false);
+}})({backwards});"#
+  )
+}
+
+/// Script ending the search started by [`find_script`] and clearing its selected match.
+#[cfg(not(gtk))]
+pub(crate) const CLEAR_MATCHES_SCRIPT: &str =
+  "window.__WRY_FIND__ = null; window.getSelection().removeAllRanges();";
@@ -0,0 +1,35 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+/// Body of the main frame script posting the idle time, in milliseconds, once no user interaction
+/// happened for `timeout`, see [`Bridge::register_main_frame`](crate::bridge::Bridge::register_main_frame).
+pub(crate) fn idle_script(timeout: Duration) -> String {
+  format!(
+    r#"
+  var timeout = {timeout};
+  var lastActivity = Date.now();
+  var timer = null;
+
+  function reset() {{
+    lastActivity = Date.now();
+    clearTimeout(timer);
+    timer = setTimeout(function () {{
+      post(Date.now() - lastActivity);
+    }}, timeout);
+  }}
+
+  ['mousemove', 'mousedown', 'keydown', 'touchstart', 'click', 'wheel'].forEach(function (event) {{
+    window.addEventListener(event, reset, {{ capture: true, passive: true }});
+  }});
+  Object.defineProperty(window, '__WRY_RESET_IDLE_TIMER__', {{ value: reset }});
+  reset();"#,
+    timeout = timeout.as_millis()
+  )
+}
+
+/// Script restarting the timer of [`idle_script`].
+pub(crate) const RESET_IDLE_TIMER_SCRIPT: &str =
+  "window.__WRY_RESET_IDLE_TIMER__ && window.__WRY_RESET_IDLE_TIMER__();";
@@ -0,0 +1,568 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{cell::RefCell, collections::VecDeque};
+
+use base64::{engine::general_purpose, Engine};
+use http::{header::ORIGIN, Request};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use url::Url;
+
+use crate::IpcThrottleReason;
+
+thread_local! {
+  /// The URL of the frame which posted the IPC message being handled.
+  static MESSAGE_SOURCE: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Calls `f` with `source` as the URL of the frame which posted the IPC message being handled.
+pub(crate) fn with_message_source<R>(source: Option<String>, f: impl FnOnce() -> R) -> R {
+  let previous = MESSAGE_SOURCE.with(|s| s.replace(source));
+  let result = f();
+  MESSAGE_SOURCE.with(|s| *s.borrow_mut() = previous);
+  result
+}
+
+/// The origin of the frame which posted the IPC message being handled, when it is known.
+pub(crate) fn message_origin() -> Option<String> {
+  MESSAGE_SOURCE.with(|s| {
+    s.borrow()
+      .as_deref()
+      .and_then(|source| Url::parse(source).ok())
+      .map(|url| crate::url_origin(&url))
+  })
+}
+
+/// Builds the request of an IPC `message`, its URI is the URL of the frame which posted it
+/// and its `Origin` header the origin of this URL, when they are known.
+pub(crate) fn message_request(message: String) -> Request<String> {
+  let mut request = Request::builder();
+  if let Some(source) = MESSAGE_SOURCE.with(|s| s.borrow().clone()) {
+    if let Ok(url) = Url::parse(&source) {
+      request = request.header(ORIGIN, crate::url_origin(&url));
+    }
+    request = request.uri(source);
+  }
+
+  request.body(message.clone()).unwrap_or_else(|_| {
+    // the source is not a valid URI
+    Request::new(message)
+  })
+}
+
+/// A message posted by the page with `window.ipc.postMessage`, see
+/// [`WebViewAttributes::ipc_handler_raw`](crate::WebViewAttributes::ipc_handler_raw).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcMessage {
+  /// A string message.
+  Text(String),
+  /// The bytes of an `ArrayBuffer`, a typed array or a `DataView`.
+  Binary(Vec<u8>),
+}
+
+/// The reply to a `window.ipc.postMessageWithReply` call, see
+/// [`WebViewAttributes::ipc_reply_handler`](crate::WebViewAttributes::ipc_reply_handler).
+///
+/// Dropping it without replying rejects the promise of the call.
+pub struct IpcReplier {
+  reply: Option<Box<dyn FnOnce(Result<String, String>)>>,
+}
+
+impl IpcReplier {
+  #[cfg(any(target_os = "macos", target_os = "ios"))]
+  pub(crate) fn new(reply: impl FnOnce(Result<String, String>) + 'static) -> Self {
+    Self {
+      reply: Some(Box::new(reply)),
+    }
+  }
+
+  /// Settles the promise of the call, resolving it with the string or rejecting it with an
+  /// `Error` of the message.
+  pub fn reply(mut self, result: Result<String, String>) {
+    if let Some(reply) = self.reply.take() {
+      reply(result);
+    }
+  }
+}
+
+impl Drop for IpcReplier {
+  fn drop(&mut self) {
+    if let Some(reply) = self.reply.take() {
+      reply(Err("The IPC message was not replied".to_string()));
+    }
+  }
+}
+
+/// Prefix of the internal messages posted by the scripts of wry through `window.ipc.postMessage`,
+/// followed by the JSON of their [`Envelope`].
+pub(crate) const ENVELOPE_PREFIX: &str = "__wry__:";
+
+/// An internal message, `data` is the message of the feature handling the `kind`.
+#[derive(Deserialize)]
+struct Envelope<T> {
+  kind: String,
+  data: T,
+}
+
+/// JavaScript expression of the internal message of `kind` carrying the `data` expression.
+pub(crate) fn envelope_script(kind: &str, data: &str) -> String {
+  format!("'{ENVELOPE_PREFIX}' + JSON.stringify({{ kind: '{kind}', data: {data} }})")
+}
+
+/// Parses the data of the internal message of `kind`, `None` for the other messages, including
+/// the malformed ones, which are handled as the messages of the page.
+pub(crate) fn open_envelope<T: DeserializeOwned>(message: &str, kind: &str) -> Option<T> {
+  let json = message.strip_prefix(ENVELOPE_PREFIX)?;
+  // `JSON.stringify` writes the kind first, the other kinds are skipped without parsing them
+  if !json.starts_with(&format!("{{\"kind\":\"{kind}\",")) {
+    return None;
+  }
+  serde_json::from_str::<Envelope<T>>(json)
+    .ok()
+    .filter(|envelope| envelope.kind == kind)
+    .map(|envelope| envelope.data)
+}
+
+/// JavaScript function expression encoding the binary messages posted to `window.ipc.postMessage`
+/// as internal messages of base64 strings, the other messages are returned unchanged.
+pub(crate) fn encode_message_function() -> String {
+  format!(
+    r#"(function (message) {{
+  var bytes = null;
+  if (message instanceof ArrayBuffer) bytes = new Uint8Array(message);
+  else if (ArrayBuffer.isView(message)) bytes = new Uint8Array(message.buffer, message.byteOffset, message.byteLength);
+  if (!bytes) return message;
+  var binary = '';
+  for (var i = 0; i < bytes.length; i += 0x8000) {{
+    binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+  }}
+  return {binary};
+}})"#,
+    binary = envelope_script("binary", "btoa(binary)"),
+  )
+}
+
+/// Decodes the messages encoded by [`encode_message_function`], the other messages are text.
+pub(crate) fn decode_message(message: String) -> IpcMessage {
+  match open_envelope::<String>(&message, "binary")
+    .and_then(|payload| general_purpose::STANDARD.decode(payload).ok())
+  {
+    Some(bytes) => IpcMessage::Binary(bytes),
+    None => IpcMessage::Text(message),
+  }
+}
+
+/// The maximum length in UTF-16 code units of the chunks posted by [`post_chunked_function`],
+/// WebView2 fails to deliver messages of about 2 MB and escaping the chunks in JSON may
+/// grow them.
+const CHUNK_LENGTH: usize = 128 * 1024;
+
+/// JavaScript function expression calling `post(message)` with the string messages split in
+/// chunks reassembled by [`ChunkAssembler`], it throws a `RangeError` when the UTF-8 size of
+/// `message` exceeds `max_size`.
+pub(crate) fn post_chunked_function(max_size: Option<usize>) -> String {
+  let max_size = max_size.map_or_else(|| "null".to_string(), |max_size| max_size.to_string());
+  format!(
+    r#"(function () {{
+  var maxSize = {max_size};
+  var prefix = Math.random().toString(36).slice(2) + '-';
+  var nextId = 0;
+  return function (post, message) {{
+    if (typeof message !== 'string') return post(message);
+    if (maxSize !== null && new TextEncoder().encode(message).length > maxSize) {{
+      throw new RangeError('The IPC message exceeds the maximum size of ' + maxSize + ' bytes');
+    }}
+    if (message.length <= {CHUNK_LENGTH}) return post(message);
+    var id = prefix + nextId++;
+    var chunks = [];
+    for (var start = 0; start < message.length;) {{
+      var end = Math.min(start + {CHUNK_LENGTH}, message.length);
+      // keeps the surrogate pairs in the same chunk
+      var code = message.charCodeAt(end - 1);
+      if (end < message.length && code >= 0xd800 && code <= 0xdbff) end--;
+      chunks.push(message.slice(start, end));
+      start = end;
+    }}
+    chunks.forEach(function (data, index) {{
+      post({chunk});
+    }});
+  }};
+}})()"#,
+    chunk = envelope_script(
+      "chunk",
+      "{ id: id, index: index, count: chunks.length, data: data }"
+    ),
+  )
+}
+
+/// A chunk posted by [`post_chunked_function`].
+#[derive(Deserialize)]
+struct Chunk {
+  id: String,
+  /// The position of the chunk in the message.
+  index: usize,
+  /// The number of chunks of the message.
+  count: usize,
+  data: String,
+}
+
+/// A message whose chunks are being received by [`ChunkAssembler`].
+struct PendingMessage {
+  id: String,
+  /// The index of the next chunk.
+  next: usize,
+  /// The UTF-8 size of the chunks received so far.
+  size: usize,
+  state: PendingState,
+}
+
+enum PendingState {
+  Receiving(String),
+  /// The message exceeds the maximum size, it is reported once its last chunk is received.
+  Oversized,
+  /// A chunk of the message was lost, it is already reported.
+  Incomplete,
+}
+
+/// The maximum number of messages whose chunks are received at once, receiving another one
+/// drops the oldest.
+const MAX_PENDING_CHUNKED_MESSAGES: usize = 8;
+
+/// Reassembles the chunks posted by [`post_chunked_function`].
+#[derive(Default)]
+pub(crate) struct ChunkAssembler {
+  /// The messages being received, oldest first.
+  pending: VecDeque<PendingMessage>,
+}
+
+impl ChunkAssembler {
+  /// Returns the message once all its chunks are received, or `message` itself if it isn't
+  /// a chunk. The messages whose size exceeds `max_size` and the messages missing a chunk are
+  /// dropped, the reason is returned instead.
+  pub fn push(
+    &mut self,
+    message: String,
+    max_size: Option<usize>,
+  ) -> Option<Result<String, IpcThrottleReason>> {
+    let exceeds = |size: usize| max_size.map_or(false, |max_size| size > max_size);
+    let Some(chunk) = open_envelope::<Chunk>(&message, "chunk") else {
+      return Some(if exceeds(message.len()) {
+        Err(IpcThrottleReason::Oversized {
+          size: message.len(),
+        })
+      } else {
+        Ok(message)
+      });
+    };
+
+    let position = match self.pending.iter().position(|m| m.id == chunk.id) {
+      Some(position) => position,
+      None => {
+        if self.pending.len() == MAX_PENDING_CHUNKED_MESSAGES {
+          self.pending.pop_front();
+        }
+        self.pending.push_back(PendingMessage {
+          id: chunk.id,
+          next: 0,
+          size: 0,
+          state: PendingState::Receiving(String::new()),
+        });
+        self.pending.len() - 1
+      }
+    };
+
+    let pending = &mut self.pending[position];
+    let mut result = None;
+    if chunk.index != pending.next && !matches!(pending.state, PendingState::Incomplete) {
+      pending.state = PendingState::Incomplete;
+      result = Some(Err(IpcThrottleReason::Incomplete {
+        missing: chunk.index.saturating_sub(pending.next),
+      }));
+    }
+    pending.next = chunk.index + 1;
+    pending.size += chunk.data.len();
+    if let PendingState::Receiving(buffer) = &mut pending.state {
+      if exceeds(pending.size) {
+        pending.state = PendingState::Oversized;
+      } else {
+        buffer.push_str(&chunk.data);
+      }
+    }
+
+    if chunk.index + 1 >= chunk.count {
+      let pending = self.pending.remove(position)?;
+      result = result.or(match pending.state {
+        PendingState::Receiving(message) => Some(Ok(message)),
+        PendingState::Oversized => Some(Err(IpcThrottleReason::Oversized { size: pending.size })),
+        PendingState::Incomplete => None,
+      });
+    }
+    result
+  }
+
+  /// Drops the messages being received, e.g. when their page is unloaded.
+  pub fn clear(&mut self) {
+    self.pending.clear();
+  }
+}
+
+/// JavaScript function expression of `window.ipc.invoke(cmd, payload)`, returning a promise
+/// settled by [`invoke_result_script`].
+///
+/// It also defines `window.__wry_binding__(name, args)`, invoking the binding `name`.
+pub(crate) fn invoke_function() -> String {
+  format!(
+    r#"(function () {{
+  var nextId = 0;
+  var pending = {{}};
+  Object.defineProperty(window, '__wry_invoke_result__', {{
+    value: function (id, ok, value) {{
+      var promise = pending[id];
+      if (!promise) return;
+      delete pending[id];
+      if (ok) promise.resolve(value);
+      else promise.reject(new Error(value));
+    }}
+  }});
+  function invoke(cmd, payload, binding) {{
+    return new Promise(function (resolve, reject) {{
+      var id = nextId++;
+      pending[id] = {{ resolve: resolve, reject: reject }};
+      window.ipc.postMessage({invoke});
+    }});
+  }}
+  Object.defineProperty(window, '__wry_binding__', {{
+    value: function (name, args) {{ return invoke(name, args, true); }}
+  }});
+  return function (cmd, payload) {{ return invoke(cmd, payload, false); }};
+}})()"#,
+    invoke = envelope_script(
+      "invoke",
+      "{ id: id, cmd: String(cmd), payload: payload === undefined ? null : payload, binding: binding }"
+    ),
+  )
+}
+
+/// A `window.ipc.invoke` call posted by [`invoke_function`].
+#[derive(Deserialize)]
+pub(crate) struct InvokeMessage {
+  pub id: u64,
+  pub cmd: String,
+  #[serde(default)]
+  pub payload: Value,
+  /// Whether `cmd` is the name of a binding, see [`binding_script`].
+  #[serde(default)]
+  pub binding: bool,
+}
+
+/// Parses the messages posted by [`invoke_function`], returns the other messages unchanged.
+pub(crate) fn parse_invoke_message(message: String) -> Result<InvokeMessage, String> {
+  open_envelope(&message, "invoke").ok_or(message)
+}
+
+/// Script settling the promise of the `window.ipc.invoke` call `id` with `result`.
+pub(crate) fn invoke_result_script(id: u64, result: Result<Value, String>) -> String {
+  let (ok, value) = match result {
+    Ok(value) => (true, value),
+    Err(error) => (false, Value::String(error)),
+  };
+  format!("window.__wry_invoke_result__({id}, {ok}, {value});")
+}
+
+/// Script dispatching the `CustomEvent` `event` with the `payload` detail on the `window`.
+pub(crate) fn emit_script(event: &str, payload: &Value) -> String {
+  let event = Value::String(event.to_string());
+  format!("window.dispatchEvent(new CustomEvent({event}, {{ detail: {payload} }}));")
+}
+
+/// Initialization script defining `window[name]`, invoking the binding `name` with its arguments.
+pub(crate) fn binding_script(name: &str) -> String {
+  let name = Value::String(name.to_string());
+  format!(
+    "Object.defineProperty(window, {name}, {{ value: function () {{ return window.__wry_binding__({name}, Array.prototype.slice.call(arguments)); }} }});"
+  )
+}
+
+/// JavaScript function expression of `window.ipc.channel(name)`, returning an object whose
+/// `postMessage` sends string messages to the handler of the channel.
+pub(crate) fn channel_function() -> String {
+  format!(
+    r#"(function (name) {{
+  name = String(name);
+  return Object.freeze({{
+    postMessage: function (message) {{
+      window.ipc.postMessage({channel});
+    }}
+  }});
+}})"#,
+    channel = envelope_script("channel", "{ channel: name, message: String(message) }"),
+  )
+}
+
+/// A message posted to a channel by [`channel_function`].
+#[derive(Deserialize)]
+pub(crate) struct ChannelMessage {
+  pub channel: String,
+  pub message: String,
+}
+
+/// Parses the messages posted by [`channel_function`], returns the other messages unchanged.
+pub(crate) fn parse_channel_message(message: String) -> Result<ChannelMessage, String> {
+  open_envelope(&message, "channel").ok_or(message)
+}
+
+/// Initialization script defining `window.ipcCommands`, with a function invoking each of the
+/// typed IPC commands `names`.
+#[cfg(feature = "serde-ipc")]
+pub(crate) fn commands_script<'a>(names: impl Iterator<Item = &'a str>) -> String {
+  let commands = names
+    .map(|name| {
+      let name = serde_json::to_string(name).unwrap();
+      format!("{name}: function (payload) {{ return window.ipc.invoke({name}, payload); }}")
+    })
+    .collect::<Vec<_>>()
+    .join(",\n  ");
+  format!(
+    "Object.defineProperty(window, 'ipcCommands', {{ value: Object.freeze({{\n  {commands}\n}}) }});"
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  /// An internal message of `kind`, written with the kind first like `JSON.stringify` does.
+  fn envelope(kind: &str, data: Value) -> String {
+    format!("{ENVELOPE_PREFIX}{{\"kind\":\"{kind}\",\"data\":{data}}}")
+  }
+
+  /// The message posted by [`post_chunked_function`] for a chunk.
+  fn chunk(id: &str, index: usize, count: usize, data: &str) -> String {
+    envelope(
+      "chunk",
+      json!({ "id": id, "index": index, "count": count, "data": data }),
+    )
+  }
+
+  #[test]
+  fn should_reassemble_chunks() {
+    let mut assembler = ChunkAssembler::default();
+    assert_eq!(assembler.push(chunk("a", 0, 3, "hel"), None), None);
+    assert_eq!(assembler.push(chunk("b", 0, 2, "wor"), None), None);
+    assert_eq!(assembler.push(chunk("a", 1, 3, "lo "), None), None);
+    assert_eq!(
+      assembler.push(chunk("a", 2, 3, "🌍"), None),
+      Some(Ok("hello 🌍".to_string()))
+    );
+    assert_eq!(
+      assembler.push(chunk("b", 1, 2, "ld"), None),
+      Some(Ok("world".to_string()))
+    );
+  }
+
+  #[test]
+  fn should_drop_messages_missing_a_chunk() {
+    let mut assembler = ChunkAssembler::default();
+    assert_eq!(assembler.push(chunk("a", 0, 3, "hel"), None), None);
+    assert_eq!(
+      assembler.push(chunk("a", 2, 3, "!"), None),
+      Some(Err(IpcThrottleReason::Incomplete { missing: 1 }))
+    );
+
+    // the chunks received after the gap are ignored
+    assert_eq!(assembler.push(chunk("b", 0, 4, "wor"), None), None);
+    assert_eq!(
+      assembler.push(chunk("b", 2, 4, "d"), None),
+      Some(Err(IpcThrottleReason::Incomplete { missing: 1 }))
+    );
+    assert_eq!(assembler.push(chunk("b", 3, 4, "!"), None), None);
+
+    // the first chunk is missing
+    assert_eq!(
+      assembler.push(chunk("c", 1, 2, "lo"), None),
+      Some(Err(IpcThrottleReason::Incomplete { missing: 1 }))
+    );
+
+    assert_eq!(assembler.push(chunk("d", 0, 2, "he"), None), None);
+    assert_eq!(
+      assembler.push(chunk("d", 1, 2, "llo"), None),
+      Some(Ok("hello".to_string()))
+    );
+  }
+
+  #[test]
+  fn should_pass_other_messages_through() {
+    let mut assembler = ChunkAssembler::default();
+    assert_eq!(
+      assembler.push("hello".to_string(), None),
+      Some(Ok("hello".to_string()))
+    );
+    // the malformed internal messages are handled as the messages of the page
+    let malformed = format!("{ENVELOPE_PREFIX}{{\"kind\":\"chunk\",\"data\":1}}");
+    assert_eq!(assembler.push(malformed.clone(), None), Some(Ok(malformed)));
+  }
+
+  #[test]
+  fn should_drop_oversized_messages() {
+    let mut assembler = ChunkAssembler::default();
+    assert_eq!(
+      assembler.push("hello".to_string(), Some(4)),
+      Some(Err(IpcThrottleReason::Oversized { size: 5 }))
+    );
+    assert_eq!(assembler.push(chunk("a", 0, 3, "hel"), Some(4)), None);
+    assert_eq!(assembler.push(chunk("a", 1, 3, "lo "), Some(4)), None);
+    assert_eq!(
+      assembler.push(chunk("a", 2, 3, "!"), Some(4)),
+      Some(Err(IpcThrottleReason::Oversized { size: 7 }))
+    );
+  }
+
+  #[test]
+  fn should_bound_pending_messages() {
+    let mut assembler = ChunkAssembler::default();
+    for i in 0..=MAX_PENDING_CHUNKED_MESSAGES {
+      assert_eq!(assembler.push(chunk(&i.to_string(), 0, 2, "a"), None), None);
+    }
+    assert_eq!(
+      assembler.push(chunk("1", 1, 2, "b"), None),
+      Some(Ok("ab".to_string()))
+    );
+    // the oldest message was dropped, its last chunk is missing the first one
+    assert_eq!(
+      assembler.push(chunk("0", 1, 2, "b"), None),
+      Some(Err(IpcThrottleReason::Incomplete { missing: 1 }))
+    );
+
+    assembler.clear();
+    assert_eq!(
+      assembler.push(chunk("2", 1, 2, "b"), None),
+      Some(Err(IpcThrottleReason::Incomplete { missing: 1 }))
+    );
+  }
+
+  #[test]
+  fn should_parse_channel_message() {
+    let message = envelope("channel", json!({ "channel": "logs", "message": "hello" }));
+    let message = parse_channel_message(message).ok().unwrap();
+    assert_eq!(message.channel, "logs");
+    assert_eq!(message.message, "hello");
+  }
+
+  #[test]
+  fn should_not_parse_other_messages_as_channel_messages() {
+    let messages = [
+      "hello".to_string(),
+      envelope("invoke", json!({ "channel": "logs", "message": "hello" })),
+      envelope("channel", json!({ "channel": "logs" })),
+      // without the prefix of the internal messages
+      json!({ "kind": "channel", "data": { "channel": "logs", "message": "hello" } }).to_string(),
+    ];
+    for message in messages {
+      assert_eq!(parse_channel_message(message.clone()).err(), Some(message));
+    }
+  }
+}
@@ -196,6 +196,14 @@
 //! - `linux-body`: Enables body support of custom protocol request on Linux. Requires
 //! webkit2gtk v2.40 or above.
 //! - `tracing`: enables [tracing] for `evaluate_script`, `ipc_handler` and `custom_protocols.
+//! - `serde-ipc`: Enables [`WebViewBuilder::with_ipc_command`] to register typed IPC commands,
+//! (de)serialized with [serde](https://docs.rs/serde).
+//! - `async`: Enables [`WebView::evaluate_script_async`] returning a future of the script result,
+//! and [`WebViewBuilder::with_async_custom_protocol`] to handle custom protocols with async functions.
+//! - `typescript`: Enables [`WebViewBuilder::typescript_definitions`] to generate the TypeScript
+//! definitions of the IPC bridge.
+//! - `rust-embed`: Enables [`WebViewBuilder::with_embedded_assets`] to serve the assets compiled
+//! into the binary with [rust-embed](https://docs.rs/rust-embed).
 //!
 //! [`tao`]: https://docs.rs/tao
 //! [`winit`]: https://docs.rs/winit
@@ -209,9 +217,34 @@
 #[macro_use]
 extern crate objc;
 
+#[cfg(feature = "protocol")]
+mod asset_dir;
+#[cfg(feature = "protocol")]
+mod asset_provider;
+mod bridge;
+mod console;
 mod error;
+#[cfg(not(target_os = "windows"))]
+mod favicon;
+mod fetch_error;
+mod find;
+mod idle;
+mod ipc;
+mod lifecycle;
+mod media;
+mod page_error;
+mod protocol_options;
+#[cfg(feature = "protocol")]
+mod protocol_router;
 mod proxy;
+mod range;
+#[cfg(feature = "async")]
+mod script_future;
+mod shared_buffer;
+#[cfg(feature = "typescript")]
+mod typescript;
 mod web_context;
+mod webrtc;
 
 #[cfg(target_os = "android")]
 pub(crate) mod android;
@@ -246,16 +279,43 @@ pub(crate) mod webview2;
 use self::webview2::*;
 #[cfg(target_os = "windows")]
 use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Controller;
-
-use std::{borrow::Cow, path::PathBuf, rc::Rc};
-
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Com::IDispatch;
+
+use std::{
+  borrow::Cow,
+  cell::{Cell, RefCell},
+  collections::{HashMap, VecDeque},
+  io::Read,
+  path::PathBuf,
+  rc::{Rc, Weak},
+  sync::Arc,
+  time::{Duration, Instant},
+};
+
+use bridge::{Bridge, BridgeCallbacks};
 use http::{Request, Response};
 
+#[cfg(feature = "protocol")]
+pub use asset_provider::AssetProvider;
+pub use console::ConsoleMessageLevel;
 pub use error::*;
+pub use fetch_error::FetchError;
+pub use find::FindOptions;
 pub use http;
+pub use ipc::{IpcMessage, IpcReplier};
+pub use lifecycle::{PageLifecycleEvent, PageLifecycleStage};
+pub use media::{AudioFocusEvent, CaptureState, LossDuration, MediaCaptureState, WebAudioState};
+pub use page_error::PageError;
+pub use protocol_options::CustomProtocolOptions;
+#[cfg(feature = "protocol")]
+pub use protocol_router::{ProtocolRouter, RouteParams};
 pub use proxy::{ProxyConfig, ProxyEndpoint};
+pub use range::range_response;
+pub use shared_buffer::{SharedBuffer, SharedBufferAccess};
 pub use url::Url;
 pub use web_context::WebContext;
+pub use webrtc::{RtcStatType, RtcStats};
 
 /// A rectangular region.
 #[derive(Clone, Copy, Debug, Default)]
@@ -270,11 +330,34 @@ pub struct Rect {
   pub height: u32,
 }
 
+impl Rect {
+  /// Converts the physical pixels of this rectangle to logical pixels.
+  pub fn to_logical(&self, scale_factor: f64) -> Rect {
+    self.scale(1.0 / scale_factor)
+  }
+
+  /// Converts the logical pixels of this rectangle to physical pixels.
+  pub fn to_physical(&self, scale_factor: f64) -> Rect {
+    self.scale(scale_factor)
+  }
+
+  fn scale(&self, factor: f64) -> Rect {
+    Rect {
+      x: (self.x as f64 * factor).round() as i32,
+      y: (self.y as f64 * factor).round() as i32,
+      width: (self.width as f64 * factor).round() as u32,
+      height: (self.height as f64 * factor).round() as u32,
+    }
+  }
+}
+
 /// Resolves a custom protocol [`Request`] asynchronously.
 ///
 /// See [`WebViewBuilder::with_asynchronous_custom_protocol`] for more information.
 pub struct RequestAsyncResponder {
   pub(crate) responder: Box<dyn FnOnce(Response<Cow<'static, [u8]>>)>,
+  /// Resolves the request with a streamed body, `None` on the platforms only accepting complete bodies.
+  pub(crate) stream_responder: Option<Box<dyn FnOnce(Response<Box<dyn Read + Send>>)>>,
 }
 
 // SAFETY: even though the webview bindings do not indicate the responder is Send,
@@ -288,6 +371,31 @@ impl RequestAsyncResponder {
     let (parts, body) = response.into_parts();
     (self.responder)(Response::from_parts(parts, body.into()))
   }
+
+  /// Resolves the request with the given response, whose body is streamed to the webview as it
+  /// is read, e.g. for large files or server-sent events.
+  ///
+  /// The body is read on a background thread, the response ends when [`Read::read`] returns
+  /// `Ok(0)` or an error.
+  ///
+  /// [`WebViewAttributes::response_transform`] doesn't apply to the streamed responses.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported, the whole body is read before the response is sent.
+  pub fn respond_with_stream<R: Read + Send + 'static>(self, response: Response<R>) {
+    let (parts, mut body) = response.into_parts();
+    match self.stream_responder {
+      Some(stream_responder) => stream_responder(Response::from_parts(parts, Box::new(body))),
+      None => {
+        let mut buffer = Vec::new();
+        if let Err(e) = body.read_to_end(&mut buffer) {
+          log::warn!("Failed to read the body of a custom protocol response: {e}");
+        }
+        (self.responder)(Response::from_parts(parts, buffer.into()))
+      }
+    }
+  }
 }
 
 pub struct WebViewAttributes {
@@ -322,11 +430,26 @@ pub struct WebViewAttributes {
   /// Headers used when loading the requested `url`.
   pub headers: Option<http::HeaderMap>,
 
+  /// Headers added to every navigation of the main frame, e.g. an authentication token, unless
+  /// the navigation request already has them. The subresources and the frames of the page don't
+  /// get them.
+  ///
+  /// The headers are added once [`WebViewAttributes::navigation_handler`] allowed the navigation.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: Added to the document request of the navigation.
+  /// - **macOS / iOS / Linux**: The `GET` navigations missing the headers are cancelled
+  /// and loaded again with them, so the navigation handler is called again for them.
+  /// - **Android**: Unsupported.
+  pub default_headers: Option<http::HeaderMap>,
+
   /// Whether page zooming by hotkeys is enabled
   ///
   /// ## Platform-specific
   ///
-  /// **macOS / Linux / Android / iOS**: Unsupported
+  /// - **macOS**: The hotkeys are `Cmd` with `=`, `-` and [`WebViewAttributes::zoom_reset_key`].
+  /// - **Linux / Android / iOS**: Unsupported
   pub zoom_hotkeys_enabled: bool,
 
   /// Whether load the provided html string to [`WebView`].
@@ -351,6 +474,43 @@ pub struct WebViewAttributes {
   /// so we prepend them to each HTML head. They are only implemented on custom protocol URLs.
   pub initialization_scripts: Vec<String>,
 
+  /// Initialization scripts injected in the main frame only, so the iframes of the page don't
+  /// see the globals they define, see [`WebViewBuilder::with_initialization_script_for_main_only`].
+  ///
+  /// They run after the [`WebViewAttributes::initialization_scripts`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The scripts are wrapped in a block run when `window === window.top`, so their
+  /// top-level `let`, `const` and `class` declarations are not globals.
+  pub main_frame_initialization_scripts: Vec<String>,
+
+  /// Javascript code injected in an isolated world of every frame when loading new pages, like
+  /// the [`WebViewAttributes::initialization_scripts`].
+  ///
+  /// The isolated world shares the DOM of the page but not its Javascript globals, so the scripts
+  /// of the page cannot tamper with or observe these scripts. `window.ipc.postMessage` is defined
+  /// in the isolated world.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Requires macOS 11+ and iOS 14+, the scripts are not injected on older versions.
+  /// - **Windows**: The scripts are injected with the DevTools protocol, `window.ipc` is not defined
+  /// in the isolated world.
+  /// - **Android**: Unsupported, the scripts are not injected.
+  pub isolated_initialization_scripts: Vec<String>,
+
+  /// Javascript code executed when the document of new pages is parsed, before their
+  /// subresources are loaded, added with [`WebViewBuilder::with_initialization_script_at`].
+  ///
+  /// The `bool` tells whether the script is only injected in the main frame.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: The scripts are wrapped in a `DOMContentLoaded` listener of the
+  /// [`WebViewAttributes::initialization_scripts`], so their top-level declarations are not globals.
+  pub document_end_scripts: Vec<(String, bool)>,
+
   /// A list of custom loading protocols with pairs of scheme uri string and a handling
   /// closure.
   ///
@@ -373,10 +533,98 @@ pub struct WebViewAttributes {
   /// - iOS: To get the path of your assets, you can call [`CFBundle::resources_path`](https://docs.rs/core-foundation/latest/core_foundation/bundle/struct.CFBundle.html#method.resources_path). So url like `wry://assets/index.html` could get the html file in assets directory.
   pub custom_protocols: Vec<(String, Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)>)>,
 
+  /// The security options of the custom protocols by name, see [`CustomProtocolOptions`].
+  pub custom_protocol_options: HashMap<String, CustomProtocolOptions>,
+
   /// The IPC handler to receive the message from Javascript on webview
   /// using `window.ipc.postMessage("insert_message_here")` to host Rust code.
   pub ipc_handler: Option<Box<dyn Fn(String)>>,
 
+  /// The IPC handler receiving the string messages posted by Javascript with
+  /// `window.ipc.postMessage` as a request, whose URI is the URL of the frame which posted the
+  /// message and whose `Origin` header is the origin of this URL, e.g. to reject the messages
+  /// of third-party iframes.
+  ///
+  /// The messages are delivered to [`WebViewAttributes::ipc_handler`] as well. The messages
+  /// consumed by the invoke, channel, command and binding handlers and the binary messages are
+  /// not delivered to it, use [`WebViewAttributes::ipc_allowed_origins`] to check the origin of
+  /// all the messages.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / Android**: The URL of the sender is unknown, the request has the `/` URI and no `Origin` header.
+  pub ipc_request_handler: Option<Box<dyn Fn(Request<String>)>>,
+
+  /// The handler of the `window.ipc.postMessageWithReply(message)` calls of Javascript, whose
+  /// returned promise is settled by the [`IpcReplier`], possibly after the handler returned.
+  ///
+  /// The [`IpcReplier`] must be used on the main thread.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Requires macOS 11+ and iOS 14+.
+  /// - **Windows / Linux / Android**: Unsupported, `window.ipc.postMessageWithReply` is undefined.
+  pub ipc_reply_handler: Option<Box<dyn Fn(String, IpcReplier)>>,
+
+  /// The IPC handler receiving both the string and the binary messages posted by Javascript
+  /// with `window.ipc.postMessage`, the binary messages being `ArrayBuffer`s, typed arrays or `DataView`s.
+  ///
+  /// Binary messages are only delivered to this handler, string messages are delivered to
+  /// [`WebViewAttributes::ipc_handler`] as well.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / Linux / iOS**: The bytes are transferred as base64 internally,
+  /// so [`WebViewAttributes::max_ipc_message_size_bytes`] applies to their encoded size.
+  /// - **Android**: Binary messages are unsupported, only string messages are delivered.
+  pub ipc_handler_raw: Option<Box<dyn Fn(IpcMessage)>>,
+
+  /// The handler of the `window.ipc.invoke(cmd, payload)` calls of Javascript, called with the
+  /// command and the JSON payload.
+  ///
+  /// The promise returned by `window.ipc.invoke` resolves with the returned value, or rejects
+  /// with an `Error` of the returned message. The calls are not delivered to the other IPC handlers.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / Linux / iOS**: Only the calls of the main frame are settled.
+  /// - **Android**: Unsupported, `window.ipc.invoke` is not defined.
+  pub ipc_invoke_handler: Option<
+    Box<dyn Fn(String, serde_json::Value) -> std::result::Result<serde_json::Value, String>>,
+  >,
+
+  /// The IPC handlers of the named channels, receiving the messages posted by Javascript
+  /// with `window.ipc.channel(name).postMessage(message)`.
+  ///
+  /// The messages of a channel without handler are dropped, and the channel messages are not
+  /// delivered to the other IPC handlers.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported, `window.ipc.channel` is not defined.
+  pub ipc_channels: Vec<(String, Box<dyn Fn(String)>)>,
+
+  /// The typed IPC commands registered with [`WebViewBuilder::with_ipc_command`], called with the
+  /// JSON payload of their `window.ipc.invoke` calls.
+  ///
+  /// They take precedence over [`WebViewAttributes::ipc_invoke_handler`] for their names.
+  #[cfg(feature = "serde-ipc")]
+  pub ipc_commands: Vec<(
+    String,
+    Box<dyn Fn(serde_json::Value) -> std::result::Result<serde_json::Value, String>>,
+  )>,
+
+  /// The Rust functions bound to Javascript, `window[name](...args)` calls the function `name`
+  /// with the JSON arguments and returns a promise settled like the `window.ipc.invoke` calls.
+  ///
+  /// ## Platform-specific
+  ///
+  /// See [`WebViewAttributes::ipc_invoke_handler`].
+  pub bindings: Vec<(
+    String,
+    Box<dyn Fn(Vec<serde_json::Value>) -> std::result::Result<serde_json::Value, String>>,
+  )>,
+
   /// A handler closure to process incoming [`FileDropEvent`] of the webview.
   ///
   /// # Blocking OS Default Behavior
@@ -460,6 +708,36 @@ pub struct WebViewAttributes {
   /// Set a handler closure to process the change of the webview's document title.
   pub document_title_changed_handler: Option<Box<dyn Fn(String)>>,
 
+  /// A closure called with the new URL of the webview when it changes, including the
+  /// same-document navigations of single-page applications, e.g. with `history.pushState`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: The URL changes are reported by an injected script.
+  pub on_url_changed: Option<Box<dyn Fn(Url)>>,
+
+  /// A closure called with the new URL of the webview after a same-document navigation,
+  /// e.g. a client-side router calling `history.pushState`, which never reaches
+  /// [`WebViewAttributes::navigation_handler`].
+  ///
+  /// `history.pushState`, `history.replaceState`, the back and forward navigations between
+  /// entries of the same document and the fragment changes are reported.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Uses the `SourceChanged` event of the same-document navigations.
+  /// - **macOS / iOS / Linux / Android**: The navigations are reported by an injected script.
+  pub on_history_state_changed: Option<Box<dyn Fn(Url)>>,
+
+  /// A closure called with the estimated progress of the page load, from `0.0` to `1.0`,
+  /// e.g. to draw a progress bar.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Estimated from the navigation steps, as WebView2 doesn't report the progress.
+  /// - **Android**: Estimated from the `document.readyState` of the page by an injected script.
+  pub on_load_progress: Option<Box<dyn Fn(f64)>>,
+
   /// Run the WebView with incognito mode. Note that WebContext will be ingored if incognito is
   /// enabled.
   ///
@@ -474,6 +752,55 @@ pub struct WebViewAttributes {
   /// Set a handler closure to process page load events.
   pub on_page_load_handler: Option<Box<dyn Fn(PageLoadEvent, String)>>,
 
+  /// A closure called when a main frame navigation fails, with the reason of the failure. The
+  /// failed navigations of the iframes are not reported.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported, the handler is never called.
+  pub on_load_error: Option<Box<dyn Fn(LoadError)>>,
+
+  /// A closure called with the original and the new URL when a main frame navigation is
+  /// redirected by the server, e.g. to detect the end of an OAuth flow without blocking the navigation.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported, the handler is never called.
+  pub on_redirect: Option<Box<dyn Fn(Url, Url)>>,
+
+  /// A closure called when a key is pressed in the webview, before the page or the webview
+  /// shortcuts handle it, returning `true` to consume the key, e.g. to handle `Ctrl+W` or `F5`
+  /// in the host instead.
+  ///
+  /// Keys without a [`KeyCode`] are not reported.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Only the keys pressed with `Ctrl` or `Alt` and the keys without a character,
+  /// e.g. `F5` or `Escape`, are reported, like the `AcceleratorKeyPressed` event of WebView2.
+  /// - **macOS**: The `Cmd` key is reported as [`AcceleratorKeyEvent::meta`].
+  /// - **Android / iOS**: Unsupported, the handler is never called.
+  pub on_accelerator_key: Option<Box<dyn Fn(AcceleratorKeyEvent) -> bool>>,
+
+  /// A closure called when `Tab`, or `Shift+Tab` when the argument is `true`, moves the focus
+  /// out of the last, or the first, focusable element of the page, e.g. to focus the next native control.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / Linux / Android / iOS**: Unsupported, the handler is never called.
+  pub on_tab_out: Option<Box<dyn Fn(bool)>>,
+
+  /// A closure called when a frame reaches a finer-grained stage of its loading than
+  /// [`PageLoadEvent`], e.g. to time script injection or hide a skeleton UI.
+  ///
+  /// The stages are reported by an injected script.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: The iframes are reported too.
+  /// - **Windows / Linux / Android**: Only the main frame is reported.
+  pub on_page_lifecycle: Option<Box<dyn Fn(PageLifecycleEvent)>>,
+
   /// Set a proxy configuration for the webview. Supports HTTP CONNECT and SOCKSv5 proxies
   ///
   /// - **macOS**: Requires macOS 14.0+ and the `mac-proxy` feature flag to be enabled.
@@ -491,6 +818,385 @@ pub struct WebViewAttributes {
   /// This is only effective if the webview was created by [`WebView::new_as_child`] or [`WebViewBuilder::new_as_child`]
   /// or on Linux, if was created by [`WebViewExtUnix::new_gtk`] or [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
   pub bounds: Option<Rect>,
+
+  /// Whether the webview created as a child should follow the size of its parent window,
+  /// filling its whole client area and ignoring [`Self::bounds`]. Defaults to `false`.
+  ///
+  /// This is only effective if the webview was created by [`WebView::new_as_child`] or [`WebViewBuilder::new_as_child`].
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Linux / Android / iOS:** Unsupported.
+  pub auto_resize: bool,
+
+  /// A closure to mutate the headers of every outgoing request made by the webview.
+  ///
+  /// The closure receives the request headers and its URL. Unlike a full request interceptor it
+  /// can't change the URL, method or body, which keeps it cheap enough to run for every request,
+  /// e.g. to add a global `Authorization` or `X-App-Version` header.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS / Linux / Android:** Only applied to requests for custom protocols
+  /// registered with [`WebViewBuilder::with_custom_protocol`] as the platform does not allow
+  /// intercepting other requests.
+  pub request_headers_interceptor: Option<Box<dyn Fn(&mut http::HeaderMap, &Url)>>,
+
+  /// A closure to rewrite HTML responses before they are handed to the webview.
+  ///
+  /// It receives the complete response and returns the response to deliver instead, which
+  /// allows injecting tags or stripping content from the served documents. Only responses with a
+  /// `Content-Type: text/html` header are passed to the closure.
+  ///
+  /// # Warning
+  ///
+  /// The whole response body is buffered in memory before the closure runs, so this can slow
+  /// down the page load noticeably for large documents.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: Also applied to the HTML documents loaded from the network, which are paused
+  /// with the DevTools protocol `Fetch` domain until the closure returns.
+  /// - **macOS / Linux / iOS / Android**: Only applied to responses of custom protocols registered
+  /// with [`WebViewBuilder::with_custom_protocol`] or [`WebViewBuilder::with_asynchronous_custom_protocol`].
+  pub response_transform: Option<Box<dyn Fn(Response<Vec<u8>>) -> Response<Vec<u8>> + Send + Sync>>,
+
+  /// Limit the rate of IPC messages delivered to [`WebViewAttributes::ipc_handler`].
+  ///
+  /// `Some((count, window))` allows at most `count` messages within any sliding `window`
+  /// of time, messages exceeding that rate are dropped. This protects the event loop from
+  /// pages that spam `window.ipc.postMessage`.
+  ///
+  /// The messages of the page count once reassembled, whether they are posted with
+  /// `window.ipc.postMessage`, `window.ipc.invoke` or a channel. The internal messages of wry's
+  /// own features, e.g. [`WebViewAttributes::on_console_message`] or [`WebViewAttributes::on_scroll`],
+  /// are never throttled.
+  pub throttle_ipc_rate: Option<(u32, Duration)>,
+
+  /// A closure called when IPC messages are dropped because of [`WebViewAttributes::throttle_ipc_rate`],
+  /// [`WebViewAttributes::max_ipc_message_size_bytes`] or because a chunk of a large message was lost.
+  ///
+  /// It receives the reason the messages were dropped, see [`IpcThrottleReason`]. The number of
+  /// messages dropped by the rate limit since the last delivered one is the `dropped` count of
+  /// [`IpcThrottleReason::RateExceeded`], a reason is passed instead of a bare count so the
+  /// oversized and incomplete messages are reported too.
+  pub on_ipc_throttled: Option<Box<dyn Fn(IpcThrottleReason)>>,
+
+  /// The origins allowed to send IPC messages, e.g. `https://example.com` or `wry://localhost`,
+  /// every origin is allowed when it is `None`.
+  ///
+  /// Every message is filtered: the messages of the other origins are not delivered to the IPC
+  /// handlers, channels, commands and bindings, nor to the handlers of the webview events
+  /// reported by an injected script, e.g. [`WebViewAttributes::on_console_message`], and the
+  /// [`WebViewAttributes::ipc_reply_handler`] calls are rejected. They are all reported to
+  /// [`WebViewAttributes::on_ipc_denied`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / Android**: Unsupported, the origin of the messages is unknown so
+  /// [`WebViewBuilder::build`] returns [`Error::NotSupported`] when it is set.
+  pub ipc_allowed_origins: Option<Vec<String>>,
+
+  /// A closure called with the origin, `None` if it is unknown, and the message of the IPC
+  /// messages denied by [`WebViewAttributes::ipc_allowed_origins`].
+  pub on_ipc_denied: Option<Box<dyn Fn(Option<String>, String)>>,
+
+  /// The zoom factor increment applied by the zoom in and zoom out hotkeys, `0.1` by default.
+  /// It must be positive, [`WebViewBuilder::build`] returns [`Error::InvalidZoomStep`] otherwise.
+  ///
+  /// Only used when [`WebViewAttributes::zoom_hotkeys_enabled`] is `true`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// **Linux / Android / iOS**: Unsupported
+  pub zoom_hotkeys_custom_step: f64,
+
+  /// The key that, pressed together with `Ctrl`, resets the zoom factor to
+  /// [`WebViewAttributes::initial_zoom`]. Defaults to [`KeyCode::Digit0`] (`Ctrl+0`).
+  ///
+  /// Only used when [`WebViewAttributes::zoom_hotkeys_enabled`] is `true`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: The key is pressed together with `Cmd` instead.
+  /// - **Linux / Android / iOS**: Unsupported
+  pub zoom_reset_key: Option<KeyCode>,
+
+  /// The zoom factor of the webview when it is created, `1.0` by default, see [`WebView::zoom`].
+  ///
+  /// The zoom reset hotkey goes back to this zoom factor, see [`WebViewAttributes::zoom_reset_key`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported
+  pub initial_zoom: f64,
+
+  /// A closure called with the new zoom factor when the page zoom changes, either
+  /// programmatically with [`WebView::zoom`] or by the user with hotkeys or gestures.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Page zoom changes are observed natively, pinch zoom is reported
+  /// with the `visualViewport` scale of the page.
+  /// - **Android**: Only pinch zoom is reported, with the `visualViewport` scale of the page.
+  pub on_page_zoom_change: Option<Box<dyn Fn(f64)>>,
+
+  /// The minimum and maximum zoom factors, see [`WebView::set_zoom_limits`].
+  pub zoom_limits: Option<(f64, f64)>,
+
+  /// Whether zooming only scales the text of the pages instead of the whole layout, `false` by default.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Uses the `zoom-text-only` setting of WebKitGTK.
+  /// - **Windows / macOS / iOS / Android**: Unsupported, WebView2 and WKWebView have no text
+  /// zoom API, zooming always scales the whole layout.
+  pub text_zoom_only: bool,
+
+  /// Whether background colors and images are printed, `false` by default.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Requires macOS 13.3+ and iOS 16.4+.
+  /// - **Windows / Android**: Injects a `print-color-adjust: exact` print stylesheet in the pages.
+  pub print_background: bool,
+
+  /// The maximum size in bytes of the IPC messages delivered to [`WebViewAttributes::ipc_handler`].
+  ///
+  /// `window.ipc.postMessage` throws a `RangeError` for larger messages, and the larger messages
+  /// reaching Rust anyway are dropped, see [`WebViewAttributes::on_ipc_throttled`] to get notified
+  /// about them. `None` (the default) means unlimited, a limit of 1 MB is recommended for
+  /// production use.
+  ///
+  /// Regardless of this limit, the large messages are transparently split in chunks by
+  /// `window.ipc.postMessage` and reassembled before being delivered.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: The messages are not split in chunks, and the larger messages are only
+  /// dropped in Rust, `window.ipc.postMessage` doesn't throw.
+  pub max_ipc_message_size_bytes: Option<usize>,
+
+  /// A closure called once the [`WebView`] is created, before [`WebViewBuilder::build`] returns.
+  ///
+  /// The web engine is fully initialized at this point, and the initial `url` or `html` is
+  /// only requested, it can't start loading before the event loop runs again. This makes it
+  /// a good place to run setup code that must happen before the page loads.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: The Android `WebView` is created asynchronously on the main looper, so it
+  /// doesn't exist yet when the closure is called. The methods called in the closure are queued
+  /// and run after the webview is created and the initial `url` or `html` is requested, the
+  /// methods returning a value, like [`WebView::url`], wait for the main looper and must not be
+  /// called in the closure.
+  pub on_webview_ready: Option<Box<dyn FnOnce(&WebView)>>,
+
+  /// A closure called once the [`WebView`] is torn down by [`WebView::close`].
+  ///
+  /// It is not called when the [`WebView`] is simply dropped.
+  pub on_closed: Option<Box<dyn FnOnce()>>,
+
+  /// A closure asking the user whether to leave a page with a `beforeunload` handler, e.g. with
+  /// unsaved form data, returning `true` to leave it.
+  ///
+  /// Called by [`WebView::close_with_confirmation`].
+  pub on_before_unload: Option<Box<dyn Fn() -> bool>>,
+
+  /// A closure deciding which areas of the webview act as a drag handle for moving the window,
+  /// typically used with frameless windows.
+  ///
+  /// It is called with the position of the primary mouse button presses and returns `true`
+  /// to start moving the window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Linux**: The presses are reported by an injected script, so the page
+  /// receives them as well.
+  /// - **Android / iOS**: Unsupported.
+  pub drag_region_handler: Option<Box<dyn Fn(DragRegionQuery) -> bool>>,
+
+  /// Whether the elements of the page with a `data-wry-click-through` attribute don't receive
+  /// the mouse input, letting it pass to the native views under the webview, e.g. for transparent
+  /// overlays where only a part of the page is interactive. `false` by default.
+  ///
+  /// The areas of the elements are tracked by an injected script and replace the regions set by
+  /// [`WebView::set_click_through_regions`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// See [`WebView::set_click_through_regions`].
+  pub click_through_elements: bool,
+
+  /// A CSS scale factor applied to the content of the pages, with the CSS `zoom` property
+  /// on the root element.
+  ///
+  /// Unlike [`WebView::zoom`], this doesn't change the `devicePixelRatio` seen by the page,
+  /// which is useful to scale content designed for a given size independently of the display DPI.
+  pub content_scale: Option<f64>,
+
+  /// A closure called when the audio playback state of the page changes.
+  ///
+  /// The state is tracked by an injected script listening to the `<audio>` and `<video>` elements,
+  /// the Web Audio API contexts and the `MediaSession` API of the main frame.
+  pub on_web_audio_state_change: Option<Box<dyn Fn(WebAudioState)>>,
+
+  /// A closure called when no user interaction (mouse, keyboard or touch) happened in the main frame
+  /// for [`WebViewAttributes::idle_timeout`], with the time elapsed since the last interaction.
+  ///
+  /// The handler is called once per idle period, the next interaction restarts the timer.
+  /// See also [`WebView::reset_idle_timer`].
+  pub on_idle: Option<Box<dyn Fn(Duration)>>,
+
+  /// The inactivity duration after which [`WebViewAttributes::on_idle`] is called.
+  ///
+  /// Defaults to 5 minutes.
+  pub idle_timeout: Duration,
+
+  /// A closure called when the audio focus of the application changes, e.g. when the audio
+  /// session is interrupted by a phone call or Siri.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS**: Uses the `AVAudioSessionInterruptionNotification` of the shared audio session.
+  /// - **macOS / Windows / Linux / Android**: Unsupported, the handler is never called.
+  pub on_audio_focus_change: Option<Box<dyn Fn(AudioFocusEvent)>>,
+
+  /// A closure called when a `fetch()` or `XMLHttpRequest` of the page fails with a network error,
+  /// e.g. a CORS error, a timeout or a DNS failure.
+  ///
+  /// The requests are intercepted by an injected script, the errors are still thrown to the page.
+  /// Aborted requests are not reported.
+  pub on_fetch_error: Option<Box<dyn Fn(FetchError)>>,
+
+  /// A closure called with the uncaught exceptions and the unhandled promise rejections of the
+  /// page and its frames, e.g. to report them to a crash dashboard.
+  ///
+  /// The errors are observed by an injected script, they are still reported to the console of
+  /// the page. The errors thrown before the injected script runs are not reported.
+  pub on_page_error: Option<Box<dyn Fn(PageError)>>,
+
+  /// A closure called with the level, the text, the source URL and the line of the messages
+  /// logged to the console of the page, e.g. to forward them to `log` or `tracing`.
+  ///
+  /// The source URL and the line are `None` when they are unknown.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The messages are observed with the `Runtime.consoleAPICalled` event of the
+  /// DevTools protocol, objects are reported with their description instead of their JSON.
+  /// - **macOS / Linux / iOS / Android**: The `console` methods are wrapped by an injected script,
+  /// the messages logged by the browser itself, e.g. the CSP violations, are not reported.
+  pub on_console_message:
+    Option<Box<dyn Fn(ConsoleMessageLevel, String, Option<String>, Option<u32>)>>,
+
+  /// A closure called with the favicon of the page, encoded as PNG, every time it changes.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Uses the `FaviconChanged` event of WebView2.
+  /// - **Linux / macOS / iOS / Android**: The icon `<link>` of the page, or `/favicon.ico`,
+  /// is fetched and re-encoded by an injected script. Icons that can't be fetched by the page,
+  /// e.g. because of CORS, are not reported.
+  pub on_favicon_changed: Option<Box<dyn Fn(Vec<u8>)>>,
+
+  /// A closure called with the new scroll offset of the page, in CSS pixels, when it is scrolled.
+  ///
+  /// The calls are throttled to one per animation frame.
+  pub on_scroll: Option<Box<dyn Fn(f64, f64)>>,
+
+  /// A closure called with the selected text of the page when the selection changes,
+  /// `None` when the selection is cleared.
+  ///
+  /// The calls are throttled to one per animation frame. The selections in iframes are not reported.
+  pub on_selection_changed: Option<Box<dyn Fn(Option<String>)>>,
+
+  /// A closure called when the page calls `window.print()`, returning whether the print dialog
+  /// is shown, suppressed or replaced by a silent export to PDF.
+  ///
+  /// To show a custom print UI instead, e.g. in kiosk deployments, return [`PrintDialogDecision::Deny`]
+  /// and show it from the handler, [`WebView::print`] can still show the native dialog afterwards.
+  ///
+  /// [`WebView::print`] is not intercepted.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS**: `window.print()` is overridden by an initialization script.
+  /// - **iOS**: [`PrintDialogDecision::Allow`] does nothing, WKWebView has no print dialog.
+  /// - **Android**: Unsupported, the handler is never called.
+  pub on_print_dialog: Option<Box<dyn Fn(PrintDialogRequest) -> PrintDialogDecision>>,
+
+  /// A closure called when the audio of the webview is muted or unmuted, see [`WebView::set_audio_muted`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS / Android**: Only called by [`WebView::set_audio_muted`].
+  pub on_audio_muted_change: Option<Box<dyn Fn(bool)>>,
+
+  /// JSON values set as `window` properties before the page and the initialization scripts run,
+  /// e.g. to pass configuration constants to the page.
+  ///
+  /// Each `(name, value)` entry sets `window[name]` to a copy of `value`.
+  pub inject_global_object: Vec<(String, serde_json::Value)>,
+
+  /// Restrict the WebRTC connections of the page to relay (TURN) servers so they do not reveal the
+  /// local and public IP addresses, e.g. when a proxy is used.
+  ///
+  /// This prevents direct peer-to-peer WebRTC connections, the connections fail without a TURN server.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Also passes `--force-webrtc-ip-handling-policy=disable_non_proxied_udp` to the browser
+  /// unless `WebViewBuilderExtWindows::with_additional_browser_args` is used.
+  pub enable_webrtc_ip_protection: bool,
+
+  /// Whether [`WebView::get_webrtc_stats`] can collect the stats of the WebRTC peer connections,
+  /// by tracking the `RTCPeerConnection`s created by the page. Disabled by default.
+  pub enable_webrtc_stats: bool,
+
+  /// Whether the timers and the animation frames of the page are throttled while the webview is
+  /// hidden or occluded, `true` by default.
+  ///
+  /// Disable it for webviews kept alive in the background that must stay up to date, e.g. dashboards
+  /// or preview panes.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Passes `--disable-background-timer-throttling --disable-renderer-backgrounding --disable-backgrounding-occluded-windows`
+  /// to the browser unless `WebViewBuilderExtWindows::with_additional_browser_args` is used.
+  /// - **macOS / iOS**: Requires macOS 14 / iOS 17 or higher.
+  /// - **Linux / Android**: Unsupported.
+  pub background_throttling: bool,
+
+  /// Hosts reached directly instead of through the [`WebViewAttributes::proxy_config`] proxy,
+  /// e.g. `["localhost", "*.internal.corp.com", "10.0.0.0/8"]`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Passed as `--proxy-bypass-list` to the browser, unless
+  /// `WebViewBuilderExtWindows::with_additional_browser_args` is used.
+  /// - **macOS**: IP ranges are not supported, the hosts are excluded domains of the proxy configuration.
+  /// - **Android / iOS**: Unsupported.
+  pub http_proxy_exclude_hosts: Vec<String>,
+
+  /// A closure called when the TLS connection of a navigation fails, e.g. because of an invalid
+  /// certificate, returning whether the navigation is blocked or continued anyway.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 101.0.1210.39 or higher.
+  /// - **macOS / iOS**: Only the server trust failures are reported.
+  /// - **Android**: Unsupported, the handler is never called.
+  pub on_https_error: Option<Box<dyn Fn(HttpsError) -> HttpsErrorDecision>>,
+
+  /// A closure called when the page starts or stops capturing the camera, the microphone or the screen,
+  /// e.g. to show a capture indicator.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Requires macOS 12+ and iOS 15+, the screen capture is not reported.
+  /// - **Windows / Android**: The `getUserMedia` and `getDisplayMedia` streams are tracked by an injected script.
+  pub on_media_capture_state_change: Option<Box<dyn Fn(MediaCaptureState)>>,
 }
 
 impl Default for WebViewAttributes {
@@ -502,10 +1208,23 @@ impl Default for WebViewAttributes {
       background_color: None,
       url: None,
       headers: None,
+      default_headers: None,
       html: None,
       initialization_scripts: vec![],
+      main_frame_initialization_scripts: vec![],
+      isolated_initialization_scripts: vec![],
+      document_end_scripts: vec![],
       custom_protocols: vec![],
+      custom_protocol_options: HashMap::new(),
       ipc_handler: None,
+      ipc_request_handler: None,
+      ipc_reply_handler: None,
+      ipc_handler_raw: None,
+      ipc_channels: vec![],
+      #[cfg(feature = "serde-ipc")]
+      ipc_commands: vec![],
+      ipc_invoke_handler: None,
+      bindings: vec![],
       file_drop_handler: None,
       navigation_handler: None,
       download_started_handler: None,
@@ -520,9 +1239,17 @@ impl Default for WebViewAttributes {
       accept_first_mouse: false,
       back_forward_navigation_gestures: false,
       document_title_changed_handler: None,
+      on_url_changed: None,
+      on_history_state_changed: None,
+      on_load_progress: None,
       incognito: false,
       autoplay: true,
       on_page_load_handler: None,
+      on_load_error: None,
+      on_redirect: None,
+      on_accelerator_key: None,
+      on_tab_out: None,
+      on_page_lifecycle: None,
       proxy_config: None,
       focused: true,
       bounds: Some(Rect {
@@ -531,6 +1258,46 @@ impl Default for WebViewAttributes {
         width: 200,
         height: 200,
       }),
+      auto_resize: false,
+      request_headers_interceptor: None,
+      response_transform: None,
+      throttle_ipc_rate: None,
+      on_ipc_throttled: None,
+      ipc_allowed_origins: None,
+      on_ipc_denied: None,
+      zoom_hotkeys_custom_step: 0.1,
+      zoom_reset_key: Some(KeyCode::Digit0),
+      initial_zoom: 1.0,
+      on_page_zoom_change: None,
+      zoom_limits: None,
+      text_zoom_only: false,
+      print_background: false,
+      max_ipc_message_size_bytes: None,
+      on_webview_ready: None,
+      on_closed: None,
+      on_before_unload: None,
+      drag_region_handler: None,
+      click_through_elements: false,
+      content_scale: None,
+      on_web_audio_state_change: None,
+      on_idle: None,
+      idle_timeout: Duration::from_secs(5 * 60),
+      on_audio_focus_change: None,
+      on_fetch_error: None,
+      on_console_message: None,
+      on_page_error: None,
+      on_favicon_changed: None,
+      on_scroll: None,
+      on_selection_changed: None,
+      on_print_dialog: None,
+      on_audio_muted_change: None,
+      inject_global_object: vec![],
+      enable_webrtc_ip_protection: false,
+      enable_webrtc_stats: false,
+      background_throttling: true,
+      http_proxy_exclude_hosts: vec![],
+      on_https_error: None,
+      on_media_capture_state_change: None,
     }
   }
 }
@@ -541,6 +1308,10 @@ impl Default for WebViewAttributes {
 /// scripts for those who prefer to control fine grained window creation and event handling.
 /// [`WebViewBuilder`] provides ability to setup initialization before web engine starts.
 pub struct WebViewBuilder<'a> {
+  /// The attributes of the webview to build.
+  ///
+  /// Every attribute has a chainable `with_*` method on the builder, prefer them over
+  /// setting the fields directly.
   pub attrs: WebViewAttributes,
   as_child: bool,
   window: Option<&'a dyn HasWindowHandle>,
@@ -681,8 +1452,62 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
-  /// Register custom loading protocols with pairs of scheme uri string and a handling
-  /// closure.
+  /// Same as [`WebViewBuilder::with_initialization_script`], but the script is only injected
+  /// in the main frame.
+  ///
+  /// See [`WebViewAttributes::main_frame_initialization_scripts`] for more details.
+  pub fn with_initialization_script_for_main_only(mut self, js: &str) -> Self {
+    if !js.is_empty() {
+      self
+        .attrs
+        .main_frame_initialization_scripts
+        .push(js.to_string());
+    }
+    self
+  }
+
+  /// Same as [`WebViewBuilder::with_initialization_script`], but the script is run at
+  /// `injection_time`, and only in the main frame when `main_only` is `true`.
+  ///
+  /// See [`WebViewAttributes::document_end_scripts`] for the scripts run at [`InjectionTime::DocumentEnd`].
+  pub fn with_initialization_script_at(
+    mut self,
+    js: &str,
+    main_only: bool,
+    injection_time: InjectionTime,
+  ) -> Self {
+    match injection_time {
+      InjectionTime::DocumentStart if main_only => {
+        self.with_initialization_script_for_main_only(js)
+      }
+      InjectionTime::DocumentStart => self.with_initialization_script(js),
+      InjectionTime::DocumentEnd => {
+        if !js.is_empty() {
+          self
+            .attrs
+            .document_end_scripts
+            .push((js.to_string(), main_only));
+        }
+        self
+      }
+    }
+  }
+
+  /// Inject the initialization script `js` in an isolated world, hidden from the scripts of the page.
+  ///
+  /// See [`WebViewAttributes::isolated_initialization_scripts`] for more details.
+  pub fn with_isolated_initialization_script(mut self, js: &str) -> Self {
+    if !js.is_empty() {
+      self
+        .attrs
+        .isolated_initialization_scripts
+        .push(js.to_string());
+    }
+    self
+  }
+
+  /// Register custom loading protocols with pairs of scheme uri string and a handling
+  /// closure.
   ///
   /// The closure takes a [Request] and returns a [Response]
   ///
@@ -755,6 +1580,131 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Set the security options of the custom protocol `name`, whether its pages are secure contexts
+  /// and the origins allowed to `fetch()` it, see [`CustomProtocolOptions`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use wry::{CustomProtocolOptions, WebViewBuilder, raw_window_handle};
+  ///
+  /// # use raw_window_handle::{HasWindowHandle, WindowHandle, RawWindowHandle, Win32WindowHandle, HandleError};
+  /// # struct T;
+  /// # impl HasWindowHandle for T {
+  /// #   fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+  /// #     let handle = RawWindowHandle::Win32(Win32WindowHandle::new(std::num::NonZeroIsize::new(0).unwrap()));
+  /// #     unsafe { Ok(WindowHandle::borrow_raw(handle)) }
+  /// #   }
+  /// # }
+  /// # let window = T;
+  /// let mut options = CustomProtocolOptions::default();
+  /// options.allowed_origins.push("https://example.com".into());
+  ///
+  /// WebViewBuilder::new(&window)
+  ///   .with_asset_dir("assets".into(), "dist")
+  ///   .with_custom_protocol_options("assets".into(), options);
+  /// ```
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: The protocol is registered as a CORS enabled scheme when it has allowed origins.
+  #[cfg(feature = "protocol")]
+  pub fn with_custom_protocol_options(
+    mut self,
+    name: String,
+    options: CustomProtocolOptions,
+  ) -> Self {
+    self.attrs.custom_protocol_options.insert(name, options);
+    self
+  }
+
+  /// Register a custom protocol serving the files of the directory `dir`, e.g. `wry://localhost/index.html`
+  /// serves `dir/index.html`.
+  ///
+  /// The directories serve their `index.html` file. The responses have the MIME type guessed from
+  /// the file extension and an `ETag`, the `Range` requests are honored. The requests of paths
+  /// outside of `dir`, including through symbolic links, get a `404 Not Found` response.
+  ///
+  /// See [`Self::with_custom_protocol`] for the origins of the pages served by custom protocols.
+  #[cfg(feature = "protocol")]
+  pub fn with_asset_dir(self, name: String, dir: impl Into<PathBuf>) -> Self {
+    self.with_asynchronous_custom_protocol(name, asset_dir::asset_dir_handler(dir.into()))
+  }
+
+  /// Register a custom protocol serving the assets of `provider`, e.g. `wry://localhost/index.html`
+  /// serves its `index.html` asset.
+  ///
+  /// The directories serve their `index.html` asset, and the missing paths without file extension
+  /// serve the root `index.html` for the routing of single page applications. The responses have
+  /// the MIME type guessed from the file extension, the `Range` requests are honored.
+  ///
+  /// See [`Self::with_custom_protocol`] for the origins of the pages served by custom protocols.
+  #[cfg(feature = "protocol")]
+  pub fn with_asset_provider<P: AssetProvider + 'static>(self, name: String, provider: P) -> Self {
+    self.with_asynchronous_custom_protocol(name, asset_provider::asset_provider_handler(provider))
+  }
+
+  /// Register a custom protocol whose requests are routed by path to the handlers of `router`.
+  ///
+  /// See [`ProtocolRouter`] for the route patterns.
+  #[cfg(feature = "protocol")]
+  pub fn with_protocol_router(self, name: String, router: ProtocolRouter) -> Self {
+    self.with_asynchronous_custom_protocol(name, move |request, responder| {
+      router.handle(request, responder)
+    })
+  }
+
+  /// Register a custom protocol serving the assets embedded with [`rust_embed::RustEmbed`].
+  ///
+  /// See [`Self::with_asset_provider`] for more details.
+  ///
+  /// # Examples
+  ///
+  /// ```ignore
+  /// #[derive(rust_embed::RustEmbed)]
+  /// #[folder = "dist/"]
+  /// struct Frontend;
+  ///
+  /// WebViewBuilder::new(&window)
+  ///   .with_embedded_assets::<Frontend>("app".into())
+  ///   .with_url("app://localhost/");
+  /// ```
+  #[cfg(feature = "rust-embed")]
+  pub fn with_embedded_assets<T: rust_embed::RustEmbed + 'static>(self, name: String) -> Self {
+    self.with_asset_provider(name, std::marker::PhantomData::<T>)
+  }
+
+  /// Same as [`Self::with_custom_protocol`] but with an async handler, whose futures are run by
+  /// the `spawn` closure on any executor.
+  ///
+  /// # Examples
+  ///
+  /// ```ignore
+  /// WebViewBuilder::new(&window).with_async_custom_protocol(
+  ///   "wry".into(),
+  ///   |future| {
+  ///     tokio::spawn(future);
+  ///   },
+  ///   |request| async move {
+  ///     let content = tokio::fs::read(request.uri().path()).await.unwrap_or_default();
+  ///     http::Response::builder().body(content).unwrap()
+  ///   },
+  /// );
+  /// ```
+  #[cfg(all(feature = "protocol", feature = "async"))]
+  pub fn with_async_custom_protocol<S, F, Fut, T>(self, name: String, spawn: S, handler: F) -> Self
+  where
+    S: Fn(std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>) + 'static,
+    F: Fn(Request<Vec<u8>>) -> Fut + 'static,
+    Fut: std::future::Future<Output = Response<T>> + Send + 'static,
+    T: Into<Cow<'static, [u8]>>,
+  {
+    self.with_asynchronous_custom_protocol(name, move |request, responder| {
+      let response = handler(request);
+      spawn(Box::pin(async move { responder.respond(response.await) }));
+    })
+  }
+
   /// Set the IPC handler to receive the message from Javascript on webview
   /// using `window.ipc.postMessage("insert_message_here")` to host Rust code.
   pub fn with_ipc_handler<F>(mut self, handler: F) -> Self
@@ -765,6 +1715,113 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Set the IPC handler receiving the string messages posted by Javascript with
+  /// `window.ipc.postMessage` as a request carrying the URL and the origin of their sender.
+  ///
+  /// See [`WebViewAttributes::ipc_request_handler`] for more details.
+  pub fn with_ipc_request_handler<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(Request<String>) + 'static,
+  {
+    self.attrs.ipc_request_handler = Some(Box::new(handler));
+    self
+  }
+
+  /// Set the handler of the `window.ipc.postMessageWithReply(message)` calls of Javascript,
+  /// replying to each message with its [`IpcReplier`].
+  ///
+  /// See [`WebViewAttributes::ipc_reply_handler`] for more details.
+  pub fn with_ipc_reply_handler<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(String, IpcReplier) + 'static,
+  {
+    self.attrs.ipc_reply_handler = Some(Box::new(handler));
+    self
+  }
+
+  /// Set the IPC handler receiving both the string and the binary messages posted by Javascript
+  /// with `window.ipc.postMessage`.
+  ///
+  /// See [`WebViewAttributes::ipc_handler_raw`] for more details.
+  pub fn with_ipc_handler_raw<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(IpcMessage) + 'static,
+  {
+    self.attrs.ipc_handler_raw = Some(Box::new(handler));
+    self
+  }
+
+  /// Register an IPC handler for the messages posted by Javascript to the channel `name`
+  /// with `window.ipc.channel(name).postMessage(message)`.
+  ///
+  /// See [`WebViewAttributes::ipc_channels`] for more details.
+  pub fn with_ipc_channel<F>(mut self, name: impl Into<String>, handler: F) -> Self
+  where
+    F: Fn(String) + 'static,
+  {
+    self
+      .attrs
+      .ipc_channels
+      .push((name.into(), Box::new(handler)));
+    self
+  }
+
+  /// Register a typed IPC command `name`, called by Javascript with `window.ipcCommands[name](payload)`
+  /// or `window.ipc.invoke(name, payload)`.
+  ///
+  /// The payload is deserialized to `T` and the returned value is serialized to resolve the promise
+  /// of the call, an error or an invalid payload rejects it with an `Error` of the message.
+  ///
+  /// ## Platform-specific
+  ///
+  /// See [`WebViewAttributes::ipc_invoke_handler`].
+  #[cfg(feature = "serde-ipc")]
+  pub fn with_ipc_command<T, R, E, F>(mut self, name: impl Into<String>, handler: F) -> Self
+  where
+    T: serde::de::DeserializeOwned,
+    R: serde::Serialize,
+    E: std::fmt::Display,
+    F: Fn(T) -> std::result::Result<R, E> + 'static,
+  {
+    let name = name.into();
+    let command = name.clone();
+    self.attrs.ipc_commands.push((
+      name,
+      Box::new(move |payload| {
+        let payload = serde_json::from_value(payload)
+          .map_err(|e| format!("Invalid payload of the `{command}` command: {e}"))?;
+        let value = handler(payload).map_err(|e| e.to_string())?;
+        serde_json::to_value(value).map_err(|e| e.to_string())
+      }),
+    ));
+    self
+  }
+
+  /// Set the handler of the `window.ipc.invoke(cmd, payload)` calls of Javascript, its returned value
+  /// resolves the promise returned by `window.ipc.invoke` and its error rejects it.
+  ///
+  /// See [`WebViewAttributes::ipc_invoke_handler`] for more details.
+  pub fn with_ipc_invoke_handler<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(String, serde_json::Value) -> std::result::Result<serde_json::Value, String> + 'static,
+  {
+    self.attrs.ipc_invoke_handler = Some(Box::new(handler));
+    self
+  }
+
+  /// Bind the Rust function `handler` to the Javascript function `window[name]`, whose arguments
+  /// are passed to `handler` and whose promise resolves with the returned value or rejects with
+  /// an `Error` of the returned message.
+  ///
+  /// See [`WebViewAttributes::bindings`] for more details.
+  pub fn with_binding<F>(mut self, name: impl Into<String>, handler: F) -> Self
+  where
+    F: Fn(Vec<serde_json::Value>) -> std::result::Result<serde_json::Value, String> + 'static,
+  {
+    self.attrs.bindings.push((name.into(), Box::new(handler)));
+    self
+  }
+
   /// Set a handler closure to process incoming [`FileDropEvent`] of the webview.
   ///
   /// # Blocking OS Default Behavior
@@ -789,6 +1846,14 @@ impl<'a> WebViewBuilder<'a> {
     Ok(self)
   }
 
+  /// Set headers added to every navigation of the main frame, not only to the initial `url`.
+  ///
+  /// See [`WebViewAttributes::default_headers`] for more details.
+  pub fn with_default_headers(mut self, headers: http::HeaderMap) -> Self {
+    self.attrs.default_headers = Some(headers);
+    self
+  }
+
   /// Load the provided URL when the builder calling [`WebViewBuilder::build`] to create the [`WebView`].
   /// The provided URL must be valid.
   pub fn with_url(mut self, url: &str) -> Result<Self> {
@@ -844,7 +1909,8 @@ impl<'a> WebViewBuilder<'a> {
   ///
   /// ## Platform-specific
   ///
-  /// **macOS / Linux / Android / iOS**: Unsupported
+  /// - **macOS**: Only the hotkeys, see [`WebViewAttributes::zoom_hotkeys_enabled`].
+  /// - **Linux / Android / iOS**: Unsupported
   pub fn with_hotkeys_zoom(mut self, zoom: bool) -> Self {
     self.attrs.zoom_hotkeys_enabled = zoom;
     self
@@ -934,6 +2000,40 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Set a closure called with the new URL of the webview when it changes.
+  ///
+  /// See [`WebViewAttributes::on_url_changed`] for more details.
+  pub fn with_on_url_changed<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(Url) + 'static,
+  {
+    self.attrs.on_url_changed = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called with the new URL of the webview after a same-document navigation,
+  /// e.g. a client-side router calling `history.pushState`.
+  ///
+  /// See [`WebViewAttributes::on_history_state_changed`] for more details.
+  pub fn with_on_history_state_changed<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(Url) + 'static,
+  {
+    self.attrs.on_history_state_changed = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called with the estimated progress of the page load, from `0.0` to `1.0`.
+  ///
+  /// See [`WebViewAttributes::on_load_progress`] for more details.
+  pub fn with_on_load_progress<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(f64) + 'static,
+  {
+    self.attrs.on_load_progress = Some(Box::new(handler));
+    self
+  }
+
   /// Run the WebView with incognito mode. Note that WebContext will be ingored if incognito is
   /// enabled.
   ///
@@ -954,6 +2054,62 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
+  /// Set a closure called when a navigation fails, with the reason of the failure.
+  ///
+  /// See [`WebViewAttributes::on_load_error`] for more details.
+  pub fn with_on_load_error<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(LoadError) + 'static,
+  {
+    self.attrs.on_load_error = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called with the original and the new URL when a navigation is redirected by the server.
+  ///
+  /// See [`WebViewAttributes::on_redirect`] for more details.
+  pub fn with_on_redirect<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(Url, Url) + 'static,
+  {
+    self.attrs.on_redirect = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called when a key is pressed in the webview, returning `true` to consume the key.
+  ///
+  /// See [`WebViewAttributes::on_accelerator_key`] for more details.
+  pub fn with_on_accelerator_key<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(AcceleratorKeyEvent) -> bool + 'static,
+  {
+    self.attrs.on_accelerator_key = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called when `Tab` moves the focus out of the page.
+  ///
+  /// See [`WebViewAttributes::on_tab_out`] for more details.
+  pub fn with_on_tab_out<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(bool) + 'static,
+  {
+    self.attrs.on_tab_out = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called when a frame reaches a finer-grained stage of its loading than
+  /// [`PageLoadEvent`].
+  ///
+  /// See [`WebViewAttributes::on_page_lifecycle`] for more details.
+  pub fn with_on_page_lifecycle<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(PageLifecycleEvent) + 'static,
+  {
+    self.attrs.on_page_lifecycle = Some(Box::new(handler));
+    self
+  }
+
   /// Set a proxy configuration for the webview.
   ///
   /// - **macOS**: Requires macOS 14.0+ and the `mac-proxy` feature flag to be enabled. Supports HTTP CONNECT and SOCKSv5 proxies.
@@ -983,414 +2139,2818 @@ impl<'a> WebViewBuilder<'a> {
     self
   }
 
-  /// Consume the builder and create the [`WebView`].
-  ///
-  /// # Panics:
+  /// Make the child webview follow the size of its parent window.
   ///
-  /// - Panics if the provided handle was not support or invalid.
-  /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
-  pub fn build(self) -> Result<WebView> {
-    let webview = if let Some(window) = &self.window {
-      if self.as_child {
-        InnerWebView::new_as_child(window, self.attrs, self.platform_specific, self.web_context)?
-      } else {
-        InnerWebView::new(window, self.attrs, self.platform_specific, self.web_context)?
-      }
-    } else {
-      #[cfg(gtk)]
-      if let Some(widget) = self.gtk_widget {
-        InnerWebView::new_gtk(widget, self.attrs, self.platform_specific, self.web_context)?
-      } else {
-        unreachable!()
-      }
-
-      #[cfg(not(gtk))]
-      unreachable!()
-    };
-
-    Ok(WebView { webview })
+  /// See [`WebViewAttributes::auto_resize`] for more details.
+  pub fn with_auto_resize(mut self, auto_resize: bool) -> Self {
+    self.attrs.auto_resize = auto_resize;
+    self
   }
-}
-
-#[cfg(windows)]
-#[derive(Clone)]
-pub(crate) struct PlatformSpecificWebViewAttributes {
-  additional_browser_args: Option<String>,
-  browser_accelerator_keys: bool,
-  theme: Option<Theme>,
-  https_scheme: bool,
-}
 
-#[cfg(windows)]
-impl Default for PlatformSpecificWebViewAttributes {
-  fn default() -> Self {
-    Self {
-      additional_browser_args: None,
-      browser_accelerator_keys: true, // This is WebView2's default behavior
-      theme: None,
-      https_scheme: false, // To match macOS & Linux behavior in the context of mixed content.
-    }
+  /// Set a closure to mutate the headers of every outgoing request made by the webview.
+  ///
+  /// The closure receives the request headers and its URL. Unlike a full request interceptor it
+  /// can't change the URL, method or body, which keeps it cheap enough to run for every request,
+  /// e.g. to add a global `Authorization` or `X-App-Version` header.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS / Linux / Android:** Only applied to requests for custom protocols
+  /// registered with [`WebViewBuilder::with_custom_protocol`] as the platform does not allow
+  /// intercepting other requests.
+  pub fn with_request_headers_interceptor<F>(mut self, interceptor: F) -> Self
+  where
+    F: Fn(&mut http::HeaderMap, &Url) + 'static,
+  {
+    self.attrs.request_headers_interceptor = Some(Box::new(interceptor));
+    self
   }
-}
 
-#[cfg(windows)]
-pub trait WebViewBuilderExtWindows {
-  /// Pass additional args to WebView2 upon creating the webview.
+  /// Set a closure to rewrite HTML responses before they are handed to the webview.
   ///
-  /// ## Warning
+  /// It receives the complete response and returns the response to deliver instead, which
+  /// allows injecting tags or stripping content from the served documents. Only responses with a
+  /// `Content-Type: text/html` header are passed to the closure.
   ///
-  /// - Webview instances with different browser arguments must also have different [data directories](struct.WebContext.html#method.new).
-  /// - By default wry passes `--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection`
-  /// `--autoplay-policy=no-user-gesture-required` if autoplay is enabled
-  /// and `--proxy-server=<scheme>://<host>:<port>` if a proxy is set.
-  /// so if you use this method, you have to add these arguments yourself if you want to keep the same behavior.
-  fn with_additional_browser_args<S: Into<String>>(self, additional_args: S) -> Self;
-
-  /// Determines whether browser-specific accelerator keys are enabled. When this setting is set to
-  /// `false`, it disables all accelerator keys that access features specific to a web browser.
-  /// The default value is `true`. See the following link to know more details.
+  /// # Warning
   ///
-  /// https://learn.microsoft.com/en-us/microsoft-edge/webview2/reference/winrt/microsoft_web_webview2_core/corewebview2settings#arebrowseracceleratorkeysenabled
-  fn with_browser_accelerator_keys(self, enabled: bool) -> Self;
-
-  /// Specifies the theme of webview2. This affects things like `prefers-color-scheme`.
+  /// The whole response body is buffered in memory before the closure runs, so this can slow
+  /// down the page load noticeably for large documents.
   ///
-  /// Defaults to [`Theme::Auto`] which will follow the OS defaults.
-  fn with_theme(self, theme: Theme) -> Self;
-
-  /// Determines whether the custom protocols should use `https://<scheme>.path/to/page` instead of the default `http://<scheme>.path/to/page`.
+  /// The closure can run on another thread when a custom protocol responds from another thread.
   ///
-  /// Using a `http` scheme will allow mixed content when trying to fetch `http` endpoints
-  /// and is therefore less secure but will match the behavior of the `<scheme>://path/to/page` protocols used on macOS and Linux.
+  /// ## Platform-specific:
   ///
-  /// The default value is `false`.
-  fn with_https_scheme(self, enabled: bool) -> Self;
-}
+  /// - **Windows**: Also applied to the HTML documents loaded from the network, which are paused
+  /// with the DevTools protocol `Fetch` domain until the closure returns.
+  /// - **macOS / Linux / iOS / Android**: Only applied to responses of custom protocols registered
+  /// with [`WebViewBuilder::with_custom_protocol`] or [`WebViewBuilder::with_asynchronous_custom_protocol`].
+  pub fn with_response_transform<F>(mut self, transform: F) -> Self
+  where
+    F: Fn(Response<Vec<u8>>) -> Response<Vec<u8>> + Send + Sync + 'static,
+  {
+    self.attrs.response_transform = Some(Box::new(transform));
+    self
+  }
 
-#[cfg(windows)]
-impl WebViewBuilderExtWindows for WebViewBuilder<'_> {
-  fn with_additional_browser_args<S: Into<String>>(mut self, additional_args: S) -> Self {
-    self.platform_specific.additional_browser_args = Some(additional_args.into());
+  /// Limit the IPC handler to at most `count` messages within any sliding `window` of time.
+  ///
+  /// Messages exceeding that rate are dropped, see [`WebViewBuilder::with_on_ipc_throttled`]
+  /// to get notified about them.
+  pub fn with_throttle_ipc_rate(mut self, count: u32, window: Duration) -> Self {
+    self.attrs.throttle_ipc_rate = Some((count, window));
     self
   }
 
-  fn with_browser_accelerator_keys(mut self, enabled: bool) -> Self {
-    self.platform_specific.browser_accelerator_keys = enabled;
+  /// Set a closure called when IPC messages are dropped because of [`WebViewBuilder::with_throttle_ipc_rate`],
+  /// [`WebViewBuilder::with_max_ipc_message_size_bytes`] or because a chunk of a large message was lost.
+  ///
+  /// It receives the reason the messages were dropped, see [`IpcThrottleReason`].
+  pub fn with_on_ipc_throttled<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(IpcThrottleReason) + 'static,
+  {
+    self.attrs.on_ipc_throttled = Some(Box::new(handler));
     self
   }
 
-  fn with_theme(mut self, theme: Theme) -> Self {
-    self.platform_specific.theme = Some(theme);
+  /// Only deliver the IPC messages sent by the frames of `origins`, e.g. `https://example.com`
+  /// or `wry://localhost`.
+  ///
+  /// See [`WebViewAttributes::ipc_allowed_origins`] for more details.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / Android**: Unsupported, [`WebViewBuilder::build`] returns [`Error::NotSupported`].
+  pub fn with_ipc_allowed_origins<I, S>(mut self, origins: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.attrs.ipc_allowed_origins = Some(origins.into_iter().map(Into::into).collect());
     self
   }
 
-  fn with_https_scheme(mut self, enabled: bool) -> Self {
-    self.platform_specific.https_scheme = enabled;
+  /// Set a closure called with the origin and the message of the IPC messages denied by
+  /// [`WebViewBuilder::with_ipc_allowed_origins`].
+  pub fn with_on_ipc_denied<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(Option<String>, String) + 'static,
+  {
+    self.attrs.on_ipc_denied = Some(Box::new(handler));
     self
   }
-}
 
-#[cfg(target_os = "android")]
-#[derive(Default)]
-pub(crate) struct PlatformSpecificWebViewAttributes {
-  on_webview_created:
-    Option<Box<dyn Fn(prelude::Context) -> std::result::Result<(), jni::errors::Error> + Send>>,
-  with_asset_loader: bool,
-  asset_loader_domain: Option<String>,
-  https_scheme: bool,
-}
+  /// Set the zoom factor increment applied by the zoom in and zoom out hotkeys, `0.1` by default.
+  ///
+  /// Only used when zooming by hotkeys is enabled, see [`WebViewBuilder::with_hotkeys_zoom`].
+  /// [`WebViewBuilder::build`] returns [`Error::InvalidZoomStep`] if `step` isn't positive.
+  ///
+  /// ## Platform-specific
+  ///
+  /// **Linux / Android / iOS**: Unsupported
+  pub fn with_zoom_hotkeys_custom_step(mut self, step: f64) -> Self {
+    self.attrs.zoom_hotkeys_custom_step = step;
+    self
+  }
 
-#[cfg(target_os = "android")]
-pub trait WebViewBuilderExtAndroid {
-  fn on_webview_created<
-    F: Fn(prelude::Context<'_, '_>) -> std::result::Result<(), jni::errors::Error> + Send + 'static,
-  >(
-    self,
-    f: F,
-  ) -> Self;
+  /// Set the key that, pressed together with `Ctrl`, resets the zoom factor to the one set with
+  /// [`WebViewBuilder::with_initial_zoom`]. Defaults to [`KeyCode::Digit0`] (`Ctrl+0`), `None`
+  /// disables the reset hotkey.
+  ///
+  /// Only used when zooming by hotkeys is enabled, see [`WebViewBuilder::with_hotkeys_zoom`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: The key is pressed together with `Cmd` instead.
+  /// - **Linux / Android / iOS**: Unsupported
+  pub fn with_zoom_reset_key(mut self, key: Option<KeyCode>) -> Self {
+    self.attrs.zoom_reset_key = key;
+    self
+  }
 
-  /// Use [WebViewAssetLoader](https://developer.android.com/reference/kotlin/androidx/webkit/WebViewAssetLoader)
-  /// to load assets from Android's `asset` folder when using `with_url` as `<protocol>://assets/` (e.g.:
-  /// `wry://assets/index.html`). Note that this registers a custom protocol with the provided
-  /// String, similar to [`with_custom_protocol`], but also sets the WebViewAssetLoader with the
-  /// necessary domain (which is fixed as `<protocol>.assets`). This cannot be used in conjunction
-  /// to `with_custom_protocol` for Android, as it changes the way in which requests are handled.
-  #[cfg(feature = "protocol")]
-  fn with_asset_loader(self, protocol: String) -> Self;
+  /// Set the zoom factor of the webview when it is created, `1.0` by default, see [`WebView::zoom`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported
+  pub fn with_initial_zoom(mut self, zoom: f64) -> Self {
+    self.attrs.initial_zoom = zoom;
+    self
+  }
 
-  /// Determines whether the custom protocols should use `https://<scheme>.localhost` instead of the default `http://<scheme>.localhost`.
+  /// Set a closure called with the new zoom factor when the page zoom changes, either
+  /// programmatically with [`WebView::zoom`] or by the user with hotkeys or gestures.
   ///
-  /// Using a `http` scheme will allow mixed content when trying to fetch `http` endpoints
-  /// and is therefore less secure but will match the behavior of the `<scheme>://localhost` protocols used on macOS and Linux.
+  /// ## Platform-specific
   ///
-  /// The default value is `false`.
-  fn with_https_scheme(self, enabled: bool) -> Self;
-}
+  /// - **macOS / iOS**: Page zoom changes are observed natively, pinch zoom is reported
+  /// with the `visualViewport` scale of the page.
+  /// - **Android**: Only pinch zoom is reported, with the `visualViewport` scale of the page.
+  pub fn with_on_page_zoom_change<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(f64) + 'static,
+  {
+    self.attrs.on_page_zoom_change = Some(Box::new(handler));
+    self
+  }
 
-#[cfg(target_os = "android")]
-impl WebViewBuilderExtAndroid for WebViewBuilder<'_> {
-  fn on_webview_created<
-    F: Fn(prelude::Context<'_, '_>) -> std::result::Result<(), jni::errors::Error> + Send + 'static,
-  >(
-    mut self,
-    f: F,
-  ) -> Self {
-    self.platform_specific.on_webview_created = Some(Box::new(f));
+  /// Set the minimum and maximum zoom factors, see [`WebView::set_zoom_limits`].
+  ///
+  /// [`WebViewBuilder::build`] fails with [`Error::InvalidZoomLimits`] unless `min` is positive
+  /// and not greater than `max`.
+  pub fn with_zoom_limits(mut self, min: f64, max: f64) -> Self {
+    self.attrs.zoom_limits = Some((min, max));
     self
   }
 
-  #[cfg(feature = "protocol")]
-  fn with_asset_loader(mut self, protocol: String) -> Self {
-    // register custom protocol with empty Response return,
-    // this is necessary due to the need of fixing a domain
-    // in WebViewAssetLoader.
-    self.attrs.custom_protocols.push((
-      protocol.clone(),
-      Box::new(|_, api| {
-        api.respond(Response::builder().body(Vec::new()).unwrap());
-      }),
-    ));
-    self.platform_specific.with_asset_loader = true;
-    self.platform_specific.asset_loader_domain = Some(format!("{}.assets", protocol));
+  /// Set whether zooming only scales the text of the pages instead of the whole layout.
+  ///
+  /// See [`WebViewAttributes::text_zoom_only`] for more details.
+  pub fn with_text_zoom_only(mut self, text_zoom_only: bool) -> Self {
+    self.attrs.text_zoom_only = text_zoom_only;
     self
   }
 
-  fn with_https_scheme(mut self, enabled: bool) -> Self {
-    self.platform_specific.https_scheme = enabled;
+  /// Set whether background colors and images are printed, `false` by default.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Requires macOS 13.3+ and iOS 16.4+.
+  /// - **Windows / Android**: Injects a `print-color-adjust: exact` print stylesheet in the pages.
+  pub fn with_print_background(mut self, print_background: bool) -> Self {
+    self.attrs.print_background = print_background;
     self
   }
-}
 
-#[cfg(any(
-  target_os = "linux",
-  target_os = "dragonfly",
-  target_os = "freebsd",
-  target_os = "netbsd",
-  target_os = "openbsd",
-))]
-pub trait WebViewBuilderExtUnix<'a> {
-  /// Create the webview inside a GTK container widget, such as GTK window.
+  /// Set the maximum size in bytes of the IPC messages delivered to the IPC handler.
   ///
-  /// - If the container is [`gtk::Box`], it is added using [`Box::pack_start(webview, true, true, 0)`](gtk::prelude::BoxExt::pack_start).
-  /// - If the container is [`gtk::Fixed`], its [size request](gtk::prelude::WidgetExt::set_size_request) will be set using the (width, height) bounds passed in
-  ///   and will be added to the container using [`Fixed::put`](gtk::prelude::FixedExt::put) using the (x, y) bounds passed in.
-  /// - For all other containers, it will be added using [`gtk::prelude::ContainerExt::add`]
+  /// `window.ipc.postMessage` throws a `RangeError` for larger messages, see
+  /// [`WebViewAttributes::max_ipc_message_size_bytes`] for more details. Unlimited by default,
+  /// a limit of 1 MB is recommended for production use.
+  pub fn with_max_ipc_message_size_bytes(mut self, max_size: usize) -> Self {
+    self.attrs.max_ipc_message_size_bytes = Some(max_size);
+    self
+  }
+
+  /// Set a closure called once the [`WebView`] is created, before [`WebViewBuilder::build`] returns.
   ///
-  /// # Panics:
+  /// See [`WebViewAttributes::on_webview_ready`] for when the web engine is ready.
   ///
-  /// - Panics if [`gtk::init`] was not called in this thread.
-  fn new_gtk<W>(widget: &'a W) -> Self
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Called before the Android `WebView` is created, see
+  /// [`WebViewAttributes::on_webview_ready`].
+  pub fn with_on_webview_ready<F>(mut self, handler: F) -> Self
   where
-    W: gtk::prelude::IsA<gtk::Container>;
-}
+    F: FnOnce(&WebView) + 'static,
+  {
+    self.attrs.on_webview_ready = Some(Box::new(handler));
+    self
+  }
 
-#[cfg(any(
-  target_os = "linux",
-  target_os = "dragonfly",
-  target_os = "freebsd",
-  target_os = "netbsd",
-  target_os = "openbsd",
-))]
-impl<'a> WebViewBuilderExtUnix<'a> for WebViewBuilder<'a> {
-  fn new_gtk<W>(widget: &'a W) -> Self
+  /// Set a closure called once the [`WebView`] is torn down by [`WebView::close`].
+  ///
+  /// It is not called when the [`WebView`] is simply dropped.
+  pub fn with_on_closed<F>(mut self, handler: F) -> Self
   where
-    W: gtk::prelude::IsA<gtk::Container>,
+    F: FnOnce() + 'static,
   {
-    use gdkx11::glib::Cast;
+    self.attrs.on_closed = Some(Box::new(handler));
+    self
+  }
 
-    Self {
-      attrs: WebViewAttributes::default(),
-      window: None,
-      as_child: false,
-      #[allow(clippy::default_constructed_unit_structs)]
-      platform_specific: PlatformSpecificWebViewAttributes::default(),
-      web_context: None,
-      gtk_widget: Some(widget.dynamic_cast_ref().unwrap()),
-    }
+  /// Set a closure asking the user whether to leave a page with a `beforeunload` handler,
+  /// returning `true` to leave it.
+  ///
+  /// See [`WebViewAttributes::on_before_unload`] for more details.
+  pub fn with_on_before_unload<F>(mut self, handler: F) -> Self
+  where
+    F: Fn() -> bool + 'static,
+  {
+    self.attrs.on_before_unload = Some(Box::new(handler));
+    self
   }
-}
 
-/// The fundamental type to present a [`WebView`].
-///
-/// [`WebViewBuilder`] / [`WebView`] are the basic building blocks to construct WebView contents and
-/// scripts for those who prefer to control fine grained window creation and event handling.
-/// [`WebView`] presents the actual WebView window and let you still able to perform actions on it.
-pub struct WebView {
-  webview: InnerWebView,
-}
+  /// Set a closure deciding which areas of the webview act as a drag handle for moving the window,
+  /// typically used with frameless windows.
+  ///
+  /// It is called with the position of the primary mouse button presses and returns `true`
+  /// to start moving the window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Linux**: The presses are reported by an injected script, so the page
+  /// receives them as well.
+  /// - **Android / iOS**: Unsupported.
+  pub fn with_drag_region_handler<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(DragRegionQuery) -> bool + 'static,
+  {
+    self.attrs.drag_region_handler = Some(Box::new(handler));
+    self
+  }
 
-impl WebView {
-  /// Create a [`WebView`] from from a type that implements [`HasWindowHandle`].
-  /// Note that calling this directly loses
-  /// abilities to initialize scripts, add ipc handler, and many more before starting WebView. To
-  /// benefit from above features, create a [`WebViewBuilder`] instead.
+  /// Set whether the elements of the page with a `data-wry-click-through` attribute let the mouse
+  /// input pass to the native views under the webview.
   ///
-  /// # Platform-specific:
+  /// See [`WebViewAttributes::click_through_elements`] for more details.
+  pub fn with_click_through_elements(mut self, enabled: bool) -> Self {
+    self.attrs.click_through_elements = enabled;
+    self
+  }
+
+  /// Set a CSS scale factor applied to the content of the pages, with the CSS `zoom` property
+  /// on the root element.
   ///
-  /// - **Linux**: Only X11 is supported, if you want to support Wayland too, use [`WebViewExtUnix::new_gtk`].
+  /// Unlike [`WebView::zoom`], this doesn't change the `devicePixelRatio` seen by the page,
+  /// which is useful to scale content designed for a given size independently of the display DPI.
+  pub fn with_content_scale(mut self, scale: f64) -> Self {
+    self.attrs.content_scale = Some(scale);
+    self
+  }
+
+  /// Set a closure called when the audio playback state of the page changes.
   ///
-  ///   Although this methods only needs an X11 window handle, you use webkit2gtk, so you still need to initialize gtk
-  ///   by callling [`gtk::init`] and advance its loop alongside your event loop using [`gtk::main_iteration_do`].
-  ///   Checkout the [Platform Considerations](https://docs.rs/wry/latest/wry/#platform-considerations) section in the crate root documentation.
-  /// - **macOS / Windows**: The webview will auto-resize when the passed handle is resized.
-  /// - **Linux (X11)**: Unlike macOS and Windows, the webview will not auto-resize and you'll need to call [`WebView::set_size`] manually.
+  /// The state is tracked by an injected script listening to the `<audio>` and `<video>` elements,
+  /// the Web Audio API contexts and the `MediaSession` API of the main frame.
+  pub fn with_on_web_audio_state_change<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(WebAudioState) + 'static,
+  {
+    self.attrs.on_web_audio_state_change = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called when no user interaction (mouse, keyboard or touch) happened in the main frame
+  /// for the [idle timeout](Self::with_idle_timeout), with the time elapsed since the last interaction.
   ///
-  /// # Panics:
+  /// The handler is called once per idle period, the next interaction restarts the timer.
+  /// See also [`WebView::reset_idle_timer`].
+  pub fn with_on_idle<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(Duration) + 'static,
+  {
+    self.attrs.on_idle = Some(Box::new(handler));
+    self
+  }
+
+  /// Set the inactivity duration after which the [`on_idle`](Self::with_on_idle) handler is called.
   ///
-  /// - Panics if the provided handle was not supported or invalid.
-  /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
-  pub fn new(window: &impl HasWindowHandle) -> Result<Self> {
-    WebViewBuilder::new(window).build()
+  /// Defaults to 5 minutes.
+  pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+    self.attrs.idle_timeout = timeout;
+    self
   }
 
-  /// Create [`WebViewBuilder`] as a child window inside the provided [`HasWindowHandle`].
+  /// Set a closure called when the audio focus of the application changes, e.g. when the audio
+  /// session is interrupted by a phone call or Siri.
   ///
   /// ## Platform-specific
   ///
-  /// - **Windows**: This will create the webview as a child window of the `parent` window.
-  /// - **macOS**: This will create the webview as a `NSView` subview of the `parent` window's
-  /// content view.
-  /// - **Linux**: This will create the webview as a child window of the `parent` window. Only X11
-  /// is supported. This method won't work on Wayland.
+  /// - **iOS**: Uses the `AVAudioSessionInterruptionNotification` of the shared audio session.
+  /// - **macOS / Windows / Linux / Android**: Unsupported, the handler is never called.
+  pub fn with_on_audio_focus_change<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(AudioFocusEvent) + 'static,
+  {
+    self.attrs.on_audio_focus_change = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called when a `fetch()` or `XMLHttpRequest` of the page fails with a network error,
+  /// e.g. a CORS error, a timeout or a DNS failure.
   ///
-  ///   Although this methods only needs an X11 window handle, you use webkit2gtk, so you still need to initialize gtk
-  ///   by callling [`gtk::init`] and advance its loop alongside your event loop using [`gtk::main_iteration_do`].
-  ///   Checkout the [Platform Considerations](https://docs.rs/wry/latest/wry/#platform-considerations) section in the crate root documentation.
+  /// The requests are intercepted by an injected script, the errors are still thrown to the page.
+  /// Aborted requests are not reported.
+  pub fn with_on_fetch_error<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(FetchError) + 'static,
+  {
+    self.attrs.on_fetch_error = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called with the uncaught exceptions and the unhandled promise rejections of
+  /// the page and its frames.
   ///
-  ///   If you want to support child webviews on X11 and Wayland at the same time,
-  ///   we recommend using [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
-  /// - **Android/iOS:** Unsupported.
+  /// See [`WebViewAttributes::on_page_error`] for more details.
+  pub fn with_on_page_error<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(PageError) + 'static,
+  {
+    self.attrs.on_page_error = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called with the level, the text, the source URL and the line of the messages
+  /// logged to the console of the page.
   ///
-  /// # Panics:
+  /// See [`WebViewAttributes::on_console_message`] for more details.
+  pub fn with_on_console_message<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(ConsoleMessageLevel, String, Option<String>, Option<u32>) + 'static,
+  {
+    self.attrs.on_console_message = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called with the favicon of the page, encoded as PNG, every time it changes.
   ///
-  /// - Panics if the provided handle was not support or invalid.
-  /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
-  pub fn new_as_child(parent: &impl HasWindowHandle) -> Result<Self> {
-    WebViewBuilder::new_as_child(parent).build()
+  /// See [`WebViewAttributes::on_favicon_changed`] for more details.
+  pub fn with_on_favicon_changed<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(Vec<u8>) + 'static,
+  {
+    self.attrs.on_favicon_changed = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called with the new scroll offset of the page, in CSS pixels, when it is scrolled.
+  ///
+  /// See [`WebViewAttributes::on_scroll`] for more details.
+  pub fn with_on_scroll<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(f64, f64) + 'static,
+  {
+    self.attrs.on_scroll = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called with the selected text of the page when the selection changes.
+  ///
+  /// See [`WebViewAttributes::on_selection_changed`] for more details.
+  pub fn with_on_selection_changed<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(Option<String>) + 'static,
+  {
+    self.attrs.on_selection_changed = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called when the page calls `window.print()`, returning whether the print dialog
+  /// is shown, suppressed or replaced by a silent export to PDF.
+  ///
+  /// To show a custom print UI instead, e.g. in kiosk deployments, return [`PrintDialogDecision::Deny`]
+  /// and show it from the handler, [`WebView::print`] can still show the native dialog afterwards.
+  ///
+  /// [`WebView::print`] is not intercepted.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS**: `window.print()` is overridden by an initialization script.
+  /// - **iOS**: [`PrintDialogDecision::Allow`] does nothing, WKWebView has no print dialog.
+  /// - **Android**: Unsupported, the handler is never called.
+  pub fn with_on_print_dialog<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(PrintDialogRequest) -> PrintDialogDecision + 'static,
+  {
+    self.attrs.on_print_dialog = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called when the audio of the webview is muted or unmuted, see [`WebView::set_audio_muted`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS / Android**: Only called by [`WebView::set_audio_muted`].
+  pub fn with_on_audio_muted_change<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(bool) + 'static,
+  {
+    self.attrs.on_audio_muted_change = Some(Box::new(handler));
+    self
+  }
+
+  /// Set `window[name]` to a copy of the JSON `value` before the page and the initialization scripts run,
+  /// e.g. to pass configuration constants to the page.
+  pub fn with_global_object(mut self, name: impl Into<String>, value: serde_json::Value) -> Self {
+    self.attrs.inject_global_object.push((name.into(), value));
+    self
+  }
+
+  /// Restrict the WebRTC connections of the page to relay (TURN) servers so they do not reveal the
+  /// local and public IP addresses, e.g. when a proxy is used.
+  ///
+  /// This prevents direct peer-to-peer WebRTC connections, the connections fail without a TURN server.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Also passes `--force-webrtc-ip-handling-policy=disable_non_proxied_udp` to the browser
+  /// unless `WebViewBuilderExtWindows::with_additional_browser_args` is used.
+  pub fn with_webrtc_ip_protection(mut self, enable: bool) -> Self {
+    self.attrs.enable_webrtc_ip_protection = enable;
+    self
+  }
+
+  /// Set whether [`WebView::get_webrtc_stats`] can collect the stats of the WebRTC peer connections,
+  /// by tracking the `RTCPeerConnection`s created by the page. Disabled by default.
+  pub fn with_webrtc_stats(mut self, enable: bool) -> Self {
+    self.attrs.enable_webrtc_stats = enable;
+    self
+  }
+
+  /// Set whether the timers and the animation frames of the page are throttled while the webview is
+  /// hidden or occluded, `true` by default.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Passes `--disable-background-timer-throttling --disable-renderer-backgrounding --disable-backgrounding-occluded-windows`
+  /// to the browser unless `WebViewBuilderExtWindows::with_additional_browser_args` is used.
+  /// - **macOS / iOS**: Requires macOS 14 / iOS 17 or higher.
+  /// - **Linux / Android**: Unsupported.
+  pub fn with_background_throttling(mut self, enabled: bool) -> Self {
+    self.attrs.background_throttling = enabled;
+    self
+  }
+
+  /// Set the hosts reached directly instead of through the [proxy](Self::with_proxy_config),
+  /// e.g. `["localhost", "*.internal.corp.com", "10.0.0.0/8"]`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Passed as `--proxy-bypass-list` to the browser, unless
+  /// `WebViewBuilderExtWindows::with_additional_browser_args` is used.
+  /// - **macOS**: IP ranges are not supported, the hosts are excluded domains of the proxy configuration.
+  /// - **Android / iOS**: Unsupported.
+  pub fn with_http_proxy_exclude_hosts(mut self, hosts: Vec<String>) -> Self {
+    self.attrs.http_proxy_exclude_hosts = hosts;
+    self
+  }
+
+  /// Set a closure called when the TLS connection of a navigation fails, e.g. because of an invalid
+  /// certificate, returning whether the navigation is blocked or continued anyway.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime version 101.0.1210.39 or higher.
+  /// - **macOS / iOS**: Only the server trust failures are reported.
+  /// - **Android**: Unsupported, the handler is never called.
+  pub fn with_on_https_error<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(HttpsError) -> HttpsErrorDecision + 'static,
+  {
+    self.attrs.on_https_error = Some(Box::new(handler));
+    self
+  }
+
+  /// Set a closure called when the page starts or stops capturing the camera, the microphone or the screen,
+  /// e.g. to show a capture indicator.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: Requires macOS 12+ and iOS 15+, the screen capture is not reported.
+  /// - **Windows / Android**: The `getUserMedia` and `getDisplayMedia` streams are tracked by an injected script.
+  pub fn with_on_media_capture_state_change<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(MediaCaptureState) + 'static,
+  {
+    self.attrs.on_media_capture_state_change = Some(Box::new(handler));
+    self
+  }
+
+  /// Generate the TypeScript definitions of `window.ipc` and of the IPC channels, commands and
+  /// bindings registered so far, e.g. to write them to a `.d.ts` file of the frontend from a build script.
+  ///
+  /// The payloads and results are typed as `unknown`, the calls take a type parameter for their result.
+  #[cfg(feature = "typescript")]
+  pub fn typescript_definitions(&self) -> String {
+    typescript::definitions(&self.attrs)
+  }
+
+  /// Consume the builder and create the [`WebView`].
+  ///
+  /// # Panics:
+  ///
+  /// - Panics if the provided handle was not support or invalid.
+  /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
+  pub fn build(mut self) -> Result<WebView> {
+    if let Some((min, max)) = self.attrs.zoom_limits {
+      check_zoom_limits(min, max)?;
+    }
+    check_zoom_step(self.attrs.zoom_hotkeys_custom_step)?;
+    // the origin of the messages is unknown, every message would be denied
+    #[cfg(any(gtk, target_os = "android"))]
+    if self.attrs.ipc_allowed_origins.is_some() {
+      return Err(Error::NotSupported("filtering the IPC messages by origin"));
+    }
+
+    intercept_custom_protocol_headers(&mut self.attrs);
+    transform_custom_protocol_responses(&mut self.attrs);
+    apply_custom_protocol_cors(&mut self.attrs);
+    route_ipc_requests(&mut self.attrs);
+    route_raw_ipc_messages(&mut self.attrs);
+    #[cfg(feature = "serde-ipc")]
+    route_ipc_commands(&mut self.attrs);
+    let invoke_webview = InvokeWebView::default();
+    route_invoke_messages(&mut self.attrs, invoke_webview.clone());
+    route_ipc_channels(&mut self.attrs);
+
+    // forces printing backgrounds on the platforms without a native setting for it
+    #[cfg(any(target_os = "windows", target_os = "android"))]
+    if self.attrs.print_background {
+      self.attrs.initialization_scripts.push(inject_style_script(
+        "@media print { * { -webkit-print-color-adjust: exact !important; print-color-adjust: exact !important; } }",
+      ));
+    }
+
+    if let Some(scale) = self.attrs.content_scale {
+      self
+        .attrs
+        .initialization_scripts
+        .push(inject_style_script(&format!("html {{ zoom: {scale}; }}")));
+    }
+
+    if self.attrs.enable_webrtc_stats {
+      self
+        .attrs
+        .initialization_scripts
+        .push(webrtc::TRACK_PEER_CONNECTIONS_SCRIPT.to_string());
+    }
+    if self.attrs.enable_webrtc_ip_protection {
+      self
+        .attrs
+        .initialization_scripts
+        .push(webrtc::RELAY_ONLY_SCRIPT.to_string());
+    }
+
+    inject_global_objects(&mut self.attrs);
+
+    #[cfg(any(target_os = "windows", target_os = "android"))]
+    for (js, main_only) in std::mem::take(&mut self.attrs.document_end_scripts) {
+      let js = format!("document.addEventListener('DOMContentLoaded', function () {{\n{js}\n}});");
+      if main_only {
+        self.attrs.main_frame_initialization_scripts.push(js);
+      } else {
+        self.attrs.initialization_scripts.push(js);
+      }
+    }
+
+    let mut bridge = Bridge::new();
+    observe_visual_viewport_zoom(&mut self.attrs, &mut bridge);
+    let window_drag_starter = WindowDragStarter::default();
+    observe_drag_regions(&mut self.attrs, &mut bridge, window_drag_starter.clone());
+    let click_through_setter = ClickThroughSetter::default();
+    observe_click_through_elements(&mut self.attrs, &mut bridge, click_through_setter.clone());
+    observe_web_audio_state(&mut self.attrs, &mut bridge);
+    observe_idle(&mut self.attrs, &mut bridge);
+    observe_fetch_errors(&mut self.attrs, &mut bridge);
+    observe_console_messages(&mut self.attrs, &mut bridge);
+    observe_page_errors(&mut self.attrs, &mut bridge);
+    observe_favicon(&mut self.attrs, &mut bridge);
+    observe_scroll(&mut self.attrs, &mut bridge);
+    observe_selection(&mut self.attrs, &mut bridge);
+    observe_url_changes(&mut self.attrs, &mut bridge);
+    observe_history_state(&mut self.attrs, &mut bridge);
+    observe_load_progress(&mut self.attrs, &mut bridge);
+    observe_page_lifecycle(&mut self.attrs, &mut bridge);
+    observe_media_capture_state(&mut self.attrs, &mut bridge);
+    let callbacks = bridge.callbacks();
+    drop_callbacks_on_load(&mut self.attrs, callbacks.clone());
+    let on_ipc_throttled = self.attrs.on_ipc_throttled.take().map(Rc::from);
+    // only the reassembled messages of the page are throttled, not the bridge messages
+    throttle_ipc_handler(&mut self.attrs, on_ipc_throttled.clone());
+    bridge.install(&mut self.attrs);
+    reassemble_ipc_chunks(&mut self.attrs, on_ipc_throttled);
+    // outermost, to filter the internal messages and the chunks too
+    filter_ipc_origins(&mut self.attrs);
+
+    let on_webview_ready = self.attrs.on_webview_ready.take();
+    let on_closed = self.attrs.on_closed.take();
+    let on_before_unload = self.attrs.on_before_unload.take().map(Rc::from);
+    let initial_zoom = self.attrs.initial_zoom;
+
+    let webview = if let Some(window) = &self.window {
+      if self.as_child {
+        InnerWebView::new_as_child(window, self.attrs, self.platform_specific, self.web_context)?
+      } else {
+        InnerWebView::new(window, self.attrs, self.platform_specific, self.web_context)?
+      }
+    } else {
+      #[cfg(gtk)]
+      if let Some(widget) = self.gtk_widget {
+        InnerWebView::new_gtk(widget, self.attrs, self.platform_specific, self.web_context)?
+      } else {
+        unreachable!()
+      }
+
+      #[cfg(not(gtk))]
+      unreachable!()
+    };
+
+    #[cfg(any(target_os = "windows", gtk))]
+    {
+      *window_drag_starter.borrow_mut() = Some(webview.window_drag_starter());
+    }
+    *click_through_setter.borrow_mut() = Some(webview.click_through_regions_setter());
+    if initial_zoom != 1.0 {
+      webview.zoom(initial_zoom);
+    }
+    let webview = Rc::new(webview);
+    *invoke_webview.borrow_mut() = Rc::downgrade(&webview);
+
+    let webview = WebView {
+      webview,
+      callbacks,
+      on_closed,
+      on_before_unload,
+    };
+    if let Some(on_webview_ready) = on_webview_ready {
+      on_webview_ready(&webview);
+    }
+
+    Ok(webview)
+  }
+}
+
+/// Prepends to the initialization scripts a script setting the [`WebViewAttributes::inject_global_object`] values.
+fn inject_global_objects(attrs: &mut WebViewAttributes) {
+  if attrs.inject_global_object.is_empty() {
+    return;
+  }
+
+  let script = std::mem::take(&mut attrs.inject_global_object)
+    .into_iter()
+    .map(|(name, value)| {
+      format!(
+        "window[{}] = JSON.parse({});",
+        serde_json::Value::String(name),
+        serde_json::Value::String(value.to_string())
+      )
+    })
+    .collect::<Vec<_>>()
+    .join("\n");
+  attrs.initialization_scripts.insert(0, script);
+}
+
+/// Wraps the custom protocol handlers so the request headers go through
+/// [`WebViewAttributes::request_headers_interceptor`] before reaching them.
+///
+/// The interceptor is kept in the attributes so the platform can still apply it
+/// to the requests it is able to intercept natively.
+fn intercept_custom_protocol_headers(attrs: &mut WebViewAttributes) {
+  let Some(interceptor) = attrs.request_headers_interceptor.take() else {
+    return;
+  };
+  let interceptor: Rc<dyn Fn(&mut http::HeaderMap, &Url)> = Rc::from(interceptor);
+
+  attrs.custom_protocols = std::mem::take(&mut attrs.custom_protocols)
+    .into_iter()
+    .map(|(name, handler)| {
+      let interceptor = interceptor.clone();
+      let handler: Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)> =
+        Box::new(move |mut request, responder| {
+          if let Ok(url) = Url::parse(&request.uri().to_string()) {
+            interceptor(request.headers_mut(), &url);
+          }
+          handler(request, responder)
+        });
+      (name, handler)
+    })
+    .collect();
+
+  attrs.request_headers_interceptor = Some(Box::new(move |headers, url| interceptor(headers, url)));
+}
+
+/// Deserializes the JSON `result` of a script, the platforms report `undefined` as an empty
+/// string, `undefined` or `null`.
+fn deserialize_script_result<T: serde::de::DeserializeOwned>(result: &str) -> Result<T> {
+  let result = match result.trim() {
+    "" | "undefined" => "null",
+    result => result,
+  };
+  Ok(serde_json::from_str(result)?)
+}
+
+/// Script adding a stylesheet with the given `css` to the pages.
+fn inject_style_script(css: &str) -> String {
+  format!(
+    r#"(function () {{
+  var style = document.createElement('style');
+  style.textContent = {};
+  function append() {{
+    (document.head || document.documentElement).appendChild(style);
+  }}
+  if (document.documentElement) append();
+  else document.addEventListener('DOMContentLoaded', append);
+}})();"#,
+    serde_json::to_string(css).unwrap_or_default()
+  )
+}
+
+/// Script adding the user stylesheet `id` of [`WebView::inject_css`] to the page, unless it is
+/// already added.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub(crate) fn user_style_script(id: StyleId, css: &str) -> String {
+  let id = id.0;
+  format!(
+    r#"(function () {{
+  if (document.querySelector('style[data-wry-style="{id}"]')) return;
+  var style = document.createElement('style');
+  style.setAttribute('data-wry-style', '{id}');
+  style.textContent = {};
+  function append() {{
+    (document.head || document.documentElement).appendChild(style);
+  }}
+  if (document.documentElement) append();
+  else document.addEventListener('DOMContentLoaded', append);
+}})();"#,
+    serde_json::to_string(css).unwrap_or_default()
+  )
+}
+
+/// Script removing the user stylesheet `id` added by [`user_style_script`] from the page.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub(crate) fn remove_user_style_script(id: StyleId) -> String {
+  format!(
+    r#"document.querySelectorAll('style[data-wry-style="{}"]').forEach(function (style) {{ style.remove(); }});"#,
+    id.0
+  )
+}
+
+/// Wraps [`WebViewAttributes::ipc_handler`] to deliver the messages to
+/// [`WebViewAttributes::ipc_request_handler`] as well.
+fn route_ipc_requests(attrs: &mut WebViewAttributes) {
+  let Some(request_handler) = attrs.ipc_request_handler.take() else {
+    return;
+  };
+
+  let handler = attrs.ipc_handler.take();
+  attrs.ipc_handler = Some(Box::new(move |message| {
+    if let Some(handler) = &handler {
+      handler(message.clone());
+    }
+    request_handler(ipc::message_request(message));
+  }));
+}
+
+/// Wraps [`WebViewAttributes::ipc_handler`] to decode the binary messages and deliver the messages
+/// to [`WebViewAttributes::ipc_handler_raw`].
+fn route_raw_ipc_messages(attrs: &mut WebViewAttributes) {
+  let Some(raw_handler) = attrs.ipc_handler_raw.take() else {
+    return;
+  };
+
+  let handler = attrs.ipc_handler.take();
+  attrs.ipc_handler = Some(Box::new(move |message| {
+    match ipc::decode_message(message) {
+      IpcMessage::Text(message) => {
+        if let Some(handler) = &handler {
+          handler(message.clone());
+        }
+        raw_handler(IpcMessage::Text(message));
+      }
+      message => raw_handler(message),
+    }
+  }));
+}
+
+/// Wraps [`WebViewAttributes::ipc_invoke_handler`] to call the [`WebViewAttributes::ipc_commands`]
+/// and defines their `window.ipcCommands` functions.
+#[cfg(feature = "serde-ipc")]
+fn route_ipc_commands(attrs: &mut WebViewAttributes) {
+  if attrs.ipc_commands.is_empty() {
+    return;
+  }
+
+  attrs.initialization_scripts.push(ipc::commands_script(
+    attrs.ipc_commands.iter().map(|(name, _)| name.as_str()),
+  ));
+
+  let commands = std::mem::take(&mut attrs.ipc_commands)
+    .into_iter()
+    .collect::<HashMap<_, _>>();
+  let invoke_handler = attrs.ipc_invoke_handler.take();
+  attrs.ipc_invoke_handler = Some(Box::new(move |cmd, payload| {
+    match (commands.get(&cmd), &invoke_handler) {
+      (Some(command), _) => command(payload),
+      (None, Some(invoke_handler)) => invoke_handler(cmd, payload),
+      (None, None) => Err(format!("No IPC command `{cmd}`")),
+    }
+  }));
+}
+
+/// The webview settling the `window.ipc.invoke` calls, once it is created.
+type InvokeWebView = Rc<RefCell<Weak<InnerWebView>>>;
+
+/// Wraps [`WebViewAttributes::ipc_handler`] to call [`WebViewAttributes::ipc_invoke_handler`] with the
+/// `window.ipc.invoke` calls and the [`WebViewAttributes::bindings`] with the calls of their
+/// `window` functions, and settle their promises.
+fn route_invoke_messages(attrs: &mut WebViewAttributes, webview: InvokeWebView) {
+  for (name, _) in &attrs.bindings {
+    attrs.initialization_scripts.push(ipc::binding_script(name));
+  }
+  let bindings = std::mem::take(&mut attrs.bindings)
+    .into_iter()
+    .collect::<HashMap<_, _>>();
+
+  let invoke_handler = attrs.ipc_invoke_handler.take();
+  if invoke_handler.is_none() && bindings.is_empty() && attrs.ipc_handler.is_none() {
+    return;
+  }
+
+  let handler = attrs.ipc_handler.take();
+  attrs.ipc_handler = Some(Box::new(move |message| {
+    let invoke = match ipc::parse_invoke_message(message) {
+      Ok(invoke) => invoke,
+      Err(message) => {
+        if let Some(handler) = &handler {
+          handler(message);
+        }
+        return;
+      }
+    };
+
+    let result = match (invoke.binding, &invoke_handler) {
+      (true, _) => match (bindings.get(&invoke.cmd), invoke.payload) {
+        (Some(binding), serde_json::Value::Array(args)) => binding(args),
+        (Some(_), _) => Err("Invalid arguments".to_string()),
+        (None, _) => Err(format!("No binding `{}`", invoke.cmd)),
+      },
+      (false, Some(invoke_handler)) => invoke_handler(invoke.cmd, invoke.payload),
+      (false, None) => Err(format!("No IPC invoke handler for `{}`", invoke.cmd)),
+    };
+    if let Some(webview) = webview.borrow().upgrade() {
+      let _ = webview.eval(
+        &ipc::invoke_result_script(invoke.id, result),
+        None::<Box<dyn Fn(String) + Send + 'static>>,
+      );
+    }
+  }));
+}
+
+/// Wraps [`WebViewAttributes::ipc_handler`] to deliver the messages of the named channels to
+/// their [`WebViewAttributes::ipc_channels`] handler.
+fn route_ipc_channels(attrs: &mut WebViewAttributes) {
+  if attrs.ipc_channels.is_empty() {
+    return;
+  }
+
+  let channels = std::mem::take(&mut attrs.ipc_channels)
+    .into_iter()
+    .collect::<HashMap<_, _>>();
+  let handler = attrs.ipc_handler.take();
+  attrs.ipc_handler = Some(Box::new(move |message| {
+    match ipc::parse_channel_message(message) {
+      Ok(message) => {
+        if let Some(channel) = channels.get(&message.channel) {
+          channel(message.message);
+        }
+      }
+      Err(message) => {
+        if let Some(handler) = &handler {
+          handler(message);
+        }
+      }
+    }
+  }));
+}
+
+/// Wraps [`WebViewAttributes::ipc_handler`] to reassemble the messages split in chunks by
+/// `window.ipc.postMessage`, before they are routed to the bridge, and to drop the messages
+/// larger than [`WebViewAttributes::max_ipc_message_size_bytes`] or missing a chunk.
+fn reassemble_ipc_chunks(
+  attrs: &mut WebViewAttributes,
+  on_throttled: Option<Rc<dyn Fn(IpcThrottleReason)>>,
+) {
+  let Some(handler) = attrs.ipc_handler.take() else {
+    return;
+  };
+
+  let max_size = attrs.max_ipc_message_size_bytes;
+  let assembler = Rc::new(RefCell::new(ipc::ChunkAssembler::default()));
+
+  // the chunks of the previous page are never completed
+  let assembler_ = assembler.clone();
+  let on_page_load_handler = attrs.on_page_load_handler.take();
+  attrs.on_page_load_handler = Some(Box::new(move |event, url| {
+    if let PageLoadEvent::Started = event {
+      assembler_.borrow_mut().clear();
+    }
+    if let Some(on_page_load_handler) = &on_page_load_handler {
+      on_page_load_handler(event, url);
+    }
+  }));
+
+  attrs.ipc_handler = Some(Box::new(move |message| {
+    let message = assembler.borrow_mut().push(message, max_size);
+    match message {
+      Some(Ok(message)) => handler(message),
+      Some(Err(reason)) => {
+        if let Some(on_throttled) = &on_throttled {
+          on_throttled(reason);
+        }
+      }
+      None => (),
+    }
+  }));
+}
+
+fn check_zoom_limits(min: f64, max: f64) -> Result<()> {
+  if min > 0.0 && min <= max {
+    Ok(())
+  } else {
+    Err(Error::InvalidZoomLimits(min, max))
+  }
+}
+
+fn check_zoom_step(step: f64) -> Result<()> {
+  if step > 0.0 && step.is_finite() {
+    Ok(())
+  } else {
+    Err(Error::InvalidZoomStep(step))
+  }
+}
+
+/// Wraps [`WebViewAttributes::ipc_handler`] and [`WebViewAttributes::ipc_reply_handler`] to deny
+/// the messages of the origins missing from [`WebViewAttributes::ipc_allowed_origins`].
+fn filter_ipc_origins(attrs: &mut WebViewAttributes) {
+  let on_denied = attrs.on_ipc_denied.take();
+  let Some(allowed_origins) = attrs.ipc_allowed_origins.take() else {
+    return;
+  };
+
+  let allowed_origins = allowed_origins
+    .into_iter()
+    .map(|origin| origin.trim_end_matches('/').to_string())
+    .collect::<Vec<_>>();
+  // returns the message when its origin is allowed, reports it otherwise
+  let filter = Rc::new(move |message: String| {
+    let origin = ipc::message_origin();
+    if origin
+      .as_ref()
+      .map_or(false, |origin| allowed_origins.contains(origin))
+    {
+      Some(message)
+    } else {
+      if let Some(on_denied) = &on_denied {
+        on_denied(origin, message);
+      }
+      None
+    }
+  });
+
+  if let Some(handler) = attrs.ipc_handler.take() {
+    let filter = filter.clone();
+    attrs.ipc_handler = Some(Box::new(move |message| {
+      if let Some(message) = filter(message) {
+        handler(message);
+      }
+    }));
+  }
+
+  if let Some(handler) = attrs.ipc_reply_handler.take() {
+    attrs.ipc_reply_handler = Some(Box::new(move |message, replier| match filter(message) {
+      Some(message) => handler(message, replier),
+      None => replier.reply(Err("The origin is not allowed".to_string())),
+    }));
+  }
+}
+
+/// Wraps [`WebViewAttributes::ipc_handler`] to drop the messages exceeding the sliding window
+/// rate limit of [`WebViewAttributes::throttle_ipc_rate`].
+fn throttle_ipc_handler(
+  attrs: &mut WebViewAttributes,
+  on_throttled: Option<Rc<dyn Fn(IpcThrottleReason)>>,
+) {
+  let Some((limit, window)) = attrs.throttle_ipc_rate else {
+    return;
+  };
+  let Some(handler) = attrs.ipc_handler.take() else {
+    return;
+  };
+
+  let timestamps = RefCell::new(VecDeque::<Instant>::new());
+  let dropped = Cell::new(0u32);
+  attrs.ipc_handler = Some(Box::new(move |message| {
+    let now = Instant::now();
+    let mut timestamps = timestamps.borrow_mut();
+    while timestamps
+      .front()
+      .map_or(false, |t| now.duration_since(*t) >= window)
+    {
+      timestamps.pop_front();
+    }
+
+    if timestamps.len() >= limit as usize {
+      dropped.set(dropped.get().saturating_add(1));
+      if let Some(on_throttled) = &on_throttled {
+        on_throttled(IpcThrottleReason::RateExceeded {
+          dropped: dropped.get(),
+        });
+      }
+      return;
+    }
+
+    timestamps.push_back(now);
+    drop(timestamps);
+    dropped.set(0);
+    handler(message)
+  }));
+}
+
+/// Reports pinch zoom changes to [`WebViewAttributes::on_page_zoom_change`] through the bridge,
+/// on the platforms that can't observe them natively.
+#[allow(unused_variables)]
+fn observe_visual_viewport_zoom(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  #[cfg(any(target_os = "macos", target_os = "ios", target_os = "android"))]
+  if let Some(handler) = attrs.on_page_zoom_change.take() {
+    let handler: Rc<dyn Fn(f64)> = Rc::from(handler);
+    let handler_ = handler.clone();
+
+    bridge.register_main_frame(
+      "visual_viewport_zoom",
+      r#"
+  var viewport = window.visualViewport;
+  if (!viewport) return;
+  var scale = viewport.scale;
+  viewport.addEventListener('resize', function () {
+    if (viewport.scale !== scale) {
+      scale = viewport.scale;
+      post(scale);
+    }
+  });"#,
+      move |payload| {
+        if let Some(zoom) = payload.as_f64() {
+          handler_(zoom);
+        }
+      },
+    );
+
+    attrs.on_page_zoom_change = Some(Box::new(move |zoom| handler(zoom)));
+  }
+}
+
+/// Starts moving the window of the webview, once it is created.
+type WindowDragStarter = Rc<RefCell<Option<Box<dyn Fn()>>>>;
+
+/// Reports the primary mouse button presses to [`WebViewAttributes::drag_region_handler`]
+/// through the bridge, on the platforms where the webview content gets the native mouse events.
+#[allow(unused_variables)]
+fn observe_drag_regions(
+  attrs: &mut WebViewAttributes,
+  bridge: &mut Bridge,
+  starter: WindowDragStarter,
+) {
+  #[cfg(any(target_os = "windows", gtk))]
+  if let Some(handler) = attrs.drag_region_handler.take() {
+    bridge.register_main_frame(
+      "drag_region",
+      r#"
+  window.addEventListener('mousedown', function (event) {
+    if (event.button === 0) post({ x: event.clientX, y: event.clientY });
+  }, true);"#,
+      move |payload| {
+        if let Ok(query) = serde_json::from_value::<DragRegionQuery>(payload) {
+          if handler(query) {
+            if let Some(start) = &*starter.borrow() {
+              start();
+            }
+          }
+        }
+      },
+    );
+  }
+}
+
+/// Sets the click-through regions of the webview, once it is created.
+type ClickThroughSetter = Rc<RefCell<Option<Box<dyn Fn(&[Rect])>>>>;
+
+/// Reports the areas of the `data-wry-click-through` elements through the bridge, see
+/// [`WebViewAttributes::click_through_elements`].
+fn observe_click_through_elements(
+  attrs: &mut WebViewAttributes,
+  bridge: &mut Bridge,
+  setter: ClickThroughSetter,
+) {
+  if !attrs.click_through_elements {
+    return;
+  }
+
+  bridge.register_main_frame(
+    "click_through",
+    r#"
+  var current = '[]';
+  var scheduled = false;
+
+  function update() {
+    scheduled = false;
+    var regions = [];
+    document.querySelectorAll('[data-wry-click-through]').forEach(function (element) {
+      var rect = element.getBoundingClientRect();
+      if (rect.width > 0 && rect.height > 0) regions.push([rect.left, rect.top, rect.width, rect.height]);
+    });
+    var serialized = JSON.stringify(regions);
+    if (serialized === current) return;
+    current = serialized;
+    post(regions);
+  }
+
+  function schedule() {
+    if (scheduled) return;
+    scheduled = true;
+    requestAnimationFrame(update);
+  }
+
+  document.addEventListener('DOMContentLoaded', function () {
+    schedule();
+    new MutationObserver(schedule).observe(document.documentElement, {
+      childList: true,
+      subtree: true,
+      attributes: true
+    });
+    window.addEventListener('resize', schedule);
+    window.addEventListener('scroll', schedule, true);
+  });"#,
+    move |payload| {
+      let Ok(regions) = serde_json::from_value::<Vec<(f64, f64, f64, f64)>>(payload) else {
+        return;
+      };
+      let regions = regions
+        .into_iter()
+        .map(|(x, y, width, height)| Rect {
+          x: x.floor() as i32,
+          y: y.floor() as i32,
+          width: width.ceil() as u32,
+          height: height.ceil() as u32,
+        })
+        .collect::<Vec<_>>();
+      if let Some(setter) = &*setter.borrow() {
+        setter(&regions);
+      }
+    },
+  );
+}
+
+/// Reports the audio playback state changes to [`WebViewAttributes::on_web_audio_state_change`]
+/// through the bridge.
+fn observe_web_audio_state(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  if let Some(handler) = attrs.on_web_audio_state_change.take() {
+    bridge.register_main_frame(
+      "web_audio_state",
+      media::WEB_AUDIO_STATE_SCRIPT,
+      move |payload| {
+        if let Ok(state) = serde_json::from_value::<WebAudioState>(payload) {
+          handler(state);
+        }
+      },
+    );
+  }
+}
+
+/// Reports the user inactivity to [`WebViewAttributes::on_idle`] through the bridge.
+fn observe_idle(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  if let Some(handler) = attrs.on_idle.take() {
+    bridge.register_main_frame(
+      "idle",
+      &idle::idle_script(attrs.idle_timeout),
+      move |payload| {
+        if let Some(elapsed) = payload.as_u64() {
+          handler(Duration::from_millis(elapsed));
+        }
+      },
+    );
+  }
+}
+
+/// Reports the failed requests of the page to [`WebViewAttributes::on_fetch_error`] through the bridge.
+fn observe_fetch_errors(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  if let Some(handler) = attrs.on_fetch_error.take() {
+    bridge.register(
+      "fetch_error",
+      fetch_error::fetch_error_script(&bridge::post_message("fetch_error")),
+      move |payload| {
+        if let Some(error) = fetch_error::parse_fetch_error(payload) {
+          handler(error);
+        }
+      },
+    );
+  }
+}
+
+/// Reports the uncaught errors of the page to [`WebViewAttributes::on_page_error`] through the bridge.
+fn observe_page_errors(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  if let Some(handler) = attrs.on_page_error.take() {
+    bridge.register(
+      "page_error",
+      page_error::page_error_script(&bridge::post_message("page_error")),
+      move |payload| {
+        if let Some(error) = page_error::parse_page_error(payload) {
+          handler(error);
+        }
+      },
+    );
+  }
+}
+
+/// Reports the messages of the page console to [`WebViewAttributes::on_console_message`]
+/// through the bridge, on the platforms without a native notification.
+#[allow(unused_variables)]
+fn observe_console_messages(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  #[cfg(not(target_os = "windows"))]
+  if let Some(handler) = attrs.on_console_message.take() {
+    bridge.register(
+      "console_message",
+      console::console_script(&bridge::post_message("console_message")),
+      move |payload| {
+        if let Some((level, message, source, line)) = console::parse_console_message(payload) {
+          handler(level, message, source, line);
+        }
+      },
+    );
+  }
+}
+
+/// JavaScript statements calling the `listener` function after the `history.pushState` and
+/// `history.replaceState` calls, the `history` methods are only patched once for all the scripts.
+#[cfg(not(target_os = "windows"))]
+fn on_history_change_script(listener: &str) -> String {
+  format!(
+    r#"if (!window.__WRY_HISTORY_LISTENERS__) {{
+    var listeners = [];
+    Object.defineProperty(window, '__WRY_HISTORY_LISTENERS__', {{ value: listeners }});
+    ['pushState', 'replaceState'].forEach(function (name) {{
+      var original = history[name];
+      history[name] = function () {{
+        var result = original.apply(this, arguments);
+        listeners.forEach(function (listener) {{ listener(); }});
+        return result;
+      }};
+    }});
+  }}
+  window.__WRY_HISTORY_LISTENERS__.push({listener});"#
+  )
+}
+
+/// Reports the URL changes to [`WebViewAttributes::on_url_changed`] through the bridge,
+/// on the platforms without a native notification.
+#[allow(unused_variables)]
+fn observe_url_changes(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  #[cfg(target_os = "android")]
+  if let Some(handler) = attrs.on_url_changed.take() {
+    bridge.register_main_frame(
+      "url_changed",
+      &format!(
+        r#"
+  var url = location.href;
+  post(url);
+  function update() {{
+    if (location.href !== url) {{
+      url = location.href;
+      post(url);
+    }}
+  }}
+  {}
+  window.addEventListener('popstate', update);
+  window.addEventListener('hashchange', update);"#,
+        on_history_change_script("update")
+      ),
+      move |payload| {
+        if let Some(url) = payload.as_str().and_then(|url| Url::parse(url).ok()) {
+          handler(url);
+        }
+      },
+    );
+  }
+}
+
+/// Reports the same-document navigations to [`WebViewAttributes::on_history_state_changed`]
+/// through the bridge, on the platforms without a native notification.
+#[allow(unused_variables)]
+fn observe_history_state(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  #[cfg(not(target_os = "windows"))]
+  if let Some(handler) = attrs.on_history_state_changed.take() {
+    bridge.register_main_frame(
+      "history_state",
+      &format!(
+        r#"
+  function update() {{
+    post(location.href);
+  }}
+  {}
+  // also fired by the fragment navigations
+  window.addEventListener('popstate', update);"#,
+        on_history_change_script("update")
+      ),
+      move |payload| {
+        if let Some(url) = payload.as_str().and_then(|url| Url::parse(url).ok()) {
+          handler(url);
+        }
+      },
+    );
+  }
+}
+
+/// Reports the load progress to [`WebViewAttributes::on_load_progress`] through the bridge,
+/// on the platforms without a native notification.
+#[allow(unused_variables)]
+fn observe_load_progress(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  #[cfg(target_os = "android")]
+  if let Some(handler) = attrs.on_load_progress.take() {
+    bridge.register_main_frame(
+      "load_progress",
+      r#"
+  var progress = { loading: 0.1, interactive: 0.7, complete: 1.0 };
+  post(progress[document.readyState]);
+  document.addEventListener('readystatechange', function () {
+    post(progress[document.readyState]);
+  });"#,
+      move |payload| {
+        if let Some(progress) = payload.as_f64() {
+          handler(progress);
+        }
+      },
+    );
+  }
+}
+
+/// Reports the lifecycle stages of the frames to [`WebViewAttributes::on_page_lifecycle`] through the bridge.
+fn observe_page_lifecycle(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  if let Some(handler) = attrs.on_page_lifecycle.take() {
+    bridge.register(
+      "page_lifecycle",
+      lifecycle::lifecycle_script(&bridge::post_message("page_lifecycle")),
+      move |payload| {
+        if let Some(event) = lifecycle::parse_lifecycle_event(payload) {
+          handler(event);
+        }
+      },
+    );
+  }
+}
+
+/// Reports the favicon changes to [`WebViewAttributes::on_favicon_changed`] through the bridge,
+/// on the platforms without a native notification.
+#[allow(unused_variables)]
+fn observe_favicon(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  #[cfg(not(target_os = "windows"))]
+  if let Some(handler) = attrs.on_favicon_changed.take() {
+    bridge.register_main_frame("favicon", favicon::FAVICON_SCRIPT, move |payload| {
+      if let Some(png) = favicon::parse_favicon(payload) {
+        handler(png);
+      }
+    });
+  }
+}
+
+/// Reports the scroll offset changes to [`WebViewAttributes::on_scroll`] through the bridge.
+fn observe_scroll(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  if let Some(handler) = attrs.on_scroll.take() {
+    bridge.register_main_frame(
+      "scroll",
+      r#"
+  var pending = false;
+  window.addEventListener('scroll', function () {
+    if (pending) return;
+    pending = true;
+    requestAnimationFrame(function () {
+      pending = false;
+      post([window.scrollX, window.scrollY]);
+    });
+  }, { passive: true });"#,
+      move |payload| {
+        if let Ok((x, y)) = serde_json::from_value::<(f64, f64)>(payload) {
+          handler(x, y);
+        }
+      },
+    );
+  }
+}
+
+/// Reports the selection changes to [`WebViewAttributes::on_selection_changed`] through the bridge.
+fn observe_selection(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  if let Some(handler) = attrs.on_selection_changed.take() {
+    bridge.register_main_frame(
+      "selection",
+      r#"
+  var pending = false;
+  var current = null;
+  document.addEventListener('selectionchange', function () {
+    if (pending) return;
+    pending = true;
+    requestAnimationFrame(function () {
+      pending = false;
+      var text = String(window.getSelection()) || null;
+      if (text === current) return;
+      current = text;
+      post(text);
+    });
+  });"#,
+      move |payload| handler(payload.as_str().map(ToString::to_string)),
+    );
+  }
+}
+
+/// Reports the media capture state changes to [`WebViewAttributes::on_media_capture_state_change`]
+/// through the bridge, on the platforms without a native notification.
+#[allow(unused_variables)]
+fn observe_media_capture_state(attrs: &mut WebViewAttributes, bridge: &mut Bridge) {
+  #[cfg(any(target_os = "windows", target_os = "android"))]
+  if let Some(handler) = attrs.on_media_capture_state_change.take() {
+    bridge.register(
+      "media_capture_state",
+      media::media_capture_state_script(&bridge::post_message("media_capture_state")),
+      move |payload| {
+        if let Ok(state) = serde_json::from_value::<MediaCaptureState>(payload) {
+          handler(state);
+        }
+      },
+    );
+  }
+}
+
+/// Drops the [`BridgeCallbacks`] still waiting for a value when a page starts loading, the page
+/// which would have posted it is gone.
+fn drop_callbacks_on_load(attrs: &mut WebViewAttributes, callbacks: BridgeCallbacks) {
+  let on_page_load_handler = attrs.on_page_load_handler.take();
+  attrs.on_page_load_handler = Some(Box::new(move |event, url| {
+    if let PageLoadEvent::Started = event {
+      callbacks.clear();
+    }
+    if let Some(on_page_load_handler) = &on_page_load_handler {
+      on_page_load_handler(event, url);
+    }
+  }));
+}
+
+/// Wraps the custom protocol responders so HTML responses go through
+/// [`WebViewAttributes::response_transform`] before reaching the webview.
+fn transform_custom_protocol_responses(attrs: &mut WebViewAttributes) {
+  let Some(transform) = attrs.response_transform.take() else {
+    return;
+  };
+  // the responders can be called from other threads
+  let transform: Arc<dyn Fn(Response<Vec<u8>>) -> Response<Vec<u8>> + Send + Sync> =
+    Arc::from(transform);
+  // WebView2 also transforms the documents loaded from the network
+  #[cfg(target_os = "windows")]
+  {
+    let transform = transform.clone();
+    attrs.response_transform = Some(Box::new(move |response| transform(response)));
+  }
+
+  attrs.custom_protocols = std::mem::take(&mut attrs.custom_protocols)
+    .into_iter()
+    .map(|(name, handler)| {
+      let transform = transform.clone();
+      let handler: Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)> =
+        Box::new(move |request, responder| {
+          let transform = transform.clone();
+          let RequestAsyncResponder {
+            responder,
+            stream_responder,
+          } = responder;
+          let responder: Box<dyn FnOnce(Response<Cow<'static, [u8]>>)> =
+            Box::new(move |response| {
+              let is_html = response
+                .headers()
+                .get(http::header::CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok())
+                .map(|content_type| content_type.to_lowercase().starts_with("text/html"))
+                .unwrap_or_default();
+
+              if is_html {
+                let (parts, body) = response.into_parts();
+                let response = transform(Response::from_parts(parts, body.into_owned()));
+                let (parts, body) = response.into_parts();
+                responder(Response::from_parts(parts, body.into()))
+              } else {
+                responder(response)
+              }
+            });
+          handler(
+            request,
+            RequestAsyncResponder {
+              responder,
+              stream_responder,
+            },
+          )
+        });
+      (name, handler)
+    })
+    .collect();
+}
+
+/// Wraps the handlers of the custom protocols with allowed origins in
+/// [`WebViewAttributes::custom_protocol_options`] to answer their CORS requests.
+fn apply_custom_protocol_cors(attrs: &mut WebViewAttributes) {
+  if attrs
+    .custom_protocol_options
+    .values()
+    .all(|options| options.allowed_origins.is_empty())
+  {
+    return;
+  }
+
+  attrs.custom_protocols = std::mem::take(&mut attrs.custom_protocols)
+    .into_iter()
+    .map(|(name, handler)| {
+      let handler = match attrs.custom_protocol_options.get(&name) {
+        Some(options) if !options.allowed_origins.is_empty() => {
+          protocol_options::cors_handler(options.allowed_origins.clone(), handler)
+        }
+        _ => handler,
+      };
+      (name, handler)
+    })
+    .collect();
+}
+
+#[cfg(windows)]
+#[derive(Clone)]
+pub(crate) struct PlatformSpecificWebViewAttributes {
+  additional_browser_args: Option<String>,
+  browser_accelerator_keys: bool,
+  theme: Option<Theme>,
+  https_scheme: bool,
+}
+
+#[cfg(windows)]
+impl Default for PlatformSpecificWebViewAttributes {
+  fn default() -> Self {
+    Self {
+      additional_browser_args: None,
+      browser_accelerator_keys: true, // This is WebView2's default behavior
+      theme: None,
+      https_scheme: false, // To match macOS & Linux behavior in the context of mixed content.
+    }
+  }
+}
+
+#[cfg(windows)]
+pub trait WebViewBuilderExtWindows {
+  /// Pass additional args to WebView2 upon creating the webview.
+  ///
+  /// ## Warning
+  ///
+  /// - Webview instances with different browser arguments must also have different [data directories](struct.WebContext.html#method.new).
+  /// - By default wry passes `--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection`
+  /// `--autoplay-policy=no-user-gesture-required` if autoplay is enabled
+  /// and `--proxy-server=<scheme>://<host>:<port>` if a proxy is set.
+  /// so if you use this method, you have to add these arguments yourself if you want to keep the same behavior.
+  fn with_additional_browser_args<S: Into<String>>(self, additional_args: S) -> Self;
+
+  /// Determines whether browser-specific accelerator keys are enabled. When this setting is set to
+  /// `false`, it disables all accelerator keys that access features specific to a web browser.
+  /// The default value is `true`. See the following link to know more details.
+  ///
+  /// https://learn.microsoft.com/en-us/microsoft-edge/webview2/reference/winrt/microsoft_web_webview2_core/corewebview2settings#arebrowseracceleratorkeysenabled
+  fn with_browser_accelerator_keys(self, enabled: bool) -> Self;
+
+  /// Specifies the theme of webview2. This affects things like `prefers-color-scheme`.
+  ///
+  /// Defaults to [`Theme::Auto`] which will follow the OS defaults.
+  fn with_theme(self, theme: Theme) -> Self;
+
+  /// Determines whether the custom protocols should use `https://<scheme>.path/to/page` instead of the default `http://<scheme>.path/to/page`.
+  ///
+  /// Using a `http` scheme will allow mixed content when trying to fetch `http` endpoints
+  /// and is therefore less secure but will match the behavior of the `<scheme>://path/to/page` protocols used on macOS and Linux.
+  ///
+  /// The default value is `false`.
+  fn with_https_scheme(self, enabled: bool) -> Self;
+}
+
+#[cfg(windows)]
+impl WebViewBuilderExtWindows for WebViewBuilder<'_> {
+  fn with_additional_browser_args<S: Into<String>>(mut self, additional_args: S) -> Self {
+    self.platform_specific.additional_browser_args = Some(additional_args.into());
+    self
+  }
+
+  fn with_browser_accelerator_keys(mut self, enabled: bool) -> Self {
+    self.platform_specific.browser_accelerator_keys = enabled;
+    self
+  }
+
+  fn with_theme(mut self, theme: Theme) -> Self {
+    self.platform_specific.theme = Some(theme);
+    self
+  }
+
+  fn with_https_scheme(mut self, enabled: bool) -> Self {
+    self.platform_specific.https_scheme = enabled;
+    self
+  }
+}
+
+#[cfg(target_os = "android")]
+#[derive(Default)]
+pub(crate) struct PlatformSpecificWebViewAttributes {
+  on_webview_created:
+    Option<Box<dyn Fn(prelude::Context) -> std::result::Result<(), jni::errors::Error> + Send>>,
+  with_asset_loader: bool,
+  asset_loader_domain: Option<String>,
+  https_scheme: bool,
+}
+
+#[cfg(target_os = "android")]
+pub trait WebViewBuilderExtAndroid {
+  fn on_webview_created<
+    F: Fn(prelude::Context<'_, '_>) -> std::result::Result<(), jni::errors::Error> + Send + 'static,
+  >(
+    self,
+    f: F,
+  ) -> Self;
+
+  /// Use [WebViewAssetLoader](https://developer.android.com/reference/kotlin/androidx/webkit/WebViewAssetLoader)
+  /// to load assets from Android's `asset` folder when using `with_url` as `<protocol>://assets/` (e.g.:
+  /// `wry://assets/index.html`). Note that this registers a custom protocol with the provided
+  /// String, similar to [`with_custom_protocol`], but also sets the WebViewAssetLoader with the
+  /// necessary domain (which is fixed as `<protocol>.assets`). This cannot be used in conjunction
+  /// to `with_custom_protocol` for Android, as it changes the way in which requests are handled.
+  #[cfg(feature = "protocol")]
+  fn with_asset_loader(self, protocol: String) -> Self;
+
+  /// Determines whether the custom protocols should use `https://<scheme>.localhost` instead of the default `http://<scheme>.localhost`.
+  ///
+  /// Using a `http` scheme will allow mixed content when trying to fetch `http` endpoints
+  /// and is therefore less secure but will match the behavior of the `<scheme>://localhost` protocols used on macOS and Linux.
+  ///
+  /// The default value is `false`.
+  fn with_https_scheme(self, enabled: bool) -> Self;
+}
+
+#[cfg(target_os = "android")]
+impl WebViewBuilderExtAndroid for WebViewBuilder<'_> {
+  fn on_webview_created<
+    F: Fn(prelude::Context<'_, '_>) -> std::result::Result<(), jni::errors::Error> + Send + 'static,
+  >(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.platform_specific.on_webview_created = Some(Box::new(f));
+    self
+  }
+
+  #[cfg(feature = "protocol")]
+  fn with_asset_loader(mut self, protocol: String) -> Self {
+    // register custom protocol with empty Response return,
+    // this is necessary due to the need of fixing a domain
+    // in WebViewAssetLoader.
+    self.attrs.custom_protocols.push((
+      protocol.clone(),
+      Box::new(|_, api| {
+        api.respond(Response::builder().body(Vec::new()).unwrap());
+      }),
+    ));
+    self.platform_specific.with_asset_loader = true;
+    self.platform_specific.asset_loader_domain = Some(format!("{}.assets", protocol));
+    self
+  }
+
+  fn with_https_scheme(mut self, enabled: bool) -> Self {
+    self.platform_specific.https_scheme = enabled;
+    self
+  }
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd",
+))]
+pub trait WebViewBuilderExtUnix<'a> {
+  /// Create the webview inside a GTK container widget, such as GTK window.
+  ///
+  /// - If the container is [`gtk::Box`], it is added using [`Box::pack_start(webview, true, true, 0)`](gtk::prelude::BoxExt::pack_start).
+  /// - If the container is [`gtk::Fixed`], its [size request](gtk::prelude::WidgetExt::set_size_request) will be set using the (width, height) bounds passed in
+  ///   and will be added to the container using [`Fixed::put`](gtk::prelude::FixedExt::put) using the (x, y) bounds passed in.
+  /// - For all other containers, it will be added using [`gtk::prelude::ContainerExt::add`]
+  ///
+  /// # Panics:
+  ///
+  /// - Panics if [`gtk::init`] was not called in this thread.
+  fn new_gtk<W>(widget: &'a W) -> Self
+  where
+    W: gtk::prelude::IsA<gtk::Container>;
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd",
+))]
+impl<'a> WebViewBuilderExtUnix<'a> for WebViewBuilder<'a> {
+  fn new_gtk<W>(widget: &'a W) -> Self
+  where
+    W: gtk::prelude::IsA<gtk::Container>,
+  {
+    use gdkx11::glib::Cast;
+
+    Self {
+      attrs: WebViewAttributes::default(),
+      window: None,
+      as_child: false,
+      #[allow(clippy::default_constructed_unit_structs)]
+      platform_specific: PlatformSpecificWebViewAttributes::default(),
+      web_context: None,
+      gtk_widget: Some(widget.dynamic_cast_ref().unwrap()),
+    }
+  }
+}
+
+/// The fundamental type to present a [`WebView`].
+///
+/// [`WebViewBuilder`] / [`WebView`] are the basic building blocks to construct WebView contents and
+/// scripts for those who prefer to control fine grained window creation and event handling.
+/// [`WebView`] presents the actual WebView window and let you still able to perform actions on it.
+pub struct WebView {
+  webview: Rc<InnerWebView>,
+  callbacks: BridgeCallbacks,
+  on_closed: Option<Box<dyn FnOnce()>>,
+  on_before_unload: Option<Rc<dyn Fn() -> bool>>,
+}
+
+impl WebView {
+  /// Create a [`WebView`] from from a type that implements [`HasWindowHandle`].
+  /// Note that calling this directly loses
+  /// abilities to initialize scripts, add ipc handler, and many more before starting WebView. To
+  /// benefit from above features, create a [`WebViewBuilder`] instead.
+  ///
+  /// # Platform-specific:
+  ///
+  /// - **Linux**: Only X11 is supported, if you want to support Wayland too, use [`WebViewExtUnix::new_gtk`].
+  ///
+  ///   Although this methods only needs an X11 window handle, you use webkit2gtk, so you still need to initialize gtk
+  ///   by callling [`gtk::init`] and advance its loop alongside your event loop using [`gtk::main_iteration_do`].
+  ///   Checkout the [Platform Considerations](https://docs.rs/wry/latest/wry/#platform-considerations) section in the crate root documentation.
+  /// - **macOS / Windows**: The webview will auto-resize when the passed handle is resized.
+  /// - **Linux (X11)**: Unlike macOS and Windows, the webview will not auto-resize and you'll need to call [`WebView::set_size`] manually.
+  ///
+  /// # Panics:
+  ///
+  /// - Panics if the provided handle was not supported or invalid.
+  /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
+  pub fn new(window: &impl HasWindowHandle) -> Result<Self> {
+    WebViewBuilder::new(window).build()
+  }
+
+  /// Tear down the webview now and call [`WebViewAttributes::on_closed`].
+  ///
+  /// Dropping the [`WebView`] releases the same resources, this method makes the teardown
+  /// explicit: the webview is destroyed, and its child window too if it was created with
+  /// [`WebView::new_as_child`], before the closure is called. Since the [`WebView`] is consumed,
+  /// it can't be used after being closed.
+  pub fn close(self) {
+    let Self {
+      webview,
+      callbacks,
+      on_closed,
+      on_before_unload,
+    } = self;
+
+    drop(webview);
+    drop(callbacks);
+    drop(on_before_unload);
+
+    if let Some(on_closed) = on_closed {
+      on_closed();
+    }
+  }
+
+  /// Ask the page whether it can be closed, honoring its `beforeunload` handlers, e.g. to keep
+  /// unsaved form data, before closing the webview with [`WebView::close`].
+  ///
+  /// The `callback` is called with `true` when the page has no `beforeunload` handler asking
+  /// for a confirmation, or when [`WebViewAttributes::on_before_unload`] confirmed leaving it.
+  /// Without an `on_before_unload` handler, the pages asking for a confirmation can't be left.
+  ///
+  /// The `beforeunload` handlers are run by dispatching a synthetic event to the page.
+  pub fn close_with_confirmation(&self, callback: impl FnOnce(bool) + 'static) -> Result<()> {
+    let on_before_unload = self.on_before_unload.clone();
+    let callback = self.callbacks.register(move |value| {
+      let asks_confirmation = value.as_bool().unwrap_or(false);
+      callback(!asks_confirmation || on_before_unload.map_or(false, |handler| handler()))
+    });
+    self.webview.eval(
+      &before_unload_script(&callback),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  /// Create [`WebViewBuilder`] as a child window inside the provided [`HasWindowHandle`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: This will create the webview as a child window of the `parent` window.
+  /// - **macOS**: This will create the webview as a `NSView` subview of the `parent` window's
+  /// content view.
+  /// - **Linux**: This will create the webview as a child window of the `parent` window. Only X11
+  /// is supported. This method won't work on Wayland.
+  ///
+  ///   Although this methods only needs an X11 window handle, you use webkit2gtk, so you still need to initialize gtk
+  ///   by callling [`gtk::init`] and advance its loop alongside your event loop using [`gtk::main_iteration_do`].
+  ///   Checkout the [Platform Considerations](https://docs.rs/wry/latest/wry/#platform-considerations) section in the crate root documentation.
+  ///
+  ///   If you want to support child webviews on X11 and Wayland at the same time,
+  ///   we recommend using [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
+  /// - **Android/iOS:** Unsupported.
+  ///
+  /// # Panics:
+  ///
+  /// - Panics if the provided handle was not support or invalid.
+  /// - Panics on Linux, if [`gtk::init`] was not called in this thread.
+  pub fn new_as_child(parent: &impl HasWindowHandle) -> Result<Self> {
+    WebViewBuilder::new_as_child(parent).build()
+  }
+
+  /// Set the user agent of the webview, used from the next request on.
+  ///
+  /// Reload the page with [`WebView::reload`] to load it with the new user agent.
+  pub fn set_user_agent(&self, user_agent: &str) -> Result<()> {
+    self.webview.set_user_agent(user_agent)
+  }
+
+  /// Get the current url of the webview
+  pub fn url(&self) -> Url {
+    self.webview.url()
+  }
+
+  /// Get the title of the current document of the webview, empty if it has none.
+  ///
+  /// See [`WebViewAttributes::document_title_changed_handler`] to be notified when it changes.
+  pub fn title(&self) -> String {
+    self.webview.title()
+  }
+
+  /// Get the frames of the page, starting with the main frame.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Only the iframes of the main frame are listed, not the nested ones.
+  /// - **macOS / iOS**: The frames are tracked by an injected script, the frames where it
+  /// didn't run yet are not listed.
+  /// - **Linux / Android**: Only the main frame is listed.
+  pub fn frames(&self) -> Vec<FrameInfo> {
+    self.webview.frames()
+  }
+
+  /// Add an initialization script injected in every frame of the pages loaded afterwards, like
+  /// the [`WebViewAttributes::initialization_scripts`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported.
+  pub fn add_initialization_script(&self, js: &str) -> Result<ScriptId> {
+    self.webview.add_initialization_script(js)
+  }
+
+  /// Remove an initialization script added with [`WebView::add_initialization_script`], the pages
+  /// loaded afterwards don't run it anymore. Removing an unknown script does nothing.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported.
+  pub fn remove_initialization_script(&self, id: ScriptId) -> Result<()> {
+    self.webview.remove_initialization_script(id)
+  }
+
+  /// Add the user stylesheet `css` to the frames of the current page and of the pages loaded
+  /// afterwards, e.g. to theme the page without changing its scripts.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: A `WebKitUserStyleSheet` of the user level.
+  /// - **Windows**: A stylesheet created with the DevTools `CSS` domain in each frame.
+  /// - **macOS / iOS**: WebKit has no public user stylesheets, a `<style>` element is added to the
+  /// documents by an initialization script. The iframes of the current page require macOS 11+ or
+  /// iOS 14+.
+  /// - **Android**: Unsupported.
+  pub fn inject_css(&self, css: &str) -> Result<StyleId> {
+    self.webview.inject_css(css)
+  }
+
+  /// Remove a user stylesheet added with [`WebView::inject_css`] from the frames of the current
+  /// page and of the pages loaded afterwards. Removing an unknown stylesheet does nothing.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported.
+  pub fn remove_css(&self, id: StyleId) -> Result<()> {
+    self.webview.remove_css(id)
+  }
+
+  /// Evaluate and run javascript code in the frames selected by `frame`, a [`FrameInfo::id`] of
+  /// [`WebView::frames`] or a [`FrameSelector`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: Requires macOS 11+ for the iframes.
+  /// - **iOS**: Requires iOS 14+ for the iframes.
+  pub fn evaluate_script_in_frame(&self, frame: impl Into<FrameSelector>, js: &str) -> Result<()> {
+    match frame.into() {
+      FrameSelector::Id(frame_id) => self.webview.eval_in_frame(frame_id, js),
+      FrameSelector::Origin(origin) => {
+        let origin = origin.trim_end_matches('/');
+        for frame in self.frames() {
+          if frame.url.map_or(false, |url| url_origin(&url) == origin) {
+            self.webview.eval_in_frame(frame.id, js)?;
+          }
+        }
+        Ok(())
+      }
+    }
+  }
+
+  /// Evaluate and run javascript code.
+  pub fn evaluate_script(&self, js: &str) -> Result<()> {
+    self
+      .webview
+      .eval(js, None::<Box<dyn Fn(String) + Send + 'static>>)
+  }
+
+  /// Dispatch a `CustomEvent` named `event` on the `window` of the page, its `detail` being the
+  /// JSON serialization of `payload`.
+  ///
+  /// The page listens to it with `window.addEventListener(event, (e) => console.log(e.detail))`.
+  pub fn emit<T: serde::Serialize>(&self, event: &str, payload: T) -> Result<()> {
+    let script = ipc::emit_script(event, &serde_json::to_value(payload)?);
+    self.evaluate_script(&script)
+  }
+
+  /// Create a [`SharedBuffer`] of `len` zeroed bytes, to post to the page with [`WebView::post_shared_buffer`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime 1.0.1661+.
+  pub fn create_shared_buffer(&self, len: usize) -> Result<SharedBuffer> {
+    #[cfg(target_os = "windows")]
+    {
+      self.webview.create_shared_buffer(len)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+      Ok(SharedBuffer::new(len))
+    }
+  }
+
+  /// Post a [`SharedBuffer`] to the page, which receives it in a `sharedbuffer` event of the `window`
+  /// with `window.addEventListener('sharedbuffer', (e) => console.log(e.detail.buffer, e.detail.additionalData))`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Requires WebView2 Runtime 1.0.1661+.
+  /// - **macOS / Linux / iOS / Android**: The page receives a copy of the buffer, `access` is ignored.
+  pub fn post_shared_buffer(
+    &self,
+    buffer: &SharedBuffer,
+    access: SharedBufferAccess,
+    additional_data: Option<&serde_json::Value>,
+  ) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+      self
+        .webview
+        .post_shared_buffer(buffer, access, additional_data)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+      let _ = access;
+      self.evaluate_script(&shared_buffer::post_script(buffer, additional_data))
+    }
+  }
+
+  /// Evaluate and run javascript code with callback function. The evaluation result will be
+  /// serialized into a JSON string and passed to the callback function.
+  ///
+  /// Exception is ignored because of the limitation on windows, see [`WebView::evaluate_script_with_result`]
+  /// to get the exceptions.
+  ///
+  /// - ** Android:** Not implemented yet.
+  pub fn evaluate_script_with_callback(
+    &self,
+    js: &str,
+    callback: impl Fn(String) + Send + 'static,
+  ) -> Result<()> {
+    self.webview.eval(js, Some(callback))
+  }
+
+  /// Evaluate and run javascript code, the `callback` is called with the JSON string of its result,
+  /// or with the message of the exception it threw.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The exceptions are reported from WebView2 Runtime 1.0.2210, older versions
+  /// report them like [`WebView::evaluate_script_with_callback`].
+  /// - **Android**: The exceptions are not reported.
+  pub fn evaluate_script_with_result(
+    &self,
+    js: &str,
+    callback: impl Fn(std::result::Result<String, String>) + Send + 'static,
+  ) -> Result<()> {
+    self.webview.eval_with_result(js, callback)
+  }
+
+  /// Evaluate and run javascript code, the returned future resolves with the JSON string of its
+  /// result or fails with [`Error::ScriptException`], or with [`Error::ScriptNotEvaluated`] when
+  /// the script is never evaluated, e.g. when the webview is dropped first.
+  ///
+  /// The script is evaluated like [`WebView::evaluate_script_with_result`], the future can be
+  /// awaited on any executor.
+  ///
+  /// ## Platform-specific
+  ///
+  /// See [`WebView::evaluate_script_with_result`].
+  #[cfg(feature = "async")]
+  pub fn evaluate_script_async(
+    &self,
+    js: &str,
+  ) -> impl std::future::Future<Output = Result<String>> + Send + 'static {
+    let (future, resolver) = script_future::ScriptFuture::new();
+    let callback_resolver = resolver.clone();
+    if let Err(error) = self.evaluate_script_with_result(js, move |result| {
+      callback_resolver.resolve(result.map_err(Error::ScriptException))
+    }) {
+      resolver.resolve(Err(error));
+    }
+    future
+  }
+
+  /// Evaluate and run javascript code, the `callback` is called with its result deserialized to `T`.
+  ///
+  /// `undefined` is deserialized like `null` on every platform, e.g. to `None` for an `Option`
+  /// or to `()`.
+  ///
+  /// - ** Android:** Not implemented yet.
+  pub fn evaluate_script_as<T: serde::de::DeserializeOwned>(
+    &self,
+    js: &str,
+    callback: impl Fn(Result<T>) + Send + 'static,
+  ) -> Result<()> {
+    self.evaluate_script_with_callback(js, move |result| {
+      callback(deserialize_script_result(&result))
+    })
+  }
+
+  /// Get the scroll offset of the page, in CSS pixels.
+  ///
+  /// The `callback` is called with the horizontal and vertical offsets once they are evaluated.
+  pub fn scroll_offset(&self, callback: impl Fn(f64, f64) + Send + 'static) -> Result<()> {
+    self.evaluate_script_with_callback("[window.scrollX, window.scrollY]", move |result| {
+      if let Ok((x, y)) = serde_json::from_str::<(f64, f64)>(&result) {
+        callback(x, y);
+      }
+    })
+  }
+
+  /// Get the selected text of the page, `None` when nothing is selected.
+  ///
+  /// The `callback` is called with the text once it is evaluated. The selections in iframes are not included.
+  pub fn selected_text(&self, callback: impl Fn(Option<String>) + Send + 'static) -> Result<()> {
+    self.evaluate_script_with_callback("String(window.getSelection())", move |result| {
+      if let Ok(text) = serde_json::from_str::<String>(&result) {
+        callback(Some(text).filter(|text| !text.is_empty()));
+      }
+    })
+  }
+
+  /// Get the serialized HTML of the document of the page, including its doctype.
+  ///
+  /// The `callback` is called with the HTML once it is evaluated. It reflects the current DOM,
+  /// not the source of the page, and doesn't include the content of the iframes.
+  pub fn document_html(&self, callback: impl Fn(String) + Send + 'static) -> Result<()> {
+    self.evaluate_script_with_callback(
+      "(document.doctype ? new XMLSerializer().serializeToString(document.doctype) : '') + document.documentElement.outerHTML",
+      move |result| {
+        if let Ok(html) = serde_json::from_str::<String>(&result) {
+          callback(html);
+        }
+      },
+    )
+  }
+
+  /// Get the rendered text of the page, the `innerText` of its body.
+  ///
+  /// The `callback` is called with the text once it is evaluated.
+  pub fn document_text(&self, callback: impl Fn(String) + Send + 'static) -> Result<()> {
+    self.evaluate_script_with_callback(
+      "document.body ? document.body.innerText : ''",
+      move |result| {
+        if let Ok(text) = serde_json::from_str::<String>(&result) {
+          callback(text);
+        }
+      },
+    )
+  }
+
+  /// Scroll the page to the given offset, in CSS pixels.
+  pub fn set_scroll_offset(&self, x: f64, y: f64) -> Result<()> {
+    self.evaluate_script(&format!("window.scrollTo({x}, {y});"))
+  }
+
+  /// Search `text` in the page and select its first match.
+  ///
+  /// The `callback` is called with the number of matches in the page. Use [`WebView::find_next`]
+  /// and [`WebView::find_previous`] to move through the matches and [`WebView::clear_matches`]
+  /// to end the search.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Uses the `WebKitFindController` of the webview.
+  /// - **macOS / iOS**: Uses `findString:withConfiguration:completionHandler:`, WebKit doesn't
+  /// count the matches so `callback` is called with `1` when the text is found and `0` otherwise.
+  /// Before macOS 11 and iOS 14, the search is done like on Windows.
+  /// - **Windows / Android**: The search is done with the non-standard `window.find()` of the
+  /// page, the matches in iframes are not included.
+  pub fn find(
+    &self,
+    text: &str,
+    options: FindOptions,
+    callback: impl Fn(usize) + Send + 'static,
+  ) -> Result<()> {
+    self.webview.find(text, options, callback)
+  }
+
+  /// Select the next match of the search started by [`WebView::find`].
+  pub fn find_next(&self) -> Result<()> {
+    self.webview.find_next()
+  }
+
+  /// Select the previous match of the search started by [`WebView::find`].
+  pub fn find_previous(&self) -> Result<()> {
+    self.webview.find_previous()
+  }
+
+  /// End the search started by [`WebView::find`] and clear its selected match.
+  pub fn clear_matches(&self) -> Result<()> {
+    self.webview.clear_matches()
+  }
+
+  /// Run a native editing command in the webview, e.g. from the items of an "Edit" menu.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The command is sent through a key event of the DevTools protocol.
+  /// - **Android**: The command is run with `document.execCommand`, [`EditCommand::Paste`] is unsupported.
+  pub fn execute_edit_command(&self, command: EditCommand) -> Result<()> {
+    self.webview.execute_edit_command(command)
+  }
+
+  /// Launch print modal for the webview content.
+  pub fn print(&self) -> Result<()> {
+    self.webview.print();
+    Ok(())
+  }
+
+  /// Open the web inspector which is usually called dev tool.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android / iOS:** Not supported.
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  pub fn open_devtools(&self) {
+    self.webview.open_devtools();
+  }
+
+  /// Close the web inspector which is usually called dev tool.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android / iOS:** Not supported.
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  pub fn close_devtools(&self) {
+    self.webview.close_devtools();
+  }
+
+  /// Gets the devtool window's current visibility state.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android / iOS:** Not supported.
+  #[cfg(any(debug_assertions, feature = "devtools"))]
+  pub fn is_devtools_open(&self) -> bool {
+    self.webview.is_devtools_open()
+  }
+
+  /// Set the webview zoom level
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported.
+  /// - **macOS**: available on macOS 11+ only.
+  /// - **iOS**: available on iOS 14+ only.
+  pub fn zoom(&self, scale_factor: f64) {
+    self.webview.zoom(scale_factor);
+  }
+
+  /// Get the webview zoom level, see [`WebView::zoom`].
+  ///
+  /// Pinch zoom is not included, see [`WebViewAttributes::on_page_zoom_change`] to be notified
+  /// of all the zoom changes.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported, always returns `1.0`.
+  /// - **macOS**: available on macOS 11+ only.
+  /// - **iOS**: available on iOS 14+ only.
+  pub fn zoom_factor(&self) -> f64 {
+    self.webview.zoom_factor()
+  }
+
+  /// Enable or disable page zooming by hotkeys and gestures,
+  /// see [`WebViewAttributes::zoom_hotkeys_enabled`].
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Linux / Android / iOS**: Not supported.
+  pub fn set_zoom_hotkeys_enabled(&self, enabled: bool) -> Result<()> {
+    self.webview.set_zoom_hotkeys_enabled(enabled)
+  }
+
+  /// Set whether the timers and the animation frames of the page are throttled while the webview is
+  /// hidden or occluded, see [`WebViewBuilder::with_background_throttling`].
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: Unsupported, the throttling is set by the browser arguments when the webview is created.
+  /// - **macOS / iOS**: Requires macOS 14 / iOS 17 or higher.
+  /// - **Linux / Android**: Unsupported.
+  pub fn set_background_throttling(&self, enabled: bool) -> Result<()> {
+    self.webview.set_background_throttling(enabled)
+  }
+
+  /// Restrict the zoom factor between `min` and `max`, the current zoom factor is clamped if needed.
+  ///
+  /// Applies to [`WebView::zoom`] and to the zoom changes of the user.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: The zoom factor is also limited between `0.25` and `5.0` by WebView2.
+  /// - **macOS / iOS**: Only [`WebView::zoom`] changes the page zoom.
+  /// - **Android**: Not supported, like [`WebView::zoom`].
+  pub fn set_zoom_limits(&self, min: f64, max: f64) -> Result<()> {
+    check_zoom_limits(min, max)?;
+    self.webview.set_zoom_limits(min, max);
+    Ok(())
+  }
+
+  /// Specify the webview background color.
+  ///
+  /// The color uses the RGBA format.
+  ///
+  /// ## Platfrom-specific:
+  ///
+  /// - **macOS / iOS**: Not implemented.
+  /// - **Windows**:
+  ///   - On Windows 7, transparency is not supported and the alpha value will be ignored.
+  ///   - On Windows higher than 7: translucent colors are not supported so any alpha value other than `0` will be replaced by `255`
+  pub fn set_background_color(&self, background_color: RGBA) -> Result<()> {
+    self.webview.set_background_color(background_color)
+  }
+
+  /// Load the given HTML string, replacing the current page.
+  pub fn load_html(&self, html: &str) {
+    self.webview.load_html(html, None)
+  }
+
+  /// Load the given HTML string, resolving its relative URLs against `base_url`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: A `<base>` element is prepended to the HTML, the relative URLs are resolved
+  /// against `base_url` but the page origin is still opaque.
+  pub fn load_html_with_base_url(&self, html: &str, base_url: &Url) {
+    self.webview.load_html(html, Some(base_url.as_str()))
+  }
+
+  /// Navigate to the specified url
+  pub fn load_url(&self, url: &str) {
+    self.webview.load_url(url)
+  }
+
+  /// Navigate to the specified url using the specified headers
+  pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) {
+    self.webview.load_url_with_headers(url, headers)
+  }
+
+  /// Navigate with the given request, e.g. to submit a `POST` form from the host.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Only `GET` requests without a body are supported, returns [`Error::NotSupported`] otherwise.
+  /// - **Android**: Only `GET` requests without a body, and `POST` requests without headers are supported,
+  /// returns [`Error::NotSupported`] otherwise. The `POST` body is sent as `application/x-www-form-urlencoded`.
+  pub fn load_request(&self, request: Request<Vec<u8>>) -> Result<()> {
+    self.webview.load_request(request)
+  }
+
+  /// Navigate to a `data:` URL built from the provided bytes and MIME type.
+  ///
+  /// This is useful to display arbitrary binary content (PDFs, images...) without
+  /// registering a custom protocol or writing it to disk first.
+  ///
+  /// Returns [`Error::InvalidMimeType`] if `mime_type` is not a well-formed `type/subtype` string.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows:** the resulting URL can not be larger than 2 MB (2 * 1024 * 1024 bytes) in total size,
+  /// same as [`WebViewAttributes::html`]. Use a custom protocol for larger data.
+  pub fn navigate_to_data_url(
+    &self,
+    data: &[u8],
+    mime_type: &str,
+    encoding: DataUrlEncoding,
+  ) -> Result<()> {
+    let url = data_url(data, mime_type, encoding)?;
+    self.webview.load_url(&url);
+    Ok(())
+  }
+
+  /// Take a PNG snapshot of the element with the given `id` attribute.
+  ///
+  /// The `callback` receives the PNG encoded bytes or [`Error::SnapshotError`] if the
+  /// element doesn't exist or the snapshot couldn't be taken. Only the part of the element
+  /// currently visible in the viewport is captured.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows:** Uses the `Page.captureScreenshot` DevTools protocol method.
+  /// - **Android:** Unsupported, the callback always receives an error.
+  pub fn snapshot_dom_to_image(
+    &self,
+    element_id: &str,
+    callback: Box<dyn FnOnce(Result<Vec<u8>>)>,
+  ) {
+    self.webview.snapshot(
+      &element_rect_script(element_id),
+      &format!("element `{element_id}`"),
+      callback,
+    )
+  }
+
+  /// Capture the currently rendered viewport as a [`FrozenDom`].
+  ///
+  /// Combined with [`WebView::restore_dom`] and [`WebView::resume_rendering`], this allows
+  /// showing the previous frame while the page content changes, e.g. for page transitions.
+  ///
+  /// The frame is passed to `callback` instead of being returned since the platforms capture
+  /// it asynchronously, and blocking until it is captured would need a nested event loop.
+  /// The [`FrozenDom`] is `Send` and can be restored later from any webview.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android:** Unsupported, the callback always receives an error.
+  pub fn freeze_dom(&self, callback: Box<dyn FnOnce(Result<FrozenDom>)>) {
+    self.webview.snapshot(
+      VIEWPORT_RECT_SCRIPT,
+      "viewport",
+      Box::new(move |png| callback(png.map(|png| FrozenDom { png }))),
+    )
+  }
+
+  /// Display a [`FrozenDom`] as a native overlay over the webview until [`WebView::resume_rendering`]
+  /// is called, the overlay stays while other pages are loaded.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS / iOS**: The overlay is an image view added over the `WKWebView`.
+  /// - **Windows**: The overlay is a child window placed over the webview, it isn't resized
+  /// with the webview.
+  /// - **Linux / Android**: There is no native overlay, an image element is added to the
+  /// current document instead. The scripts of the page can see it, and it is removed with the
+  /// document once the next page load commits.
+  pub fn restore_dom(&self, frozen: FrozenDom) -> Result<()> {
+    self.webview.restore_dom(frozen.png)
+  }
+
+  /// Remove the overlay displayed by [`WebView::restore_dom`].
+  pub fn resume_rendering(&self) -> Result<()> {
+    self.webview.resume_rendering()
+  }
+
+  /// Clear all browsing data
+  pub fn clear_all_browsing_data(&self) -> Result<()> {
+    self.webview.clear_all_browsing_data()
+  }
+
+  /// Get the webview bounds.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: In physical pixels.
+  /// - **Linux / macOS / iOS**: In logical pixels.
+  /// - **Android**: Unsupported, always zero.
+  pub fn bounds(&self) -> Rect {
+    self.webview.bounds()
+  }
+
+  /// Set the regions of the webview that don't receive the mouse input, letting it pass to the native
+  /// views under the webview, e.g. for transparent overlays where only a part of the page is interactive.
+  ///
+  /// The regions are relative to the top-left corner of the webview, in logical pixels, and an empty
+  /// slice removes them. See also [`WebViewAttributes::click_through_elements`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Only supported by the child webviews, the regions are not rendered either.
+  /// - **Android / iOS**: Unsupported.
+  pub fn set_click_through_regions(&self, regions: &[Rect]) -> Result<()> {
+    self.webview.set_click_through_regions(regions)
+  }
+
+  /// Set the webview bounds, in the same units as [`WebView::bounds`].
+  ///
+  /// This is only effective if the webview was created as a child
+  /// or created using [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
+  pub fn set_bounds(&self, bounds: Rect) {
+    self.webview.set_bounds(bounds)
+  }
+
+  /// Get the webview bounds in logical pixels, see [`WebView::scale_factor`].
+  pub fn logical_bounds(&self) -> Rect {
+    let bounds = self.webview.bounds();
+    if cfg!(target_os = "windows") {
+      bounds.to_logical(self.webview.scale_factor())
+    } else {
+      bounds
+    }
+  }
+
+  /// Get the webview bounds in physical pixels, see [`WebView::scale_factor`].
+  pub fn physical_bounds(&self) -> Rect {
+    let bounds = self.webview.bounds();
+    if cfg!(target_os = "windows") {
+      bounds
+    } else {
+      bounds.to_physical(self.webview.scale_factor())
+    }
+  }
+
+  /// Set the webview bounds in logical pixels, see [`WebView::set_bounds`].
+  pub fn set_logical_bounds(&self, bounds: Rect) {
+    if cfg!(target_os = "windows") {
+      self
+        .webview
+        .set_bounds(bounds.to_physical(self.webview.scale_factor()))
+    } else {
+      self.webview.set_bounds(bounds)
+    }
+  }
+
+  /// Set the webview bounds in physical pixels, see [`WebView::set_bounds`].
+  pub fn set_physical_bounds(&self, bounds: Rect) {
+    if cfg!(target_os = "windows") {
+      self.webview.set_bounds(bounds)
+    } else {
+      self
+        .webview
+        .set_bounds(bounds.to_logical(self.webview.scale_factor()))
+    }
+  }
+
+  /// The ratio between the physical and the logical pixels of the monitor showing the webview,
+  /// e.g. `2.0` on a high DPI monitor.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: A child webview is rescaled when its parent moves to a monitor with a different DPI.
+  /// - **Android**: Unsupported, always `1.0`.
+  pub fn scale_factor(&self) -> f64 {
+    self.webview.scale_factor()
+  }
+
+  /// Shows or hides the webview.
+  pub fn set_visible(&self, visible: bool) {
+    self.webview.set_visible(visible)
+  }
+
+  /// Try moving focus to the webview, e.g. to give the keyboard focus back to the page
+  /// from a native control. See also [`WebViewAttributes::focused`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported.
+  pub fn focus(&self) {
+    self.webview.focus()
+  }
+
+  /// Expand the hit-test area of interactive elements by `radius_css_px` CSS pixels.
+  ///
+  /// Taps landing on a non-interactive area are redirected to the closest link, button or
+  /// form control within the radius, which helps when serving content designed for a mouse
+  /// on touch screens. A radius of `0.` disables it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Windows**: Applies to the current page and the pages loaded afterwards.
+  /// - **Android**: Only applies to the current page.
+  /// - **macOS / Linux**: Unsupported, this is a no-op.
+  pub fn set_touch_adjust_radius(&self, radius_css_px: f64) {
+    self.webview.set_touch_adjust_radius(radius_css_px)
+  }
+
+  /// Reload the current page.
+  pub fn reload(&self) {
+    self.webview.reload()
+  }
+
+  /// Stop loading the current page, if it is still loading.
+  pub fn stop(&self) {
+    self.webview.stop()
+  }
+
+  /// Reload the current page, bypassing the local caches.
+  ///
+  /// The HTTP cache of the webview is cleared before reloading, for every origin since the
+  /// platforms can't clear the cache of a single one, so the resources of the page are loaded
+  /// again too. Useful during development to make sure the latest version of the page is loaded.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: The page is requested again with `Cache-Control: no-cache, no-store, must-revalidate`
+  /// and `Pragma: no-cache` headers.
+  /// - **Windows**: The cache is only cleared with WebView2 Runtime 1.0.1245.22 and later, which
+  /// provides `ICoreWebView2Profile2`.
+  /// - **Android**: The cache is shared by all the webviews of the application.
+  pub fn reload_ignoring_local_cache(&self) {
+    self.webview.reload_ignoring_local_cache()
+  }
+
+  /// Get the stats of the active WebRTC peer connections of the page, i.e. the ones with a
+  /// `connected` or `completed` ICE connection state.
+  ///
+  /// Only the numeric stats are collected, and the `callback` receives an empty list if the
+  /// page has no active peer connection, or if [`WebViewBuilder::with_webrtc_stats`] was not
+  /// enabled. The `callback` is dropped without being called if the page navigates first.
+  pub fn get_webrtc_stats(&self, callback: Box<dyn FnOnce(Vec<RtcStats>)>) -> Result<()> {
+    let callback = self
+      .callbacks
+      .register(move |value| callback(webrtc::parse_stats(value)));
+    self.webview.eval(
+      &webrtc::stats_script(&callback),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  /// Restart the inactivity timer of [`WebViewAttributes::on_idle`], as if the user interacted
+  /// with the page.
+  ///
+  /// Does nothing if no `on_idle` handler was set.
+  pub fn reset_idle_timer(&self) -> Result<()> {
+    self.webview.eval(
+      idle::RESET_IDLE_TIMER_SCRIPT,
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  /// Set the level of the window containing the webview, i.e. whether it floats above or stays
+  /// below the other windows.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: Sets the `NSWindow` level to `NSNormalWindowLevel`, `NSFloatingWindowLevel`
+  /// or the desktop icon window level.
+  /// - **Windows**: Moves the root window to the top-most windows or below all the windows,
+  /// the windows level is not kept when another window is placed at the bottom.
+  /// - **Linux**: Uses [`gtk::Window::set_keep_above`] and [`gtk::Window::set_keep_below`], requires
+  /// the webview to be in a [`gtk::Window`].
+  /// - **iOS / Android**: Unsupported, returns [`Error::NotSupported`].
+  pub fn set_window_level(&self, level: WindowLevel) -> Result<()> {
+    self.webview.set_window_level(level)
+  }
+
+  /// Change the stacking order of the webview among the other children of its parent,
+  /// e.g. to order overlapping child webviews.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / Linux**: Only supported for webviews created with [`WebView::new_as_child`]
+  /// or [`WebViewBuilder::new_as_child`], returns [`Error::NotAChildWebView`] otherwise.
+  /// - **Android**: Unsupported, returns [`Error::NotSupported`].
+  pub fn set_z_order(&self, order: ZOrder) -> Result<()> {
+    self.webview.set_z_order(order)
+  }
+
+  /// Move the webview above the other children of its parent, see [`WebView::set_z_order`].
+  pub fn bring_to_front(&self) -> Result<()> {
+    self.set_z_order(ZOrder::Top)
+  }
+
+  /// Move the webview below the other children of its parent, see [`WebView::set_z_order`].
+  pub fn send_to_back(&self) -> Result<()> {
+    self.set_z_order(ZOrder::Bottom)
+  }
+
+  /// Move the webview to another parent window or view, keeping its page and state.
+  ///
+  /// The webview keeps its bounds relative to the new parent.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / Linux**: Only supported for webviews created with [`WebView::new_as_child`]
+  /// or [`WebViewBuilder::new_as_child`], returns [`Error::NotAChildWebView`] otherwise.
+  /// - **Linux**: Only X11 is supported.
+  /// - **Android**: Unsupported, returns [`Error::NotSupported`].
+  pub fn set_parent(&self, parent: &impl HasWindowHandle) -> Result<()> {
+    self.webview.set_parent(parent)
+  }
+
+  /// Mute or unmute the audio of the webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS / Android**: Mutes the `<audio>` and `<video>` elements of the page with a script,
+  /// the Web Audio API is not muted.
+  /// - **Android**: Only the current page is muted, the state is not kept on navigation.
+  pub fn set_audio_muted(&self, muted: bool) -> Result<()> {
+    self.webview.set_audio_muted(muted)
   }
 
-  /// Get the current url of the webview
-  pub fn url(&self) -> Url {
-    self.webview.url()
+  /// Whether the audio of the webview is muted, see [`WebView::set_audio_muted`].
+  pub fn is_audio_muted(&self) -> bool {
+    self.webview.is_audio_muted()
   }
 
-  /// Evaluate and run javascript code.
-  pub fn evaluate_script(&self, js: &str) -> Result<()> {
-    self
-      .webview
-      .eval(js, None::<Box<dyn Fn(String) + Send + 'static>>)
+  /// Whether the page is currently playing audio, even if the webview is muted.
+  ///
+  /// See [`WebViewAttributes::on_web_audio_state_change`] to be notified of the audio playback changes.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS / Android**: Unsupported, returns [`Error::NotSupported`].
+  pub fn is_playing_audio(&self) -> Result<bool> {
+    self.webview.is_playing_audio()
   }
 
-  /// Evaluate and run javascript code with callback function. The evaluation result will be
-  /// serialized into a JSON string and passed to the callback function.
+  /// Get the entries of the back/forward navigation history, ordered from the oldest to the newest.
   ///
-  /// Exception is ignored because of the limitation on windows. You can catch it yourself and return as string as a workaround.
+  /// ## Platform-specific
   ///
-  /// - ** Android:** Not implemented yet.
-  pub fn evaluate_script_with_callback(
-    &self,
-    js: &str,
-    callback: impl Fn(String) + Send + 'static,
-  ) -> Result<()> {
-    self.webview.eval(js, Some(callback))
+  /// - **Windows**: The history is read with the `Page.getNavigationHistory` method of the DevTools
+  /// protocol, `callback` is called once it completes.
+  /// - **Android**: The history entries are not exposed, only the current entry is returned.
+  pub fn get_history(&self, callback: Box<dyn FnOnce(Vec<HistoryEntry>)>) {
+    self.webview.history(callback)
   }
 
-  /// Launch print modal for the webview content.
-  pub fn print(&self) -> Result<()> {
-    self.webview.print();
-    Ok(())
+  /// Get the entries of the back/forward navigation history, ordered from the oldest to the newest,
+  /// e.g. to show the menu of a long-pressed back button.
+  ///
+  /// The entries are the ones of [`WebView::get_history`], their indices can be passed to
+  /// [`WebView::go_to_history_entry`]. See [`WebView::get_history`] for the platform-specific notes.
+  pub fn navigation_history(&self, callback: Box<dyn FnOnce(Vec<HistoryEntry>)>) {
+    self.get_history(callback)
   }
 
-  /// Open the web inspector which is usually called dev tool.
+  /// Navigate to the entry at `index` of the history returned by [`WebView::navigation_history`].
+  ///
+  /// The history is read again before navigating, `callback` receives
+  /// [`Error::HistoryIndexOutOfBounds`] if it has no entry at `index`.
   ///
   /// ## Platform-specific
   ///
-  /// - **Android / iOS:** Not supported.
-  #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn open_devtools(&self) {
-    self.webview.open_devtools();
+  /// - **Windows**: `callback` is called once the history is read with the DevTools protocol.
+  /// - **Android**: Only the current entry, at index `0`, is known.
+  pub fn go_to_history_entry(&self, index: usize, callback: Box<dyn FnOnce(Result<()>)>) {
+    self.webview.go_to_history_index(index, callback)
   }
 
-  /// Close the web inspector which is usually called dev tool.
+  /// Navigate to an entry of the history returned by [`WebView::get_history`].
+  ///
+  /// The entry is located by its position relative to the current entry at the time of
+  /// [`WebView::get_history`], the history must not have changed in the meantime.
   ///
   /// ## Platform-specific
   ///
-  /// - **Windows / Android / iOS:** Not supported.
-  #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn close_devtools(&self) {
-    self.webview.close_devtools();
+  /// - **Windows / Android**: Uses `history.go()`.
+  pub fn navigate_to_history_entry(&self, entry: &HistoryEntry) -> Result<()> {
+    self.webview.navigate_to_history_offset(entry.offset)
   }
 
-  /// Gets the devtool window's current visibility state.
+  /// Save the navigation history and the scroll offset of the webview, e.g. to restore a tab
+  /// with [`WebView::restore_state`] after the application restarts.
+  ///
+  /// The state is opaque and platform-specific, it can only be restored on the same platform.
+  /// It is passed to `callback` since some platforms read it asynchronously.
   ///
   /// ## Platform-specific
   ///
-  /// - **Windows / Android / iOS:** Not supported.
-  #[cfg(any(debug_assertions, feature = "devtools"))]
-  pub fn is_devtools_open(&self) -> bool {
-    self.webview.is_devtools_open()
+  /// - **Windows**: The history is read with the `Page.getNavigationHistory` method of the DevTools
+  /// protocol, `callback` is called once it completes. WebView2 can't restore the history, so
+  /// [`WebView::restore_state`] rebuilds it by loading its entries in order.
+  /// - **macOS / iOS**: Uses `interactionState`, requires macOS 12+ or iOS 15+.
+  /// - **Linux**: Uses the session state of WebKitGTK.
+  /// - **Android**: Unsupported, `callback` receives [`Error::NotSupported`].
+  pub fn save_state(&self, callback: Box<dyn FnOnce(Result<Vec<u8>>)>) {
+    self.webview.save_state(callback)
   }
 
-  /// Set the webview zoom level
+  /// Restore a state saved by [`WebView::save_state`] and load its current page.
   ///
-  /// ## Platform-specific:
-  ///
-  /// - **Android**: Not supported.
-  /// - **macOS**: available on macOS 11+ only.
-  /// - **iOS**: available on iOS 14+ only.
-  pub fn zoom(&self, scale_factor: f64) {
-    self.webview.zoom(scale_factor);
+  /// See [`WebView::save_state`] for the platform-specific notes.
+  pub fn restore_state(&self, state: &[u8]) -> Result<()> {
+    self.webview.restore_state(state)
   }
 
-  /// Specify the webview background color.
+  /// Register a custom protocol after the webview is built, or replace the handler of a
+  /// registered one, see [`WebViewBuilder::with_asynchronous_custom_protocol`].
   ///
-  /// The color uses the RGBA format.
+  /// The handler receives the requests as is, the custom protocol options and transforms set on
+  /// the [`WebViewBuilder`] don't apply to it.
   ///
-  /// ## Platfrom-specific:
+  /// ## Platform-specific
   ///
-  /// - **macOS / iOS**: Not implemented.
-  /// - **Windows**:
-  ///   - On Windows 7, transparency is not supported and the alpha value will be ignored.
-  ///   - On Windows higher than 7: translucent colors are not supported so any alpha value other than `0` will be replaced by `255`
-  pub fn set_background_color(&self, background_color: RGBA) -> Result<()> {
-    self.webview.set_background_color(background_color)
-  }
-
-  /// Navigate to the specified url
-  pub fn load_url(&self, url: &str) {
-    self.webview.load_url(url)
+  /// - **Linux**: The schemes are registered to the [`WebContext`], returns
+  /// [`Error::DuplicateCustomProtocol`] if another webview of the context registered `name`.
+  /// - **macOS / iOS**: Only replaces the handler of a protocol registered with the
+  /// [`WebViewBuilder`], the schemes are part of the configuration of the `WKWebView`. Returns
+  /// [`Error::NotSupported`] for the other protocols, register them with the builder, e.g. with
+  /// a handler answering `404 Not Found` until this method replaces it.
+  /// - **Android**: Unsupported, returns [`Error::NotSupported`], the custom protocols can only
+  /// be registered with the [`WebViewBuilder`].
+  #[cfg(feature = "protocol")]
+  pub fn register_protocol<F>(&self, name: String, handler: F) -> Result<()>
+  where
+    F: Fn(Request<Vec<u8>>, RequestAsyncResponder) + 'static,
+  {
+    self.webview.register_protocol(name, Box::new(handler))
   }
 
-  /// Navigate to the specified url using the specified headers
-  pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) {
-    self.webview.load_url_with_headers(url, headers)
+  /// Unregister the custom protocol `name`, its requests are no longer handled. Does nothing if
+  /// the protocol is not registered.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / macOS / iOS**: The scheme stays registered, its requests get a
+  /// `404 Not Found` response.
+  /// - **Android**: Unsupported, returns [`Error::NotSupported`].
+  #[cfg(feature = "protocol")]
+  pub fn unregister_protocol(&self, name: &str) -> Result<()> {
+    self.webview.unregister_protocol(name)
   }
 
-  /// Clear all browsing data
-  pub fn clear_all_browsing_data(&self) -> Result<()> {
-    self.webview.clear_all_browsing_data()
+  /// Navigate to the previous page of the history, if any.
+  pub fn go_back(&self) {
+    self.webview.go_back()
   }
 
-  pub fn bounds(&self) -> Rect {
-    self.webview.bounds()
+  /// Navigate to the next page of the history, if any.
+  pub fn go_forward(&self) {
+    self.webview.go_forward()
   }
 
-  /// Set the webview bounds.
-  ///
-  /// This is only effective if the webview was created as a child
-  /// or created using [`WebViewBuilderExtUnix::new_gtk`] with [`gtk::Fixed`].
-  pub fn set_bounds(&self, bounds: Rect) {
-    self.webview.set_bounds(bounds)
+  /// Whether there is a previous page in the history to navigate to with [`WebView::go_back`].
+  pub fn can_go_back(&self) -> bool {
+    self.webview.can_go_back()
   }
 
-  /// Shows or hides the webview.
-  pub fn set_visible(&self, visible: bool) {
-    self.webview.set_visible(visible)
+  /// Whether there is a next page in the history to navigate to with [`WebView::go_forward`].
+  pub fn can_go_forward(&self) -> bool {
+    self.webview.can_go_forward()
   }
 
-  /// Try moving focus to the webview.
-  pub fn focus(&self) {
-    self.webview.focus()
+  /// Whether the webview is visible, see [`WebView::set_visible`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android**: Unsupported, always returns `true`.
+  pub fn is_visible(&self) -> bool {
+    self.webview.is_visible()
   }
 }
 
@@ -1458,6 +5018,20 @@ pub trait WebViewExtWindows {
   /// [1]: https://learn.microsoft.com/en-us/dotnet/api/microsoft.web.webview2.core.corewebview2memoryusagetargetlevel
   /// [2]: https://learn.microsoft.com/en-us/dotnet/api/microsoft.web.webview2.core.corewebview2.memoryusagetargetlevel?view=webview2-dotnet-1.0.2088.41#remarks
   fn set_memory_usage_level(&self, level: MemoryUsageLevel);
+
+  /// Exposes the COM `object` to Javascript as `chrome.webview.hostObjects[name]`, whose
+  /// methods and properties are called through promises, or synchronously through
+  /// `chrome.webview.hostObjects.sync[name]`.
+  ///
+  /// Adding an object with the name of an existing one replaces it.
+  ///
+  /// See the [guide for WebView2][1] for more details.
+  ///
+  /// [1]: https://learn.microsoft.com/en-us/microsoft-edge/webview2/how-to/hostobject
+  fn add_host_object(&self, name: &str, object: &IDispatch) -> Result<()>;
+
+  /// Removes the host object `name` added with [`WebViewExtWindows::add_host_object`].
+  fn remove_host_object(&self, name: &str) -> Result<()>;
 }
 
 #[cfg(target_os = "windows")]
@@ -1473,6 +5047,14 @@ impl WebViewExtWindows for WebView {
   fn set_memory_usage_level(&self, level: MemoryUsageLevel) {
     self.webview.set_memory_usage_level(level);
   }
+
+  fn add_host_object(&self, name: &str, object: &IDispatch) -> Result<()> {
+    self.webview.add_host_object(name, object)
+  }
+
+  fn remove_host_object(&self, name: &str) -> Result<()> {
+    self.webview.remove_host_object(name)
+  }
 }
 
 /// Additional methods on `WebView` that are specific to Linux.
@@ -1532,7 +5114,7 @@ impl WebViewExtMacOS for WebView {
   }
 
   fn ns_window(&self) -> cocoa::base::id {
-    self.webview.ns_window
+    self.webview.ns_window.get()
   }
 }
 
@@ -1593,6 +5175,369 @@ pub enum PageLoadEvent {
   Finished,
 }
 
+/// Physical key codes, named after the US keyboard layout.
+///
+/// See [`WebViewAttributes::zoom_reset_key`] and [`WebViewAttributes::on_accelerator_key`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+  Digit0,
+  Digit1,
+  Digit2,
+  Digit3,
+  Digit4,
+  Digit5,
+  Digit6,
+  Digit7,
+  Digit8,
+  Digit9,
+  Numpad0,
+  Numpad1,
+  Numpad2,
+  Numpad3,
+  Numpad4,
+  Numpad5,
+  Numpad6,
+  Numpad7,
+  Numpad8,
+  Numpad9,
+  KeyA,
+  KeyB,
+  KeyC,
+  KeyD,
+  KeyE,
+  KeyF,
+  KeyG,
+  KeyH,
+  KeyI,
+  KeyJ,
+  KeyK,
+  KeyL,
+  KeyM,
+  KeyN,
+  KeyO,
+  KeyP,
+  KeyQ,
+  KeyR,
+  KeyS,
+  KeyT,
+  KeyU,
+  KeyV,
+  KeyW,
+  KeyX,
+  KeyY,
+  KeyZ,
+  Backspace,
+  Delete,
+  Escape,
+  Tab,
+  Enter,
+  Space,
+  ArrowLeft,
+  ArrowUp,
+  ArrowRight,
+  ArrowDown,
+  F1,
+  F2,
+  F3,
+  F4,
+  F5,
+  F6,
+  F7,
+  F8,
+  F9,
+  F10,
+  F11,
+  F12,
+}
+
+impl KeyCode {
+  /// Every key code, to look up the native key codes of the platforms.
+  #[allow(dead_code)]
+  pub(crate) const ALL: [KeyCode; 69] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Backspace,
+    KeyCode::Delete,
+    KeyCode::Escape,
+    KeyCode::Tab,
+    KeyCode::Enter,
+    KeyCode::Space,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowRight,
+    KeyCode::ArrowDown,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+  ];
+}
+
+/// A key pressed in the webview, see [`WebViewAttributes::on_accelerator_key`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AcceleratorKeyEvent {
+  /// The pressed key.
+  pub key: KeyCode,
+  /// Whether `Ctrl` is held.
+  pub ctrl: bool,
+  /// Whether `Shift` is held.
+  pub shift: bool,
+  /// Whether `Alt`, or `Option` on macOS, is held.
+  pub alt: bool,
+  /// Whether the `Windows`, `Super` or `Cmd` key is held.
+  pub meta: bool,
+}
+
+/// A position in the webview checked by [`WebViewAttributes::drag_region_handler`].
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+pub struct DragRegionQuery {
+  /// Horizontal position from the left edge of the webview, in logical pixels.
+  pub x: f64,
+  /// Vertical position from the top edge of the webview, in logical pixels.
+  pub y: f64,
+}
+
+/// A rendered frame of the webview captured by [`WebView::freeze_dom`].
+#[derive(Debug, Clone)]
+pub struct FrozenDom {
+  png: Vec<u8>,
+}
+
+/// Script displaying the `png` frame of [`WebView::restore_dom`] as an image over the document.
+#[cfg(any(gtk, target_os = "android"))]
+pub(crate) fn restore_dom_script(png: &[u8]) -> String {
+  use base64::{engine::general_purpose, Engine};
+  format!(
+    r#"(function (src) {{
+  var overlay = document.getElementById('__wry_frozen_dom__') || document.createElement('img');
+  overlay.id = '__wry_frozen_dom__';
+  overlay.src = src;
+  overlay.style.cssText = 'position: fixed; inset: 0; width: 100vw; height: 100vh; z-index: 2147483647; pointer-events: none;';
+  document.documentElement.appendChild(overlay);
+}})("data:image/png;base64,{}");"#,
+    general_purpose::STANDARD.encode(png)
+  )
+}
+
+/// Script removing the image added by [`restore_dom_script`].
+#[cfg(any(gtk, target_os = "android"))]
+pub(crate) const RESUME_RENDERING_SCRIPT: &str = "(function () { var overlay = document.getElementById('__wry_frozen_dom__'); if (overlay) overlay.remove(); })();";
+
+impl FrozenDom {
+  /// The PNG encoded frame.
+  pub fn png(&self) -> &[u8] {
+    &self.png
+  }
+}
+
+/// Encoding used for the payload of a `data:` URL.
+///
+/// See [`WebView::navigate_to_data_url`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataUrlEncoding {
+  /// `data:<mime>;base64,<payload>`
+  Base64,
+  /// `data:<mime>,<percent-encoded payload>`
+  UrlEncoded,
+}
+
+fn data_url(data: &[u8], mime_type: &str, encoding: DataUrlEncoding) -> Result<String> {
+  if !is_valid_mime_type(mime_type) {
+    return Err(Error::InvalidMimeType(mime_type.to_string()));
+  }
+
+  Ok(match encoding {
+    DataUrlEncoding::Base64 => {
+      use base64::{engine::general_purpose, Engine};
+      format!(
+        "data:{};base64,{}",
+        mime_type,
+        general_purpose::STANDARD.encode(data)
+      )
+    }
+    DataUrlEncoding::UrlEncoded => format!(
+      "data:{},{}",
+      mime_type,
+      percent_encoding::percent_encode(data, percent_encoding::NON_ALPHANUMERIC)
+    ),
+  })
+}
+
+/// Checks that `mime` is a `type/subtype` pair made of RFC 7230 tokens,
+/// optionally followed by `;key=value` parameters.
+fn is_valid_mime_type(mime: &str) -> bool {
+  fn is_token(s: &str) -> bool {
+    !s.is_empty()
+      && s
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+  }
+
+  let mut parts = mime.split(';');
+  let essence = parts.next().unwrap_or_default().trim();
+  let valid_essence = match essence.split_once('/') {
+    Some((ty, subtype)) => is_token(ty) && is_token(subtype),
+    None => false,
+  };
+
+  valid_essence
+    && parts.all(|param| match param.trim().split_once('=') {
+      Some((key, value)) => is_token(key) && !value.is_empty(),
+      None => false,
+    })
+}
+
+/// Script redirecting clicks on non-interactive areas to the closest interactive element
+/// within `radius` CSS pixels, see [`WebView::set_touch_adjust_radius`].
+#[cfg(any(target_os = "ios", target_os = "android", target_os = "windows"))]
+pub(crate) fn touch_adjust_script(radius: f64) -> String {
+  format!(
+    r#"(function (radius) {{
+  window.__WRY_TOUCH_ADJUST_RADIUS__ = radius;
+  if (window.__WRY_TOUCH_ADJUST_INSTALLED__) return;
+  window.__WRY_TOUCH_ADJUST_INSTALLED__ = true;
+  var selector = 'a[href], button, input, select, textarea, label, summary, [onclick], [role="button"], [role="link"], [tabindex]:not([tabindex="-1"])';
+  document.addEventListener('click', function (event) {{
+    var radius = window.__WRY_TOUCH_ADJUST_RADIUS__;
+    if (!radius || !event.isTrusted || (event.target.closest && event.target.closest(selector))) return;
+    var closest = null;
+    var closestDistance = Infinity;
+    document.querySelectorAll(selector).forEach(function (element) {{
+      var rect = element.getBoundingClientRect();
+      var dx = Math.max(rect.left - event.clientX, 0, event.clientX - rect.right);
+      var dy = Math.max(rect.top - event.clientY, 0, event.clientY - rect.bottom);
+      var distance = Math.sqrt(dx * dx + dy * dy);
+      if (distance <= radius && distance < closestDistance) {{
+        closest = element;
+        closestDistance = distance;
+      }}
+    }});
+    if (closest) {{
+      event.preventDefault();
+      event.stopPropagation();
+      closest.focus();
+      closest.click();
+    }}
+  }}, true);
+}})({});"#,
+    radius.max(0.)
+  )
+}
+
+/// Bounding rectangle of a DOM element relative to the viewport, in CSS pixels.
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) struct ElementRect {
+  pub x: f64,
+  pub y: f64,
+  pub width: f64,
+  pub height: f64,
+}
+
+/// Request headers bypassing the HTTP caches, see [`WebView::reload_ignoring_local_cache`].
+#[cfg(any(target_os = "windows", target_os = "android"))]
+pub(crate) fn no_cache_headers() -> http::HeaderMap {
+  let mut headers = http::HeaderMap::new();
+  headers.insert(
+    http::header::CACHE_CONTROL,
+    http::HeaderValue::from_static("no-cache, no-store, must-revalidate"),
+  );
+  headers.insert(
+    http::header::PRAGMA,
+    http::HeaderValue::from_static("no-cache"),
+  );
+  headers
+}
+
+/// Script evaluating to the [`ElementRect`] of the element with the given id, or `null`.
+pub(crate) fn element_rect_script(element_id: &str) -> String {
+  format!(
+    r#"(function () {{
+  var element = document.getElementById({});
+  if (!element) return null;
+  var rect = element.getBoundingClientRect();
+  var x = Math.max(rect.left, 0);
+  var y = Math.max(rect.top, 0);
+  return {{
+    x: x,
+    y: y,
+    width: Math.min(rect.right, window.innerWidth) - x,
+    height: Math.min(rect.bottom, window.innerHeight) - y
+  }};
+}})()"#,
+    serde_json::to_string(element_id).unwrap_or_default()
+  )
+}
+
+/// Script evaluating to the [`ElementRect`] of the whole viewport.
+const VIEWPORT_RECT_SCRIPT: &str =
+  "({ x: 0, y: 0, width: window.innerWidth, height: window.innerHeight })";
+
+/// Parses the JSON result of [`element_rect_script`] and [`VIEWPORT_RECT_SCRIPT`], `target`
+/// describes the captured area in the errors, e.g. ``element `id` ``.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) fn parse_element_rect(json: &str, target: &str) -> Result<ElementRect> {
+  match serde_json::from_str::<Option<ElementRect>>(json) {
+    Ok(Some(rect)) if rect.width > 0. && rect.height > 0. => Ok(rect),
+    Ok(Some(_)) => Err(Error::SnapshotError(format!("{target} is not visible"))),
+    _ => Err(Error::SnapshotError(format!("{target} not found"))),
+  }
+}
+
 #[cfg(any(
   target_os = "linux",
   target_os = "dragonfly",
@@ -1605,6 +5550,343 @@ pub enum PageLoadEvent {
 #[derive(Default)]
 pub(crate) struct PlatformSpecificWebViewAttributes;
 
+/// The level of the window containing the webview, see [`WebView::set_window_level`].
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WindowLevel {
+  /// The default level of the windows.
+  #[default]
+  Normal,
+  /// Above the normal windows, e.g. for tooltips and HUDs.
+  Floating,
+  /// Below the normal windows, e.g. for desktop widgets.
+  Desktop,
+}
+
+/// A native editing command, see [`WebView::execute_edit_command`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditCommand {
+  /// Cut the selection to the clipboard.
+  Cut,
+  /// Copy the selection to the clipboard.
+  Copy,
+  /// Paste the clipboard at the caret.
+  Paste,
+  /// Select all the content of the focused editable element, or of the page.
+  SelectAll,
+  /// Undo the last edit.
+  Undo,
+  /// Redo the last undone edit.
+  Redo,
+}
+
+/// The position of a child webview among the other children of its parent, see [`WebView::set_z_order`].
+#[non_exhaustive]
+#[derive(Clone, Copy)]
+pub enum ZOrder<'a> {
+  /// Above all the other children.
+  Top,
+  /// Below all the other children.
+  Bottom,
+  /// Right above another child webview of the same parent.
+  Above(&'a WebView),
+}
+
+/// A print dialog requested by the page, see [`WebViewAttributes::on_print_dialog`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrintDialogRequest {
+  /// The URL of the page requesting the print dialog.
+  pub url: Url,
+  /// Whether the dialog was requested by a script of the page.
+  pub initiated_by_js: bool,
+}
+
+/// What to do with a print dialog requested by the page, see [`WebViewAttributes::on_print_dialog`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrintDialogDecision {
+  /// Show the print dialog.
+  Allow,
+  /// Do not show the print dialog.
+  Deny,
+  /// Do not show the print dialog and export the page to a PDF file at the given path instead.
+  RedirectToPdf(PathBuf),
+}
+
+/// Name of the isolated world of [`WebViewAttributes::isolated_initialization_scripts`].
+#[cfg(not(target_os = "android"))]
+pub(crate) const ISOLATED_WORLD_NAME: &str = "wry";
+
+/// Kind of the internal IPC message sent by [`override_print_script`] when the page calls
+/// `window.print()`.
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "ios"))]
+const PRINT_DIALOG_MESSAGE_KIND: &str = "print_dialog";
+
+/// Whether `message` is the internal IPC message sent by [`override_print_script`].
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "ios"))]
+pub(crate) fn is_print_dialog_message(message: &str) -> bool {
+  ipc::open_envelope::<serde_json::Value>(message, PRINT_DIALOG_MESSAGE_KIND).is_some()
+}
+
+/// Initialization script routing `window.print()` to the IPC channel, the original function
+/// stays available as `window.__WRY_NATIVE_PRINT__`.
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "ios"))]
+pub(crate) fn override_print_script() -> String {
+  format!(
+    r#"(function () {{
+  var print = window.print;
+  Object.defineProperty(window, '__WRY_NATIVE_PRINT__', {{ value: print.bind(window) }});
+  window.print = function () {{
+    window.ipc.postMessage({});
+  }};
+}})();"#,
+    ipc::envelope_script(PRINT_DIALOG_MESSAGE_KIND, "null")
+  )
+}
+
+/// The identifier of an initialization script added with [`WebView::add_initialization_script`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScriptId(u64);
+
+impl ScriptId {
+  #[cfg(not(target_os = "android"))]
+  pub(crate) fn next() -> Self {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+  }
+}
+
+/// When an initialization script runs, see [`WebViewBuilder::with_initialization_script_at`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InjectionTime {
+  /// Before the document is parsed, the default.
+  #[default]
+  DocumentStart,
+  /// Once the document is parsed, before its subresources are loaded.
+  DocumentEnd,
+}
+
+/// A frame of the page, see [`WebView::frames`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameInfo {
+  /// The identifier of the frame, for [`WebView::evaluate_script_in_frame`].
+  ///
+  /// Only valid while the frame exists.
+  pub id: u64,
+  /// The URL of the document of the frame, if known.
+  pub url: Option<Url>,
+  /// The name of the frame, the `name` attribute of its `<iframe>`, empty if it has none.
+  pub name: String,
+  /// Whether this is the main frame of the page.
+  pub is_main_frame: bool,
+}
+
+/// The identifier of a user stylesheet added with [`WebView::inject_css`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StyleId(u64);
+
+impl StyleId {
+  #[cfg(not(target_os = "android"))]
+  pub(crate) fn next() -> Self {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+  }
+}
+
+/// The frames in which [`WebView::evaluate_script_in_frame`] evaluates a script.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameSelector {
+  /// The frame with this [`FrameInfo::id`].
+  Id(u64),
+  /// Every frame whose document has this origin, e.g. `https://example.com`.
+  Origin(String),
+}
+
+impl From<u64> for FrameSelector {
+  fn from(frame_id: u64) -> Self {
+    Self::Id(frame_id)
+  }
+}
+
+/// Serializes the origin of `url`, `Url::origin` being opaque for the custom protocols.
+pub(crate) fn url_origin(url: &Url) -> String {
+  match (url.host_str(), url.port()) {
+    (Some(host), Some(port)) => format!("{}://{host}:{port}", url.scheme()),
+    (Some(host), None) => format!("{}://{host}", url.scheme()),
+    (None, _) => "null".to_string(),
+  }
+}
+
+/// The [`FrameInfo::id`] of the main frame on the platforms tracking the frames natively.
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+pub(crate) const MAIN_FRAME_ID: u64 = 0;
+
+/// An entry of the navigation history, see [`WebView::get_history`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+  /// The URL of the entry.
+  pub url: Url,
+  /// The title of the page of the entry, if known.
+  pub title: Option<String>,
+  /// Whether this is the current entry.
+  pub is_current: bool,
+  /// The position relative to the current entry, negative for the back entries.
+  pub(crate) offset: i32,
+}
+
+impl HistoryEntry {
+  pub(crate) fn new(url: Url, title: Option<String>, offset: i32) -> Self {
+    Self {
+      url,
+      title,
+      is_current: offset == 0,
+      offset,
+    }
+  }
+}
+
+/// Script calling the `callback` function expression with whether the `beforeunload` handlers
+/// of the page ask for a confirmation before leaving it.
+fn before_unload_script(callback: &str) -> String {
+  format!(
+    r#"(function (callback) {{
+  var event = new Event('beforeunload', {{ cancelable: true }});
+  // the return value of `onbeforeunload` is ignored for synthetic events, it is caught here
+  var handler = window.onbeforeunload;
+  var returned;
+  if (typeof handler === 'function') {{
+    window.onbeforeunload = function (event) {{
+      returned = handler.call(this, event);
+    }};
+  }}
+  try {{
+    window.dispatchEvent(event);
+  }} finally {{
+    window.onbeforeunload = handler;
+  }}
+  callback(event.defaultPrevented || (returned !== undefined && returned !== null));
+}})({callback});"#
+  )
+}
+
+/// Script navigating the session history by `offset` entries.
+#[cfg(any(target_os = "windows", target_os = "android"))]
+pub(crate) fn history_go_script(offset: i32) -> String {
+  format!("history.go({offset});")
+}
+
+/// A TLS error of a navigation, see [`WebViewAttributes::on_https_error`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpsError {
+  /// The URL of the failed navigation.
+  pub url: Url,
+  /// The kind of error.
+  pub kind: HttpsErrorKind,
+}
+
+/// The kind of an [`HttpsError`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpsErrorKind {
+  /// The certificate is expired or not yet valid.
+  CertExpired,
+  /// The certificate is revoked, self-signed or not signed by a trusted authority.
+  CertUntrusted,
+  /// The certificate does not match the host name.
+  HostnameMismatch,
+  /// The host is required to use HTTPS with a valid certificate by the HSTS policy.
+  HstsViolation,
+  /// The certificate or the connection uses an insecure algorithm.
+  WeakCipher,
+  /// Another TLS error.
+  Other,
+}
+
+/// Whether to block or continue a navigation with an [`HttpsError`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpsErrorDecision {
+  /// Block the navigation, the platform error page is shown.
+  Block,
+  /// Ignore the error and continue the navigation.
+  Continue(ContinueReason),
+}
+
+/// Why an [`HttpsError`] is ignored, logged when the navigation is continued.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContinueReason {
+  /// The user accepted the risk.
+  UserApproved,
+  /// The certificate is known by the application, e.g. a pinned or self-signed certificate.
+  TrustedByApplication,
+  /// The error is expected during development, e.g. with a local server.
+  Development,
+}
+
+/// Why IPC messages were dropped, see [`WebViewAttributes::on_ipc_throttled`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpcThrottleReason {
+  /// The rate of [`WebViewAttributes::throttle_ipc_rate`] is exceeded, `dropped` messages were
+  /// dropped since the last delivered one.
+  RateExceeded { dropped: u32 },
+  /// A message of `size` bytes exceeds [`WebViewAttributes::max_ipc_message_size_bytes`].
+  Oversized { size: usize },
+  /// A large message split in chunks by `window.ipc.postMessage` was dropped because `missing`
+  /// of its chunks were lost, instead of delivering it truncated.
+  Incomplete { missing: usize },
+}
+
+/// A failed navigation, see [`WebViewAttributes::on_load_error`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+  /// The URL of the failed navigation.
+  pub url: Url,
+  /// The reason of the failure.
+  pub code: LoadErrorCode,
+  /// The platform description of the failure.
+  pub description: String,
+}
+
+/// The reason of a [`LoadError`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadErrorCode {
+  /// The host name could not be resolved.
+  HostNotFound,
+  /// The server could not be reached or refused the connection.
+  CannotConnect,
+  /// The connection was lost or reset during the navigation.
+  ConnectionLost,
+  /// The device is not connected to the network.
+  Offline,
+  /// The server did not respond in time.
+  Timeout,
+  /// The navigation was cancelled, e.g. by a new navigation or [`WebView::stop`].
+  Cancelled,
+  /// The TLS connection failed, see [`WebViewAttributes::on_https_error`].
+  Certificate,
+  /// The navigation was redirected too many times.
+  TooManyRedirects,
+  /// The URL scheme is not supported.
+  UnsupportedUrl,
+  /// Another error.
+  Other,
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -1616,4 +5898,126 @@ mod tests {
       panic!("{}", error);
     }
   }
+
+  #[test]
+  fn should_build_data_url() {
+    assert_eq!(
+      data_url(b"hi", "text/plain", DataUrlEncoding::Base64).unwrap(),
+      "data:text/plain;base64,aGk="
+    );
+    assert_eq!(
+      data_url(
+        b"a b",
+        "text/plain;charset=utf-8",
+        DataUrlEncoding::UrlEncoded
+      )
+      .unwrap(),
+      "data:text/plain;charset=utf-8,a%20b"
+    );
+    assert!(data_url(b"", "text", DataUrlEncoding::Base64).is_err());
+    assert!(data_url(b"", "text/plain;charset", DataUrlEncoding::Base64).is_err());
+  }
+
+  #[test]
+  fn should_reject_invalid_zoom_steps() {
+    assert!(check_zoom_step(0.25).is_ok());
+    assert!(check_zoom_step(0.0).is_err());
+    assert!(check_zoom_step(-0.1).is_err());
+    assert!(check_zoom_step(f64::NAN).is_err());
+  }
+
+  /// Attributes whose IPC handler collects the messages, and the handler collecting the reasons
+  /// of the dropped messages.
+  fn throttled_attributes() -> (
+    WebViewAttributes,
+    Rc<RefCell<Vec<String>>>,
+    Rc<dyn Fn(IpcThrottleReason)>,
+    Rc<RefCell<Vec<IpcThrottleReason>>>,
+  ) {
+    let messages = Rc::new(RefCell::new(Vec::new()));
+    let messages_ = messages.clone();
+    let attrs = WebViewAttributes {
+      ipc_handler: Some(Box::new(move |message| {
+        messages_.borrow_mut().push(message)
+      })),
+      ..Default::default()
+    };
+    let reasons = Rc::new(RefCell::new(Vec::new()));
+    let reasons_ = reasons.clone();
+    let on_throttled: Rc<dyn Fn(IpcThrottleReason)> =
+      Rc::new(move |reason| reasons_.borrow_mut().push(reason));
+    (attrs, messages, on_throttled, reasons)
+  }
+
+  #[test]
+  fn should_throttle_ipc_messages() {
+    let (mut attrs, messages, on_throttled, reasons) = throttled_attributes();
+    attrs.throttle_ipc_rate = Some((2, Duration::from_millis(50)));
+    throttle_ipc_handler(&mut attrs, Some(on_throttled));
+    let handler = attrs.ipc_handler.unwrap();
+
+    for message in ["a", "b", "c", "d"] {
+      handler(message.to_string());
+    }
+    assert_eq!(*messages.borrow(), ["a", "b"]);
+    assert_eq!(
+      *reasons.borrow(),
+      [
+        IpcThrottleReason::RateExceeded { dropped: 1 },
+        IpcThrottleReason::RateExceeded { dropped: 2 }
+      ]
+    );
+
+    // the window slides
+    std::thread::sleep(Duration::from_millis(60));
+    handler("e".to_string());
+    assert_eq!(*messages.borrow(), ["a", "b", "e"]);
+  }
+
+  #[test]
+  fn should_not_throttle_bridge_messages() {
+    let (mut attrs, messages, on_throttled, reasons) = throttled_attributes();
+    attrs.throttle_ipc_rate = Some((1, Duration::from_secs(60)));
+    throttle_ipc_handler(&mut attrs, Some(on_throttled));
+
+    let payloads = Rc::new(RefCell::new(Vec::new()));
+    let payloads_ = payloads.clone();
+    let mut bridge = Bridge::new();
+    bridge.register("scroll", String::new(), move |payload| {
+      payloads_.borrow_mut().push(payload)
+    });
+    bridge.install(&mut attrs);
+    let handler = attrs.ipc_handler.unwrap();
+
+    for i in 0..3 {
+      handler(format!(
+        "{}{{\"kind\":\"bridge\",\"data\":{{\"name\":\"scroll\",\"payload\":{i}}}}}",
+        ipc::ENVELOPE_PREFIX
+      ));
+    }
+    handler("a".to_string());
+    handler("b".to_string());
+    assert_eq!(*payloads.borrow(), [0, 1, 2]);
+    assert_eq!(*messages.borrow(), ["a"]);
+    assert_eq!(
+      *reasons.borrow(),
+      [IpcThrottleReason::RateExceeded { dropped: 1 }]
+    );
+  }
+
+  #[test]
+  fn should_drop_oversized_ipc_messages() {
+    let (mut attrs, messages, on_throttled, reasons) = throttled_attributes();
+    attrs.max_ipc_message_size_bytes = Some(4);
+    reassemble_ipc_chunks(&mut attrs, Some(on_throttled));
+    let handler = attrs.ipc_handler.unwrap();
+
+    handler("abcd".to_string());
+    handler("abcde".to_string());
+    assert_eq!(*messages.borrow(), ["abcd"]);
+    assert_eq!(
+      *reasons.borrow(),
+      [IpcThrottleReason::Oversized { size: 5 }]
+    );
+  }
 }
@@ -0,0 +1,86 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Deserialize;
+use url::Url;
+
+/// A stage of the page lifecycle, see [`WebViewAttributes::on_page_lifecycle`](crate::WebViewAttributes::on_page_lifecycle).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageLifecycleEvent {
+  /// The reached stage.
+  pub stage: PageLifecycleStage,
+  /// The URL of the document of the frame.
+  pub url: Url,
+  /// Whether the event comes from the main frame, `false` for iframes.
+  pub is_main_frame: bool,
+}
+
+/// The stages of [`PageLifecycleEvent`], in the order they are usually reached.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PageLifecycleStage {
+  /// The document was parsed, its deferred scripts ran, but stylesheets, images and iframes may still be loading.
+  DomContentLoaded,
+  /// Something was rendered for the first time.
+  ///
+  /// Reported from the `first-paint` or `first-contentful-paint` performance entry, whichever comes first,
+  /// and not reported by engines without the Paint Timing API.
+  FirstPaint,
+  /// The document and its subresources stopped loading, the `load` event of the frame.
+  LoadStopped,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPageLifecycleEvent {
+  stage: PageLifecycleStage,
+  url: String,
+  is_main_frame: bool,
+}
+
+/// Parses the value posted by [`lifecycle_script`].
+pub(crate) fn parse_lifecycle_event(value: serde_json::Value) -> Option<PageLifecycleEvent> {
+  let event = serde_json::from_value::<RawPageLifecycleEvent>(value).ok()?;
+  Some(PageLifecycleEvent {
+    stage: event.stage,
+    url: Url::parse(&event.url).ok()?,
+    is_main_frame: event.is_main_frame,
+  })
+}
+
+/// Initialization script posting the lifecycle stages of the frame to the `post` function expression.
+pub(crate) fn lifecycle_script(post: &str) -> String {
+  format!(
+    r#"(function (post) {{
+  var isMainFrame = window.top === window;
+  function report(stage) {{
+    try {{
+      post({{ stage: stage, url: window.location.href, isMainFrame: isMainFrame }});
+    }} catch (e) {{}}
+  }}
+
+  document.addEventListener('DOMContentLoaded', function () {{
+    report('domContentLoaded');
+  }});
+  window.addEventListener('load', function () {{
+    report('loadStopped');
+  }});
+
+  if (window.PerformanceObserver) {{
+    var painted = false;
+    try {{
+      var observer = new PerformanceObserver(function () {{
+        if (painted) return;
+        painted = true;
+        observer.disconnect();
+        report('firstPaint');
+      }});
+      observer.observe({{ type: 'paint', buffered: true }});
+    }} catch (e) {{}}
+  }}
+}})({post});"#
+  )
+}
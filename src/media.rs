@@ -0,0 +1,218 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Deserialize;
+
+/// The audio playback state of the page, see [`WebViewAttributes::on_web_audio_state_change`](crate::WebViewAttributes::on_web_audio_state_change).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebAudioState {
+  /// Audio is playing.
+  Playing,
+  /// Media is playing, but all of it is muted.
+  Muted,
+  /// Media playback was started and is currently paused.
+  Paused,
+  /// No media is playing nor paused.
+  Idle,
+}
+
+/// Body of the main frame script posting the [`WebAudioState`] of the page every time it changes,
+/// see [`Bridge::register_main_frame`](crate::bridge::Bridge::register_main_frame).
+pub(crate) const WEB_AUDIO_STATE_SCRIPT: &str = r#"
+  var contexts = [];
+  var state = 'idle';
+
+  function computeState() {
+    var media = Array.prototype.slice.call(document.querySelectorAll('audio, video'));
+    var playing = media.filter(function (m) { return !m.paused && !m.ended; });
+    if (playing.some(function (m) { return !m.muted && m.volume > 0; })) return 'playing';
+    if (contexts.some(function (c) { return c.state === 'running'; })) return 'playing';
+    if (playing.length) return 'muted';
+    if (media.some(function (m) { return m.paused && !m.ended && m.currentTime > 0; })) return 'paused';
+    if (contexts.some(function (c) { return c.state === 'suspended'; })) return 'paused';
+    var session = navigator.mediaSession && navigator.mediaSession.playbackState;
+    if (session === 'playing' || session === 'paused') return session;
+    return 'idle';
+  }
+
+  function update() {
+    var next = computeState();
+    if (next !== state) {
+      state = next;
+      post(state);
+    }
+  }
+
+  ['play', 'playing', 'pause', 'ended', 'emptied', 'volumechange'].forEach(function (event) {
+    document.addEventListener(event, update, true);
+  });
+  document.addEventListener('visibilitychange', update);
+
+  ['AudioContext', 'webkitAudioContext'].forEach(function (name) {
+    var AudioContext = window[name];
+    if (!AudioContext) return;
+    window[name] = new Proxy(AudioContext, {
+      construct: function (target, args, newTarget) {
+        var context = Reflect.construct(target, args, newTarget);
+        contexts.push(context);
+        context.addEventListener('statechange', function () {
+          if (context.state === 'closed') contexts.splice(contexts.indexOf(context), 1);
+          update();
+        });
+        return context;
+      }
+    });
+  });"#;
+
+/// A change of the audio focus of the application, see [`WebViewAttributes::on_audio_focus_change`](crate::WebViewAttributes::on_audio_focus_change).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFocusEvent {
+  /// The audio focus was given back, the playback can be resumed.
+  Gained,
+  /// The audio focus was lost and is not expected to be given back.
+  ///
+  /// The page media should be paused, e.g. with
+  /// `document.querySelectorAll('video, audio').forEach((m) => m.pause())`.
+  Lost(LossDuration),
+  /// The audio focus was temporarily lost, e.g. by a phone call or Siri, and will likely be given back.
+  LostTransient,
+}
+
+/// How long the audio focus is lost for, see [`AudioFocusEvent::Lost`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LossDuration {
+  /// The interruption ended but the playback should not be resumed.
+  Permanent,
+  /// The interruption end may never be notified, e.g. the application was suspended or the
+  /// audio route was disconnected.
+  Unknown,
+}
+
+/// Initialization script muting the `<audio>` and `<video>` elements of the page while
+/// `window.__WRY_AUDIO_MUTED__` is set, see [`set_audio_muted_script`].
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "android"))]
+pub(crate) const MUTE_MEDIA_SCRIPT: &str = r#"(function () {
+  function apply(media) {
+    if (window.__WRY_AUDIO_MUTED__) {
+      if (!media.__wryMuted) media.__wryWasMuted = media.muted;
+      media.__wryMuted = true;
+      media.muted = true;
+    } else if (media.__wryMuted) {
+      media.__wryMuted = false;
+      media.muted = !!media.__wryWasMuted;
+    }
+  }
+  Object.defineProperty(window, '__WRY_APPLY_AUDIO_MUTED__', {
+    value: function () {
+      document.querySelectorAll('audio, video').forEach(apply);
+    }
+  });
+  document.addEventListener('play', function (event) {
+    if (event.target instanceof HTMLMediaElement) apply(event.target);
+  }, true);
+})();"#;
+
+/// Script muting or unmuting the page media, and the media started later on, with [`MUTE_MEDIA_SCRIPT`].
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "android"))]
+pub(crate) fn set_audio_muted_script(muted: bool) -> String {
+  format!(
+    "window.__WRY_AUDIO_MUTED__ = {muted}; window.__WRY_APPLY_AUDIO_MUTED__ && window.__WRY_APPLY_AUDIO_MUTED__();"
+  )
+}
+
+/// The media capture state of the page, see [`WebViewAttributes::on_media_capture_state_change`](crate::WebViewAttributes::on_media_capture_state_change).
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct MediaCaptureState {
+  /// The state of the camera capture.
+  pub camera: CaptureState,
+  /// The state of the microphone capture.
+  pub microphone: CaptureState,
+  /// The state of the screen capture.
+  pub screen: CaptureState,
+}
+
+/// The state of a capture device, see [`MediaCaptureState`].
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureState {
+  /// The device is not captured.
+  #[default]
+  Off,
+  /// The device is captured.
+  Active,
+  /// The device is captured but muted.
+  Muted,
+}
+
+/// Initialization script posting the [`MediaCaptureState`] of the page to the `post` function expression
+/// every time it changes.
+#[cfg(any(target_os = "windows", target_os = "android"))]
+pub(crate) fn media_capture_state_script(post: &str) -> String {
+  format!(
+    r#"(function (post) {{
+  var mediaDevices = navigator.mediaDevices;
+  if (!mediaDevices) return;
+  var tracks = [];
+  var state = JSON.stringify({{ camera: 'off', microphone: 'off', screen: 'off' }});
+
+  function captureState(source) {{
+    var live = tracks.filter(function (track) {{
+      return track.source === source && track.track.readyState === 'live';
+    }});
+    if (!live.length) return 'off';
+    return live.some(function (track) {{ return track.track.enabled && !track.track.muted; }}) ? 'active' : 'muted';
+  }}
+
+  function update() {{
+    tracks = tracks.filter(function (track) {{ return track.track.readyState === 'live'; }});
+    var next = JSON.stringify({{
+      camera: captureState('camera'),
+      microphone: captureState('microphone'),
+      screen: captureState('screen')
+    }});
+    if (next !== state) {{
+      state = next;
+      post(JSON.parse(state));
+    }}
+  }}
+
+  function watch(stream, screen) {{
+    stream.getTracks().forEach(function (track) {{
+      var source = screen ? 'screen' : track.kind === 'video' ? 'camera' : 'microphone';
+      if (screen && track.kind === 'audio') return;
+      tracks.push({{ source: source, track: track }});
+      track.addEventListener('ended', update);
+      track.addEventListener('mute', update);
+      track.addEventListener('unmute', update);
+      // `enabled` has no change event
+      var enabled = Object.getOwnPropertyDescriptor(MediaStreamTrack.prototype, 'enabled');
+      Object.defineProperty(track, 'enabled', {{
+        get: function () {{ return enabled.get.call(track); }},
+        set: function (value) {{ enabled.set.call(track, value); update(); }}
+      }});
+      var stop = track.stop;
+      track.stop = function () {{ stop.call(track); update(); }};
+    }});
+    update();
+    return stream;
+  }}
+
+  ['getUserMedia', 'getDisplayMedia'].forEach(function (name) {{
+    var original = mediaDevices[name];
+    if (!original) return;
+    mediaDevices[name] = function () {{
+      return original.apply(mediaDevices, arguments).then(function (stream) {{
+        return watch(stream, name === 'getDisplayMedia');
+      }});
+    }};
+  }});
+}})({post});"#
+  )
+}
@@ -0,0 +1,91 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Deserialize;
+
+/// An uncaught exception or an unhandled promise rejection of the page, see
+/// [`WebViewAttributes::on_page_error`](crate::WebViewAttributes::on_page_error).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageError {
+  /// The message of the error, or the string conversion of the rejection reason.
+  pub message: String,
+  /// The stack trace of the error, when the thrown value is an `Error` whose stack is available.
+  pub stack: Option<String>,
+  /// The URL of the script which threw the error, when it is known.
+  pub source: Option<String>,
+  /// The line of the error in [`PageError::source`], when it is known.
+  pub line: Option<u32>,
+  /// The column of the error in [`PageError::source`], when it is known.
+  pub column: Option<u32>,
+  /// Whether the error is an unhandled promise rejection instead of an uncaught exception.
+  pub is_unhandled_rejection: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPageError {
+  message: String,
+  stack: Option<String>,
+  source: Option<String>,
+  line: Option<u32>,
+  column: Option<u32>,
+  is_unhandled_rejection: bool,
+}
+
+/// Parses the value posted by [`page_error_script`].
+pub(crate) fn parse_page_error(value: serde_json::Value) -> Option<PageError> {
+  let error = serde_json::from_value::<RawPageError>(value).ok()?;
+  Some(PageError {
+    message: error.message,
+    stack: error.stack.filter(|stack| !stack.is_empty()),
+    source: error.source.filter(|source| !source.is_empty()),
+    line: error.line.filter(|line| *line > 0),
+    column: error.column.filter(|column| *column > 0),
+    is_unhandled_rejection: error.is_unhandled_rejection,
+  })
+}
+
+/// Initialization script posting the uncaught exceptions and unhandled promise rejections to
+/// the `post` function expression.
+pub(crate) fn page_error_script(post: &str) -> String {
+  format!(
+    r#"(function (post) {{
+  function describe(value) {{
+    if (value instanceof Error) return {{ message: String(value.message || value), stack: value.stack ? String(value.stack) : null }};
+    try {{
+      return {{ message: typeof value === 'string' ? value : JSON.stringify(value) || String(value), stack: null }};
+    }} catch (e) {{
+      return {{ message: String(value), stack: null }};
+    }}
+  }}
+
+  window.addEventListener('error', function (event) {{
+    // the load errors of the elements are not exceptions
+    if (!(event instanceof ErrorEvent)) return;
+    var error = event.error !== undefined && event.error !== null ? describe(event.error) : {{ message: event.message, stack: null }};
+    post({{
+      message: error.message || String(event.message),
+      stack: error.stack,
+      source: event.filename || null,
+      line: event.lineno || null,
+      column: event.colno || null,
+      isUnhandledRejection: false
+    }});
+  }});
+
+  window.addEventListener('unhandledrejection', function (event) {{
+    var error = describe(event.reason);
+    post({{
+      message: error.message,
+      stack: error.stack,
+      source: null,
+      line: null,
+      column: null,
+      isUnhandledRejection: true
+    }});
+  }});
+}})({post});"#
+  )
+}
@@ -0,0 +1,122 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::io::Read;
+
+use http::{
+  header::{
+    ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+    ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN, VARY,
+  },
+  HeaderMap, HeaderValue, Method, Request, Response, StatusCode,
+};
+
+use crate::RequestAsyncResponder;
+
+/// The security options of a custom protocol, see
+/// [`WebViewBuilder::with_custom_protocol_options`](crate::WebViewBuilder::with_custom_protocol_options).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomProtocolOptions {
+  /// Whether the pages of the protocol are secure contexts, e.g. to use `crypto.subtle` or
+  /// service workers, `true` by default.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Android**: Unsupported, the protocols are served from
+  /// `http://<scheme>.localhost` or `https://<scheme>.localhost` instead of registered as schemes,
+  /// so the WebView2 `CoreWebView2CustomSchemeRegistration::TreatAsSecure` flag doesn't apply,
+  /// and the pages are always secure contexts since the `localhost` origins are trustworthy.
+  /// - **macOS / iOS**: Unsupported, WebKit decides.
+  pub secure_context: bool,
+  /// The origins allowed to `fetch()` and `XMLHttpRequest` the protocol from other origins,
+  /// e.g. `https://example.com`, or `*` for any origin. Empty by default.
+  ///
+  /// The responses to these origins get the `Access-Control-Allow-Origin` header, and their
+  /// preflight requests are answered without reaching the protocol handler.
+  ///
+  /// This is done by wry on every platform, on Windows the WebView2
+  /// `CoreWebView2CustomSchemeRegistration::AllowedOrigins` are not used since the protocols are
+  /// not registered as schemes, see [`CustomProtocolOptions::secure_context`].
+  ///
+  /// See [`WebViewBuilder::with_custom_protocol`](crate::WebViewBuilder::with_custom_protocol)
+  /// for the origins of the pages served by custom protocols on each platform.
+  pub allowed_origins: Vec<String>,
+}
+
+impl Default for CustomProtocolOptions {
+  fn default() -> Self {
+    Self {
+      secure_context: true,
+      allowed_origins: Vec::new(),
+    }
+  }
+}
+
+/// Wraps a custom protocol `handler` to answer the CORS requests of the `allowed_origins`.
+pub(crate) fn cors_handler(
+  allowed_origins: Vec<String>,
+  handler: Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)>,
+) -> Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)> {
+  Box::new(move |request, responder| {
+    let origin = request
+      .headers()
+      .get(ORIGIN)
+      .and_then(|origin| origin.to_str().ok())
+      .filter(|origin| {
+        allowed_origins
+          .iter()
+          .any(|allowed| allowed == "*" || allowed == origin)
+      })
+      .and_then(|origin| HeaderValue::from_str(origin).ok());
+    let Some(origin) = origin else {
+      return handler(request, responder);
+    };
+
+    // answers the preflight requests
+    if request.method() == Method::OPTIONS
+      && request
+        .headers()
+        .contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+    {
+      let mut response = Response::new(Vec::new());
+      *response.status_mut() = StatusCode::NO_CONTENT;
+      let headers = response.headers_mut();
+      add_cors_headers(headers, &origin);
+      if let Some(method) = request.headers().get(ACCESS_CONTROL_REQUEST_METHOD) {
+        headers.insert(ACCESS_CONTROL_ALLOW_METHODS, method.clone());
+      }
+      if let Some(request_headers) = request.headers().get(ACCESS_CONTROL_REQUEST_HEADERS) {
+        headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, request_headers.clone());
+      }
+      return responder.respond(response);
+    }
+
+    let RequestAsyncResponder {
+      responder,
+      stream_responder,
+    } = responder;
+    let origin_ = origin.clone();
+    handler(
+      request,
+      RequestAsyncResponder {
+        responder: Box::new(move |mut response| {
+          add_cors_headers(response.headers_mut(), &origin);
+          responder(response)
+        }),
+        stream_responder: stream_responder.map(|stream_responder| {
+          Box::new(move |mut response: Response<_>| {
+            add_cors_headers(response.headers_mut(), &origin_);
+            stream_responder(response)
+          }) as Box<dyn FnOnce(Response<Box<dyn Read + Send>>)>
+        }),
+      },
+    )
+  })
+}
+
+fn add_cors_headers(headers: &mut HeaderMap, origin: &HeaderValue) {
+  headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+  headers.append(VARY, HeaderValue::from_static("Origin"));
+}
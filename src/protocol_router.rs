@@ -0,0 +1,225 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashMap;
+
+use http::{Request, StatusCode};
+
+use crate::{asset_dir::empty_response, RequestAsyncResponder};
+
+type RouteHandler = Box<dyn Fn(Request<Vec<u8>>, RouteParams, RequestAsyncResponder)>;
+
+/// A segment of a route pattern.
+enum Segment {
+  /// A segment matching itself.
+  Literal(String),
+  /// A `:name` segment matching any segment, captured as `name`.
+  Param(String),
+  /// A trailing `*` segment matching the rest of the path, captured as `*`.
+  Wildcard,
+}
+
+/// The parameters captured by the pattern of a [`ProtocolRouter`] route.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteParams {
+  params: HashMap<String, String>,
+}
+
+impl RouteParams {
+  /// Returns the value of the `:name` parameter, or the rest of the path matched by a trailing `*`
+  /// for `name = "*"`. The values are percent-decoded.
+  pub fn get(&self, name: &str) -> Option<&str> {
+    self.params.get(name).map(String::as_str)
+  }
+}
+
+/// Routes the requests of a custom protocol to handlers by path, see
+/// [`WebViewBuilder::with_protocol_router`](crate::WebViewBuilder::with_protocol_router).
+///
+/// The patterns are absolute paths whose segments are literals, `:name` parameters matching any
+/// segment, or a trailing `*` matching the rest of the path, e.g. `/api/users/:id` or `/assets/*`.
+/// The routes are tried in the order they are added.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wry::{http::Response, ProtocolRouter};
+///
+/// let router = ProtocolRouter::new()
+///   .route("/api/users/:id", |_request, params, responder| {
+///     let id = params.get("id").unwrap_or_default().to_string();
+///     responder.respond(Response::new(id.into_bytes()));
+///   })
+///   .route("/assets/*", |_request, params, responder| {
+///     let path = params.get("*").unwrap_or_default().to_string();
+///     responder.respond(Response::new(path.into_bytes()));
+///   });
+/// ```
+#[derive(Default)]
+pub struct ProtocolRouter {
+  routes: Vec<(Vec<Segment>, RouteHandler)>,
+  fallback: Option<RouteHandler>,
+}
+
+impl ProtocolRouter {
+  /// Creates a router without routes, answering `404 Not Found` to every request.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a route calling `handler` for the requests whose path matches `pattern`.
+  pub fn route<F>(mut self, pattern: &str, handler: F) -> Self
+  where
+    F: Fn(Request<Vec<u8>>, RouteParams, RequestAsyncResponder) + 'static,
+  {
+    self
+      .routes
+      .push((parse_pattern(pattern), Box::new(handler)));
+    self
+  }
+
+  /// Sets the handler of the requests matching no route, instead of a `404 Not Found` response.
+  pub fn fallback<F>(mut self, handler: F) -> Self
+  where
+    F: Fn(Request<Vec<u8>>, RouteParams, RequestAsyncResponder) + 'static,
+  {
+    self.fallback = Some(Box::new(handler));
+    self
+  }
+
+  pub(crate) fn handle(&self, request: Request<Vec<u8>>, responder: RequestAsyncResponder) {
+    let path = request.uri().path().to_string();
+    for (pattern, handler) in &self.routes {
+      if let Some(params) = match_pattern(pattern, &path) {
+        return handler(request, params, responder);
+      }
+    }
+
+    match &self.fallback {
+      Some(fallback) => fallback(request, RouteParams::default(), responder),
+      None => responder.respond(empty_response(StatusCode::NOT_FOUND)),
+    }
+  }
+}
+
+fn parse_pattern(pattern: &str) -> Vec<Segment> {
+  pattern
+    .split('/')
+    .filter(|segment| !segment.is_empty())
+    .map(|segment| match segment {
+      "*" => Segment::Wildcard,
+      segment => match segment.strip_prefix(':') {
+        Some(name) => Segment::Param(name.to_string()),
+        None => Segment::Literal(segment.to_string()),
+      },
+    })
+    .collect()
+}
+
+fn match_pattern(pattern: &[Segment], path: &str) -> Option<RouteParams> {
+  let decode = |segment: &str| {
+    percent_encoding::percent_decode_str(segment)
+      .decode_utf8_lossy()
+      .into_owned()
+  };
+
+  let mut params = HashMap::new();
+  let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+  for segment in pattern {
+    match segment {
+      Segment::Wildcard => {
+        let rest = segments.by_ref().map(decode).collect::<Vec<_>>().join("/");
+        params.insert("*".to_string(), rest);
+      }
+      Segment::Param(name) => {
+        params.insert(name.clone(), decode(segments.next()?));
+      }
+      Segment::Literal(literal) => {
+        if decode(segments.next()?) != *literal {
+          return None;
+        }
+      }
+    }
+  }
+
+  segments.next().is_none().then_some(RouteParams { params })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, rc::Rc};
+
+  use super::*;
+
+  fn params(pattern: &str, path: &str) -> Option<Vec<(String, String)>> {
+    let mut params = match_pattern(&parse_pattern(pattern), path)?
+      .params
+      .into_iter()
+      .collect::<Vec<_>>();
+    params.sort();
+    Some(params)
+  }
+
+  fn param(name: &str, value: &str) -> (String, String) {
+    (name.to_string(), value.to_string())
+  }
+
+  #[test]
+  fn should_match_pattern() {
+    assert_eq!(params("/api/users", "/api/users"), Some(vec![]));
+    assert_eq!(params("/api/users", "/api/users/"), Some(vec![]));
+    assert_eq!(params("/", "/"), Some(vec![]));
+    assert_eq!(
+      params("/api/users/:id", "/api/users/42"),
+      Some(vec![param("id", "42")])
+    );
+    assert_eq!(
+      params("/api/:kind/:id", "/api/users/a%20b"),
+      Some(vec![param("id", "a b"), param("kind", "users")])
+    );
+    assert_eq!(
+      params("/assets/*", "/assets/css/app.css"),
+      Some(vec![param("*", "css/app.css")])
+    );
+    assert_eq!(params("/assets/*", "/assets"), Some(vec![param("*", "")]));
+  }
+
+  #[test]
+  fn should_not_match_pattern() {
+    assert_eq!(params("/api/users", "/api/user"), None);
+    assert_eq!(params("/api/users", "/api/users/42"), None);
+    assert_eq!(params("/api/users/:id", "/api/users"), None);
+    assert_eq!(params("/assets/*", "/images/logo.png"), None);
+  }
+
+  #[test]
+  fn should_route_in_order() {
+    let routed = Rc::new(RefCell::new(Vec::new()));
+    let route = |name: &'static str| {
+      let routed = routed.clone();
+      move |_: Request<Vec<u8>>, _: RouteParams, _: RequestAsyncResponder| {
+        routed.borrow_mut().push(name)
+      }
+    };
+    let router = ProtocolRouter::new()
+      .route("/api/users/me", route("me"))
+      .route("/api/users/:id", route("user"))
+      .fallback(route("fallback"));
+
+    for path in ["/api/users/me", "/api/users/42", "/index.html"] {
+      let request = Request::builder()
+        .uri(format!("wry://localhost{path}"))
+        .body(Vec::new())
+        .unwrap();
+      router.handle(
+        request,
+        RequestAsyncResponder {
+          responder: Box::new(|_| ()),
+          stream_responder: None,
+        },
+      );
+    }
+    assert_eq!(*routed.borrow(), ["me", "user", "fallback"]);
+  }
+}
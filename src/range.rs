@@ -0,0 +1,172 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::io::{self, Read, Seek, SeekFrom, Take};
+
+use http::{
+  header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, RANGE},
+  Request, Response, StatusCode,
+};
+
+/// Builds the response of a custom protocol `request` honoring its `Range` header, so the media
+/// elements of the page can seek in the `content` served by the protocol.
+///
+/// The response is:
+/// - `206 Partial Content` with the requested part of `content` for a satisfiable single range,
+/// - `416 Range Not Satisfiable` with an empty body for an unsatisfiable range,
+/// - `200 OK` with the whole `content` otherwise, multiple ranges are not supported.
+///
+/// The `Content-Type` header is left to the caller. The body is only read as it is streamed,
+/// see [`RequestAsyncResponder::respond_with_stream`](crate::RequestAsyncResponder::respond_with_stream).
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::fs::File;
+/// use wry::{http::header::CONTENT_TYPE, WebViewBuilder, raw_window_handle};
+///
+/// # use raw_window_handle::{HasWindowHandle, WindowHandle, RawWindowHandle, Win32WindowHandle, HandleError};
+/// # struct T;
+/// # impl HasWindowHandle for T {
+/// #   fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+/// #     let handle = RawWindowHandle::Win32(Win32WindowHandle::new(std::num::NonZeroIsize::new(0).unwrap()));
+/// #     unsafe { Ok(WindowHandle::borrow_raw(handle)) }
+/// #   }
+/// # }
+/// # let window = T;
+/// WebViewBuilder::new(&window)
+///   .with_asynchronous_custom_protocol("wry".into(), |request, responder| {
+///     let response = File::open("video.mp4")
+///       .and_then(|file| wry::range_response(&request, file));
+///     match response {
+///       Ok(mut response) => {
+///         response.headers_mut().insert(CONTENT_TYPE, "video/mp4".parse().unwrap());
+///         responder.respond_with_stream(response);
+///       }
+///       Err(_) => responder.respond(
+///         http::Response::builder().status(404).body(Vec::new()).unwrap(),
+///       ),
+///     }
+///   });
+/// ```
+pub fn range_response<B, R: Read + Seek>(
+  request: &Request<B>,
+  mut content: R,
+) -> io::Result<Response<Take<R>>> {
+  let len = content.seek(SeekFrom::End(0))?;
+  let range = request
+    .headers()
+    .get(RANGE)
+    .and_then(|range| range.to_str().ok())
+    .and_then(|range| parse_range(range, len));
+
+  let response = Response::builder().header(ACCEPT_RANGES, "bytes");
+  let response = match range {
+    Some(Some((start, end))) => {
+      content.seek(SeekFrom::Start(start))?;
+      response
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+        .header(CONTENT_LENGTH, end - start + 1)
+        .body(content.take(end - start + 1))
+    }
+    Some(None) => response
+      .status(StatusCode::RANGE_NOT_SATISFIABLE)
+      .header(CONTENT_RANGE, format!("bytes */{len}"))
+      .header(CONTENT_LENGTH, 0)
+      .body(content.take(0)),
+    None => {
+      content.seek(SeekFrom::Start(0))?;
+      response
+        .status(StatusCode::OK)
+        .header(CONTENT_LENGTH, len)
+        .body(content.take(len))
+    }
+  };
+  response.map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+/// Parses a `Range` header of a content of `len` bytes, returns `None` when the header is
+/// ignored and `Some(None)` when the range is unsatisfiable.
+fn parse_range(range: &str, len: u64) -> Option<Option<(u64, u64)>> {
+  let range = range.trim().strip_prefix("bytes=")?;
+  if range.contains(',') {
+    return None;
+  }
+
+  let (start, end) = range.split_once('-')?;
+  let (start, end) = (start.trim(), end.trim());
+  let range = if start.is_empty() {
+    // the last `end` bytes
+    let suffix = end.parse::<u64>().ok()?;
+    (suffix > 0 && len > 0).then(|| (len.saturating_sub(suffix), len - 1))
+  } else {
+    let start = start.parse::<u64>().ok()?;
+    let end = match end {
+      "" => len.saturating_sub(1),
+      end => {
+        let end = end.parse::<u64>().ok()?;
+        // an invalid range
+        if end < start {
+          return None;
+        }
+        end.min(len.saturating_sub(1))
+      }
+    };
+    (start < len).then_some((start, end))
+  };
+  Some(range)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Cursor;
+
+  use super::*;
+
+  #[test]
+  fn should_parse_range() {
+    assert_eq!(parse_range("bytes=0-9", 100), Some(Some((0, 9))));
+    assert_eq!(parse_range("bytes=90-", 100), Some(Some((90, 99))));
+    assert_eq!(parse_range("bytes=-10", 100), Some(Some((90, 99))));
+    assert_eq!(parse_range("bytes=-200", 100), Some(Some((0, 99))));
+    assert_eq!(parse_range("bytes=50-200", 100), Some(Some((50, 99))));
+    assert_eq!(parse_range(" bytes= 1 - 2 ", 100), Some(Some((1, 2))));
+  }
+
+  #[test]
+  fn should_reject_unsatisfiable_range() {
+    assert_eq!(parse_range("bytes=100-", 100), Some(None));
+    assert_eq!(parse_range("bytes=-0", 100), Some(None));
+    assert_eq!(parse_range("bytes=0-", 0), Some(None));
+  }
+
+  #[test]
+  fn should_ignore_invalid_range() {
+    assert_eq!(parse_range("items=0-9", 100), None);
+    assert_eq!(parse_range("bytes=0-9,20-29", 100), None);
+    assert_eq!(parse_range("bytes=9-0", 100), None);
+    assert_eq!(parse_range("bytes=a-b", 100), None);
+    assert_eq!(parse_range("bytes=5", 100), None);
+  }
+
+  #[test]
+  fn should_respond_with_range() {
+    let request = Request::builder()
+      .header(RANGE, "bytes=2-4")
+      .body(())
+      .unwrap();
+    let response = range_response(&request, Cursor::new(b"0123456789".to_vec())).unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes 2-4/10");
+    let mut body = String::new();
+    response.into_body().read_to_string(&mut body).unwrap();
+    assert_eq!(body, "234");
+
+    let request = Request::builder().body(()).unwrap();
+    let response = range_response(&request, Cursor::new(b"0123456789".to_vec())).unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_LENGTH], "10");
+  }
+}
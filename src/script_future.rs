@@ -0,0 +1,92 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  future::Future,
+  pin::Pin,
+  sync::{Arc, Mutex},
+  task::{Context, Poll, Waker},
+};
+
+use crate::{Error, Result};
+
+#[derive(Default)]
+struct State {
+  result: Option<Result<String>>,
+  // whether the result was set, it is taken by the future
+  settled: bool,
+  waker: Option<Waker>,
+}
+
+/// The future of [`WebView::evaluate_script_async`](crate::WebView::evaluate_script_async),
+/// settled by its [`ScriptResolver`].
+pub(crate) struct ScriptFuture {
+  state: Arc<Mutex<State>>,
+}
+
+/// Settles a [`ScriptFuture`] from the callback of the evaluation.
+///
+/// The future fails with [`Error::ScriptNotEvaluated`] when the last clone is dropped without
+/// settling it, e.g. when the callback is dropped without being called.
+#[derive(Clone)]
+pub(crate) struct ScriptResolver {
+  guard: Arc<ResolverGuard>,
+}
+
+struct ResolverGuard {
+  state: Arc<Mutex<State>>,
+}
+
+impl Drop for ResolverGuard {
+  fn drop(&mut self) {
+    settle(&self.state, Err(Error::ScriptNotEvaluated));
+  }
+}
+
+/// Settles `state` with `result`, unless it is settled already.
+fn settle(state: &Mutex<State>, result: Result<String>) {
+  let mut state = state.lock().unwrap();
+  if !state.settled {
+    state.settled = true;
+    state.result = Some(result);
+    if let Some(waker) = state.waker.take() {
+      waker.wake();
+    }
+  }
+}
+
+impl ScriptFuture {
+  pub fn new() -> (Self, ScriptResolver) {
+    let state = Arc::new(Mutex::new(State::default()));
+    (
+      Self {
+        state: state.clone(),
+      },
+      ScriptResolver {
+        guard: Arc::new(ResolverGuard { state }),
+      },
+    )
+  }
+}
+
+impl ScriptResolver {
+  pub fn resolve(&self, result: Result<String>) {
+    settle(&self.guard.state, result);
+  }
+}
+
+impl Future for ScriptFuture {
+  type Output = Result<String>;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let mut state = self.state.lock().unwrap();
+    match state.result.take() {
+      Some(result) => Poll::Ready(result),
+      None => {
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+      }
+    }
+  }
+}
@@ -0,0 +1,112 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::ops::{Deref, DerefMut};
+
+#[cfg(not(target_os = "windows"))]
+use base64::{engine::general_purpose, Engine};
+#[cfg(target_os = "windows")]
+use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2SharedBuffer;
+
+/// The access of the page to a [`SharedBuffer`] posted with
+/// [`WebView::post_shared_buffer`](crate::WebView::post_shared_buffer).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedBufferAccess {
+  /// The page can only read the buffer.
+  ReadOnly,
+  /// The page can read and write the buffer.
+  ReadWrite,
+}
+
+/// A buffer of bytes shared with the page, created with
+/// [`WebView::create_shared_buffer`](crate::WebView::create_shared_buffer).
+///
+/// The page receives it as an `ArrayBuffer` in the `detail.buffer` of a `sharedbuffer` event
+/// dispatched on the `window`, whose `detail.additionalData` is the additional data of the post.
+///
+/// ## Platform-specific
+///
+/// - **Windows**: The buffer is a WebView2 shared memory, posted without copy. The writes of the
+/// page with [`SharedBufferAccess::ReadWrite`] are visible to Rust.
+/// - **macOS / Linux / iOS / Android**: The buffer is copied to the page as base64 on every post.
+pub struct SharedBuffer {
+  #[cfg(target_os = "windows")]
+  pub(crate) buffer: ICoreWebView2SharedBuffer,
+  #[cfg(target_os = "windows")]
+  data: *mut u8,
+  #[cfg(target_os = "windows")]
+  len: usize,
+  #[cfg(not(target_os = "windows"))]
+  data: Vec<u8>,
+}
+
+impl SharedBuffer {
+  #[cfg(target_os = "windows")]
+  pub(crate) fn new(buffer: ICoreWebView2SharedBuffer) -> windows::core::Result<Self> {
+    let (data, len) = unsafe { (buffer.Buffer()?, buffer.Size()?) };
+    Ok(Self {
+      buffer,
+      data,
+      len: len as usize,
+    })
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  pub(crate) fn new(len: usize) -> Self {
+    Self { data: vec![0; len] }
+  }
+}
+
+impl Deref for SharedBuffer {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    #[cfg(target_os = "windows")]
+    unsafe {
+      std::slice::from_raw_parts(self.data, self.len)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+      &self.data
+    }
+  }
+}
+
+impl DerefMut for SharedBuffer {
+  fn deref_mut(&mut self) -> &mut [u8] {
+    #[cfg(target_os = "windows")]
+    unsafe {
+      std::slice::from_raw_parts_mut(self.data, self.len)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+      &mut self.data
+    }
+  }
+}
+
+/// Script forwarding the `sharedbufferreceived` events of WebView2 as `sharedbuffer` events of the `window`.
+#[cfg(target_os = "windows")]
+pub(crate) const FORWARD_SHARED_BUFFER_SCRIPT: &str = r#"window.chrome.webview.addEventListener('sharedbufferreceived', function (e) {
+  window.dispatchEvent(new CustomEvent('sharedbuffer', { detail: { buffer: e.getBuffer(), additionalData: e.additionalData === undefined ? null : e.additionalData } }));
+});"#;
+
+/// Script dispatching a `sharedbuffer` event with a copy of `buffer` on the `window`.
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn post_script(
+  buffer: &SharedBuffer,
+  additional_data: Option<&serde_json::Value>,
+) -> String {
+  let data = general_purpose::STANDARD.encode(&buffer.data);
+  let additional_data = additional_data.unwrap_or(&serde_json::Value::Null);
+  format!(
+    r#"(function () {{
+  var binary = atob('{data}');
+  var bytes = new Uint8Array(binary.length);
+  for (var i = 0; i < binary.length; i++) bytes[i] = binary.charCodeAt(i);
+  window.dispatchEvent(new CustomEvent('sharedbuffer', {{ detail: {{ buffer: bytes.buffer, additionalData: {additional_data} }} }}));
+}})();"#
+  )
+}
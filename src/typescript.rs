@@ -0,0 +1,59 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::fmt::Write;
+
+use crate::WebViewAttributes;
+
+/// TypeScript definitions of `window.ipc` and of the IPC channels, commands and bindings
+/// registered in `attrs`, see [`WebViewBuilder::typescript_definitions`](crate::WebViewBuilder::typescript_definitions).
+pub(crate) fn definitions(attrs: &WebViewAttributes) -> String {
+  let channels = attrs
+    .ipc_channels
+    .iter()
+    .map(|(name, _)| string_literal(name))
+    .collect::<Vec<_>>();
+  let channel_type = if channels.is_empty() {
+    "never".to_string()
+  } else {
+    channels.join(" | ")
+  };
+
+  let mut definitions = String::from(
+    "// Generated by wry, do not edit.\n\nexport {};\n\ndeclare global {\n  interface WryIpcChannel {\n    postMessage(message: string): void;\n  }\n\n  interface WryIpc {\n    postMessage(message: string | ArrayBuffer | ArrayBufferView): void;\n    postMessageWithReply?(message: string): Promise<string>;\n    invoke<T = unknown>(cmd: string, payload?: unknown): Promise<T>;\n",
+  );
+  let _ = writeln!(
+    definitions,
+    "    channel(name: {channel_type}): WryIpcChannel;\n  }}\n"
+  );
+
+  definitions.push_str("  interface Window {\n    readonly ipc: WryIpc;\n");
+  #[cfg(feature = "serde-ipc")]
+  if !attrs.ipc_commands.is_empty() {
+    definitions.push_str("    readonly ipcCommands: {\n");
+    for (name, _) in &attrs.ipc_commands {
+      let _ = writeln!(
+        definitions,
+        "      readonly {}: <T = unknown>(payload?: unknown) => Promise<T>;",
+        string_literal(name)
+      );
+    }
+    definitions.push_str("    };\n");
+  }
+  for (name, _) in &attrs.bindings {
+    let _ = writeln!(
+      definitions,
+      "    readonly {}: <T = unknown>(...args: unknown[]) => Promise<T>;",
+      string_literal(name)
+    );
+  }
+  definitions.push_str("  }\n}\n");
+
+  definitions
+}
+
+/// A TypeScript string literal of `value`, also valid as a property name.
+fn string_literal(value: &str) -> String {
+  serde_json::to_string(value).unwrap()
+}
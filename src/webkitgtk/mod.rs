@@ -11,18 +11,25 @@ use gtk::{
   gio::Cancellable,
   prelude::*,
 };
+use http::Request;
 use javascriptcore::ValueExt;
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
 #[cfg(any(debug_assertions, feature = "devtools"))]
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::{
+  cell::{Cell, RefCell},
+  collections::{HashMap, HashSet},
+  rc::{Rc, Weak},
+  sync::{Arc, Mutex},
+};
 use url::Url;
 use webkit2gtk::{
-  AutoplayPolicy, InputMethodContextExt, LoadEvent, NavigationPolicyDecision,
-  NavigationPolicyDecisionExt, NetworkProxyMode, NetworkProxySettings, PolicyDecisionType,
-  SettingsExt, URIRequest, URIRequestExt, UserContentInjectedFrames, UserContentManagerExt,
-  UserScript, UserScriptInjectionTime, WebInspectorExt, WebView, WebViewExt, WebsiteDataManagerExt,
-  WebsitePolicies,
+  AutoplayPolicy, BackForwardListExt, BackForwardListItemExt, FindControllerExt,
+  InputMethodContextExt, LoadEvent, NavigationPolicyDecision, NavigationPolicyDecisionExt,
+  NetworkProxyMode, NetworkProxySettings, PolicyDecisionType, PrintOperationExt, SettingsExt,
+  SnapshotOptions, SnapshotRegion, URIRequest, URIRequestExt, UserContentInjectedFrames,
+  UserContentManagerExt, UserScript, UserScriptInjectionTime, UserStyleLevel, UserStyleSheet,
+  WebInspectorExt, WebView, WebViewExt, WebsiteDataManagerExt, WebsitePolicies,
 };
 use webkit2gtk_sys::{
   webkit_get_major_version, webkit_get_micro_version, webkit_get_minor_version,
@@ -34,19 +41,33 @@ use web_context::WebContextExt;
 pub use web_context::WebContextImpl;
 
 use crate::{
-  proxy::ProxyConfig, web_context::WebContext, Error, PageLoadEvent, Rect, Result,
-  WebViewAttributes, RGBA,
+  proxy::ProxyConfig, web_context::WebContext, AcceleratorKeyEvent, EditCommand, Error, FrameInfo,
+  HistoryEntry, HttpsError, HttpsErrorDecision, HttpsErrorKind, KeyCode, LoadError, LoadErrorCode,
+  PageLoadEvent, PrintDialogDecision, PrintDialogRequest, Rect, RequestAsyncResponder, Result,
+  ScriptId, StyleId, WebViewAttributes, WindowLevel, ZOrder, RGBA,
 };
 
 mod file_drop;
 mod synthetic_mouse_events;
 mod web_context;
 
+/// The scripts evaluated before the page is committed, with the callback of their result.
+type PendingScripts = Arc<
+  Mutex<
+    Option<
+      Vec<(
+        String,
+        Option<Box<dyn Fn(std::result::Result<String, String>) + Send>>,
+      )>,
+    >,
+  >,
+>;
+
 pub(crate) struct InnerWebView {
   pub webview: WebView,
   #[cfg(any(debug_assertions, feature = "devtools"))]
   is_inspector_open: Arc<AtomicBool>,
-  pending_scripts: Arc<Mutex<Option<Vec<String>>>>,
+  pending_scripts: PendingScripts,
 
   is_child: bool,
   xlib: Option<Xlib>,
@@ -56,6 +77,47 @@ pub(crate) struct InnerWebView {
   gtk_window: Option<gtk::Window>,
 
   is_in_fixed_parent: bool,
+  zoom_limits: Rc<Cell<Option<(f64, f64)>>>,
+  initialization_scripts: RefCell<HashMap<ScriptId, UserScript>>,
+  user_style_sheets: RefCell<HashMap<StyleId, UserStyleSheet>>,
+  // the `counted-matches` handler of the last `find`
+  find_count_handler: Rc<Cell<Option<gtk::glib::SignalHandlerId>>>,
+  custom_protocols: Rc<RefCell<CustomProtocols>>,
+  // the schemes registered to the web context, by any webview
+  context_protocols: Rc<RefCell<HashSet<String>>>,
+}
+
+type CustomProtocolHandler = Rc<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)>;
+
+/// The custom protocols of a webview. Their schemes are registered to the web context with a
+/// handler looking up this table, so they can be changed after the webview is built.
+#[derive(Default)]
+struct CustomProtocols {
+  handlers: HashMap<String, CustomProtocolHandler>,
+  // the schemes registered to the web context
+  registered: HashSet<String>,
+}
+
+/// The handler of the scheme `name` registered to the web context, which outlives the webview
+/// so it doesn't keep its `protocols` alive.
+fn custom_protocol_dispatcher(
+  protocols: Weak<RefCell<CustomProtocols>>,
+  name: String,
+) -> impl Fn(Request<Vec<u8>>, RequestAsyncResponder) {
+  move |request, responder| {
+    let handler = protocols
+      .upgrade()
+      .and_then(|protocols| protocols.borrow().handlers.get(&name).cloned());
+    match handler {
+      Some(handler) => handler(request, responder),
+      None => responder.respond(
+        http::Response::builder()
+          .status(http::StatusCode::NOT_FOUND)
+          .body(Vec::new())
+          .unwrap(),
+      ),
+    }
+  }
 }
 
 impl Drop for InnerWebView {
@@ -207,7 +269,12 @@ impl InnerWebView {
       };
       use webkit2gtk::WebContextExt;
       if let Some(website_data_manager) = web_context.context().website_data_manager() {
-        let mut settings = NetworkProxySettings::new(Some(proxy_uri.as_str()), &[]);
+        let ignore_hosts = attributes
+          .http_proxy_exclude_hosts
+          .iter()
+          .map(String::as_str)
+          .collect::<Vec<_>>();
+        let mut settings = NetworkProxySettings::new(Some(proxy_uri.as_str()), &ignore_hosts);
         website_data_manager
           .set_network_proxy_settings(NetworkProxyMode::Custom, Some(&mut settings));
       }
@@ -245,13 +312,50 @@ impl InnerWebView {
 
       if let Some(js) = msg.js_value() {
         if let Some(ipc_handler) = &ipc_handler {
-          ipc_handler(js.to_string());
+          // the script message doesn't tell its frame, so its source is unknown
+          crate::ipc::with_message_source(None, || ipc_handler(js.to_string()));
         }
       }
     });
 
     // Register the handler we just connected
     manager.register_script_message_handler(&window_id.to_string());
+    if !attributes.isolated_initialization_scripts.is_empty() {
+      manager.register_script_message_handler_in_world(
+        &window_id.to_string(),
+        crate::ISOLATED_WORLD_NAME,
+      );
+    }
+
+    // url changed handler
+    if let Some(on_url_changed) = attributes.on_url_changed {
+      webview.connect_uri_notify(move |webview| {
+        if let Some(url) = webview.uri().and_then(|uri| Url::parse(&uri).ok()) {
+          on_url_changed(url);
+        }
+      });
+    }
+
+    // load error handler
+    if let Some(on_load_error) = attributes.on_load_error {
+      webview.connect_load_failed(move |_webview, _event, uri, error| {
+        if let Ok(url) = Url::parse(uri) {
+          on_load_error(LoadError {
+            url,
+            code: load_error_code(error),
+            description: error.message().to_string(),
+          });
+        }
+        false
+      });
+    }
+
+    // load progress handler
+    if let Some(on_load_progress) = attributes.on_load_progress {
+      webview.connect_estimated_load_progress_notify(move |webview| {
+        on_load_progress(webview.estimated_load_progress());
+      });
+    }
 
     // document title changed handler
     if let Some(document_title_changed_handler) = attributes.document_title_changed_handler {
@@ -260,6 +364,158 @@ impl InnerWebView {
       });
     }
 
+    // page zoom changed handler, also clamping the zoom changes of the user to the zoom limits
+    let zoom_limits = Rc::new(Cell::new(attributes.zoom_limits));
+    let zoom_limits_ = zoom_limits.clone();
+    let on_page_zoom_change = attributes.on_page_zoom_change.take();
+    webview.connect_zoom_level_notify(move |webview| {
+      let zoom = webview.zoom_level();
+      if let Some((min, max)) = zoom_limits_.get() {
+        if !(min..=max).contains(&zoom) {
+          // notified again with the clamped zoom level
+          webview.set_zoom_level(zoom.clamp(min, max));
+          return;
+        }
+      }
+      if let Some(on_page_zoom_change) = &on_page_zoom_change {
+        on_page_zoom_change(zoom);
+      }
+    });
+
+    // https error handler
+    if let Some(on_https_error) = attributes.on_https_error.take() {
+      webview.connect_load_failed_with_tls_errors(
+        move |webview, failing_uri, certificate, errors| {
+          let Ok(url) = Url::parse(failing_uri) else {
+            return false;
+          };
+          let Some(host) = url.host_str().map(ToString::to_string) else {
+            return false;
+          };
+
+          match on_https_error(HttpsError {
+            url,
+            kind: https_error_kind(errors),
+          }) {
+            HttpsErrorDecision::Block => false,
+            HttpsErrorDecision::Continue(reason) => {
+              use webkit2gtk::WebContextExt as _;
+              log::warn!("ignoring the TLS errors of {failing_uri}: {reason:?}");
+              if let Some(context) = webview.context() {
+                context.allow_tls_certificate_for_host(certificate, &host);
+                webview.load_uri(failing_uri);
+              }
+              true
+            }
+          }
+        },
+      );
+    }
+
+    // media capture state handler
+    if let Some(on_media_capture_state_change) = attributes.on_media_capture_state_change.take() {
+      let on_media_capture_state_change = std::rc::Rc::new(move |webview: &WebView| {
+        on_media_capture_state_change(crate::MediaCaptureState {
+          camera: capture_state(webview.camera_capture_state()),
+          microphone: capture_state(webview.microphone_capture_state()),
+          screen: capture_state(webview.display_capture_state()),
+        })
+      });
+      let handler = on_media_capture_state_change.clone();
+      webview.connect_camera_capture_state_notify(move |webview| handler(webview));
+      let handler = on_media_capture_state_change.clone();
+      webview.connect_microphone_capture_state_notify(move |webview| handler(webview));
+      webview.connect_display_capture_state_notify(move |webview| {
+        on_media_capture_state_change(webview)
+      });
+    }
+
+    // audio muted handler
+    if let Some(on_audio_muted_change) = attributes.on_audio_muted_change.take() {
+      webview.connect_is_muted_notify(move |webview| on_audio_muted_change(webview.is_muted()));
+    }
+
+    // print dialog handler
+    if let Some(on_print_dialog) = attributes.on_print_dialog.take() {
+      webview.connect_print(move |webview, print_operation| {
+        let Some(url) = webview.uri().and_then(|uri| Url::parse(&uri).ok()) else {
+          return false;
+        };
+
+        match on_print_dialog(PrintDialogRequest {
+          url,
+          initiated_by_js: true,
+        }) {
+          PrintDialogDecision::Allow => false,
+          PrintDialogDecision::Deny => true,
+          PrintDialogDecision::RedirectToPdf(path) => {
+            if let Ok(uri) = gtk::glib::filename_to_uri(&path, None) {
+              let settings = gtk::PrintSettings::new();
+              settings.set("printer", Some("Print to File"));
+              settings.set("output-file-format", Some("pdf"));
+              settings.set("output-uri", Some(&uri));
+              print_operation.set_print_settings(&settings);
+              print_operation.print();
+            }
+            true
+          }
+        }
+      });
+    }
+
+    // redirect handler
+    if let Some(on_redirect) = attributes.on_redirect.take() {
+      let current = std::cell::RefCell::new(None::<Url>);
+      webview.connect_load_changed(move |webview, load_event| {
+        let Some(url) = webview.uri().and_then(|uri| Url::parse(&uri).ok()) else {
+          return;
+        };
+        match load_event {
+          LoadEvent::Started => *current.borrow_mut() = Some(url),
+          LoadEvent::Redirected => {
+            if let Some(from) = current.replace(Some(url.clone())) {
+              on_redirect(from, url);
+            }
+          }
+          _ => (),
+        }
+      });
+    }
+
+    // accelerator key handler
+    if let Some(on_accelerator_key) = attributes.on_accelerator_key.take() {
+      webview.connect_key_press_event(move |webview, event| {
+        // the key without modifiers, e.g. `1` instead of `!` with Shift
+        let keyval = gdk::Keymap::for_display(&webview.display())
+          .and_then(|keymap| {
+            keymap.translate_keyboard_state(
+              event.hardware_keycode() as u32,
+              gdk::ModifierType::empty(),
+              event.group() as i32,
+            )
+          })
+          .map(|(keyval, ..)| gdk::keys::Key::from(keyval))
+          .unwrap_or_else(|| event.keyval())
+          .to_lower();
+        let Some(key) = KeyCode::ALL.into_iter().find(|key| gdk_key(*key) == keyval) else {
+          return gtk::glib::Propagation::Proceed;
+        };
+
+        let state = event.state();
+        if on_accelerator_key(AcceleratorKeyEvent {
+          key,
+          ctrl: state.contains(gdk::ModifierType::CONTROL_MASK),
+          shift: state.contains(gdk::ModifierType::SHIFT_MASK),
+          alt: state.contains(gdk::ModifierType::MOD1_MASK),
+          meta: state.intersects(gdk::ModifierType::SUPER_MASK | gdk::ModifierType::META_MASK),
+        }) {
+          gtk::glib::Propagation::Stop
+        } else {
+          gtk::glib::Propagation::Proceed
+        }
+      });
+    }
+
     let on_page_load_handler = attributes.on_page_load_handler.take();
     if on_page_load_handler.is_some() {
       webview.connect_load_changed(move |webview, load_event| match load_event {
@@ -286,6 +542,43 @@ impl InnerWebView {
 
     synthetic_mouse_events::setup(&webview);
 
+    // add the default headers to the main frame navigations missing them, by loading them again.
+    // The navigation policy can't tell the main frame apart, but its main resource can, and the
+    // navigation handler already allowed the navigation when the resource starts loading.
+    if let Some(default_headers) = attributes.default_headers.take() {
+      // the last navigation loaded again, in case WebKit drops one of the headers
+      let reloaded = RefCell::new(None::<gtk::glib::GString>);
+      webview.connect_resource_load_started(move |webview, resource, request| {
+        if webview.main_resource().as_ref() != Some(resource) {
+          return;
+        }
+        let (Some(uri), Some(headers)) = (request.uri(), request.http_headers()) else {
+          return;
+        };
+        let is_reloaded = reloaded.take().as_ref() == Some(&uri);
+        if is_reloaded
+          || request.http_method().as_deref() != Some("GET")
+          || default_headers
+            .keys()
+            .all(|name| headers.one(name.as_str()).is_some())
+        {
+          return;
+        }
+
+        let new_request = URIRequest::new(&uri);
+        if let Some(mut new_headers) = new_request.http_headers() {
+          headers.foreach(|name, value| new_headers.append(name, value));
+          for (name, value) in default_headers.iter() {
+            if headers.one(name.as_str()).is_none() {
+              new_headers.append(name.as_str(), value.to_str().unwrap_or_default());
+            }
+          }
+        }
+        reloaded.replace(Some(uri));
+        webview.load_request(&new_request);
+      });
+    }
+
     if attributes.navigation_handler.is_some() || attributes.new_window_req_handler.is_some() {
       webview.connect_decide_policy(move |_webview, policy_decision, policy_type| {
         let handler = match policy_type {
@@ -385,6 +678,9 @@ impl InnerWebView {
       if attributes.devtools {
         settings.set_enable_developer_extras(true);
       }
+
+      settings.set_print_backgrounds(attributes.print_background);
+      settings.set_zoom_text_only(attributes.text_zoom_only);
     }
 
     // Transparent
@@ -436,22 +732,69 @@ impl InnerWebView {
       x11_window: None,
       gtk_window: None,
       is_in_fixed_parent,
+      zoom_limits,
+      initialization_scripts: Default::default(),
+      user_style_sheets: Default::default(),
+      find_count_handler: Default::default(),
+      custom_protocols: Default::default(),
+      context_protocols: web_context.os.registered_protocols(),
     };
 
     // Initialize message handler
-    let mut init = String::with_capacity(115 + 20 + 22);
-    init.push_str("Object.defineProperty(window, 'ipc', {value: Object.freeze({postMessage:function(x){window.webkit.messageHandlers[\"");
+    let post_chunked = crate::ipc::post_chunked_function(attributes.max_ipc_message_size_bytes);
+    let encode_message = crate::ipc::encode_message_function();
+    let invoke = crate::ipc::invoke_function();
+    let channel = crate::ipc::channel_function();
+    let mut init = String::with_capacity(
+      180 + 20 + 41 + post_chunked.len() + encode_message.len() + invoke.len() + channel.len(),
+    );
+    init.push_str("(function(){var post=");
+    init.push_str(&post_chunked);
+    init.push_str(";Object.defineProperty(window, 'ipc', {value: Object.freeze({postMessage:function(x){post(function(m){window.webkit.messageHandlers[\"");
     init.push_str(&window_id.to_string());
-    init.push_str("\"].postMessage(x)}})})");
+    init.push_str("\"].postMessage(m)},");
+    init.push_str(&encode_message);
+    init.push_str("(x))},invoke:");
+    init.push_str(&invoke);
+    init.push_str(",channel:");
+    init.push_str(&channel);
+    init.push_str("})})})()");
     w.init(&init)?;
 
     // Initialize scripts
-    for js in attributes.initialization_scripts {
+    for js in attributes
+      .initialization_scripts
+      .into_iter()
+      .chain(attributes.main_frame_initialization_scripts)
+    {
       w.init(&js)?;
     }
+    for (js, main_only) in attributes.document_end_scripts {
+      w.add_user_script(&js, main_only, UserScriptInjectionTime::End)?;
+    }
+    if !attributes.isolated_initialization_scripts.is_empty() {
+      w.init_isolated(&format!(
+        "(function(){{var post={};Object.defineProperty(window, 'ipc', {{value: Object.freeze({{postMessage:function(x){{post(function(m){{window.webkit.messageHandlers[\"{window_id}\"].postMessage(m)}},{}(x))}}}})}})}})()",
+        post_chunked, encode_message,
+      ))?;
+      for js in attributes.isolated_initialization_scripts {
+        w.init_isolated(&js)?;
+      }
+    }
 
     for (name, handler) in attributes.custom_protocols {
-      match web_context.register_uri_scheme(&name, handler) {
+      let options = attributes
+        .custom_protocol_options
+        .get(&name)
+        .cloned()
+        .unwrap_or_default();
+      let mut protocols = w.custom_protocols.borrow_mut();
+      protocols.handlers.insert(name.clone(), Rc::from(handler));
+      protocols.registered.insert(name.clone());
+      drop(protocols);
+
+      let handler = custom_protocol_dispatcher(Rc::downgrade(&w.custom_protocols), name.clone());
+      match web_context::register_custom_protocol(web_context, &name, handler, &options) {
         // Swallow duplicate scheme errors to preserve current behavior.
         // FIXME: we should log this error in the future
         Err(Error::DuplicateCustomProtocol(_)) => (),
@@ -471,13 +814,15 @@ impl InnerWebView {
     let pending_scripts = w.pending_scripts.clone();
     w.webview.connect_load_changed(move |webview, event| {
       if let LoadEvent::Committed = event {
-        let mut pending_scripts_ = pending_scripts.lock().unwrap();
-        if let Some(pending_scripts) = &*pending_scripts_ {
-          let cancellable: Option<&Cancellable> = None;
-          for script in pending_scripts {
-            webview.run_javascript(script, cancellable, |_| ());
+        let pending_scripts = pending_scripts.lock().unwrap().take();
+        for (script, callback) in pending_scripts.into_iter().flatten() {
+          match callback {
+            Some(callback) => run_javascript_with_result(webview, &script, callback),
+            None => {
+              let cancellable: Option<&Cancellable> = None;
+              webview.run_javascript(&script, cancellable, |_| ());
+            }
           }
-          *pending_scripts_ = None;
         }
       }
     });
@@ -486,10 +831,74 @@ impl InnerWebView {
   }
 
   pub fn print(&self) {
-    let _ = self.eval(
-      "window.print()",
-      None::<Box<dyn FnOnce(String) + Send + 'static>>,
-    );
+    // `window.print()` would be intercepted by `on_print_dialog`
+    let parent = self
+      .webview
+      .toplevel()
+      .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+    webkit2gtk::PrintOperation::new(&self.webview).run_dialog(parent.as_ref());
+  }
+
+  pub fn restore_dom(&self, png: Vec<u8>) -> Result<()> {
+    self.eval(
+      &crate::restore_dom_script(&png),
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn resume_rendering(&self) -> Result<()> {
+    self.eval(
+      crate::RESUME_RENDERING_SCRIPT,
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn snapshot(
+    &self,
+    rect_script: &str,
+    target: &str,
+    callback: Box<dyn FnOnce(Result<Vec<u8>>)>,
+  ) {
+    let webview = self.webview.clone();
+    let target = target.to_string();
+    let cancellable: Option<&Cancellable> = None;
+
+    self
+      .webview
+      .run_javascript(rect_script, cancellable, move |result| {
+        let rect = result.map_err(Error::from).and_then(|js_result| {
+          let json = js_result
+            .js_value()
+            .and_then(|js_value| js_value.to_json(0))
+            .unwrap_or_default();
+          crate::parse_element_rect(&json, &target)
+        });
+        let rect = match rect {
+          Ok(rect) => rect,
+          Err(e) => return callback(Err(e)),
+        };
+
+        let cancellable: Option<&Cancellable> = None;
+        webview.snapshot(
+          SnapshotRegion::Visible,
+          SnapshotOptions::NONE,
+          cancellable,
+          move |surface| {
+            let png = surface.map_err(Error::from).and_then(|surface| {
+              let pixbuf = gdk::pixbuf_get_from_surface(
+                &surface,
+                rect.x as i32,
+                rect.y as i32,
+                rect.width.ceil() as i32,
+                rect.height.ceil() as i32,
+              )
+              .ok_or_else(|| Error::SnapshotError("failed to read the snapshot surface".into()))?;
+              pixbuf.save_to_bufferv("png", &[]).map_err(Into::into)
+            });
+            callback(png);
+          },
+        );
+      });
   }
 
   pub fn url(&self) -> Url {
@@ -498,13 +907,28 @@ impl InnerWebView {
     Url::parse(uri.as_str()).unwrap()
   }
 
+  pub fn set_user_agent(&self, user_agent: &str) -> Result<()> {
+    if let Some(settings) = WebViewExt::settings(&self.webview) {
+      settings.set_user_agent(Some(user_agent));
+    }
+    Ok(())
+  }
+
+  pub fn title(&self) -> String {
+    self
+      .webview
+      .title()
+      .map(|t| t.to_string())
+      .unwrap_or_default()
+  }
+
   pub fn eval(
     &self,
     js: &str,
     callback: Option<impl FnOnce(String) + Send + 'static>,
   ) -> Result<()> {
     if let Some(pending_scripts) = &mut *self.pending_scripts.lock().unwrap() {
-      pending_scripts.push(js.into());
+      pending_scripts.push((js.into(), None));
     } else {
       let cancellable: Option<&Cancellable> = None;
 
@@ -534,15 +958,44 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn eval_with_result(
+    &self,
+    js: &str,
+    callback: impl Fn(std::result::Result<String, String>) + Send + 'static,
+  ) -> Result<()> {
+    if let Some(pending_scripts) = &mut *self.pending_scripts.lock().unwrap() {
+      // evaluated with the callback once the page is committed
+      pending_scripts.push((js.into(), Some(Box::new(callback))));
+      return Ok(());
+    }
+
+    run_javascript_with_result(&self.webview, js, callback);
+
+    Ok(())
+  }
+
   fn init(&self, js: &str) -> Result<()> {
+    // FIXME: We allow subframe injection because webview2 does and cannot be disabled (currently).
+    // once webview2 allows disabling all-frame script injection, TopFrame should be set
+    // if it does not break anything. (originally added for isolation pattern).
+    self.add_user_script(js, true, UserScriptInjectionTime::Start)
+  }
+
+  fn add_user_script(
+    &self,
+    js: &str,
+    main_only: bool,
+    injection_time: UserScriptInjectionTime,
+  ) -> Result<()> {
     if let Some(manager) = self.webview.user_content_manager() {
       let script = UserScript::new(
         js,
-        // FIXME: We allow subframe injection because webview2 does and cannot be disabled (currently).
-        // once webview2 allows disabling all-frame script injection, TopFrame should be set
-        // if it does not break anything. (originally added for isolation pattern).
-        UserContentInjectedFrames::TopFrame,
-        UserScriptInjectionTime::Start,
+        if main_only {
+          UserContentInjectedFrames::TopFrame
+        } else {
+          UserContentInjectedFrames::AllFrames
+        },
+        injection_time,
         &[],
         &[],
       );
@@ -553,6 +1006,143 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn add_initialization_script(&self, js: &str) -> Result<ScriptId> {
+    let Some(manager) = self.webview.user_content_manager() else {
+      return Err(Error::InitScriptError);
+    };
+    let script = UserScript::new(
+      js,
+      UserContentInjectedFrames::AllFrames,
+      UserScriptInjectionTime::Start,
+      &[],
+      &[],
+    );
+    manager.add_script(&script);
+    let id = ScriptId::next();
+    self.initialization_scripts.borrow_mut().insert(id, script);
+    Ok(id)
+  }
+
+  pub fn remove_initialization_script(&self, id: ScriptId) -> Result<()> {
+    let Some(script) = self.initialization_scripts.borrow_mut().remove(&id) else {
+      return Ok(());
+    };
+    let Some(manager) = self.webview.user_content_manager() else {
+      return Err(Error::InitScriptError);
+    };
+    manager.remove_script(&script);
+    Ok(())
+  }
+
+  pub fn inject_css(&self, css: &str) -> Result<StyleId> {
+    let Some(manager) = self.webview.user_content_manager() else {
+      return Err(Error::InitScriptError);
+    };
+    // the user stylesheets apply to the current page too
+    let style_sheet = UserStyleSheet::new(
+      css,
+      UserContentInjectedFrames::AllFrames,
+      UserStyleLevel::User,
+      &[],
+      &[],
+    );
+    manager.add_style_sheet(&style_sheet);
+    let id = StyleId::next();
+    self.user_style_sheets.borrow_mut().insert(id, style_sheet);
+    Ok(id)
+  }
+
+  pub fn remove_css(&self, id: StyleId) -> Result<()> {
+    let Some(style_sheet) = self.user_style_sheets.borrow_mut().remove(&id) else {
+      return Ok(());
+    };
+    let Some(manager) = self.webview.user_content_manager() else {
+      return Err(Error::InitScriptError);
+    };
+    manager.remove_style_sheet(&style_sheet);
+    Ok(())
+  }
+
+  pub fn find(
+    &self,
+    text: &str,
+    options: crate::FindOptions,
+    callback: impl Fn(usize) + Send + 'static,
+  ) -> Result<()> {
+    let Some(controller) = self.webview.find_controller() else {
+      return Err(Error::NotSupported("finding text"));
+    };
+    if let Some(handler) = self.find_count_handler.take() {
+      controller.disconnect(handler);
+    }
+    controller.search_finish();
+    if text.is_empty() {
+      callback(0);
+      return Ok(());
+    }
+
+    let mut find_options = webkit2gtk::FindOptions::empty();
+    if !options.case_sensitive {
+      find_options |= webkit2gtk::FindOptions::CASE_INSENSITIVE;
+    }
+    if options.backwards {
+      find_options |= webkit2gtk::FindOptions::BACKWARDS;
+    }
+    if options.wrap_around {
+      find_options |= webkit2gtk::FindOptions::WRAP_AROUND;
+    }
+
+    // the matches are counted apart from the search selecting the first one
+    let find_count_handler = self.find_count_handler.clone();
+    let handler = controller.connect_counted_matches(move |controller, count| {
+      if let Some(handler) = find_count_handler.take() {
+        controller.disconnect(handler);
+      }
+      callback(count as usize);
+    });
+    self.find_count_handler.set(Some(handler));
+    controller.count_matches(text, find_options.bits(), u32::MAX);
+    controller.search(text, find_options.bits(), u32::MAX);
+    Ok(())
+  }
+
+  pub fn find_next(&self) -> Result<()> {
+    if let Some(controller) = self.webview.find_controller() {
+      controller.search_next();
+    }
+    Ok(())
+  }
+
+  pub fn find_previous(&self) -> Result<()> {
+    if let Some(controller) = self.webview.find_controller() {
+      controller.search_previous();
+    }
+    Ok(())
+  }
+
+  pub fn clear_matches(&self) -> Result<()> {
+    if let Some(controller) = self.webview.find_controller() {
+      controller.search_finish();
+    }
+    Ok(())
+  }
+
+  fn init_isolated(&self, js: &str) -> Result<()> {
+    let Some(manager) = self.webview.user_content_manager() else {
+      return Err(Error::InitScriptError);
+    };
+    let script = UserScript::for_world(
+      js,
+      UserContentInjectedFrames::AllFrames,
+      UserScriptInjectionTime::Start,
+      crate::ISOLATED_WORLD_NAME,
+      &[],
+      &[],
+    );
+    manager.add_script(&script);
+    Ok(())
+  }
+
   #[cfg(any(debug_assertions, feature = "devtools"))]
   pub fn open_devtools(&self) {
     if let Some(inspector) = WebViewExt::inspector(&self.webview) {
@@ -575,9 +1165,22 @@ impl InnerWebView {
   }
 
   pub fn zoom(&self, scale_factor: f64) {
+    let scale_factor = match self.zoom_limits.get() {
+      Some((min, max)) => scale_factor.clamp(min, max),
+      None => scale_factor,
+    };
     WebViewExt::set_zoom_level(&self.webview, scale_factor);
   }
 
+  pub fn set_zoom_limits(&self, min: f64, max: f64) {
+    self.zoom_limits.set(Some((min, max)));
+    self.zoom(self.zoom_factor());
+  }
+
+  pub fn zoom_factor(&self) -> f64 {
+    WebViewExt::zoom_level(&self.webview)
+  }
+
   pub fn set_background_color(&self, background_color: RGBA) -> Result<()> {
     self.webview.set_background_color(&gtk::gdk::RGBA::new(
       background_color.0 as _,
@@ -588,10 +1191,26 @@ impl InnerWebView {
     Ok(())
   }
 
+  pub fn load_html(&self, html: &str, base_url: Option<&str>) {
+    self.webview.load_html(html, base_url);
+  }
+
   pub fn load_url(&self, url: &str) {
     self.webview.load_uri(url)
   }
 
+  pub fn load_request(&self, request: Request<Vec<u8>>) -> Result<()> {
+    if request.method() != http::Method::GET || !request.body().is_empty() {
+      return Err(Error::NotSupported(
+        "loading a request with a method or body",
+      ));
+    }
+
+    let (parts, _) = request.into_parts();
+    self.load_url_with_headers(&parts.uri.to_string(), parts.headers);
+    Ok(())
+  }
+
   pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) {
     let req = URIRequest::builder().uri(url).build();
 
@@ -661,6 +1280,42 @@ impl InnerWebView {
     bounds
   }
 
+  pub fn scale_factor(&self) -> f64 {
+    self.webview.scale_factor() as f64
+  }
+
+  pub fn set_click_through_regions(&self, regions: &[Rect]) -> Result<()> {
+    set_click_through_regions(&self.click_through_widget(), regions);
+    Ok(())
+  }
+
+  pub fn window_drag_starter(&self) -> Box<dyn Fn()> {
+    let webview = self.webview.downgrade();
+    Box::new(move || {
+      if let Some(webview) = webview.upgrade() {
+        start_window_drag(&webview);
+      }
+    })
+  }
+
+  pub fn click_through_regions_setter(&self) -> Box<dyn Fn(&[Rect])> {
+    let widget = self.click_through_widget().downgrade();
+    Box::new(move |regions| {
+      if let Some(widget) = widget.upgrade() {
+        set_click_through_regions(&widget, regions);
+      }
+    })
+  }
+
+  /// The widget shaped by the click-through regions, the X11 child window of a child webview
+  /// so the input reaches the parent window.
+  fn click_through_widget(&self) -> gtk::Widget {
+    match &self.gtk_window {
+      Some(window) if self.is_child => window.clone().upcast(),
+      _ => self.webview.clone().upcast(),
+    }
+  }
+
   pub fn set_bounds(&self, bounds: Rect) {
     if self.is_child {
       if let Some(window) = &self.gtk_window {
@@ -721,6 +1376,280 @@ impl InnerWebView {
   pub fn focus(&self) {
     self.webview.grab_focus();
   }
+
+  pub fn set_touch_adjust_radius(&self, _radius_css_px: f64) {
+    // Unsupported
+  }
+
+  pub fn reload(&self) {
+    self.webview.reload();
+  }
+
+  pub fn stop(&self) {
+    self.webview.stop_loading();
+  }
+
+  pub fn reload_ignoring_local_cache(&self) {
+    use webkit2gtk::WebContextExt;
+    let Some(data_manager) =
+      WebViewExt::context(&self.webview).and_then(|context| context.website_data_manager())
+    else {
+      self.webview.reload_bypass_cache();
+      return;
+    };
+
+    // the callback is called on the main thread
+    let webview: gtk::glib::SendWeakRef<WebView> = self.webview.downgrade().into();
+    webkit2gtk::WebsiteDataManagerExtManual::clear(
+      &data_manager,
+      webkit2gtk::WebsiteDataTypes::DISK_CACHE | webkit2gtk::WebsiteDataTypes::MEMORY_CACHE,
+      gtk::glib::TimeSpan::from_seconds(0),
+      None::<&Cancellable>,
+      move |_| {
+        if let Some(webview) = webview.upgrade() {
+          webview.reload_bypass_cache();
+        }
+      },
+    );
+  }
+
+  pub fn set_window_level(&self, level: WindowLevel) -> Result<()> {
+    let window = self
+      .webview
+      .toplevel()
+      .and_then(|widget| widget.downcast::<gtk::Window>().ok())
+      .ok_or(Error::NotSupported("setting the level of a non-GTK window"))?;
+
+    window.set_keep_above(level == WindowLevel::Floating);
+    window.set_keep_below(level == WindowLevel::Desktop);
+    Ok(())
+  }
+
+  pub fn register_protocol(
+    &self,
+    name: String,
+    handler: Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)>,
+  ) -> Result<()> {
+    let mut protocols = self.custom_protocols.borrow_mut();
+    if protocols.registered.contains(&name) {
+      protocols.handlers.insert(name, Rc::from(handler));
+      return Ok(());
+    }
+    // the scheme is handled by another webview of the web context
+    if !self.context_protocols.borrow_mut().insert(name.clone()) {
+      return Err(Error::DuplicateCustomProtocol(name));
+    }
+    protocols.handlers.insert(name.clone(), Rc::from(handler));
+    protocols.registered.insert(name.clone());
+    drop(protocols);
+
+    let context = self.webview.context().ok_or(Error::MissingManager)?;
+    let handler = custom_protocol_dispatcher(Rc::downgrade(&self.custom_protocols), name.clone());
+    web_context::actually_register_uri_scheme(&context, &name, handler, true)
+  }
+
+  pub fn unregister_protocol(&self, name: &str) -> Result<()> {
+    // WebKitGTK can't unregister the schemes, their requests get a `404 Not Found` response
+    self.custom_protocols.borrow_mut().handlers.remove(name);
+    Ok(())
+  }
+
+  pub fn set_audio_muted(&self, muted: bool) -> Result<()> {
+    self.webview.set_is_muted(muted);
+    Ok(())
+  }
+
+  pub fn is_audio_muted(&self) -> bool {
+    self.webview.is_muted()
+  }
+
+  pub fn is_playing_audio(&self) -> Result<bool> {
+    Ok(self.webview.is_playing_audio())
+  }
+
+  pub fn history(&self, callback: Box<dyn FnOnce(Vec<HistoryEntry>)>) {
+    callback(self.history_entries())
+  }
+
+  fn history_entries(&self) -> Vec<HistoryEntry> {
+    let Some(list) = self.webview.back_forward_list() else {
+      return Vec::new();
+    };
+
+    let back = list.back_list().len() as i32;
+    let forward = list.forward_list().len() as i32;
+    (-back..=forward)
+      .filter_map(|offset| {
+        let item = list.nth_item(offset)?;
+        let url = Url::parse(&item.uri()?).ok()?;
+        Some(HistoryEntry::new(
+          url,
+          item.title().map(|title| title.to_string()),
+          offset,
+        ))
+      })
+      .collect()
+  }
+
+  pub fn go_to_history_index(&self, index: usize, callback: Box<dyn FnOnce(Result<()>)>) {
+    let result = match self.history_entries().get(index) {
+      Some(entry) => self.navigate_to_history_offset(entry.offset),
+      None => Err(Error::HistoryIndexOutOfBounds(index)),
+    };
+    callback(result)
+  }
+
+  pub fn navigate_to_history_offset(&self, offset: i32) -> Result<()> {
+    if let Some(item) = self
+      .webview
+      .back_forward_list()
+      .and_then(|list| list.nth_item(offset))
+    {
+      self.webview.go_to_back_forward_list_item(&item);
+    }
+    Ok(())
+  }
+
+  pub fn is_visible(&self) -> bool {
+    self.webview.is_visible()
+  }
+
+  pub fn set_parent(&self, parent: &impl HasWindowHandle) -> Result<()> {
+    if !self.is_child {
+      return Err(Error::NotAChildWebView);
+    }
+
+    let parent = match parent.window_handle()?.as_raw() {
+      RawWindowHandle::Xlib(w) => w.window,
+      _ => return Err(Error::UnsupportedWindowHandle),
+    };
+
+    let bounds = self.bounds();
+    if let (Some(xlib), Some(display), Some(window)) =
+      (&self.xlib, self.x11_display, self.x11_window)
+    {
+      unsafe {
+        (xlib.XReparentWindow)(display as _, window, parent, bounds.x, bounds.y);
+        (xlib.XFlush)(display as _);
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn set_z_order(&self, order: ZOrder) -> Result<()> {
+    if !self.is_child {
+      return Err(Error::NotAChildWebView);
+    }
+
+    if let (Some(xlib), Some(display), Some(window)) =
+      (&self.xlib, self.x11_display, self.x11_window)
+    {
+      unsafe {
+        match order {
+          ZOrder::Top => {
+            (xlib.XRaiseWindow)(display as _, window);
+          }
+          ZOrder::Bottom => {
+            (xlib.XLowerWindow)(display as _, window);
+          }
+          ZOrder::Above(other) => {
+            let mut changes: XWindowChanges = std::mem::zeroed();
+            changes.sibling = other.webview.x11_window.ok_or(Error::NotAChildWebView)?;
+            changes.stack_mode = Above;
+            (xlib.XConfigureWindow)(
+              display as _,
+              window,
+              (CWSibling | CWStackMode) as u32,
+              &mut changes,
+            );
+          }
+        }
+        (xlib.XFlush)(display as _);
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn go_back(&self) {
+    self.webview.go_back();
+  }
+
+  pub fn go_forward(&self) {
+    self.webview.go_forward();
+  }
+
+  pub fn can_go_back(&self) -> bool {
+    self.webview.can_go_back()
+  }
+
+  pub fn can_go_forward(&self) -> bool {
+    self.webview.can_go_forward()
+  }
+
+  pub fn execute_edit_command(&self, command: EditCommand) -> Result<()> {
+    let command = match command {
+      EditCommand::Cut => "Cut",
+      EditCommand::Copy => "Copy",
+      EditCommand::Paste => "Paste",
+      EditCommand::SelectAll => "SelectAll",
+      EditCommand::Undo => "Undo",
+      EditCommand::Redo => "Redo",
+    };
+    self.webview.execute_editing_command(command);
+    Ok(())
+  }
+
+  pub fn set_zoom_hotkeys_enabled(&self, _enabled: bool) -> Result<()> {
+    Err(Error::NotSupported("toggling the zoom hotkeys"))
+  }
+
+  pub fn set_background_throttling(&self, _enabled: bool) -> Result<()> {
+    Err(Error::NotSupported("changing the background throttling"))
+  }
+
+  pub fn save_state(&self, callback: Box<dyn FnOnce(Result<Vec<u8>>)>) {
+    callback(
+      self
+        .webview
+        .session_state()
+        .and_then(|state| state.serialize())
+        .map(|bytes| bytes.to_vec())
+        .ok_or(Error::InvalidSessionState),
+    )
+  }
+
+  pub fn restore_state(&self, state: &[u8]) -> Result<()> {
+    let state = webkit2gtk::WebViewSessionState::new(&gtk::glib::Bytes::from(state));
+    self.webview.restore_session_state(&state);
+
+    // only the back/forward list is restored, its current item must be loaded
+    let item = self
+      .webview
+      .back_forward_list()
+      .and_then(|list| list.current_item())
+      .ok_or(Error::InvalidSessionState)?;
+    self.webview.go_to_back_forward_list_item(&item);
+    Ok(())
+  }
+
+  pub fn frames(&self) -> Vec<FrameInfo> {
+    // WebKitGTK doesn't expose the frames to the UI process
+    vec![FrameInfo {
+      id: crate::MAIN_FRAME_ID,
+      url: self.webview.uri().and_then(|uri| Url::parse(&uri).ok()),
+      name: String::new(),
+      is_main_frame: true,
+    }]
+  }
+
+  pub fn eval_in_frame(&self, frame_id: u64, js: &str) -> Result<()> {
+    if frame_id != crate::MAIN_FRAME_ID {
+      return Err(Error::FrameNotFound(frame_id));
+    }
+    self.eval(js, None::<Box<dyn FnOnce(String) + Send + 'static>>)
+  }
 }
 
 pub fn platform_webview_version() -> Result<String> {
@@ -734,6 +1663,197 @@ pub fn platform_webview_version() -> Result<String> {
   Ok(format!("{}.{}.{}", major, minor, patch))
 }
 
+/// Evaluates `js` in `webview`, `callback` is called with the JSON string of its result or with
+/// the message of the exception it threw.
+fn run_javascript_with_result(
+  webview: &WebView,
+  js: &str,
+  callback: impl Fn(std::result::Result<String, String>) + 'static,
+) {
+  let cancellable: Option<&Cancellable> = None;
+  webview.run_javascript(js, cancellable, move |result| {
+    let result = result
+      .map(|result| {
+        result
+          .js_value()
+          .and_then(|value| value.to_json(0))
+          .map(|json| json.to_string())
+          .unwrap_or_default()
+      })
+      .map_err(|error| error.message().to_string());
+    callback(result);
+  });
+}
+
+fn capture_state(state: webkit2gtk::MediaCaptureState) -> crate::CaptureState {
+  match state {
+    webkit2gtk::MediaCaptureState::Active => crate::CaptureState::Active,
+    webkit2gtk::MediaCaptureState::Muted => crate::CaptureState::Muted,
+    _ => crate::CaptureState::Off,
+  }
+}
+
+fn https_error_kind(errors: gtk::gio::TlsCertificateFlags) -> HttpsErrorKind {
+  use gtk::gio::TlsCertificateFlags;
+
+  if errors.intersects(TlsCertificateFlags::EXPIRED | TlsCertificateFlags::NOT_ACTIVATED) {
+    HttpsErrorKind::CertExpired
+  } else if errors.contains(TlsCertificateFlags::BAD_IDENTITY) {
+    HttpsErrorKind::HostnameMismatch
+  } else if errors.contains(TlsCertificateFlags::INSECURE) {
+    HttpsErrorKind::WeakCipher
+  } else if errors.intersects(TlsCertificateFlags::UNKNOWN_CA | TlsCertificateFlags::REVOKED) {
+    HttpsErrorKind::CertUntrusted
+  } else {
+    HttpsErrorKind::Other
+  }
+}
+
+fn load_error_code(error: &gtk::glib::Error) -> LoadErrorCode {
+  use gtk::gio::{IOErrorEnum, ResolverError};
+  use webkit2gtk::{NetworkError, PolicyError};
+
+  if let Some(ResolverError::NotFound | ResolverError::TemporaryFailure) = error.kind() {
+    return LoadErrorCode::HostNotFound;
+  }
+  if let Some(NetworkError::Cancelled) = error.kind() {
+    return LoadErrorCode::Cancelled;
+  }
+  if let Some(NetworkError::UnknownProtocol) = error.kind() {
+    return LoadErrorCode::UnsupportedUrl;
+  }
+  if let Some(PolicyError::CannotShowUri) = error.kind() {
+    return LoadErrorCode::UnsupportedUrl;
+  }
+  match error.kind::<IOErrorEnum>() {
+    Some(IOErrorEnum::ConnectionRefused | IOErrorEnum::HostUnreachable) => {
+      LoadErrorCode::CannotConnect
+    }
+    Some(IOErrorEnum::ConnectionClosed | IOErrorEnum::BrokenPipe) => LoadErrorCode::ConnectionLost,
+    Some(IOErrorEnum::NetworkUnreachable) => LoadErrorCode::Offline,
+    Some(IOErrorEnum::TimedOut) => LoadErrorCode::Timeout,
+    Some(IOErrorEnum::Cancelled) => LoadErrorCode::Cancelled,
+    _ if error.kind::<gtk::gio::TlsError>().is_some() => LoadErrorCode::Certificate,
+    _ => LoadErrorCode::Other,
+  }
+}
+
+/// Removes the click-through `regions` from the input shape of `widget`.
+fn set_click_through_regions(widget: &gtk::Widget, regions: &[Rect]) {
+  use gtk::cairo::{RectangleInt, Region};
+
+  if regions.is_empty() {
+    widget.input_shape_combine_region(None);
+    return;
+  }
+
+  // large enough to still cover the widget when it is resized
+  let shape = Region::create_rectangle(&RectangleInt::new(0, 0, i16::MAX as i32, i16::MAX as i32));
+  for rect in regions {
+    let _ = shape.subtract_rectangle(&RectangleInt::new(
+      rect.x,
+      rect.y,
+      rect.width as i32,
+      rect.height as i32,
+    ));
+  }
+  widget.input_shape_combine_region(Some(&shape));
+}
+
+/// Maps a [`KeyCode`] to its GDK key, without modifiers.
+fn gdk_key(key: KeyCode) -> gdk::keys::Key {
+  use gdk::keys::constants;
+
+  match key {
+    KeyCode::Digit0 => constants::_0,
+    KeyCode::Digit1 => constants::_1,
+    KeyCode::Digit2 => constants::_2,
+    KeyCode::Digit3 => constants::_3,
+    KeyCode::Digit4 => constants::_4,
+    KeyCode::Digit5 => constants::_5,
+    KeyCode::Digit6 => constants::_6,
+    KeyCode::Digit7 => constants::_7,
+    KeyCode::Digit8 => constants::_8,
+    KeyCode::Digit9 => constants::_9,
+    KeyCode::Numpad0 => constants::KP_0,
+    KeyCode::Numpad1 => constants::KP_1,
+    KeyCode::Numpad2 => constants::KP_2,
+    KeyCode::Numpad3 => constants::KP_3,
+    KeyCode::Numpad4 => constants::KP_4,
+    KeyCode::Numpad5 => constants::KP_5,
+    KeyCode::Numpad6 => constants::KP_6,
+    KeyCode::Numpad7 => constants::KP_7,
+    KeyCode::Numpad8 => constants::KP_8,
+    KeyCode::Numpad9 => constants::KP_9,
+    KeyCode::KeyA => constants::a,
+    KeyCode::KeyB => constants::b,
+    KeyCode::KeyC => constants::c,
+    KeyCode::KeyD => constants::d,
+    KeyCode::KeyE => constants::e,
+    KeyCode::KeyF => constants::f,
+    KeyCode::KeyG => constants::g,
+    KeyCode::KeyH => constants::h,
+    KeyCode::KeyI => constants::i,
+    KeyCode::KeyJ => constants::j,
+    KeyCode::KeyK => constants::k,
+    KeyCode::KeyL => constants::l,
+    KeyCode::KeyM => constants::m,
+    KeyCode::KeyN => constants::n,
+    KeyCode::KeyO => constants::o,
+    KeyCode::KeyP => constants::p,
+    KeyCode::KeyQ => constants::q,
+    KeyCode::KeyR => constants::r,
+    KeyCode::KeyS => constants::s,
+    KeyCode::KeyT => constants::t,
+    KeyCode::KeyU => constants::u,
+    KeyCode::KeyV => constants::v,
+    KeyCode::KeyW => constants::w,
+    KeyCode::KeyX => constants::x,
+    KeyCode::KeyY => constants::y,
+    KeyCode::KeyZ => constants::z,
+    KeyCode::Backspace => constants::BackSpace,
+    KeyCode::Delete => constants::Delete,
+    KeyCode::Escape => constants::Escape,
+    KeyCode::Tab => constants::Tab,
+    KeyCode::Enter => constants::Return,
+    KeyCode::Space => constants::space,
+    KeyCode::ArrowLeft => constants::Left,
+    KeyCode::ArrowUp => constants::Up,
+    KeyCode::ArrowRight => constants::Right,
+    KeyCode::ArrowDown => constants::Down,
+    KeyCode::F1 => constants::F1,
+    KeyCode::F2 => constants::F2,
+    KeyCode::F3 => constants::F3,
+    KeyCode::F4 => constants::F4,
+    KeyCode::F5 => constants::F5,
+    KeyCode::F6 => constants::F6,
+    KeyCode::F7 => constants::F7,
+    KeyCode::F8 => constants::F8,
+    KeyCode::F9 => constants::F9,
+    KeyCode::F10 => constants::F10,
+    KeyCode::F11 => constants::F11,
+    KeyCode::F12 => constants::F12,
+  }
+}
+
+/// Starts moving the window of `webview` with the pointer, as if its title bar was pressed.
+fn start_window_drag(webview: &WebView) {
+  let Some(pointer) = gdk::Display::default()
+    .and_then(|display| display.default_seat())
+    .and_then(|seat| seat.pointer())
+  else {
+    return;
+  };
+  let (_, x, y) = pointer.position();
+
+  if let Some(window) = webview
+    .toplevel()
+    .and_then(|widget| widget.downcast::<gtk::Window>().ok())
+  {
+    window.begin_move_drag(1, x, y, gtk::current_event_time());
+  }
+}
+
 // SAFETY: only use this when you are sure the span will be dropped on the same thread it was entered
 #[cfg(feature = "tracing")]
 struct SendEnteredSpan(tracing::span::EnteredSpan);
@@ -4,13 +4,14 @@
 
 //! Unix platform extensions for [`WebContext`](super::WebContext).
 
-use crate::{web_context::WebContextData, Error, RequestAsyncResponder};
-use gtk::glib;
+use crate::{web_context::WebContextData, CustomProtocolOptions, Error, RequestAsyncResponder};
+use gtk::{glib, prelude::Cast};
 use http::{header::CONTENT_TYPE, Request, Response as HttpResponse};
 use std::{
   borrow::Cow,
   cell::RefCell,
   collections::{HashSet, VecDeque},
+  io::Read,
   path::PathBuf,
   rc::Rc,
   str::FromStr,
@@ -30,7 +31,8 @@ pub struct WebContextImpl {
   context: WebContext,
   manager: UserContentManager,
   webview_uri_loader: Rc<WebViewUriLoader>,
-  registered_protocols: HashSet<String>,
+  // shared with the webviews registering protocols after they are built
+  registered_protocols: Rc<RefCell<HashSet<String>>>,
   automation: bool,
   app_info: Option<ApplicationInfo>,
 }
@@ -98,6 +100,11 @@ impl WebContextImpl {
     }
   }
 
+  /// The schemes registered to the context.
+  pub(crate) fn registered_protocols(&self) -> Rc<RefCell<HashSet<String>>> {
+    self.registered_protocols.clone()
+  }
+
   pub fn set_allows_automation(&mut self, flag: bool) {
     use webkit2gtk::WebContextExt;
     self.automation = flag;
@@ -167,8 +174,13 @@ impl WebContextExt for super::WebContext {
   where
     F: Fn(Request<Vec<u8>>, RequestAsyncResponder) + 'static,
   {
-    actually_register_uri_scheme(self, name, handler)?;
-    if self.os.registered_protocols.insert(name.to_string()) {
+    actually_register_uri_scheme(&self.os.context, name, handler, true)?;
+    if self
+      .os
+      .registered_protocols
+      .borrow_mut()
+      .insert(name.to_string())
+    {
       Ok(())
     } else {
       Err(Error::DuplicateCustomProtocol(name.to_string()))
@@ -179,8 +191,13 @@ impl WebContextExt for super::WebContext {
   where
     F: Fn(Request<Vec<u8>>, RequestAsyncResponder) + 'static,
   {
-    if self.os.registered_protocols.insert(name.to_string()) {
-      actually_register_uri_scheme(self, name, handler)
+    if self
+      .os
+      .registered_protocols
+      .borrow_mut()
+      .insert(name.to_string())
+    {
+      actually_register_uri_scheme(&self.os.context, name, handler, true)
     } else {
       Err(Error::DuplicateCustomProtocol(name.to_string()))
     }
@@ -279,21 +296,57 @@ impl WebContextExt for super::WebContext {
   }
 }
 
-fn actually_register_uri_scheme<F>(
+/// Register a custom protocol of a webview with its [`CustomProtocolOptions`], see
+/// [`WebContextExt::register_uri_scheme`] for the duplicate schemes.
+pub(crate) fn register_custom_protocol<F>(
   context: &mut super::WebContext,
   name: &str,
   handler: F,
+  options: &CustomProtocolOptions,
+) -> crate::Result<()>
+where
+  F: Fn(Request<Vec<u8>>, RequestAsyncResponder) + 'static,
+{
+  use webkit2gtk::{SecurityManagerExt, WebContextExt};
+  actually_register_uri_scheme(&context.os.context, name, handler, options.secure_context)?;
+  if !options.allowed_origins.is_empty() {
+    context
+      .os
+      .context
+      .security_manager()
+      .ok_or(Error::MissingManager)?
+      .register_uri_scheme_as_cors_enabled(name);
+  }
+
+  if context
+    .os
+    .registered_protocols
+    .borrow_mut()
+    .insert(name.to_string())
+  {
+    Ok(())
+  } else {
+    Err(Error::DuplicateCustomProtocol(name.to_string()))
+  }
+}
+
+pub(crate) fn actually_register_uri_scheme<F>(
+  context: &WebContext,
+  name: &str,
+  handler: F,
+  secure_context: bool,
 ) -> crate::Result<()>
 where
   F: Fn(Request<Vec<u8>>, RequestAsyncResponder) + 'static,
 {
   use webkit2gtk::{SecurityManagerExt, URISchemeRequestExt, WebContextExt};
-  let context = &context.os.context;
   // Enable secure context
-  context
-    .security_manager()
-    .ok_or(Error::MissingManager)?
-    .register_uri_scheme_as_secure(name);
+  if secure_context {
+    context
+      .security_manager()
+      .ok_or(Error::MissingManager)?
+      .register_uri_scheme_as_secure(name);
+  }
 
   context.register_uri_scheme(name, move |request| {
     #[cfg(feature = "tracing")]
@@ -374,10 +427,8 @@ where
       };
 
       let request_ = request.clone();
-      let responder: Box<dyn FnOnce(HttpResponse<Cow<'static, [u8]>>)> =
-        Box::new(move |http_response| {
-          let buffer = http_response.body();
-          let input = gtk::gio::MemoryInputStream::from_bytes(&gtk::glib::Bytes::from(buffer));
+      let finish =
+        move |input: &gtk::gio::InputStream, length: i64, http_response: HttpResponse<()>| {
           let content_type = http_response
             .headers()
             .get(CONTENT_TYPE)
@@ -386,7 +437,7 @@ where
           use soup::{MessageHeaders, MessageHeadersType};
           use webkit2gtk::URISchemeResponse;
 
-          let response = URISchemeResponse::new(&input, buffer.len() as i64);
+          let response = URISchemeResponse::new(input, length);
           response.set_status(http_response.status().as_u16() as u32, None);
           if let Some(content_type) = content_type {
             response.set_content_type(content_type);
@@ -398,11 +449,37 @@ where
           }
           response.set_http_headers(headers);
           request_.finish_with_response(&response);
+        };
+      let finish_ = finish.clone();
+
+      let responder: Box<dyn FnOnce(HttpResponse<Cow<'static, [u8]>>)> =
+        Box::new(move |http_response| {
+          let (parts, buffer) = http_response.into_parts();
+          let input = gtk::gio::MemoryInputStream::from_bytes(&gtk::glib::Bytes::from(&*buffer));
+          finish(
+            input.upcast_ref(),
+            buffer.len() as i64,
+            HttpResponse::from_parts(parts, ()),
+          );
+        });
+      // the body is read by the thread pool of gio
+      let stream_responder: Box<dyn FnOnce(HttpResponse<Box<dyn Read + Send>>)> =
+        Box::new(move |http_response| {
+          let (parts, body) = http_response.into_parts();
+          let input = gtk::gio::ReadInputStream::new(body);
+          // the length of the streamed responses is unknown
+          finish_(input.upcast_ref(), -1, HttpResponse::from_parts(parts, ()));
         });
 
       #[cfg(feature = "tracing")]
       let _span = tracing::info_span!("wry::custom_protocol::call_handler").entered();
-      handler(http_request, RequestAsyncResponder { responder });
+      handler(
+        http_request,
+        RequestAsyncResponder {
+          responder,
+          stream_responder: Some(stream_responder),
+        },
+      );
     } else {
       request.finish_error(&mut glib::Error::new(
         glib::FileError::Exist,
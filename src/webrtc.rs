@@ -0,0 +1,199 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Statistics of a WebRTC peer connection object, see [`WebView::get_webrtc_stats`](crate::WebView::get_webrtc_stats).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RtcStats {
+  /// The type of the monitored object.
+  pub kind: RtcStatType,
+  /// The unique id of the monitored object.
+  pub id: String,
+  /// The time the stats were collected at, in milliseconds since the UNIX epoch.
+  pub timestamp: f64,
+  /// The numeric stats of the object, e.g. `bytesReceived` or `currentRoundTripTime`.
+  pub values: HashMap<String, f64>,
+}
+
+/// The type of the object monitored by [`RtcStats`], see [`RTCStatsType`](https://www.w3.org/TR/webrtc-stats/#rtcstatstype-str*).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RtcStatType {
+  Codec,
+  InboundRtp,
+  OutboundRtp,
+  RemoteInboundRtp,
+  RemoteOutboundRtp,
+  MediaSource,
+  MediaPlayout,
+  PeerConnection,
+  DataChannel,
+  Transport,
+  CandidatePair,
+  LocalCandidate,
+  RemoteCandidate,
+  Certificate,
+  /// A type not known by wry.
+  Other(String),
+}
+
+impl From<&str> for RtcStatType {
+  fn from(kind: &str) -> Self {
+    match kind {
+      "codec" => Self::Codec,
+      "inbound-rtp" => Self::InboundRtp,
+      "outbound-rtp" => Self::OutboundRtp,
+      "remote-inbound-rtp" => Self::RemoteInboundRtp,
+      "remote-outbound-rtp" => Self::RemoteOutboundRtp,
+      "media-source" => Self::MediaSource,
+      "media-playout" => Self::MediaPlayout,
+      "peer-connection" => Self::PeerConnection,
+      "data-channel" => Self::DataChannel,
+      "transport" => Self::Transport,
+      "candidate-pair" => Self::CandidatePair,
+      "local-candidate" => Self::LocalCandidate,
+      "remote-candidate" => Self::RemoteCandidate,
+      "certificate" => Self::Certificate,
+      other => Self::Other(other.to_string()),
+    }
+  }
+}
+
+#[derive(Deserialize)]
+struct RawRtcStats {
+  #[serde(rename = "type")]
+  kind: String,
+  id: String,
+  timestamp: f64,
+  values: HashMap<String, f64>,
+}
+
+/// Initialization script keeping track of the `RTCPeerConnection`s created by the page, without
+/// keeping them alive when `WeakRef` is available.
+pub(crate) const TRACK_PEER_CONNECTIONS_SCRIPT: &str = r#"(function () {
+  var RTCPeerConnection = window.RTCPeerConnection;
+  if (!RTCPeerConnection || window.__WRY_PEER_CONNECTIONS__) return;
+  var refs = [];
+  function connections() {
+    refs = refs.filter(function (ref) {
+      var connection = ref.deref();
+      return connection && connection.connectionState !== 'closed';
+    });
+    return refs.map(function (ref) { return ref.deref(); });
+  }
+  Object.defineProperty(window, '__WRY_PEER_CONNECTIONS__', { value: connections });
+  window.RTCPeerConnection = new Proxy(RTCPeerConnection, {
+    construct: function (target, args, newTarget) {
+      var connection = Reflect.construct(target, args, newTarget);
+      refs.push(typeof WeakRef === 'function' ? new WeakRef(connection) : {
+        deref: function () { return connection; }
+      });
+      return connection;
+    }
+  });
+})();"#;
+
+/// Script posting the stats of the active peer connections to the `callback` function expression.
+pub(crate) fn stats_script(callback: &str) -> String {
+  format!(
+    r#"(function (callback) {{
+  var connections = (window.__WRY_PEER_CONNECTIONS__ ? window.__WRY_PEER_CONNECTIONS__() : []).filter(function (connection) {{
+    return connection.iceConnectionState === 'connected' || connection.iceConnectionState === 'completed';
+  }});
+  Promise.all(connections.map(function (connection) {{
+    return connection.getStats();
+  }})).then(function (reports) {{
+    var stats = [];
+    reports.forEach(function (report) {{
+      report.forEach(function (entry) {{
+        var values = {{}};
+        Object.keys(entry).forEach(function (key) {{
+          if (key !== 'timestamp' && typeof entry[key] === 'number') values[key] = entry[key];
+        }});
+        stats.push({{ type: entry.type, id: entry.id, timestamp: entry.timestamp, values: values }});
+      }});
+    }});
+    callback(stats);
+  }}).catch(function () {{
+    callback([]);
+  }});
+}})({callback});"#
+  )
+}
+
+/// Parses the value posted by [`stats_script`].
+pub(crate) fn parse_stats(value: serde_json::Value) -> Vec<RtcStats> {
+  serde_json::from_value::<Vec<RawRtcStats>>(value)
+    .unwrap_or_default()
+    .into_iter()
+    .map(|stats| RtcStats {
+      kind: stats.kind.as_str().into(),
+      id: stats.id,
+      timestamp: stats.timestamp,
+      values: stats.values,
+    })
+    .collect()
+}
+
+/// Initialization script restricting the ICE candidates of the `RTCPeerConnection`s to relay ones,
+/// so WebRTC does not leak the local and public IP addresses.
+///
+/// The prefixed constructors are patched too, and so are the windows of the same origin iframes
+/// as soon as the page reaches them or inserts them, since the initialization scripts do not run
+/// in the fresh `about:blank` ones.
+pub(crate) const RELAY_ONLY_SCRIPT: &str = r#"(function () {
+  function relayOnly(configuration) {
+    return Object.assign({}, configuration, { iceTransportPolicy: 'relay' });
+  }
+  function patch(win) {
+    try {
+      if (!win || win.__WRY_RELAY_ONLY__) return;
+      Object.defineProperty(win, '__WRY_RELAY_ONLY__', { value: true });
+    } catch (e) {
+      // a cross origin window, which runs the initialization scripts itself
+      return;
+    }
+    ['RTCPeerConnection', 'webkitRTCPeerConnection'].forEach(function (name) {
+      var RTCPeerConnection = win[name];
+      if (!RTCPeerConnection) return;
+      var setConfiguration = RTCPeerConnection.prototype.setConfiguration;
+      if (setConfiguration && !setConfiguration.__wryRelayOnly) {
+        var patched = function (configuration) {
+          return setConfiguration.call(this, relayOnly(configuration));
+        };
+        patched.__wryRelayOnly = true;
+        RTCPeerConnection.prototype.setConfiguration = patched;
+      }
+      win[name] = new Proxy(RTCPeerConnection, {
+        construct: function (target, args, newTarget) {
+          return Reflect.construct(target, [relayOnly(args[0])].concat(Array.prototype.slice.call(args, 1)), newTarget);
+        }
+      });
+    });
+    ['contentWindow', 'contentDocument'].forEach(function (property) {
+      var HTMLIFrameElement = win.HTMLIFrameElement;
+      var descriptor = HTMLIFrameElement && Object.getOwnPropertyDescriptor(HTMLIFrameElement.prototype, property);
+      if (!descriptor || !descriptor.get) return;
+      Object.defineProperty(HTMLIFrameElement.prototype, property, {
+        configurable: true,
+        enumerable: descriptor.enumerable,
+        get: function () {
+          var value = descriptor.get.call(this);
+          patch(property === 'contentWindow' ? value : value && value.defaultView);
+          return value;
+        }
+      });
+    });
+    // the iframes reached through `window.frames` instead of their element
+    if (win.MutationObserver && win.document) {
+      new win.MutationObserver(function () {
+        for (var i = 0; i < win.frames.length; i++) patch(win.frames[i]);
+      }).observe(win.document, { childList: true, subtree: true });
+    }
+  }
+  patch(window);
+})();"#;
@@ -0,0 +1,196 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// Displays the frame of `WebView::restore_dom` in a child window placed over the webview, the
+// frame is decoded with WIC and painted with GDI so it stays until the window is destroyed.
+
+use std::ffi::c_void;
+
+use windows::{
+  core::{ComInterface, PCWSTR},
+  Win32::{
+    Foundation::*,
+    Graphics::{
+      Gdi::{
+        BeginPaint, EndPaint, InvalidateRect, StretchDIBits, BITMAPINFO, BITMAPINFOHEADER, BI_RGB,
+        DIB_RGB_COLORS, HBRUSH, PAINTSTRUCT, SRCCOPY,
+      },
+      Imaging::{
+        CLSID_WICImagingFactory, GUID_WICPixelFormat32bppPBGRA, IWICBitmapSource,
+        IWICImagingFactory, WICConvertBitmapSource, WICDecodeMetadataCacheOnDemand,
+      },
+    },
+    System::{
+      Com::{CoCreateInstance, CLSCTX_INPROC_SERVER},
+      LibraryLoader::GetModuleHandleW,
+    },
+    UI::{
+      Shell::SHCreateMemStream,
+      WindowsAndMessaging::{
+        self as win32wm, CreateWindowExW, DefWindowProcW, GetClientRect, RegisterClassExW,
+        SetWindowPos, CS_HREDRAW, CS_VREDRAW, HCURSOR, HICON, HMENU, SWP_NOACTIVATE,
+        WINDOW_EX_STYLE, WNDCLASSEXW, WS_CHILD, WS_VISIBLE,
+      },
+    },
+  },
+};
+
+use super::encode_wide;
+use crate::{Error, Result};
+
+// the decoded frame, owned by the overlay window through `GWLP_USERDATA`
+struct Frame {
+  width: i32,
+  height: i32,
+  pixels: Vec<u8>,
+}
+
+fn decode_png(png: &[u8]) -> windows::core::Result<Frame> {
+  unsafe {
+    let factory: IWICImagingFactory =
+      CoCreateInstance(&CLSID_WICImagingFactory, None, CLSCTX_INPROC_SERVER)?;
+    let stream =
+      SHCreateMemStream(Some(png)).ok_or_else(|| windows::core::Error::from(E_OUTOFMEMORY))?;
+    let decoder =
+      factory.CreateDecoderFromStream(&stream, std::ptr::null(), WICDecodeMetadataCacheOnDemand)?;
+    let frame = decoder.GetFrame(0)?.cast::<IWICBitmapSource>()?;
+    let frame = WICConvertBitmapSource(&GUID_WICPixelFormat32bppPBGRA, &frame)?;
+
+    let (mut width, mut height) = (0, 0);
+    frame.GetSize(&mut width, &mut height)?;
+    let mut pixels = vec![0; width as usize * height as usize * 4];
+    frame.CopyPixels(std::ptr::null(), width * 4, &mut pixels)?;
+
+    Ok(Frame {
+      width: width as i32,
+      height: height as i32,
+      pixels,
+    })
+  }
+}
+
+unsafe extern "system" fn overlay_window_proc(
+  hwnd: HWND,
+  msg: u32,
+  wparam: WPARAM,
+  lparam: LPARAM,
+) -> LRESULT {
+  match msg {
+    win32wm::WM_PAINT => {
+      let frame = win32wm::GetWindowLongPtrW(hwnd, win32wm::GWLP_USERDATA) as *const Frame;
+      let mut paint = PAINTSTRUCT::default();
+      let hdc = BeginPaint(hwnd, &mut paint);
+      if let Some(frame) = frame.as_ref() {
+        let mut rect = RECT::default();
+        let _ = GetClientRect(hwnd, &mut rect);
+        let info = BITMAPINFO {
+          bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: frame.width,
+            // a negative height makes the bitmap top-down like the WIC pixels
+            biHeight: -frame.height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+          },
+          ..Default::default()
+        };
+        StretchDIBits(
+          hdc,
+          0,
+          0,
+          rect.right - rect.left,
+          rect.bottom - rect.top,
+          0,
+          0,
+          frame.width,
+          frame.height,
+          Some(frame.pixels.as_ptr() as *const c_void),
+          &info,
+          DIB_RGB_COLORS,
+          SRCCOPY,
+        );
+      }
+      let _ = EndPaint(hwnd, &paint);
+      LRESULT(0)
+    }
+    // the whole window is painted from the frame
+    win32wm::WM_ERASEBKGND => LRESULT(1),
+    win32wm::WM_NCDESTROY => {
+      let frame = win32wm::SetWindowLongPtrW(hwnd, win32wm::GWLP_USERDATA, 0) as *mut Frame;
+      if !frame.is_null() {
+        drop(Box::from_raw(frame));
+      }
+      DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+    _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+  }
+}
+
+/// Creates a child window of `parent` covering its client area and displaying `png`.
+pub(crate) fn create(parent: HWND, png: &[u8]) -> Result<HWND> {
+  let frame = decode_png(png)
+    .map_err(|e| Error::SnapshotError(format!("the frame is not a PNG image: {e}")))?;
+
+  let class_name = encode_wide("WRY_FROZEN_DOM");
+  let class = WNDCLASSEXW {
+    cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+    style: CS_HREDRAW | CS_VREDRAW,
+    lpfnWndProc: Some(overlay_window_proc),
+    cbClsExtra: 0,
+    cbWndExtra: 0,
+    hInstance: unsafe { HINSTANCE(GetModuleHandleW(PCWSTR::null()).unwrap_or_default().0) },
+    hIcon: HICON::default(),
+    hCursor: HCURSOR::default(),
+    hbrBackground: HBRUSH::default(),
+    lpszMenuName: PCWSTR::null(),
+    lpszClassName: PCWSTR::from_raw(class_name.as_ptr()),
+    hIconSm: HICON::default(),
+  };
+
+  unsafe {
+    RegisterClassExW(&class);
+
+    let mut rect = RECT::default();
+    let _ = GetClientRect(parent, &mut rect);
+
+    let hwnd = CreateWindowExW(
+      WINDOW_EX_STYLE::default(),
+      PCWSTR::from_raw(class_name.as_ptr()),
+      PCWSTR::null(),
+      WS_CHILD | WS_VISIBLE,
+      0,
+      0,
+      rect.right - rect.left,
+      rect.bottom - rect.top,
+      parent,
+      HMENU::default(),
+      GetModuleHandleW(PCWSTR::null()).unwrap_or_default(),
+      None,
+    );
+    if hwnd == HWND::default() {
+      return Err(webview2_com::Error::WindowsError(windows::core::Error::from_win32()).into());
+    }
+
+    win32wm::SetWindowLongPtrW(
+      hwnd,
+      win32wm::GWLP_USERDATA,
+      Box::into_raw(Box::new(frame)) as isize,
+    );
+    // above the window of the webview controller
+    let _ = SetWindowPos(
+      hwnd,
+      win32wm::HWND_TOP,
+      0,
+      0,
+      0,
+      0,
+      SWP_NOACTIVATE | win32wm::SWP_NOMOVE | win32wm::SWP_NOSIZE,
+    );
+    let _ = InvalidateRect(hwnd, None, true);
+
+    Ok(hwnd)
+  }
+}
@@ -3,10 +3,22 @@
 // SPDX-License-Identifier: MIT
 
 mod file_drop;
+mod frozen_overlay;
+mod read_stream;
+mod response_transform;
+mod user_styles;
 
 use std::{
-  borrow::Cow, collections::HashSet, fmt::Write, iter::once, os::windows::prelude::OsStrExt,
-  path::PathBuf, rc::Rc, sync::mpsc,
+  borrow::Cow,
+  cell::{Cell, RefCell},
+  collections::{HashMap, HashSet},
+  fmt::Write,
+  io::Read,
+  iter::once,
+  os::windows::prelude::OsStrExt,
+  path::PathBuf,
+  rc::Rc,
+  sync::mpsc,
 };
 
 use http::{Request, Response as HttpResponse, StatusCode};
@@ -19,30 +31,46 @@ use windows::{
   Win32::{
     Foundation::*,
     Globalization::{self, MAX_LOCALE_NAME},
-    Graphics::Gdi::{MapWindowPoints, RedrawWindow, HBRUSH, HRGN, RDW_INTERNALPAINT},
+    Graphics::Gdi::{
+      CombineRgn, CreateRectRgn, DeleteObject, MapWindowPoints, RedrawWindow, SetWindowRgn, HBRUSH,
+      HRGN, RDW_INTERNALPAINT, RGN_DIFF,
+    },
     System::{
-      Com::{CoInitializeEx, IStream, COINIT_APARTMENTTHREADED},
+      Com::{CoInitializeEx, IDispatch, IStream, COINIT_APARTMENTTHREADED},
       LibraryLoader::GetModuleHandleW,
+      Variant::{VariantClear, VARIANT, VT_DISPATCH},
       WinRT::EventRegistrationToken,
     },
     UI::{
-      Shell::{DefSubclassProc, SHCreateMemStream, SetWindowSubclass},
+      HiDpi::GetDpiForWindow,
+      Input::KeyboardAndMouse::{
+        GetKeyState, ReleaseCapture, VIRTUAL_KEY, VK_ADD, VK_BACK, VK_CONTROL, VK_DELETE, VK_DOWN,
+        VK_ESCAPE, VK_F1, VK_F10, VK_F11, VK_F12, VK_F2, VK_F3, VK_F4, VK_F5, VK_F6, VK_F7, VK_F8,
+        VK_F9, VK_LEFT, VK_LWIN, VK_MENU, VK_NUMPAD0, VK_NUMPAD1, VK_NUMPAD2, VK_NUMPAD3,
+        VK_NUMPAD4, VK_NUMPAD5, VK_NUMPAD6, VK_NUMPAD7, VK_NUMPAD8, VK_NUMPAD9, VK_OEM_MINUS,
+        VK_OEM_PLUS, VK_RETURN, VK_RIGHT, VK_RWIN, VK_SHIFT, VK_SPACE, VK_SUBTRACT, VK_TAB, VK_UP,
+      },
+      Shell::{DefSubclassProc, RemoveWindowSubclass, SHCreateMemStream, SetWindowSubclass},
       WindowsAndMessaging::{
         self as win32wm, CreateWindowExW, DefWindowProcW, DestroyWindow, GetClientRect, GetParent,
-        PostMessageW, RegisterClassExW, RegisterWindowMessageA, SetWindowPos, ShowWindow,
-        CS_HREDRAW, CS_VREDRAW, CW_USEDEFAULT, HCURSOR, HICON, HMENU, SWP_ASYNCWINDOWPOS,
-        SWP_NOACTIVATE, SWP_NOZORDER, SW_HIDE, SW_SHOW, WINDOW_EX_STYLE, WNDCLASSEXW, WS_CHILD,
-        WS_CLIPCHILDREN, WS_VISIBLE,
+        IsWindow, PostMessageW, RegisterClassExW, RegisterWindowMessageA, SetParent, SetWindowPos,
+        ShowWindow, CS_HREDRAW, CS_VREDRAW, CW_USEDEFAULT, HCURSOR, HICON, HMENU,
+        SWP_ASYNCWINDOWPOS, SWP_NOACTIVATE, SWP_NOZORDER, SW_HIDE, SW_SHOW,
+        USER_DEFAULT_SCREEN_DPI, WINDOW_EX_STYLE, WNDCLASSEXW, WS_CHILD, WS_CLIPCHILDREN,
+        WS_VISIBLE,
       },
     },
   },
 };
 
-use self::file_drop::FileDropController;
+use self::{file_drop::FileDropController, read_stream::ReadStream, user_styles::UserStyles};
 use super::Theme;
 use crate::{
-  proxy::ProxyConfig, Error, MemoryUsageLevel, PageLoadEvent, Rect, RequestAsyncResponder, Result,
-  WebContext, WebViewAttributes, RGBA,
+  proxy::ProxyConfig, AcceleratorKeyEvent, EditCommand, Error, FrameInfo, HistoryEntry, HttpsError,
+  HttpsErrorDecision, HttpsErrorKind, KeyCode, LoadError, LoadErrorCode, MemoryUsageLevel,
+  PageLoadEvent, PrintDialogDecision, PrintDialogRequest, Rect, RequestAsyncResponder, Result,
+  ScriptId, SharedBuffer, SharedBufferAccess, StyleId, WebContext, WebViewAttributes, WindowLevel,
+  ZOrder, RGBA,
 };
 
 impl From<webview2_com::Error> for Error {
@@ -57,17 +85,40 @@ pub(crate) struct InnerWebView {
   pub controller: ICoreWebView2Controller,
   webview: ICoreWebView2,
   env: ICoreWebView2Environment,
+  zoom_limits: Rc<Cell<Option<(f64, f64)>>>,
+  frames: Frames,
+  // the WebView2 ids of the scripts added with `add_initialization_script`
+  initialization_scripts: RefCell<HashMap<ScriptId, String>>,
+  // the initialization script of `set_touch_adjust_radius`
+  touch_adjust_script: Cell<Option<ScriptId>>,
+  user_styles: UserStyles,
+  // the window of `restore_dom`, destroyed by `resume_rendering`
+  frozen_overlay: Cell<HWND>,
+  https_scheme: bool,
+  custom_protocols: CustomProtocols,
   // Store FileDropController in here to make sure it gets dropped when
   // the webview gets dropped, otherwise we'll have a memory leak
   #[allow(dead_code)]
   file_drop_controller: Option<FileDropController>,
 }
 
+// the custom protocols served by the `WebResourceRequested` handler, by name
+type CustomProtocols =
+  Rc<RefCell<Vec<(String, Rc<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)>)>>>;
+
 impl Drop for InnerWebView {
   fn drop(&mut self) {
+    let _ = self.resume_rendering();
     let _ = unsafe { self.controller.Close() };
     if self.is_child {
-      let _ = unsafe { DestroyWindow(self.hwnd) };
+      unsafe {
+        RemoveWindowSubclass(
+          GetParent(self.hwnd),
+          Some(parent_subclass_proc),
+          self.hwnd.0 as usize,
+        );
+        let _ = DestroyWindow(self.hwnd);
+      }
     }
   }
 }
@@ -153,6 +204,27 @@ impl InnerWebView {
       )
     };
 
+    unsafe {
+      SetWindowSubclass(
+        child,
+        Some(child_dpi_subclass_proc),
+        child.0 as usize,
+        Box::into_raw(Box::new(Cell::new(GetDpiForWindow(child)))) as _,
+      );
+    }
+
+    if attributes.auto_resize {
+      unsafe {
+        SetWindowSubclass(
+          HWND(parent),
+          Some(parent_subclass_proc),
+          child.0 as usize,
+          child.0 as usize,
+        );
+        fill_parent(child);
+      }
+    }
+
     Self::new_as_child_hwnd(child, attributes, pl_attrs, web_context)
   }
 
@@ -180,17 +252,39 @@ impl InnerWebView {
 
     let env = Self::create_environment(&web_context, pl_attrs.clone(), &attributes)?;
     let controller = Self::create_controller(hwnd, &env, attributes.incognito)?;
-    let webview = Self::init_webview(hwnd, attributes, &env, &controller, pl_attrs)?;
+    let zoom_limits = Rc::new(Cell::new(attributes.zoom_limits));
+    let https_scheme = pl_attrs.https_scheme;
+    let custom_protocols = CustomProtocols::default();
+    let webview = Self::init_webview(
+      hwnd,
+      attributes,
+      &env,
+      &controller,
+      pl_attrs,
+      zoom_limits.clone(),
+      custom_protocols.clone(),
+    )?;
+    let frames = track_frames(&webview)?;
 
     let file_drop_controller =
       file_drop_handler.map(|handler| FileDropController::new(hwnd, handler));
 
+    let user_styles = UserStyles::new(webview.clone());
+
     Ok(Self {
       hwnd,
       controller,
       is_child: false,
       webview,
       env,
+      zoom_limits,
+      frames,
+      initialization_scripts: Default::default(),
+      touch_adjust_script: Default::default(),
+      user_styles,
+      frozen_overlay: Cell::new(HWND::default()),
+      https_scheme,
+      custom_protocols,
       file_drop_controller,
     })
   }
@@ -213,14 +307,14 @@ impl InnerWebView {
         // remove "mini menu" - See https://github.com/tauri-apps/wry/issues/535
         // and "smart screen" - See https://github.com/tauri-apps/tauri/issues/1345
         format!(
-          "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection{}{}",
+          "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection{}{}{}{}",
           if attributes.autoplay {
             " --autoplay-policy=no-user-gesture-required"
           } else {
             ""
           },
           if let Some(proxy_setting) = &attributes.proxy_config {
-            match proxy_setting {
+            let proxy_server = match proxy_setting {
               ProxyConfig::Http(endpoint) => {
                 format!(" --proxy-server=http://{}:{}", endpoint.host, endpoint.port)
               }
@@ -228,9 +322,27 @@ impl InnerWebView {
                 " --proxy-server=socks5://{}:{}",
                 endpoint.host, endpoint.port
               ),
+            };
+            if attributes.http_proxy_exclude_hosts.is_empty() {
+              proxy_server
+            } else {
+              format!(
+                "{proxy_server} --proxy-bypass-list={}",
+                attributes.http_proxy_exclude_hosts.join(";")
+              )
             }
           } else {
             "".to_string()
+          },
+          if attributes.enable_webrtc_ip_protection {
+            " --force-webrtc-ip-handling-policy=disable_non_proxied_udp"
+          } else {
+            ""
+          },
+          if attributes.background_throttling {
+            ""
+          } else {
+            " --disable-background-timer-throttling --disable-renderer-backgrounding --disable-backgrounding-occluded-windows"
           }
         )
       }))
@@ -326,6 +438,8 @@ impl InnerWebView {
     env: &ICoreWebView2Environment,
     controller: &ICoreWebView2Controller,
     pl_attrs: super::PlatformSpecificWebViewAttributes,
+    zoom_limits: Rc<Cell<Option<(f64, f64)>>>,
+    custom_protocols: CustomProtocols,
   ) -> webview2_com::Result<ICoreWebView2> {
     let webview =
       unsafe { controller.CoreWebView2() }.map_err(webview2_com::Error::WindowsError)?;
@@ -403,6 +517,447 @@ impl InnerWebView {
         .map_err(webview2_com::Error::WindowsError)?;
     }
 
+    // accelerator key handler, added before the zoom hotkeys so the keys consumed by the host don't zoom
+    if let Some(on_accelerator_key) = attributes.on_accelerator_key.take() {
+      unsafe {
+        controller
+          .add_AcceleratorKeyPressed(
+            &AcceleratorKeyPressedEventHandler::create(Box::new(move |_, args| {
+              let Some(args) = args else {
+                return Ok(());
+              };
+
+              let mut kind = COREWEBVIEW2_KEY_EVENT_KIND::default();
+              args.KeyEventKind(&mut kind)?;
+              if kind != COREWEBVIEW2_KEY_EVENT_KIND_KEY_DOWN
+                && kind != COREWEBVIEW2_KEY_EVENT_KIND_SYSTEM_KEY_DOWN
+              {
+                return Ok(());
+              }
+
+              let mut virtual_key_code = 0;
+              args.VirtualKey(&mut virtual_key_code)?;
+              let Some(key) = KeyCode::ALL
+                .into_iter()
+                .find(|key| virtual_key(*key) == virtual_key_code)
+              else {
+                return Ok(());
+              };
+
+              let pressed = |key: VIRTUAL_KEY| GetKeyState(key.0 as i32) < 0;
+              if on_accelerator_key(AcceleratorKeyEvent {
+                key,
+                ctrl: pressed(VK_CONTROL),
+                shift: pressed(VK_SHIFT),
+                alt: pressed(VK_MENU),
+                meta: pressed(VK_LWIN) || pressed(VK_RWIN),
+              }) {
+                args.SetHandled(true)?;
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    // tab out handler
+    if let Some(on_tab_out) = attributes.on_tab_out.take() {
+      unsafe {
+        controller
+          .add_MoveFocusRequested(
+            &MoveFocusRequestedEventHandler::create(Box::new(move |_, args| {
+              if let Some(args) = args {
+                let mut reason = COREWEBVIEW2_MOVE_FOCUS_REASON::default();
+                args.Reason(&mut reason)?;
+                on_tab_out(reason == COREWEBVIEW2_MOVE_FOCUS_REASON_PREVIOUS);
+                args.SetHandled(true)?;
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    // zoom hotkeys with a custom step, reset key or initial zoom, the built-in hotkeys of WebView2
+    // are kept otherwise. They can be enabled later with `set_zoom_hotkeys_enabled` so the handler
+    // is added even when they are disabled
+    if attributes.zoom_hotkeys_custom_step != 0.1
+      || attributes.zoom_reset_key != Some(KeyCode::Digit0)
+      || attributes.initial_zoom != 1.0
+    {
+      let step = attributes.zoom_hotkeys_custom_step;
+      let reset_key = attributes.zoom_reset_key.map(virtual_key);
+      let initial_zoom = attributes.initial_zoom;
+      unsafe {
+        controller
+          .add_AcceleratorKeyPressed(
+            &AcceleratorKeyPressedEventHandler::create(Box::new(move |controller, args| {
+              let (Some(controller), Some(args)) = (controller, args) else {
+                return Ok(());
+              };
+
+              let mut kind = COREWEBVIEW2_KEY_EVENT_KIND::default();
+              args.KeyEventKind(&mut kind)?;
+              if kind != COREWEBVIEW2_KEY_EVENT_KIND_KEY_DOWN
+                || GetKeyState(VK_CONTROL.0 as i32) >= 0
+              {
+                return Ok(());
+              }
+
+              // consumed by the accelerator key handler
+              let mut handled = BOOL::default();
+              args.Handled(&mut handled)?;
+              if handled.as_bool() {
+                return Ok(());
+              }
+
+              let mut enabled = BOOL::default();
+              controller
+                .CoreWebView2()?
+                .Settings()?
+                .IsZoomControlEnabled(&mut enabled)?;
+              if !enabled.as_bool() {
+                return Ok(());
+              }
+
+              let mut key = 0;
+              args.VirtualKey(&mut key)?;
+              let mut zoom = 1.0;
+              controller.ZoomFactor(&mut zoom)?;
+
+              let zoom = if key == VK_OEM_PLUS.0 as u32 || key == VK_ADD.0 as u32 {
+                zoom + step
+              } else if key == VK_OEM_MINUS.0 as u32 || key == VK_SUBTRACT.0 as u32 {
+                zoom - step
+              } else if Some(key) == reset_key {
+                initial_zoom
+              } else {
+                return Ok(());
+              };
+
+              // WebView2 only supports zoom factors between 25% and 500%
+              controller.SetZoomFactor(zoom.clamp(0.25, 5.0))?;
+              args.SetHandled(true)
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    // page zoom changed handler, also clamping the zoom changes of the user to the zoom limits
+    let on_page_zoom_change = attributes.on_page_zoom_change.take();
+    unsafe {
+      controller
+        .add_ZoomFactorChanged(
+          &ZoomFactorChangedEventHandler::create(Box::new(move |controller, _| {
+            if let Some(controller) = controller {
+              let mut zoom = 1.0;
+              controller.ZoomFactor(&mut zoom)?;
+              if let Some((min, max)) = zoom_limits.get() {
+                if !(min..=max).contains(&zoom) {
+                  // notified again with the clamped zoom factor
+                  return controller.SetZoomFactor(zoom.clamp(min, max));
+                }
+              }
+              if let Some(on_page_zoom_change) = &on_page_zoom_change {
+                on_page_zoom_change(zoom);
+              }
+            }
+            Ok(())
+          })),
+          &mut token,
+        )
+        .map_err(webview2_com::Error::WindowsError)?;
+    }
+
+    // https error handler
+    if let Some(on_https_error) = attributes.on_https_error.take() {
+      let webview14: ICoreWebView2_14 =
+        webview.cast().map_err(webview2_com::Error::WindowsError)?;
+      unsafe {
+        webview14
+          .add_ServerCertificateErrorDetected(
+            &ServerCertificateErrorDetectedEventHandler::create(Box::new(move |_, args| {
+              if let Some(args) = args {
+                let mut uri = PWSTR::null();
+                args.RequestUri(&mut uri)?;
+                let uri = take_pwstr(uri);
+                let Ok(url) = Url::parse(&uri) else {
+                  return Ok(());
+                };
+
+                let mut status = COREWEBVIEW2_WEB_ERROR_STATUS::default();
+                args.ErrorStatus(&mut status)?;
+                let kind = match status {
+                  COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_EXPIRED => HttpsErrorKind::CertExpired,
+                  COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_COMMON_NAME_IS_INCORRECT => {
+                    HttpsErrorKind::HostnameMismatch
+                  }
+                  COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_REVOKED
+                  | COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_IS_INVALID => {
+                    HttpsErrorKind::CertUntrusted
+                  }
+                  _ => HttpsErrorKind::Other,
+                };
+
+                let action = match on_https_error(HttpsError { url, kind }) {
+                  HttpsErrorDecision::Block => COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION_DEFAULT,
+                  HttpsErrorDecision::Continue(reason) => {
+                    log::warn!("ignoring the TLS errors of {uri}: {reason:?}");
+                    COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION_ALWAYS_ALLOW
+                  }
+                };
+                args.SetAction(action)?;
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    // audio muted handler
+    if let Some(on_audio_muted_change) = attributes.on_audio_muted_change.take() {
+      let webview8: ICoreWebView2_8 = webview.cast().map_err(webview2_com::Error::WindowsError)?;
+      unsafe {
+        webview8
+          .add_IsMutedChanged(
+            &IsMutedChangedEventHandler::create(Box::new(move |webview, _| {
+              if let Some(webview) = webview.and_then(|w| w.cast::<ICoreWebView2_8>().ok()) {
+                let mut muted = BOOL::default();
+                webview.IsMuted(&mut muted)?;
+                on_audio_muted_change(muted.as_bool());
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    // favicon changed handler
+    if let Some(on_favicon_changed) = attributes.on_favicon_changed.take() {
+      let on_favicon_changed = Rc::new(on_favicon_changed);
+      let webview15: ICoreWebView2_15 =
+        webview.cast().map_err(webview2_com::Error::WindowsError)?;
+      unsafe {
+        webview15
+          .add_FaviconChanged(
+            &FaviconChangedEventHandler::create(Box::new(move |webview, _| {
+              if let Some(webview) = webview.and_then(|w| w.cast::<ICoreWebView2_15>().ok()) {
+                let on_favicon_changed = on_favicon_changed.clone();
+                webview.GetFavicon(
+                  COREWEBVIEW2_FAVICON_IMAGE_FORMAT_PNG,
+                  &GetFaviconCompletedHandler::create(Box::new(move |result, stream| {
+                    result?;
+                    let Some(stream) = stream else {
+                      return Ok(());
+                    };
+
+                    let mut png = Vec::new();
+                    let mut buffer: [u8; 1024] = [0; 1024];
+                    loop {
+                      let mut cb_read = 0;
+                      stream
+                        .Read(
+                          buffer.as_mut_ptr() as *mut _,
+                          buffer.len() as u32,
+                          Some(&mut cb_read),
+                        )
+                        .ok()?;
+
+                      if cb_read == 0 {
+                        break;
+                      }
+
+                      png.extend_from_slice(&buffer[..(cb_read as usize)]);
+                    }
+
+                    if !png.is_empty() {
+                      on_favicon_changed(png);
+                    }
+                    Ok(())
+                  })),
+                )?;
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    // load progress handler, WebView2 has no progress notification so it is estimated
+    // from the navigation steps
+    if let Some(on_load_progress) = attributes.on_load_progress.take() {
+      let on_load_progress: Rc<dyn Fn(f64)> = Rc::from(on_load_progress);
+      unsafe {
+        let on_load_progress_ = on_load_progress.clone();
+        webview
+          .add_NavigationStarting(
+            &NavigationStartingEventHandler::create(Box::new(move |_, _| {
+              on_load_progress_(0.1);
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+
+        let on_load_progress_ = on_load_progress.clone();
+        webview
+          .add_ContentLoading(
+            &ContentLoadingEventHandler::create(Box::new(move |_, _| {
+              on_load_progress_(0.3);
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+
+        let on_load_progress_ = on_load_progress.clone();
+        webview
+          .cast::<ICoreWebView2_2>()
+          .map_err(webview2_com::Error::WindowsError)?
+          .add_DOMContentLoaded(
+            &DOMContentLoadedEventHandler::create(Box::new(move |_, _| {
+              on_load_progress_(0.7);
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+
+        webview
+          .add_NavigationCompleted(
+            &NavigationCompletedEventHandler::create(Box::new(move |_, _| {
+              on_load_progress(1.0);
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    // load error handler
+    if let Some(on_load_error) = attributes.on_load_error.take() {
+      unsafe {
+        webview
+          .add_NavigationCompleted(
+            &NavigationCompletedEventHandler::create(Box::new(move |webview, args| {
+              if let (Some(webview), Some(args)) = (webview, args) {
+                let mut is_success = BOOL::default();
+                args.IsSuccess(&mut is_success)?;
+                if is_success.as_bool() {
+                  return Ok(());
+                }
+
+                let mut status = COREWEBVIEW2_WEB_ERROR_STATUS::default();
+                args.WebErrorStatus(&mut status)?;
+                if let Ok(url) = Url::parse(&url_from_webview(&webview)) {
+                  on_load_error(LoadError {
+                    url,
+                    code: load_error_code(status),
+                    description: load_error_description(status).to_string(),
+                  });
+                }
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    // url changed handler
+    if let Some(on_url_changed) = attributes.on_url_changed.take() {
+      unsafe {
+        webview
+          .add_SourceChanged(
+            &SourceChangedEventHandler::create(Box::new(move |webview, _| {
+              if let Some(webview) = webview {
+                if let Ok(url) = Url::parse(&url_from_webview(&webview)) {
+                  on_url_changed(url);
+                }
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    // history state changed handler
+    if let Some(on_history_state_changed) = attributes.on_history_state_changed.take() {
+      unsafe {
+        webview
+          .add_SourceChanged(
+            &SourceChangedEventHandler::create(Box::new(move |webview, args| {
+              if let (Some(webview), Some(args)) = (webview, args) {
+                let mut is_new_document = BOOL::default();
+                args.IsNewDocument(&mut is_new_document)?;
+                if is_new_document.as_bool() {
+                  return Ok(());
+                }
+
+                if let Ok(url) = Url::parse(&url_from_webview(&webview)) {
+                  on_history_state_changed(url);
+                }
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    if let Some(on_console_message) = attributes.on_console_message.take() {
+      unsafe {
+        let receiver = webview
+          .GetDevToolsProtocolEventReceiver(PCWSTR::from_raw(
+            encode_wide("Runtime.consoleAPICalled").as_ptr(),
+          ))
+          .map_err(webview2_com::Error::WindowsError)?;
+        receiver
+          .add_DevToolsProtocolEventReceived(
+            &DevToolsProtocolEventReceivedEventHandler::create(Box::new(move |_, args| {
+              if let Some(args) = args {
+                let mut json = PWSTR::null();
+                args.ParameterObjectAsJson(&mut json)?;
+                if let Some((level, message, source, line)) =
+                  crate::console::parse_console_api_called(&take_pwstr(json))
+                {
+                  on_console_message(level, message, source, line);
+                }
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+
+        // the events of the Runtime domain are only sent once it is enabled
+        webview
+          .CallDevToolsProtocolMethod(
+            PCWSTR::from_raw(encode_wide("Runtime.enable").as_ptr()),
+            PCWSTR::from_raw(encode_wide("{}").as_ptr()),
+            &CallDevToolsProtocolMethodCompletedHandler::create(Box::new(|_, _| Ok(()))),
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
     // document title changed handler
     if let Some(document_title_changed_handler) = attributes.document_title_changed_handler {
       unsafe {
@@ -459,27 +1014,58 @@ impl InnerWebView {
     // Initialize scripts
     Self::add_script_to_execute_on_document_created(
       &webview,
-      String::from(
-        r#"Object.defineProperty(window, 'ipc', { value: Object.freeze({ postMessage: s=> window.chrome.webview.postMessage(s) }) });"#,
+      format!(
+        r#"(function () {{ var post = {}; Object.defineProperty(window, 'ipc', {{ value: Object.freeze({{ postMessage: s=> post(m=> window.chrome.webview.postMessage(m), {}(s)), invoke: {}, channel: {} }}) }}); }})();"#,
+        crate::ipc::post_chunked_function(attributes.max_ipc_message_size_bytes),
+        crate::ipc::encode_message_function(),
+        crate::ipc::invoke_function(),
+        crate::ipc::channel_function(),
       ),
     )?;
+    Self::add_script_to_execute_on_document_created(
+      &webview,
+      crate::shared_buffer::FORWARD_SHARED_BUFFER_SCRIPT.to_string(),
+    )?;
     for js in attributes.initialization_scripts {
       Self::add_script_to_execute_on_document_created(&webview, js)?;
     }
+    for js in attributes.main_frame_initialization_scripts {
+      // WebView2 injects the scripts in every frame
+      Self::add_script_to_execute_on_document_created(
+        &webview,
+        format!("if (window === window.top) {{\n{js}\n}}"),
+      )?;
+    }
+    for js in attributes.isolated_initialization_scripts {
+      Self::add_isolated_script_to_execute_on_document_created(&webview, js)?;
+    }
+    let on_print_dialog = attributes.on_print_dialog.take();
+    if on_print_dialog.is_some() {
+      Self::add_script_to_execute_on_document_created(&webview, crate::override_print_script())?;
+    }
 
     // Message handler
     let ipc_handler = attributes.ipc_handler.take();
     unsafe {
       webview.add_WebMessageReceived(
-        &WebMessageReceivedEventHandler::create(Box::new(move |_, args| {
+        &WebMessageReceivedEventHandler::create(Box::new(move |webview, args| {
           if let Some(args) = args {
             let mut js = PWSTR::null();
             args.TryGetWebMessageAsString(&mut js)?;
             let js = take_pwstr(js);
+            if crate::is_print_dialog_message(&js) {
+              if let (Some(on_print_dialog), Some(webview)) = (&on_print_dialog, webview) {
+                handle_print_dialog(&webview, on_print_dialog)?;
+                return Ok(());
+              }
+            }
             if let Some(ipc_handler) = &ipc_handler {
               #[cfg(feature = "tracing")]
               let _span = tracing::info_span!("wry::ipc::handle").entered();
-              ipc_handler(js);
+              let mut source = PWSTR::null();
+              args.Source(&mut source)?;
+              let source = take_pwstr(source);
+              crate::ipc::with_message_source(Some(source), || ipc_handler(js));
             }
           }
 
@@ -490,6 +1076,44 @@ impl InnerWebView {
     }
     .map_err(webview2_com::Error::WindowsError)?;
 
+    // redirect handler
+    if let Some(on_redirect) = attributes.on_redirect.take() {
+      // the id and URL of the current navigation, redirects keep the id of the navigation
+      let current = RefCell::new(None::<(u64, Url)>);
+      unsafe {
+        webview
+          .add_NavigationStarting(
+            &NavigationStartingEventHandler::create(Box::new(move |_, args| {
+              if let Some(args) = args {
+                let mut uri = PWSTR::null();
+                args.Uri(&mut uri)?;
+                let Ok(url) = Url::parse(&take_pwstr(uri)) else {
+                  return Ok(());
+                };
+
+                let mut id = 0;
+                args.NavigationId(&mut id)?;
+                let mut is_redirected = BOOL::default();
+                args.IsRedirected(&mut is_redirected)?;
+
+                let previous = current.replace(Some((id, url.clone())));
+                if is_redirected.as_bool() {
+                  if let Some((previous_id, from)) = previous {
+                    if previous_id == id {
+                      on_redirect(from, url);
+                    }
+                  }
+                }
+              }
+
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
     if let Some(nav_callback) = attributes.navigation_handler {
       unsafe {
         webview
@@ -513,20 +1137,97 @@ impl InnerWebView {
       }
     }
 
-    if attributes.download_started_handler.is_some()
-      || attributes.download_completed_handler.is_some()
-    {
+    // the headers of `NavigationStarting` are read-only, so the default headers are added to the
+    // document request of the main frame navigation, once the navigation handler allowed it
+    if let Some(default_headers) = attributes.default_headers.take() {
+      let navigation = Rc::new(RefCell::new(None::<String>));
+      let navigation_ = navigation.clone();
       unsafe {
-        let webview4: ICoreWebView2_4 =
-          webview.cast().map_err(webview2_com::Error::WindowsError)?;
-
-        let mut download_started_handler = attributes.download_started_handler.take();
-        let download_completed_handler = attributes.download_completed_handler.take();
-
-        webview4
-          .add_DownloadStarting(
-            &DownloadStartingEventHandler::create(Box::new(move |_, args| {
-              if let Some(args) = args {
+        webview
+          .add_NavigationStarting(
+            &NavigationStartingEventHandler::create(Box::new(move |_, args| {
+              if let Some(args) = args {
+                let mut cancel = BOOL::default();
+                args.Cancel(&mut cancel)?;
+                let mut uri = PWSTR::null();
+                args.Uri(&mut uri)?;
+                let uri = take_pwstr(uri);
+                navigation_.replace((!cancel.as_bool()).then_some(uri));
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+
+        webview
+          .AddWebResourceRequestedFilter(
+            PCWSTR::from_raw(encode_wide("*").as_ptr()),
+            COREWEBVIEW2_WEB_RESOURCE_CONTEXT_DOCUMENT,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+        webview
+          .add_WebResourceRequested(
+            &WebResourceRequestedEventHandler::create(Box::new(move |_, args| {
+              let Some(args) = args else {
+                return Ok(());
+              };
+              let mut context = COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL;
+              args.ResourceContext(&mut context)?;
+              if context != COREWEBVIEW2_WEB_RESOURCE_CONTEXT_DOCUMENT {
+                return Ok(());
+              }
+
+              let request = args.Request()?;
+              let mut uri = PWSTR::null();
+              request.Uri(&mut uri)?;
+              let uri = take_pwstr(uri);
+              // the fragment is not part of the request
+              let is_main_frame = navigation.borrow().as_deref().map_or(false, |navigation| {
+                navigation.split('#').next() == uri.split('#').next()
+              });
+              if !is_main_frame {
+                return Ok(());
+              }
+              navigation.replace(None);
+
+              let headers = request.Headers()?;
+              for (name, value) in default_headers.iter() {
+                let Ok(value) = value.to_str() else {
+                  continue;
+                };
+                let name = encode_wide(name.as_str());
+                let mut contains = BOOL::default();
+                headers.Contains(PCWSTR::from_raw(name.as_ptr()), &mut contains)?;
+                if !contains.as_bool() {
+                  headers.SetHeader(
+                    PCWSTR::from_raw(name.as_ptr()),
+                    PCWSTR::from_raw(encode_wide(value).as_ptr()),
+                  )?;
+                }
+              }
+              Ok(())
+            })),
+            &mut token,
+          )
+          .map_err(webview2_com::Error::WindowsError)?;
+      }
+    }
+
+    if attributes.download_started_handler.is_some()
+      || attributes.download_completed_handler.is_some()
+    {
+      unsafe {
+        let webview4: ICoreWebView2_4 =
+          webview.cast().map_err(webview2_com::Error::WindowsError)?;
+
+        let mut download_started_handler = attributes.download_started_handler.take();
+        let download_completed_handler = attributes.download_completed_handler.take();
+
+        webview4
+          .add_DownloadStarting(
+            &DownloadStartingEventHandler::create(Box::new(move |_, args| {
+              if let Some(args) = args {
                 let mut uri = PWSTR::null();
                 args.DownloadOperation()?.Uri(&mut uri)?;
                 let uri = take_pwstr(uri);
@@ -615,160 +1316,230 @@ impl InnerWebView {
       "http"
     };
     let mut custom_protocol_names = HashSet::new();
-    if !attributes.custom_protocols.is_empty() {
-      for (name, _) in &attributes.custom_protocols {
-        // WebView2 supports non-standard protocols only on Windows 10+, so we have to use this workaround
-        // See https://github.com/MicrosoftEdge/WebView2Feedback/issues/73
-        custom_protocol_names.insert(name.clone());
-        unsafe {
-          webview.AddWebResourceRequestedFilter(
-            PCWSTR::from_raw(encode_wide(format!("{scheme}://{name}.*")).as_ptr()),
-            COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
-          )
-        }
-        .map_err(webview2_com::Error::WindowsError)?;
+    let request_headers_interceptor = attributes.request_headers_interceptor.take();
+    for (name, _) in &attributes.custom_protocols {
+      // WebView2 supports non-standard protocols only on Windows 10+, so we have to use this workaround
+      // See https://github.com/MicrosoftEdge/WebView2Feedback/issues/73
+      custom_protocol_names.insert(name.clone());
+      unsafe {
+        webview.AddWebResourceRequestedFilter(
+          PCWSTR::from_raw(encode_wide(format!("{scheme}://{name}.*")).as_ptr()),
+          COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
+        )
       }
+      .map_err(webview2_com::Error::WindowsError)?;
+    }
 
-      let custom_protocols = attributes.custom_protocols;
-      let env = env.clone();
-      let main_thread_id = std::thread::current().id();
-
+    if request_headers_interceptor.is_some() {
       unsafe {
-        webview
-          .add_WebResourceRequested(
-            &WebResourceRequestedEventHandler::create(Box::new(move |_, args| {
-              #[cfg(feature = "tracing")]
-              let span =
-                tracing::info_span!("wry::custom_protocol::handle", uri = tracing::field::Empty)
-                  .entered();
-              if let Some(args) = args {
-                let webview_request = args.Request()?;
-                let mut request = Request::builder();
-
-                // request method (GET, POST, PUT etc..)
-                let mut request_method = PWSTR::null();
-                webview_request.Method(&mut request_method)?;
-                let request_method = take_pwstr(request_method);
-
-                // get all headers from the request
-                let headers = webview_request.Headers()?.GetIterator()?;
-                let mut has_current = BOOL::default();
-                headers.HasCurrentHeader(&mut has_current)?;
-                if has_current.as_bool() {
-                  loop {
-                    let mut key = PWSTR::null();
-                    let mut value = PWSTR::null();
-                    headers.GetCurrentHeader(&mut key, &mut value)?;
-                    let (key, value) = (take_pwstr(key), take_pwstr(value));
-                    request = request.header(&key, &value);
-
-                    headers.MoveNext(&mut has_current)?;
-                    if !has_current.as_bool() {
-                      break;
-                    }
+        webview.AddWebResourceRequestedFilter(
+          PCWSTR::from_raw(encode_wide("*").as_ptr()),
+          COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
+        )
+      }
+      .map_err(webview2_com::Error::WindowsError)?;
+    }
+
+    custom_protocols.borrow_mut().extend(
+      attributes
+        .custom_protocols
+        .into_iter()
+        .map(|(name, handler)| (name, Rc::from(handler))),
+    );
+    let env_ = env.clone();
+    let main_thread_id = std::thread::current().id();
+
+    unsafe {
+      webview
+        .add_WebResourceRequested(
+          &WebResourceRequestedEventHandler::create(Box::new(move |_, args| {
+            #[cfg(feature = "tracing")]
+            let span =
+              tracing::info_span!("wry::custom_protocol::handle", uri = tracing::field::Empty)
+                .entered();
+            if let Some(args) = args {
+              let webview_request = args.Request()?;
+              let mut request = Request::builder();
+
+              // request method (GET, POST, PUT etc..)
+              let mut request_method = PWSTR::null();
+              webview_request.Method(&mut request_method)?;
+              let request_method = take_pwstr(request_method);
+
+              // get all headers from the request
+              let headers = webview_request.Headers()?.GetIterator()?;
+              let mut has_current = BOOL::default();
+              headers.HasCurrentHeader(&mut has_current)?;
+              if has_current.as_bool() {
+                loop {
+                  let mut key = PWSTR::null();
+                  let mut value = PWSTR::null();
+                  headers.GetCurrentHeader(&mut key, &mut value)?;
+                  let (key, value) = (take_pwstr(key), take_pwstr(value));
+                  request = request.header(&key, &value);
+
+                  headers.MoveNext(&mut has_current)?;
+                  if !has_current.as_bool() {
+                    break;
                   }
                 }
+              }
 
-                // get the body content if available
-                let mut body_sent = Vec::new();
-                if let Ok(content) = webview_request.Content() {
-                  let mut buffer: [u8; 1024] = [0; 1024];
-                  loop {
-                    let mut cb_read = 0;
-                    let content: IStream = content.cast()?;
-                    content
-                      .Read(
-                        buffer.as_mut_ptr() as *mut _,
-                        buffer.len() as u32,
-                        Some(&mut cb_read),
-                      )
-                      .ok()?;
-
-                    if cb_read == 0 {
-                      break;
-                    }
+              // uri
+              let mut uri = PWSTR::null();
+              webview_request.Uri(&mut uri)?;
+              let uri = take_pwstr(uri);
 
-                    body_sent.extend_from_slice(&buffer[..(cb_read as usize)]);
-                  }
+              #[cfg(feature = "tracing")]
+              span.record("uri", &uri);
+
+              let custom_protocol = custom_protocols
+                .borrow()
+                .iter()
+                .find(|(name, _)| uri.starts_with(&format!("{scheme}://{name}.")))
+                .cloned();
+
+              // custom protocol requests already go through the interceptor,
+              // see `intercept_custom_protocol_headers`
+              if custom_protocol.is_none() {
+                if let (Some(interceptor), Some(original_headers)) =
+                  (&request_headers_interceptor, request.headers_ref())
+                {
+                  intercept_request_headers(&webview_request, &uri, original_headers, interceptor)?;
                 }
+                return Ok(());
+              }
 
-                // uri
-                let mut uri = PWSTR::null();
-                webview_request.Uri(&mut uri)?;
-                let uri = take_pwstr(uri);
+              // get the body content if available
+              let mut body_sent = Vec::new();
+              if let Ok(content) = webview_request.Content() {
+                let mut buffer: [u8; 1024] = [0; 1024];
+                loop {
+                  let mut cb_read = 0;
+                  let content: IStream = content.cast()?;
+                  content
+                    .Read(
+                      buffer.as_mut_ptr() as *mut _,
+                      buffer.len() as u32,
+                      Some(&mut cb_read),
+                    )
+                    .ok()?;
+
+                  if cb_read == 0 {
+                    break;
+                  }
 
-                #[cfg(feature = "tracing")]
-                span.record("uri", &uri);
+                  body_sent.extend_from_slice(&buffer[..(cb_read as usize)]);
+                }
+              }
 
-                if let Some(custom_protocol) = custom_protocols
-                  .iter()
-                  .find(|(name, _)| uri.starts_with(&format!("{scheme}://{name}.")))
+              if let Some(custom_protocol) = custom_protocol {
+                // Undo the protocol workaround when giving path to resolver
+                let path = uri.replace(
+                  &format!("{scheme}://{}.", custom_protocol.0),
+                  &format!("{}://", custom_protocol.0),
+                );
+
+                let final_request = match request
+                  .uri(&path)
+                  .method(request_method.as_str())
+                  .body(body_sent)
                 {
-                  // Undo the protocol workaround when giving path to resolver
-                  let path = uri.replace(
-                    &format!("{scheme}://{}.", custom_protocol.0),
-                    &format!("{}://", custom_protocol.0),
-                  );
-
-                  let final_request = match request
-                    .uri(&path)
-                    .method(request_method.as_str())
-                    .body(body_sent)
-                  {
-                    Ok(req) => req,
-                    Err(_) => return Err(E_FAIL.into()),
-                  };
-
-                  let env = env.clone();
-                  let deferral = args.GetDeferral();
-
-                  let responder: Box<dyn FnOnce(HttpResponse<Cow<'static, [u8]>>)> =
-                    Box::new(move |sent_response| {
-                      let handler = move || {
-                        match prepare_web_request_response(&env, &sent_response) {
-                          Ok(response) => {
-                            let _ = args.SetResponse(&response);
-                          }
-                          Err(_) => {
-                            let status = StatusCode::BAD_REQUEST;
-                            if let Ok(res) = env.CreateWebResourceResponse(
-                              None,
-                              status.as_u16() as i32,
-                              PCWSTR::from_raw(
-                                encode_wide(status.canonical_reason().unwrap_or("")).as_ptr(),
-                              ),
-                              PCWSTR::from_raw(encode_wide(String::new()).as_ptr()),
-                            ) {
-                              let _ = args.SetResponse(&res);
-                            }
-                          }
+                  Ok(req) => req,
+                  Err(_) => return Err(E_FAIL.into()),
+                };
+
+                let env = env_.clone();
+                let deferral = args.GetDeferral();
+
+                // resolves the request on the main thread with the response created by `create_response`
+                let complete = move |create_response: CreateResponse| {
+                  let mut create_response = Some(create_response);
+                  let handler = move || {
+                    let Some(create_response) = create_response.take() else {
+                      return;
+                    };
+                    match create_response(&env) {
+                      Ok(response) => {
+                        let _ = args.SetResponse(&response);
+                      }
+                      Err(_) => {
+                        let status = StatusCode::BAD_REQUEST;
+                        if let Ok(res) = env.CreateWebResourceResponse(
+                          None,
+                          status.as_u16() as i32,
+                          PCWSTR::from_raw(
+                            encode_wide(status.canonical_reason().unwrap_or("")).as_ptr(),
+                          ),
+                          PCWSTR::from_raw(encode_wide(String::new()).as_ptr()),
+                        ) {
+                          let _ = args.SetResponse(&res);
                         }
+                      }
+                    }
 
-                        if let Ok(deferral) = &deferral {
-                          let _ = deferral.Complete();
-                        }
-                      };
+                    if let Ok(deferral) = &deferral {
+                      let _ = deferral.Complete();
+                    }
+                  };
 
-                      if std::thread::current().id() == main_thread_id {
-                        handler();
-                      } else {
-                        dispatch_handler(hwnd, handler);
-                      }
-                    });
+                  if std::thread::current().id() == main_thread_id {
+                    handler();
+                  } else {
+                    dispatch_handler(hwnd, handler);
+                  }
+                };
+                let complete_ = complete.clone();
+
+                let responder: Box<dyn FnOnce(HttpResponse<Cow<'static, [u8]>>)> =
+                  Box::new(move |sent_response| {
+                    complete(Box::new(move |env: &ICoreWebView2Environment| {
+                      prepare_web_request_response(env, &sent_response)
+                    }))
+                  });
+                let stream_responder: Box<dyn FnOnce(HttpResponse<Box<dyn Read + Send>>)> =
+                  Box::new(move |sent_response| {
+                    complete_(Box::new(move |env: &ICoreWebView2Environment| {
+                      let (parts, body) = sent_response.into_parts();
+                      let stream: IStream = ReadStream::new(body).into();
+                      create_web_resource_response(
+                        env,
+                        &HttpResponse::from_parts(parts, ()),
+                        Some(&stream),
+                      )
+                    }))
+                  });
 
-                  #[cfg(feature = "tracing")]
-                  let _span = tracing::info_span!("wry::custom_protocol::call_handler").entered();
-                  (custom_protocol.1)(final_request, RequestAsyncResponder { responder });
-                  return Ok(());
-                }
+                #[cfg(feature = "tracing")]
+                let _span = tracing::info_span!("wry::custom_protocol::call_handler").entered();
+                (custom_protocol.1)(
+                  final_request,
+                  RequestAsyncResponder {
+                    responder,
+                    stream_responder: Some(stream_responder),
+                  },
+                );
+                return Ok(());
               }
+            }
 
-              Ok(())
-            })),
-            &mut token,
-          )
-          .map_err(webview2_com::Error::WindowsError)?;
-      }
+            Ok(())
+          })),
+          &mut token,
+        )
+        .map_err(webview2_com::Error::WindowsError)?;
+    }
+
+    // the custom protocol responses are transformed by their handlers, see `transform_custom_protocol_responses`
+    if let Some(transform) = attributes.response_transform.take() {
+      response_transform::transform_responses(
+        &webview,
+        Rc::from(transform),
+        custom_protocol_names
+          .iter()
+          .map(|name| format!("{scheme}://{name}."))
+          .collect(),
+      )?;
     }
 
     // Enable clipboard
@@ -919,7 +1690,8 @@ impl InnerWebView {
   fn add_script_to_execute_on_document_created(
     webview: &ICoreWebView2,
     js: String,
-  ) -> webview2_com::Result<()> {
+  ) -> webview2_com::Result<String> {
+    let (tx, rx) = mpsc::channel();
     let handler_webview = webview.clone();
     AddScriptToExecuteOnDocumentCreatedCompletedHandler::wait_for_async_operation(
       Box::new(move |handler| unsafe {
@@ -927,7 +1699,37 @@ impl InnerWebView {
           .AddScriptToExecuteOnDocumentCreated(PCWSTR::from_raw(encode_wide(js).as_ptr()), &handler)
           .map_err(webview2_com::Error::WindowsError)
       }),
-      Box::new(|_, _| Ok(())),
+      Box::new(move |error_code, id| {
+        error_code?;
+        tx.send(id).expect("send over mpsc channel");
+        Ok(())
+      }),
+    )?;
+
+    rx.recv().map_err(|_| webview2_com::Error::SendError)
+  }
+
+  fn add_isolated_script_to_execute_on_document_created(
+    webview: &ICoreWebView2,
+    js: String,
+  ) -> webview2_com::Result<()> {
+    let params = serde_json::json!({
+      "source": js,
+      "worldName": crate::ISOLATED_WORLD_NAME,
+    })
+    .to_string();
+    let handler_webview = webview.clone();
+    CallDevToolsProtocolMethodCompletedHandler::wait_for_async_operation(
+      Box::new(move |handler| unsafe {
+        handler_webview
+          .CallDevToolsProtocolMethod(
+            PCWSTR::from_raw(encode_wide("Page.addScriptToEvaluateOnNewDocument").as_ptr()),
+            PCWSTR::from_raw(encode_wide(params).as_ptr()),
+            &handler,
+          )
+          .map_err(webview2_com::Error::WindowsError)
+      }),
+      Box::new(|error, _| error),
     )
   }
 
@@ -952,16 +1754,125 @@ impl InnerWebView {
   }
 
   pub fn print(&self) {
+    // bypass the `on_print_dialog` override
     let _ = self.eval(
-      "window.print()",
+      "(window.__WRY_NATIVE_PRINT__ || window.print)()",
       None::<Box<dyn FnOnce(String) + Send + 'static>>,
     );
   }
 
+  pub fn restore_dom(&self, png: Vec<u8>) -> Result<()> {
+    self.resume_rendering()?;
+    self
+      .frozen_overlay
+      .set(frozen_overlay::create(self.hwnd, &png)?);
+    Ok(())
+  }
+
+  pub fn resume_rendering(&self) -> Result<()> {
+    let overlay = self.frozen_overlay.take();
+    if overlay != HWND::default() {
+      unsafe {
+        let _ = DestroyWindow(overlay);
+      }
+    }
+    Ok(())
+  }
+
+  pub fn snapshot(
+    &self,
+    rect_script: &str,
+    target: &str,
+    callback: Box<dyn FnOnce(Result<Vec<u8>>)>,
+  ) {
+    let webview = self.webview.clone();
+    let js = rect_script.to_string();
+    let target = target.to_string();
+
+    let handler = ExecuteScriptCompletedHandler::create(Box::new(move |_, rect| {
+      let rect = match crate::parse_element_rect(&rect, &target) {
+        Ok(rect) => rect,
+        Err(e) => {
+          callback(Err(e));
+          return Ok(());
+        }
+      };
+
+      let params = serde_json::json!({
+        "format": "png",
+        "clip": {
+          "x": rect.x,
+          "y": rect.y,
+          "width": rect.width,
+          "height": rect.height,
+          "scale": 1,
+        },
+      })
+      .to_string();
+
+      let handler =
+        CallDevToolsProtocolMethodCompletedHandler::create(Box::new(move |error, result| {
+          let png = error
+            .map_err(|e| Error::WebView2Error(webview2_com::Error::WindowsError(e)))
+            .and_then(|_| {
+              #[derive(serde::Deserialize)]
+              struct Screenshot {
+                data: String,
+              }
+
+              use base64::{engine::general_purpose, Engine};
+              let screenshot: Screenshot = serde_json::from_str(&result)?;
+              general_purpose::STANDARD
+                .decode(screenshot.data)
+                .map_err(|e| Error::SnapshotError(e.to_string()))
+            });
+          callback(png);
+          Ok(())
+        }));
+
+      unsafe {
+        webview.CallDevToolsProtocolMethod(
+          PCWSTR::from_raw(encode_wide("Page.captureScreenshot").as_ptr()),
+          PCWSTR::from_raw(encode_wide(params).as_ptr()),
+          &handler,
+        )
+      }
+    }));
+
+    let _ = unsafe {
+      self
+        .webview
+        .ExecuteScript(PCWSTR::from_raw(encode_wide(js).as_ptr()), &handler)
+    };
+  }
+
   pub fn url(&self) -> Url {
     Url::parse(&url_from_webview(&self.webview)).unwrap()
   }
 
+  pub fn set_user_agent(&self, user_agent: &str) -> Result<()> {
+    unsafe {
+      self
+        .webview
+        .Settings()
+        .and_then(|settings| settings.cast::<ICoreWebView2Settings2>())
+        .and_then(|settings| {
+          settings.SetUserAgent(PCWSTR::from_raw(encode_wide(user_agent).as_ptr()))
+        })
+        .map_err(|e| Error::WebView2Error(webview2_com::Error::WindowsError(e)))
+    }
+  }
+
+  pub fn title(&self) -> String {
+    let mut title = PWSTR::null();
+    unsafe {
+      if self.webview.DocumentTitle(&mut title).is_err() {
+        return String::new();
+      }
+    }
+    take_pwstr(title)
+  }
+
   pub fn eval(
     &self,
     js: &str,
@@ -975,6 +1886,45 @@ impl InnerWebView {
     }
   }
 
+  pub fn eval_with_result(
+    &self,
+    js: &str,
+    callback: impl Fn(std::result::Result<String, String>) + Send + 'static,
+  ) -> Result<()> {
+    let Ok(webview) = self.webview.cast::<ICoreWebView2_21>() else {
+      // the runtimes older than 1.0.2210 don't report the exceptions
+      return self.eval(js, Some(move |result| callback(Ok(result))));
+    };
+
+    let handler =
+      ExecuteScriptWithResultCompletedHandler::create(Box::new(move |error, result| {
+        if let Err(error) = error {
+          callback(Err(error.message().to_string()));
+          return Ok(());
+        }
+        let Some(result) = result else {
+          callback(Err("The script was not evaluated".to_string()));
+          return Ok(());
+        };
+
+        let mut succeeded = BOOL::default();
+        unsafe { result.Succeeded(&mut succeeded)? };
+        if succeeded.as_bool() {
+          let mut json = PWSTR::null();
+          unsafe { result.ResultAsJson(&mut json)? };
+          callback(Ok(take_pwstr(json)));
+        } else {
+          let exception = unsafe { result.Exception()? };
+          let mut message = PWSTR::null();
+          unsafe { exception.Message(&mut message)? };
+          callback(Err(take_pwstr(message)));
+        }
+        Ok(())
+      }));
+    unsafe { webview.ExecuteScriptWithResult(PCWSTR::from_raw(encode_wide(js).as_ptr()), &handler) }
+      .map_err(|e| webview2_com::Error::WindowsError(e).into())
+  }
+
   #[cfg(any(debug_assertions, feature = "devtools"))]
   pub fn open_devtools(&self) {
     let _ = unsafe { self.webview.OpenDevToolsWindow() };
@@ -989,13 +1939,45 @@ impl InnerWebView {
   }
 
   pub fn zoom(&self, scale_factor: f64) {
+    let scale_factor = match self.zoom_limits.get() {
+      Some((min, max)) => scale_factor.clamp(min, max),
+      None => scale_factor,
+    };
     let _ = unsafe { self.controller.SetZoomFactor(scale_factor) };
   }
 
+  pub fn set_zoom_limits(&self, min: f64, max: f64) {
+    self.zoom_limits.set(Some((min, max)));
+    self.zoom(self.zoom_factor());
+  }
+
+  pub fn zoom_factor(&self) -> f64 {
+    let mut zoom_factor = 1.0;
+    let _ = unsafe { self.controller.ZoomFactor(&mut zoom_factor) };
+    zoom_factor
+  }
+
   pub fn set_background_color(&self, background_color: RGBA) -> Result<()> {
     set_background_color(&self.controller, background_color).map_err(Into::into)
   }
 
+  pub fn load_html(&self, html: &str, base_url: Option<&str>) {
+    // `NavigateToString` has no base URL, the relative URLs are resolved with a `<base>` element
+    // instead, which the HTML parser moves to the document head
+    let html = match base_url {
+      Some(base_url) => Cow::Owned(format!(
+        "<base href=\"{}\">{html}",
+        base_url.replace('&', "&amp;").replace('"', "&quot;")
+      )),
+      None => Cow::Borrowed(html),
+    };
+    let _ = unsafe {
+      self
+        .webview
+        .NavigateToString(PCWSTR::from_raw(encode_wide(html.as_ref()).as_ptr()))
+    };
+  }
+
   pub fn load_url(&self, url: &str) {
     let url = encode_wide(url);
     let _ = unsafe { self.webview.Navigate(PCWSTR::from_raw(url.as_ptr())) };
@@ -1005,6 +1987,19 @@ impl InnerWebView {
     load_url_with_headers(&self.webview, &self.env, url, headers);
   }
 
+  pub fn load_request(&self, request: Request<Vec<u8>>) -> Result<()> {
+    let body = request.body();
+    load_request(
+      &self.webview,
+      &self.env,
+      &request.uri().to_string(),
+      request.method().as_str(),
+      request.headers(),
+      (!body.is_empty()).then_some(body.as_slice()),
+    );
+    Ok(())
+  }
+
   pub fn clear_all_browsing_data(&self) -> Result<()> {
     let handler = ClearBrowsingDataCompletedHandler::create(Box::new(move |_| Ok(())));
     unsafe {
@@ -1055,6 +2050,33 @@ impl InnerWebView {
     bounds
   }
 
+  pub fn scale_factor(&self) -> f64 {
+    window_scale_factor(self.hwnd)
+  }
+
+  pub fn set_click_through_regions(&self, regions: &[Rect]) -> Result<()> {
+    if !self.is_child {
+      return Err(Error::NotAChildWebView);
+    }
+    unsafe { set_click_through_regions(self.hwnd, regions) };
+    Ok(())
+  }
+
+  pub fn window_drag_starter(&self) -> Box<dyn Fn()> {
+    let hwnd = self.hwnd;
+    Box::new(move || unsafe { start_window_drag(hwnd) })
+  }
+
+  pub fn click_through_regions_setter(&self) -> Box<dyn Fn(&[Rect])> {
+    let hwnd = self.hwnd;
+    let is_child = self.is_child;
+    Box::new(move |regions| {
+      if is_child {
+        unsafe { set_click_through_regions(hwnd, regions) };
+      }
+    })
+  }
+
   pub fn set_bounds(&self, bounds: Rect) {
     if self.is_child {
       unsafe {
@@ -1095,52 +2117,1215 @@ impl InnerWebView {
     }
   }
 
-  pub fn set_memory_usage_level(&self, level: MemoryUsageLevel) {
-    let Ok(webview) = self.webview.cast::<ICoreWebView2_19>() else {
-      return;
-    };
-    // https://learn.microsoft.com/en-us/dotnet/api/microsoft.web.webview2.core.corewebview2memoryusagetargetlevel
-    let level = match level {
-      MemoryUsageLevel::Normal => 0,
-      MemoryUsageLevel::Low => 1,
+  pub fn add_initialization_script(&self, js: &str) -> Result<ScriptId> {
+    let script_id = Self::add_script_to_execute_on_document_created(&self.webview, js.to_string())?;
+    let id = ScriptId::next();
+    self
+      .initialization_scripts
+      .borrow_mut()
+      .insert(id, script_id);
+    Ok(id)
+  }
+
+  pub fn remove_initialization_script(&self, id: ScriptId) -> Result<()> {
+    let Some(script_id) = self.initialization_scripts.borrow_mut().remove(&id) else {
+      return Ok(());
     };
-    let level = COREWEBVIEW2_MEMORY_USAGE_TARGET_LEVEL(level);
-    let _ = unsafe { webview.SetMemoryUsageTargetLevel(level) };
+    unsafe {
+      self
+        .webview
+        .RemoveScriptToExecuteOnDocumentCreated(PCWSTR::from_raw(encode_wide(script_id).as_ptr()))
+    }
+    .map_err(|e| webview2_com::Error::WindowsError(e).into())
   }
-}
 
-unsafe fn prepare_web_request_response(
-  env: &ICoreWebView2Environment,
-  sent_response: &HttpResponse<Cow<'static, [u8]>>,
-) -> windows::core::Result<ICoreWebView2WebResourceResponse> {
-  let content = sent_response.body();
-  let status_code = sent_response.status();
+  pub fn find(
+    &self,
+    text: &str,
+    options: crate::FindOptions,
+    callback: impl Fn(usize) + Send + 'static,
+  ) -> Result<()> {
+    // the Find API of WebView2 is newer than the bindings, the page searches with `window.find()`
+    self.eval(
+      &crate::find::find_script(text, options),
+      Some(move |result: String| {
+        if let Ok(count) = result.parse() {
+          callback(count);
+        }
+      }),
+    )
+  }
 
-  let mut headers_map = String::new();
+  pub fn find_next(&self) -> Result<()> {
+    self.eval(
+      &crate::find::find_next_script(false),
+      None::<Box<dyn FnOnce(String) + Send + 'static>>,
+    )
+  }
 
-  // build headers
-  for (name, value) in sent_response.headers().iter() {
-    let header_key = name.to_string();
-    if let Ok(value) = value.to_str() {
-      let _ = writeln!(headers_map, "{}: {}", header_key, value);
-    }
+  pub fn find_previous(&self) -> Result<()> {
+    self.eval(
+      &crate::find::find_next_script(true),
+      None::<Box<dyn FnOnce(String) + Send + 'static>>,
+    )
   }
 
-  let mut stream = None;
-  if !content.is_empty() {
-    stream = SHCreateMemStream(Some(content));
+  pub fn clear_matches(&self) -> Result<()> {
+    self.eval(
+      crate::find::CLEAR_MATCHES_SCRIPT,
+      None::<Box<dyn FnOnce(String) + Send + 'static>>,
+    )
   }
 
-  // FIXME: Set http response version
+  pub fn inject_css(&self, css: &str) -> Result<StyleId> {
+    let id = StyleId::next();
+    self.user_styles.add(id, css)?;
+    Ok(id)
+  }
 
-  env.CreateWebResourceResponse(
-    stream.as_ref(),
-    status_code.as_u16() as i32,
-    PCWSTR::from_raw(encode_wide(status_code.canonical_reason().unwrap_or("OK")).as_ptr()),
-    PCWSTR::from_raw(encode_wide(headers_map).as_ptr()),
+  pub fn remove_css(&self, id: StyleId) -> Result<()> {
+    self.user_styles.remove(id)
+  }
+
+  pub fn create_shared_buffer(&self, len: usize) -> Result<SharedBuffer> {
+    let env = self
+      .env
+      .cast::<ICoreWebView2Environment12>()
+      .map_err(|_| Error::NotSupported("creating shared buffers before WebView2 1.0.1661"))?;
+    unsafe {
+      env
+        .CreateSharedBuffer(len as u64)
+        .and_then(SharedBuffer::new)
+        .map_err(|e| webview2_com::Error::WindowsError(e).into())
+    }
+  }
+
+  pub fn post_shared_buffer(
+    &self,
+    buffer: &SharedBuffer,
+    access: SharedBufferAccess,
+    additional_data: Option<&serde_json::Value>,
+  ) -> Result<()> {
+    let webview = self
+      .webview
+      .cast::<ICoreWebView2_17>()
+      .map_err(|_| Error::NotSupported("posting shared buffers before WebView2 1.0.1661"))?;
+    let access = match access {
+      SharedBufferAccess::ReadOnly => COREWEBVIEW2_SHARED_BUFFER_ACCESS_READ_ONLY,
+      SharedBufferAccess::ReadWrite => COREWEBVIEW2_SHARED_BUFFER_ACCESS_READ_WRITE,
+    };
+    let additional_data = additional_data.map(|data| encode_wide(data.to_string()));
+    let additional_data = additional_data
+      .as_ref()
+      .map_or(PCWSTR::null(), |data| PCWSTR::from_raw(data.as_ptr()));
+    unsafe { webview.PostSharedBufferToScript(&buffer.buffer, access, additional_data) }
+      .map_err(|e| webview2_com::Error::WindowsError(e).into())
+  }
+
+  pub fn add_host_object(&self, name: &str, object: &IDispatch) -> Result<()> {
+    let name = encode_wide(name);
+    let mut variant = VARIANT::default();
+    unsafe {
+      // the variant holds a reference to the object, released by `VariantClear`
+      let value = &mut *variant.Anonymous.Anonymous;
+      value.vt = VT_DISPATCH;
+      value.Anonymous.pdispVal = std::mem::ManuallyDrop::new(Some(object.clone()));
+
+      let result = self
+        .webview
+        .AddHostObjectToScript(PCWSTR::from_raw(name.as_ptr()), &mut variant);
+      let _ = VariantClear(&mut variant);
+      result.map_err(|e| webview2_com::Error::WindowsError(e).into())
+    }
+  }
+
+  pub fn remove_host_object(&self, name: &str) -> Result<()> {
+    let name = encode_wide(name);
+    unsafe {
+      self
+        .webview
+        .RemoveHostObjectFromScript(PCWSTR::from_raw(name.as_ptr()))
+    }
+    .map_err(|e| webview2_com::Error::WindowsError(e).into())
+  }
+
+  pub fn set_memory_usage_level(&self, level: MemoryUsageLevel) {
+    let Ok(webview) = self.webview.cast::<ICoreWebView2_19>() else {
+      return;
+    };
+    // https://learn.microsoft.com/en-us/dotnet/api/microsoft.web.webview2.core.corewebview2memoryusagetargetlevel
+    let level = match level {
+      MemoryUsageLevel::Normal => 0,
+      MemoryUsageLevel::Low => 1,
+    };
+    let level = COREWEBVIEW2_MEMORY_USAGE_TARGET_LEVEL(level);
+    let _ = unsafe { webview.SetMemoryUsageTargetLevel(level) };
+  }
+
+  pub fn set_touch_adjust_radius(&self, radius_css_px: f64) {
+    let script = crate::touch_adjust_script(radius_css_px);
+    // apply to the pages loaded later, replacing the previous radius, and to the current one
+    if let Some(id) = self.touch_adjust_script.take() {
+      let _ = self.remove_initialization_script(id);
+    }
+    self
+      .touch_adjust_script
+      .set(self.add_initialization_script(&script).ok());
+    let _ = self.eval(&script, None::<Box<dyn FnOnce(String) + Send + 'static>>);
+  }
+
+  pub fn reload(&self) {
+    let _ = unsafe { self.webview.Reload() };
+  }
+
+  pub fn stop(&self) {
+    let _ = unsafe { self.webview.Stop() };
+  }
+
+  pub fn reload_ignoring_local_cache(&self) {
+    let webview = self.webview.clone();
+    let env = self.env.clone();
+    let reload = move || {
+      let url = url_from_webview(&webview);
+      load_url_with_headers(&webview, &env, &url, crate::no_cache_headers());
+    };
+
+    // reloads once the disk cache is cleared, or right away without `ICoreWebView2Profile2`
+    let profile = unsafe {
+      self
+        .webview
+        .cast::<ICoreWebView2_13>()
+        .and_then(|webview| webview.Profile())
+        .and_then(|profile| profile.cast::<ICoreWebView2Profile2>())
+    };
+    let Ok(profile) = profile else {
+      return reload();
+    };
+    let reload = Rc::new(RefCell::new(Some(reload)));
+    let reload_ = reload.clone();
+    let handler = ClearBrowsingDataCompletedHandler::create(Box::new(move |_| {
+      if let Some(reload) = reload_.borrow_mut().take() {
+        reload();
+      }
+      Ok(())
+    }));
+    if unsafe { profile.ClearBrowsingData(COREWEBVIEW2_BROWSING_DATA_KINDS_DISK_CACHE, &handler) }
+      .is_err()
+    {
+      if let Some(reload) = reload.borrow_mut().take() {
+        reload();
+      }
+    }
+  }
+
+  pub fn set_window_level(&self, level: WindowLevel) -> Result<()> {
+    let insert_after = match level {
+      WindowLevel::Normal => win32wm::HWND_NOTOPMOST,
+      WindowLevel::Floating => win32wm::HWND_TOPMOST,
+      WindowLevel::Desktop => win32wm::HWND_BOTTOM,
+    };
+
+    unsafe {
+      let window = win32wm::GetAncestor(self.hwnd, win32wm::GA_ROOT);
+      SetWindowPos(
+        window,
+        insert_after,
+        0,
+        0,
+        0,
+        0,
+        SWP_NOACTIVATE | win32wm::SWP_NOMOVE | win32wm::SWP_NOSIZE,
+      )
+      .map_err(webview2_com::Error::WindowsError)?;
+    }
+
+    Ok(())
+  }
+
+  pub fn register_protocol(
+    &self,
+    name: String,
+    handler: Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)>,
+  ) -> Result<()> {
+    let mut custom_protocols = self.custom_protocols.borrow_mut();
+    if let Some(index) = custom_protocols.iter().position(|(n, _)| *n == name) {
+      custom_protocols[index].1 = Rc::from(handler);
+      return Ok(());
+    }
+
+    let filter = encode_wide(format!("{}://{name}.*", self.custom_protocol_scheme()));
+    unsafe {
+      self
+        .webview
+        .AddWebResourceRequestedFilter(
+          PCWSTR::from_raw(filter.as_ptr()),
+          COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
+        )
+        .map_err(webview2_com::Error::WindowsError)?;
+    }
+    custom_protocols.push((name, Rc::from(handler)));
+    Ok(())
+  }
+
+  pub fn unregister_protocol(&self, name: &str) -> Result<()> {
+    let mut custom_protocols = self.custom_protocols.borrow_mut();
+    let Some(index) = custom_protocols.iter().position(|(n, _)| n == name) else {
+      return Ok(());
+    };
+
+    let filter = encode_wide(format!("{}://{name}.*", self.custom_protocol_scheme()));
+    unsafe {
+      self
+        .webview
+        .RemoveWebResourceRequestedFilter(
+          PCWSTR::from_raw(filter.as_ptr()),
+          COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
+        )
+        .map_err(webview2_com::Error::WindowsError)?;
+    }
+    custom_protocols.remove(index);
+    Ok(())
+  }
+
+  fn custom_protocol_scheme(&self) -> &'static str {
+    if self.https_scheme {
+      "https"
+    } else {
+      "http"
+    }
+  }
+
+  pub fn set_audio_muted(&self, muted: bool) -> Result<()> {
+    unsafe {
+      self
+        .webview
+        .cast::<ICoreWebView2_8>()
+        .and_then(|webview| webview.SetIsMuted(muted))
+        .map_err(|e| Error::WebView2Error(webview2_com::Error::WindowsError(e)))
+    }
+  }
+
+  pub fn is_audio_muted(&self) -> bool {
+    let mut muted = BOOL::default();
+    unsafe {
+      let _ = self
+        .webview
+        .cast::<ICoreWebView2_8>()
+        .and_then(|webview| webview.IsMuted(&mut muted));
+    }
+    muted.as_bool()
+  }
+
+  pub fn is_playing_audio(&self) -> Result<bool> {
+    let mut playing = BOOL::default();
+    unsafe {
+      self
+        .webview
+        .cast::<ICoreWebView2_8>()
+        .and_then(|webview| webview.IsDocumentPlayingAudio(&mut playing))
+        .map_err(|e| Error::WebView2Error(webview2_com::Error::WindowsError(e)))?;
+    }
+    Ok(playing.as_bool())
+  }
+
+  pub fn history(&self, callback: Box<dyn FnOnce(Vec<HistoryEntry>)>) {
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct NavigationHistory {
+      current_index: i32,
+      entries: Vec<NavigationEntry>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct NavigationEntry {
+      url: String,
+      title: String,
+    }
+
+    // the current entry, when the history can't be read
+    let mut title = PWSTR::null();
+    let title = unsafe { self.webview.DocumentTitle(&mut title) }
+      .ok()
+      .map(|_| take_pwstr(title));
+    let current = HistoryEntry::new(self.url(), title, 0);
+
+    // WebView2 doesn't expose the history, it is read with the DevTools protocol
+    let callback = Rc::new(RefCell::new(Some(callback)));
+    let callback_ = callback.clone();
+    let current_ = current.clone();
+    let result = response_transform::call_method(
+      &self.webview,
+      "Page.getNavigationHistory",
+      serde_json::json!({}),
+      move |result| {
+        let history =
+          result.and_then(|result| serde_json::from_value::<NavigationHistory>(result).ok());
+        let entries = match history {
+          Some(history) => history
+            .entries
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, entry)| {
+              let url = Url::parse(&entry.url).ok()?;
+              let title = Some(entry.title).filter(|title| !title.is_empty());
+              Some(HistoryEntry::new(
+                url,
+                title,
+                i as i32 - history.current_index,
+              ))
+            })
+            .collect(),
+          None => vec![current_],
+        };
+        if let Some(callback) = callback_.borrow_mut().take() {
+          callback(entries);
+        }
+      },
+    );
+    if result.is_err() {
+      if let Some(callback) = callback.borrow_mut().take() {
+        callback(vec![current]);
+      }
+    }
+  }
+
+  pub fn go_to_history_index(&self, index: usize, callback: Box<dyn FnOnce(Result<()>)>) {
+    let webview = self.webview.clone();
+    self.history(Box::new(move |entries| {
+      let result = match entries.get(index) {
+        Some(entry) => {
+          Self::execute_script(&webview, crate::history_go_script(entry.offset), |_| ())
+            .map_err(|err| Error::WebView2Error(webview2_com::Error::WindowsError(err)))
+        }
+        None => Err(Error::HistoryIndexOutOfBounds(index)),
+      };
+      callback(result)
+    }));
+  }
+
+  pub fn navigate_to_history_offset(&self, offset: i32) -> Result<()> {
+    self.eval(
+      &crate::history_go_script(offset),
+      None::<Box<dyn FnOnce(String) + Send + 'static>>,
+    )
+  }
+
+  pub fn is_visible(&self) -> bool {
+    let mut visible = BOOL::default();
+    let _ = unsafe { self.controller.IsVisible(&mut visible) };
+    visible.as_bool()
+  }
+
+  pub fn set_parent(&self, parent: &impl HasWindowHandle) -> Result<()> {
+    if !self.is_child {
+      return Err(Error::NotAChildWebView);
+    }
+
+    let parent = match parent.window_handle()?.as_raw() {
+      RawWindowHandle::Win32(parent) => HWND(parent.hwnd.get() as _),
+      _ => return Err(Error::UnsupportedWindowHandle),
+    };
+
+    unsafe {
+      // move the `auto_resize` subclass, if any, to the new parent
+      let auto_resize = RemoveWindowSubclass(
+        GetParent(self.hwnd),
+        Some(parent_subclass_proc),
+        self.hwnd.0 as usize,
+      )
+      .as_bool();
+
+      SetParent(self.hwnd, parent);
+
+      if auto_resize {
+        SetWindowSubclass(
+          parent,
+          Some(parent_subclass_proc),
+          self.hwnd.0 as usize,
+          self.hwnd.0 as usize,
+        );
+        fill_parent(self.hwnd);
+      }
+
+      let _ = self.controller.NotifyParentWindowPositionChanged();
+    }
+
+    Ok(())
+  }
+
+  pub fn set_z_order(&self, order: ZOrder) -> Result<()> {
+    if !self.is_child {
+      return Err(Error::NotAChildWebView);
+    }
+
+    unsafe {
+      let insert_after = match order {
+        ZOrder::Top => win32wm::HWND_TOP,
+        ZOrder::Bottom => win32wm::HWND_BOTTOM,
+        // windows are placed below `insert_after`, so insert after the window right above `other`
+        ZOrder::Above(other) => {
+          let previous = win32wm::GetWindow(other.webview.hwnd, win32wm::GW_HWNDPREV);
+          if previous == HWND::default() {
+            win32wm::HWND_TOP
+          } else {
+            previous
+          }
+        }
+      };
+
+      SetWindowPos(
+        self.hwnd,
+        insert_after,
+        0,
+        0,
+        0,
+        0,
+        SWP_NOACTIVATE | win32wm::SWP_NOMOVE | win32wm::SWP_NOSIZE,
+      )
+      .map_err(webview2_com::Error::WindowsError)?;
+    }
+
+    Ok(())
+  }
+
+  pub fn go_back(&self) {
+    let _ = unsafe { self.webview.GoBack() };
+  }
+
+  pub fn go_forward(&self) {
+    let _ = unsafe { self.webview.GoForward() };
+  }
+
+  pub fn can_go_back(&self) -> bool {
+    let mut can_go_back = BOOL::default();
+    let _ = unsafe { self.webview.CanGoBack(&mut can_go_back) };
+    can_go_back.as_bool()
+  }
+
+  pub fn can_go_forward(&self) -> bool {
+    let mut can_go_forward = BOOL::default();
+    let _ = unsafe { self.webview.CanGoForward(&mut can_go_forward) };
+    can_go_forward.as_bool()
+  }
+
+  pub fn execute_edit_command(&self, command: EditCommand) -> Result<()> {
+    // WebView2 has no editing API, the commands are sent with a key event of the DevTools protocol
+    let command = match command {
+      EditCommand::Cut => "cut",
+      EditCommand::Copy => "copy",
+      EditCommand::Paste => "paste",
+      EditCommand::SelectAll => "selectAll",
+      EditCommand::Undo => "undo",
+      EditCommand::Redo => "redo",
+    };
+    let params = serde_json::json!({
+      "type": "rawKeyDown",
+      "commands": [command],
+    })
+    .to_string();
+
+    let handler = CallDevToolsProtocolMethodCompletedHandler::create(Box::new(|_, _| Ok(())));
+    unsafe {
+      self
+        .webview
+        .CallDevToolsProtocolMethod(
+          PCWSTR::from_raw(encode_wide("Input.dispatchKeyEvent").as_ptr()),
+          PCWSTR::from_raw(encode_wide(params).as_ptr()),
+          &handler,
+        )
+        .map_err(|e| Error::WebView2Error(webview2_com::Error::WindowsError(e)))
+    }
+  }
+
+  pub fn set_zoom_hotkeys_enabled(&self, enabled: bool) -> Result<()> {
+    unsafe {
+      let settings = self
+        .webview
+        .Settings()
+        .map_err(|e| Error::WebView2Error(webview2_com::Error::WindowsError(e)))?;
+      settings
+        .SetIsZoomControlEnabled(enabled)
+        .map_err(|e| Error::WebView2Error(webview2_com::Error::WindowsError(e)))?;
+      settings
+        .cast::<ICoreWebView2Settings5>()
+        .and_then(|settings5| settings5.SetIsPinchZoomEnabled(enabled))
+        .map_err(|e| Error::WebView2Error(webview2_com::Error::WindowsError(e)))
+    }
+  }
+
+  pub fn set_background_throttling(&self, _enabled: bool) -> Result<()> {
+    // The throttling is set by the browser arguments shared by the webviews of the environment
+    Err(Error::NotSupported("changing the background throttling"))
+  }
+
+  pub fn save_state(&self, callback: Box<dyn FnOnce(Result<Vec<u8>>)>) {
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct NavigationHistory {
+      current_index: usize,
+      entries: Vec<NavigationEntry>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct NavigationEntry {
+      url: String,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Evaluation {
+      result: RemoteObject,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct RemoteObject {
+      value: (f64, f64),
+    }
+
+    // WebView2 doesn't expose the history, it is read with the DevTools protocol, then the scroll offset
+    let url = url_from_webview(&self.webview);
+    let webview = self.webview.clone();
+    let callback = Rc::new(RefCell::new(Some(callback)));
+    let callback_ = callback.clone();
+    let result = response_transform::call_method(
+      &self.webview,
+      "Page.getNavigationHistory",
+      serde_json::json!({}),
+      move |history| {
+        let history =
+          history.and_then(|history| serde_json::from_value::<NavigationHistory>(history).ok());
+        let (entries, current) = match history {
+          Some(history) => (
+            history.entries.into_iter().map(|entry| entry.url).collect(),
+            history.current_index,
+          ),
+          None => (vec![url.clone()], 0),
+        };
+
+        let callback = callback_.clone();
+        let result = response_transform::call_method(
+          &webview,
+          "Runtime.evaluate",
+          serde_json::json!({ "expression": "[window.scrollX, window.scrollY]", "returnByValue": true }),
+          move |evaluation| {
+            let scroll = evaluation
+              .and_then(|evaluation| serde_json::from_value::<Evaluation>(evaluation).ok())
+              .map(|evaluation| evaluation.result.value)
+              .unwrap_or_default();
+            let state = serde_json::to_vec(&SessionState {
+              url,
+              scroll,
+              entries,
+              current,
+            })
+            .map_err(Into::into);
+            if let Some(callback) = callback.borrow_mut().take() {
+              callback(state);
+            }
+          },
+        );
+        if let Err(error) = result {
+          if let Some(callback) = callback_.borrow_mut().take() {
+            callback(Err(error.into()));
+          }
+        }
+      },
+    );
+    if let Err(error) = result {
+      if let Some(callback) = callback.borrow_mut().take() {
+        callback(Err(error.into()));
+      }
+    }
+  }
+
+  pub fn restore_state(&self, state: &[u8]) -> Result<()> {
+    let SessionState {
+      url,
+      scroll: (x, y),
+      entries,
+      current,
+    } = serde_json::from_slice(state).map_err(|_| Error::InvalidSessionState)?;
+    let (mut entries, current) = if entries.is_empty() {
+      (vec![url], 0)
+    } else {
+      (entries, current)
+    };
+    if current >= entries.len() {
+      return Err(Error::InvalidSessionState);
+    }
+
+    // WebView2 can't restore the history, it is rebuilt by loading its entries in order, then the
+    // current entry is navigated to again and its scroll offset is restored once it is loaded
+    let back = Cell::new(current as i32 + 1 - entries.len() as i32);
+    entries.reverse();
+    let first = entries.pop().unwrap_or_default();
+    let pending = RefCell::new(entries);
+    let token = Rc::new(Cell::new(EventRegistrationToken::default()));
+    let token_ = token.clone();
+    let handler = NavigationCompletedEventHandler::create(Box::new(move |webview, _| {
+      let Some(webview) = webview else {
+        return Ok(());
+      };
+      if let Some(url) = pending.borrow_mut().pop() {
+        let url = encode_wide(url);
+        return unsafe { webview.Navigate(PCWSTR::from_raw(url.as_ptr())) };
+      }
+      let back = back.replace(0);
+      if back != 0 {
+        return Self::execute_script(&webview, crate::history_go_script(back), |_| ());
+      }
+      unsafe { webview.remove_NavigationCompleted(token_.get())? };
+      Self::execute_script(&webview, format!("window.scrollTo({x}, {y});"), |_| ())
+    }));
+
+    unsafe {
+      let mut registration = EventRegistrationToken::default();
+      self
+        .webview
+        .add_NavigationCompleted(&handler, &mut registration)
+        .map_err(|e| Error::WebView2Error(webview2_com::Error::WindowsError(e)))?;
+      token.set(registration);
+    }
+
+    self.load_url(&first);
+    Ok(())
+  }
+
+  pub fn frames(&self) -> Vec<FrameInfo> {
+    let main_frame = FrameInfo {
+      id: crate::MAIN_FRAME_ID,
+      url: Url::parse(&url_from_webview(&self.webview)).ok(),
+      name: String::new(),
+      is_main_frame: true,
+    };
+
+    let frames = self.frames.borrow();
+    let iframes = frames.iter().map(|frame| {
+      let mut name = PWSTR::null();
+      let name = unsafe { frame.frame.Name(&mut name) }
+        .map(|_| take_pwstr(name))
+        .unwrap_or_default();
+      FrameInfo {
+        id: frame.id,
+        url: frame.url.clone(),
+        name,
+        is_main_frame: false,
+      }
+    });
+
+    std::iter::once(main_frame).chain(iframes).collect()
+  }
+
+  pub fn eval_in_frame(&self, frame_id: u64, js: &str) -> Result<()> {
+    if frame_id == crate::MAIN_FRAME_ID {
+      return self.eval(js, None::<Box<dyn FnOnce(String) + Send + 'static>>);
+    }
+
+    let frames = self.frames.borrow();
+    let frame = frames
+      .iter()
+      .find(|frame| frame.id == frame_id)
+      .ok_or(Error::FrameNotFound(frame_id))?;
+    let handler = ExecuteScriptCompletedHandler::create(Box::new(|_, _| Ok(())));
+    unsafe {
+      frame
+        .frame
+        .cast::<ICoreWebView2Frame2>()
+        .and_then(|frame| frame.ExecuteScript(PCWSTR::from_raw(encode_wide(js).as_ptr()), &handler))
+        .map_err(|e| Error::WebView2Error(webview2_com::Error::WindowsError(e)))
+    }
+  }
+}
+
+/// Creates the response of a custom protocol request, see `complete` in `init_webview`.
+type CreateResponse = Box<
+  dyn FnOnce(&ICoreWebView2Environment) -> windows::core::Result<ICoreWebView2WebResourceResponse>,
+>;
+
+unsafe fn prepare_web_request_response(
+  env: &ICoreWebView2Environment,
+  sent_response: &HttpResponse<Cow<'static, [u8]>>,
+) -> windows::core::Result<ICoreWebView2WebResourceResponse> {
+  let content = sent_response.body();
+  let mut stream = None;
+  if !content.is_empty() {
+    stream = SHCreateMemStream(Some(content));
+  }
+
+  create_web_resource_response(env, sent_response, stream.as_ref())
+}
+
+/// Creates a response with the status and headers of `sent_response` and the given body `stream`.
+unsafe fn create_web_resource_response<T>(
+  env: &ICoreWebView2Environment,
+  sent_response: &HttpResponse<T>,
+  stream: Option<&IStream>,
+) -> windows::core::Result<ICoreWebView2WebResourceResponse> {
+  let status_code = sent_response.status();
+
+  let mut headers_map = String::new();
+
+  // build headers
+  for (name, value) in sent_response.headers().iter() {
+    let header_key = name.to_string();
+    if let Ok(value) = value.to_str() {
+      let _ = writeln!(headers_map, "{}: {}", header_key, value);
+    }
+  }
+
+  // FIXME: Set http response version
+
+  env.CreateWebResourceResponse(
+    stream,
+    status_code.as_u16() as i32,
+    PCWSTR::from_raw(encode_wide(status_code.canonical_reason().unwrap_or("OK")).as_ptr()),
+    PCWSTR::from_raw(encode_wide(headers_map).as_ptr()),
   )
 }
 
+/// Runs the request headers interceptor and writes the changes back to the native request.
+unsafe fn intercept_request_headers(
+  webview_request: &ICoreWebView2WebResourceRequest,
+  uri: &str,
+  original_headers: &http::HeaderMap,
+  interceptor: &dyn Fn(&mut http::HeaderMap, &Url),
+) -> windows::core::Result<()> {
+  let Ok(url) = Url::parse(uri) else {
+    return Ok(());
+  };
+
+  let mut headers = original_headers.clone();
+  interceptor(&mut headers, &url);
+
+  let native_headers = webview_request.Headers()?;
+  for name in original_headers.keys() {
+    if !headers.contains_key(name) {
+      native_headers.RemoveHeader(PCWSTR::from_raw(encode_wide(name.as_str()).as_ptr()))?;
+    }
+  }
+  for name in headers.keys() {
+    let value = headers
+      .get_all(name)
+      .iter()
+      .filter_map(|v| v.to_str().ok())
+      .collect::<Vec<_>>()
+      .join(", ");
+    native_headers.SetHeader(
+      PCWSTR::from_raw(encode_wide(name.as_str()).as_ptr()),
+      PCWSTR::from_raw(encode_wide(value).as_ptr()),
+    )?;
+  }
+
+  Ok(())
+}
+
+/// Maps a [`KeyCode`] to its Windows virtual-key code.
+fn virtual_key(key: KeyCode) -> u32 {
+  match key {
+    KeyCode::Digit0 => 0x30,
+    KeyCode::Digit1 => 0x31,
+    KeyCode::Digit2 => 0x32,
+    KeyCode::Digit3 => 0x33,
+    KeyCode::Digit4 => 0x34,
+    KeyCode::Digit5 => 0x35,
+    KeyCode::Digit6 => 0x36,
+    KeyCode::Digit7 => 0x37,
+    KeyCode::Digit8 => 0x38,
+    KeyCode::Digit9 => 0x39,
+    KeyCode::Numpad0 => VK_NUMPAD0.0 as u32,
+    KeyCode::Numpad1 => VK_NUMPAD1.0 as u32,
+    KeyCode::Numpad2 => VK_NUMPAD2.0 as u32,
+    KeyCode::Numpad3 => VK_NUMPAD3.0 as u32,
+    KeyCode::Numpad4 => VK_NUMPAD4.0 as u32,
+    KeyCode::Numpad5 => VK_NUMPAD5.0 as u32,
+    KeyCode::Numpad6 => VK_NUMPAD6.0 as u32,
+    KeyCode::Numpad7 => VK_NUMPAD7.0 as u32,
+    KeyCode::Numpad8 => VK_NUMPAD8.0 as u32,
+    KeyCode::Numpad9 => VK_NUMPAD9.0 as u32,
+    KeyCode::KeyA => 0x41,
+    KeyCode::KeyB => 0x42,
+    KeyCode::KeyC => 0x43,
+    KeyCode::KeyD => 0x44,
+    KeyCode::KeyE => 0x45,
+    KeyCode::KeyF => 0x46,
+    KeyCode::KeyG => 0x47,
+    KeyCode::KeyH => 0x48,
+    KeyCode::KeyI => 0x49,
+    KeyCode::KeyJ => 0x4A,
+    KeyCode::KeyK => 0x4B,
+    KeyCode::KeyL => 0x4C,
+    KeyCode::KeyM => 0x4D,
+    KeyCode::KeyN => 0x4E,
+    KeyCode::KeyO => 0x4F,
+    KeyCode::KeyP => 0x50,
+    KeyCode::KeyQ => 0x51,
+    KeyCode::KeyR => 0x52,
+    KeyCode::KeyS => 0x53,
+    KeyCode::KeyT => 0x54,
+    KeyCode::KeyU => 0x55,
+    KeyCode::KeyV => 0x56,
+    KeyCode::KeyW => 0x57,
+    KeyCode::KeyX => 0x58,
+    KeyCode::KeyY => 0x59,
+    KeyCode::KeyZ => 0x5A,
+    KeyCode::Backspace => VK_BACK.0 as u32,
+    KeyCode::Delete => VK_DELETE.0 as u32,
+    KeyCode::Escape => VK_ESCAPE.0 as u32,
+    KeyCode::Tab => VK_TAB.0 as u32,
+    KeyCode::Enter => VK_RETURN.0 as u32,
+    KeyCode::Space => VK_SPACE.0 as u32,
+    KeyCode::ArrowLeft => VK_LEFT.0 as u32,
+    KeyCode::ArrowUp => VK_UP.0 as u32,
+    KeyCode::ArrowRight => VK_RIGHT.0 as u32,
+    KeyCode::ArrowDown => VK_DOWN.0 as u32,
+    KeyCode::F1 => VK_F1.0 as u32,
+    KeyCode::F2 => VK_F2.0 as u32,
+    KeyCode::F3 => VK_F3.0 as u32,
+    KeyCode::F4 => VK_F4.0 as u32,
+    KeyCode::F5 => VK_F5.0 as u32,
+    KeyCode::F6 => VK_F6.0 as u32,
+    KeyCode::F7 => VK_F7.0 as u32,
+    KeyCode::F8 => VK_F8.0 as u32,
+    KeyCode::F9 => VK_F9.0 as u32,
+    KeyCode::F10 => VK_F10.0 as u32,
+    KeyCode::F11 => VK_F11.0 as u32,
+    KeyCode::F12 => VK_F12.0 as u32,
+  }
+}
+
+/// Starts moving the window under the cursor, as if its title bar was pressed.
+/// Starts moving the top-level window of the webview window `hwnd` with the pointer, as if its
+/// title bar was pressed.
+unsafe fn start_window_drag(hwnd: HWND) {
+  let root = win32wm::GetAncestor(hwnd, win32wm::GA_ROOT);
+  let mut point = POINT::default();
+  if root.0 == 0 || win32wm::GetCursorPos(&mut point).is_err() {
+    return;
+  }
+
+  // the window is hit on its caption until the move ends
+  SetWindowSubclass(
+    root,
+    Some(window_drag_subclass_proc),
+    WINDOW_DRAG_SUBCLASS_ID,
+    0,
+  );
+  let _ = ReleaseCapture();
+  let lparam = ((point.y as u16 as isize) << 16) | point.x as u16 as isize;
+  let _ = PostMessageW(
+    root,
+    win32wm::WM_NCLBUTTONDOWN,
+    WPARAM(win32wm::HTCAPTION as usize),
+    LPARAM(lparam),
+  );
+}
+
+const WINDOW_DRAG_SUBCLASS_ID: usize = 0x5752_5944;
+
+/// Answers the hit tests of the top-level window `hwnd` with its caption while it is moved by
+/// [`start_window_drag`].
+unsafe extern "system" fn window_drag_subclass_proc(
+  hwnd: HWND,
+  msg: u32,
+  wparam: WPARAM,
+  lparam: LPARAM,
+  uidsubclass: usize,
+  _dwrefdata: usize,
+) -> LRESULT {
+  match msg {
+    win32wm::WM_NCHITTEST => LRESULT(win32wm::HTCAPTION as _),
+    win32wm::WM_NCLBUTTONDOWN => {
+      // the move loop runs until the button is released
+      let result = DefSubclassProc(hwnd, msg, wparam, lparam);
+      RemoveWindowSubclass(hwnd, Some(window_drag_subclass_proc), uidsubclass);
+      result
+    }
+    _ => DefSubclassProc(hwnd, msg, wparam, lparam),
+  }
+}
+
+unsafe fn handle_print_dialog(
+  webview: &ICoreWebView2,
+  on_print_dialog: &dyn Fn(PrintDialogRequest) -> PrintDialogDecision,
+) -> windows::core::Result<()> {
+  let Ok(url) = Url::parse(&url_from_webview(webview)) else {
+    return Ok(());
+  };
+
+  match on_print_dialog(PrintDialogRequest {
+    url,
+    initiated_by_js: true,
+  }) {
+    PrintDialogDecision::Allow => {
+      InnerWebView::execute_script(webview, "window.__WRY_NATIVE_PRINT__()".to_string(), |_| ())
+    }
+    PrintDialogDecision::Deny => Ok(()),
+    PrintDialogDecision::RedirectToPdf(path) => {
+      let path = encode_wide(path);
+      webview.cast::<ICoreWebView2_7>()?.PrintToPdf(
+        PCWSTR::from_raw(path.as_ptr()),
+        None,
+        &PrintToPdfCompletedHandler::create(Box::new(|_, _| Ok(()))),
+      )
+    }
+  }
+}
+
+/// Resizes the child webview window `dwrefdata` to fill its parent `hwnd`, see [`WebViewAttributes::auto_resize`].
+unsafe extern "system" fn parent_subclass_proc(
+  hwnd: HWND,
+  msg: u32,
+  wparam: WPARAM,
+  lparam: LPARAM,
+  _uidsubclass: usize,
+  dwrefdata: usize,
+) -> LRESULT {
+  if msg == win32wm::WM_SIZE && wparam.0 != win32wm::SIZE_MINIMIZED as usize {
+    fill_parent(HWND(dwrefdata as _));
+  }
+
+  DefSubclassProc(hwnd, msg, wparam, lparam)
+}
+
+fn window_scale_factor(hwnd: HWND) -> f64 {
+  match unsafe { GetDpiForWindow(hwnd) } {
+    0 => 1.0,
+    dpi => dpi as f64 / USER_DEFAULT_SCREEN_DPI as f64,
+  }
+}
+
+/// Removes the click-through `regions`, in logical pixels, from the window region of the child
+/// webview window `hwnd`, so the mouse input reaches the windows under them.
+unsafe fn set_click_through_regions(hwnd: HWND, regions: &[Rect]) {
+  if !IsWindow(hwnd).as_bool() {
+    return;
+  }
+  if regions.is_empty() {
+    SetWindowRgn(hwnd, HRGN::default(), true);
+    return;
+  }
+
+  let scale_factor = window_scale_factor(hwnd);
+  let physical = |value: i64| (value as f64 * scale_factor).round() as i32;
+  // large enough to still cover the window when it is resized
+  let region = CreateRectRgn(0, 0, i16::MAX as i32, i16::MAX as i32);
+  for rect in regions {
+    let hole = CreateRectRgn(
+      physical(rect.x as i64),
+      physical(rect.y as i64),
+      physical(rect.x as i64 + rect.width as i64),
+      physical(rect.y as i64 + rect.height as i64),
+    );
+    CombineRgn(region, region, hole, RGN_DIFF);
+    DeleteObject(hole);
+  }
+  // the system owns the region from now on
+  SetWindowRgn(hwnd, region, true);
+}
+
+/// Rescales the child webview window `hwnd` when its parent moves to a monitor with a different DPI,
+/// `dwrefdata` holds the last DPI of the window.
+unsafe extern "system" fn child_dpi_subclass_proc(
+  hwnd: HWND,
+  msg: u32,
+  wparam: WPARAM,
+  lparam: LPARAM,
+  _uidsubclass: usize,
+  dwrefdata: usize,
+) -> LRESULT {
+  match msg {
+    win32wm::WM_DPICHANGED_AFTERPARENT => {
+      let dpi = &*(dwrefdata as *const Cell<u32>);
+      let new_dpi = GetDpiForWindow(hwnd);
+      let old_dpi = dpi.replace(new_dpi);
+
+      let mut rect = RECT::default();
+      if new_dpi != 0
+        && old_dpi != 0
+        && new_dpi != old_dpi
+        && GetClientRect(hwnd, &mut rect).is_ok()
+      {
+        let position = &mut [POINT {
+          x: rect.left,
+          y: rect.top,
+        }];
+        MapWindowPoints(hwnd, GetParent(hwnd), position);
+        let scale = |value: i32| (value as i64 * new_dpi as i64 / old_dpi as i64) as i32;
+        let _ = SetWindowPos(
+          hwnd,
+          HWND::default(),
+          scale(position[0].x),
+          scale(position[0].y),
+          scale(rect.right - rect.left),
+          scale(rect.bottom - rect.top),
+          SWP_ASYNCWINDOWPOS | SWP_NOACTIVATE | SWP_NOZORDER,
+        );
+      }
+    }
+
+    win32wm::WM_DESTROY => {
+      drop(Box::from_raw(dwrefdata as *mut Cell<u32>));
+    }
+
+    _ => (),
+  }
+
+  DefSubclassProc(hwnd, msg, wparam, lparam)
+}
+
+unsafe fn fill_parent(child: HWND) {
+  let mut rect = RECT::default();
+  if GetClientRect(GetParent(child), &mut rect).is_ok() {
+    let _ = SetWindowPos(
+      child,
+      HWND::default(),
+      0,
+      0,
+      rect.right - rect.left,
+      rect.bottom - rect.top,
+      SWP_NOACTIVATE | SWP_NOZORDER,
+    );
+  }
+}
+
+/// The iframes of the main frame, see [`track_frames`].
+type Frames = Rc<RefCell<Vec<TrackedFrame>>>;
+
+struct TrackedFrame {
+  id: u64,
+  url: Option<Url>,
+  frame: ICoreWebView2Frame,
+}
+
+/// Keeps track of the iframes of the main frame and of their URL.
+fn track_frames(webview: &ICoreWebView2) -> webview2_com::Result<Frames> {
+  let frames = Frames::default();
+  let frames_ = frames.clone();
+  let next_id = Cell::new(crate::MAIN_FRAME_ID + 1);
+
+  let mut token = EventRegistrationToken::default();
+  unsafe {
+    webview
+      .cast::<ICoreWebView2_4>()
+      .map_err(webview2_com::Error::WindowsError)?
+      .add_FrameCreated(
+        &FrameCreatedEventHandler::create(Box::new(move |_, args| {
+          let Some(args) = args else {
+            return Ok(());
+          };
+          let frame = args.Frame()?;
+          let id = next_id.get();
+          next_id.set(id + 1);
+
+          let mut token = EventRegistrationToken::default();
+          let frames = frames_.clone();
+          frame.add_Destroyed(
+            &FrameDestroyedEventHandler::create(Box::new(move |_, _| {
+              frames.borrow_mut().retain(|frame| frame.id != id);
+              Ok(())
+            })),
+            &mut token,
+          )?;
+
+          let frames = frames_.clone();
+          frame
+            .cast::<ICoreWebView2Frame2>()?
+            .add_NavigationStarting(
+              &FrameNavigationStartingEventHandler::create(Box::new(move |_, args| {
+                if let Some(args) = args {
+                  let mut uri = PWSTR::null();
+                  args.Uri(&mut uri)?;
+                  let url = Url::parse(&take_pwstr(uri)).ok();
+                  if let Some(frame) = frames.borrow_mut().iter_mut().find(|frame| frame.id == id) {
+                    frame.url = url;
+                  }
+                }
+                Ok(())
+              })),
+              &mut token,
+            )?;
+
+          frames_.borrow_mut().push(TrackedFrame {
+            id,
+            url: None,
+            frame,
+          });
+          Ok(())
+        })),
+        &mut token,
+      )
+      .map_err(webview2_com::Error::WindowsError)?;
+  }
+
+  Ok(frames)
+}
+
+/// The state saved by [`InnerWebView::save_state`].
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SessionState {
+  url: String,
+  scroll: (f64, f64),
+  // the URLs of the history entries, and the index of the current one
+  #[serde(default)]
+  entries: Vec<String>,
+  #[serde(default)]
+  current: usize,
+}
+
+fn load_error_code(status: COREWEBVIEW2_WEB_ERROR_STATUS) -> LoadErrorCode {
+  match status {
+    COREWEBVIEW2_WEB_ERROR_STATUS_HOST_NAME_NOT_RESOLVED => LoadErrorCode::HostNotFound,
+    COREWEBVIEW2_WEB_ERROR_STATUS_SERVER_UNREACHABLE
+    | COREWEBVIEW2_WEB_ERROR_STATUS_CANNOT_CONNECT => LoadErrorCode::CannotConnect,
+    COREWEBVIEW2_WEB_ERROR_STATUS_CONNECTION_ABORTED
+    | COREWEBVIEW2_WEB_ERROR_STATUS_CONNECTION_RESET => LoadErrorCode::ConnectionLost,
+    COREWEBVIEW2_WEB_ERROR_STATUS_DISCONNECTED => LoadErrorCode::Offline,
+    COREWEBVIEW2_WEB_ERROR_STATUS_TIMEOUT => LoadErrorCode::Timeout,
+    COREWEBVIEW2_WEB_ERROR_STATUS_OPERATION_CANCELED => LoadErrorCode::Cancelled,
+    COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_COMMON_NAME_IS_INCORRECT
+    | COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_EXPIRED
+    | COREWEBVIEW2_WEB_ERROR_STATUS_CLIENT_CERTIFICATE_CONTAINS_ERRORS
+    | COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_REVOKED
+    | COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_IS_INVALID => LoadErrorCode::Certificate,
+    COREWEBVIEW2_WEB_ERROR_STATUS_REDIRECT_FAILED => LoadErrorCode::TooManyRedirects,
+    _ => LoadErrorCode::Other,
+  }
+}
+
+// WebView2 has no description of the errors
+fn load_error_description(status: COREWEBVIEW2_WEB_ERROR_STATUS) -> &'static str {
+  match status {
+    COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_COMMON_NAME_IS_INCORRECT => {
+      "The certificate doesn't match the host name"
+    }
+    COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_EXPIRED => "The certificate has expired",
+    COREWEBVIEW2_WEB_ERROR_STATUS_CLIENT_CERTIFICATE_CONTAINS_ERRORS => {
+      "The client certificate contains errors"
+    }
+    COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_REVOKED => "The certificate has been revoked",
+    COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_IS_INVALID => "The certificate is invalid",
+    COREWEBVIEW2_WEB_ERROR_STATUS_SERVER_UNREACHABLE => "The server is unreachable",
+    COREWEBVIEW2_WEB_ERROR_STATUS_TIMEOUT => "The connection timed out",
+    COREWEBVIEW2_WEB_ERROR_STATUS_ERROR_HTTP_INVALID_SERVER_RESPONSE => {
+      "The server returned an invalid response"
+    }
+    COREWEBVIEW2_WEB_ERROR_STATUS_CONNECTION_ABORTED => "The connection was aborted",
+    COREWEBVIEW2_WEB_ERROR_STATUS_CONNECTION_RESET => "The connection was reset",
+    COREWEBVIEW2_WEB_ERROR_STATUS_DISCONNECTED => "The network connection was lost",
+    COREWEBVIEW2_WEB_ERROR_STATUS_CANNOT_CONNECT => "Cannot connect to the server",
+    COREWEBVIEW2_WEB_ERROR_STATUS_HOST_NAME_NOT_RESOLVED => "The host name could not be resolved",
+    COREWEBVIEW2_WEB_ERROR_STATUS_OPERATION_CANCELED => "The navigation was cancelled",
+    COREWEBVIEW2_WEB_ERROR_STATUS_REDIRECT_FAILED => "The redirect failed",
+    COREWEBVIEW2_WEB_ERROR_STATUS_UNEXPECTED_ERROR => "An unexpected error occurred",
+    COREWEBVIEW2_WEB_ERROR_STATUS_VALID_AUTHENTICATION_CREDENTIALS_REQUIRED => {
+      "Authentication credentials are required"
+    }
+    COREWEBVIEW2_WEB_ERROR_STATUS_VALID_PROXY_AUTHENTICATION_REQUIRED => {
+      "Proxy authentication is required"
+    }
+    _ => "The navigation failed",
+  }
+}
+
 fn encode_wide(string: impl AsRef<std::ffi::OsStr>) -> Vec<u16> {
   string.as_ref().encode_wide().chain(once(0)).collect()
 }
@@ -1150,6 +3335,17 @@ fn load_url_with_headers(
   env: &ICoreWebView2Environment,
   url: &str,
   headers: http::HeaderMap,
+) {
+  load_request(webview, env, url, "GET", &headers, None);
+}
+
+fn load_request(
+  webview: &ICoreWebView2,
+  env: &ICoreWebView2Environment,
+  url: &str,
+  method: &str,
+  headers: &http::HeaderMap,
+  body: Option<&[u8]>,
 ) {
   let url = encode_wide(url);
 
@@ -1167,10 +3363,11 @@ fn load_url_with_headers(
   unsafe {
     let env = env.cast::<ICoreWebView2Environment9>().unwrap();
 
+    let body = body.and_then(|body| SHCreateMemStream(Some(body)));
     if let Ok(request) = env.CreateWebResourceRequest(
       PCWSTR::from_raw(url.as_ptr()),
-      PCWSTR::from_raw(encode_wide("GET").as_ptr()),
-      None,
+      PCWSTR::from_raw(encode_wide(method).as_ptr()),
+      body.as_ref(),
       PCWSTR::from_raw(headers_map.as_ptr()),
     ) {
       let webview: ICoreWebView2_10 = webview.cast().unwrap();
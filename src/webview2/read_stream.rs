@@ -0,0 +1,127 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// A forward-only `IStream` reading a Rust `Read`, WebView2 reads the custom streams
+// of the responses on a background thread so blocking reads are fine.
+
+use std::{ffi::c_void, io::Read, sync::Mutex};
+
+use windows::{
+  core::{Result, HRESULT},
+  Win32::{
+    Foundation::{E_FAIL, E_NOTIMPL, STG_E_ACCESSDENIED, S_FALSE, S_OK},
+    System::Com::{
+      ISequentialStream_Impl, IStream, IStream_Impl, LOCKTYPE, STATFLAG, STATSTG, STGC,
+      STREAM_SEEK, STREAM_SEEK_CUR,
+    },
+  },
+};
+use windows_implement::implement;
+
+#[implement(IStream)]
+pub(crate) struct ReadStream {
+  reader: Mutex<Box<dyn Read + Send>>,
+  position: Mutex<u64>,
+}
+
+impl ReadStream {
+  pub(crate) fn new(reader: Box<dyn Read + Send>) -> Self {
+    Self {
+      reader: Mutex::new(reader),
+      position: Mutex::new(0),
+    }
+  }
+}
+
+impl ISequentialStream_Impl for ReadStream {
+  fn Read(&self, pv: *mut c_void, cb: u32, pcbread: *mut u32) -> HRESULT {
+    let Ok(mut reader) = self.reader.lock() else {
+      return E_FAIL;
+    };
+    let buffer = unsafe { std::slice::from_raw_parts_mut(pv as *mut u8, cb as usize) };
+
+    // returns as soon as some bytes are read so the streamed events are not delayed
+    let read = loop {
+      match reader.read(buffer) {
+        Ok(count) => break count,
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+        Err(e) => {
+          log::warn!("Failed to read the body of a custom protocol response: {e}");
+          // fails the request instead of ending the body early
+          return E_FAIL;
+        }
+      }
+    };
+
+    if let Ok(mut position) = self.position.lock() {
+      *position += read as u64;
+    }
+    if !pcbread.is_null() {
+      unsafe { *pcbread = read as u32 };
+    }
+    // a read of zero bytes ends the stream
+    if read == 0 && cb > 0 {
+      S_FALSE
+    } else {
+      S_OK
+    }
+  }
+
+  fn Write(&self, _pv: *const c_void, _cb: u32, _pcbwritten: *mut u32) -> HRESULT {
+    STG_E_ACCESSDENIED
+  }
+}
+
+impl IStream_Impl for ReadStream {
+  fn Seek(&self, dlibmove: i64, dworigin: STREAM_SEEK, plibnewposition: *mut u64) -> Result<()> {
+    // only reports the current position, the stream is forward-only
+    if dworigin != STREAM_SEEK_CUR || dlibmove != 0 {
+      return Err(E_NOTIMPL.into());
+    }
+    if !plibnewposition.is_null() {
+      let position = self.position.lock().map(|p| *p).unwrap_or_default();
+      unsafe { *plibnewposition = position };
+    }
+    Ok(())
+  }
+
+  fn SetSize(&self, _libnewsize: u64) -> Result<()> {
+    Err(E_NOTIMPL.into())
+  }
+
+  fn CopyTo(
+    &self,
+    _pstm: Option<&IStream>,
+    _cb: u64,
+    _pcbread: *mut u64,
+    _pcbwritten: *mut u64,
+  ) -> Result<()> {
+    Err(E_NOTIMPL.into())
+  }
+
+  fn Commit(&self, _grfcommitflags: &STGC) -> Result<()> {
+    Ok(())
+  }
+
+  fn Revert(&self) -> Result<()> {
+    Err(E_NOTIMPL.into())
+  }
+
+  fn LockRegion(&self, _liboffset: u64, _cb: u64, _dwlocktype: &LOCKTYPE) -> Result<()> {
+    Err(E_NOTIMPL.into())
+  }
+
+  fn UnlockRegion(&self, _liboffset: u64, _cb: u64, _dwlocktype: u32) -> Result<()> {
+    Err(E_NOTIMPL.into())
+  }
+
+  fn Stat(&self, _pstatstg: *mut STATSTG, _grfstatflag: &STATFLAG) -> Result<()> {
+    // the size of the stream is unknown
+    Err(E_NOTIMPL.into())
+  }
+
+  fn Clone(&self) -> Result<IStream> {
+    Err(E_NOTIMPL.into())
+  }
+}
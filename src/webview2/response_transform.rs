@@ -0,0 +1,206 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// Applies `WebViewAttributes::response_transform` to the HTML documents loaded from the network,
+// WebView2 has no API to replace a response so they are paused with the DevTools `Fetch` domain.
+
+use std::rc::Rc;
+
+use base64::{engine::general_purpose, Engine};
+use http::{
+  header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE},
+  HeaderName, HeaderValue, Response,
+};
+use serde_json::{json, Value};
+use webview2_com::{Microsoft::Web::WebView2::Win32::*, *};
+use windows::{
+  core::{PCWSTR, PWSTR},
+  Win32::System::WinRT::EventRegistrationToken,
+};
+
+use super::encode_wide;
+
+type ResponseTransform = Rc<dyn Fn(Response<Vec<u8>>) -> Response<Vec<u8>>>;
+
+/// Pauses the document responses of `webview` to transform the HTML ones, except the responses
+/// of the URLs starting with one of `skipped_prefixes`, i.e. the custom protocols which are
+/// transformed by their handlers.
+pub(crate) fn transform_responses(
+  webview: &ICoreWebView2,
+  transform: ResponseTransform,
+  skipped_prefixes: Vec<String>,
+) -> webview2_com::Result<()> {
+  let mut token = EventRegistrationToken::default();
+  let webview_ = webview.clone();
+  unsafe {
+    let receiver = webview
+      .GetDevToolsProtocolEventReceiver(PCWSTR::from_raw(
+        encode_wide("Fetch.requestPaused").as_ptr(),
+      ))
+      .map_err(webview2_com::Error::WindowsError)?;
+    receiver
+      .add_DevToolsProtocolEventReceived(
+        &DevToolsProtocolEventReceivedEventHandler::create(Box::new(move |_, args| {
+          if let Some(args) = args {
+            let mut json = PWSTR::null();
+            args.ParameterObjectAsJson(&mut json)?;
+            if let Ok(paused) = serde_json::from_str::<Value>(&take_pwstr(json)) {
+              request_paused(&webview_, &transform, &skipped_prefixes, paused);
+            }
+          }
+          Ok(())
+        })),
+        &mut token,
+      )
+      .map_err(webview2_com::Error::WindowsError)?;
+  }
+
+  call_method(
+    webview,
+    "Fetch.enable",
+    json!({
+      "patterns": [{ "urlPattern": "*", "resourceType": "Document", "requestStage": "Response" }],
+    }),
+    |_| {},
+  )
+}
+
+fn request_paused(
+  webview: &ICoreWebView2,
+  transform: &ResponseTransform,
+  skipped_prefixes: &[String],
+  paused: Value,
+) {
+  let Some(request_id) = paused["requestId"].as_str().map(ToString::to_string) else {
+    return;
+  };
+
+  let url = paused["request"]["url"].as_str().unwrap_or_default();
+  let status = paused["responseStatusCode"].as_u64();
+  let headers = paused["responseHeaders"]
+    .as_array()
+    .map(|headers| {
+      headers
+        .iter()
+        .filter_map(|header| Some((header["name"].as_str()?, header["value"].as_str()?)))
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+  let is_html = headers.iter().any(|(name, value)| {
+    name.eq_ignore_ascii_case(CONTENT_TYPE.as_str())
+      && value.to_lowercase().starts_with("text/html")
+  });
+
+  let builder = status
+    // the redirects and failed requests have no body to transform
+    .filter(|status| !(300..400).contains(status))
+    .filter(|_| {
+      is_html
+        && !skipped_prefixes
+          .iter()
+          .any(|prefix| url.starts_with(prefix))
+    })
+    .map(|status| {
+      let mut builder = Response::builder().status(status as u16);
+      for (name, value) in &headers {
+        // the body is read decoded
+        if name.eq_ignore_ascii_case(CONTENT_ENCODING.as_str())
+          || name.eq_ignore_ascii_case(CONTENT_LENGTH.as_str())
+        {
+          continue;
+        }
+        if let (Ok(name), Ok(value)) = (
+          HeaderName::from_bytes(name.as_bytes()),
+          HeaderValue::from_str(value),
+        ) {
+          builder = builder.header(name, value);
+        }
+      }
+      builder
+    });
+  let Some(builder) = builder else {
+    return continue_request(webview, request_id);
+  };
+
+  let webview_ = webview.clone();
+  let transform = transform.clone();
+  let id = request_id.clone();
+  let result = call_method(
+    webview,
+    "Fetch.getResponseBody",
+    json!({ "requestId": &request_id }),
+    move |body| {
+      let body = body.and_then(|body| {
+        let content = body["body"].as_str()?;
+        if body["base64Encoded"].as_bool().unwrap_or_default() {
+          general_purpose::STANDARD.decode(content).ok()
+        } else {
+          Some(content.as_bytes().to_vec())
+        }
+      });
+      let Some(response) = body.and_then(|body| builder.body(body).ok()) else {
+        return continue_request(&webview_, id);
+      };
+
+      let response = transform(response);
+      let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+          Some(json!({ "name": name.as_str(), "value": value.to_str().ok()? }))
+        })
+        .collect::<Vec<_>>();
+      let _ = call_method(
+        &webview_,
+        "Fetch.fulfillRequest",
+        json!({
+          "requestId": id,
+          "responseCode": response.status().as_u16(),
+          "responseHeaders": headers,
+          "body": general_purpose::STANDARD.encode(response.body()),
+        }),
+        |_| {},
+      );
+    },
+  );
+  if result.is_err() {
+    log::warn!("Failed to read the body of {url}, it is not transformed");
+    continue_request(webview, request_id);
+  }
+}
+
+fn continue_request(webview: &ICoreWebView2, request_id: String) {
+  let _ = call_method(
+    webview,
+    "Fetch.continueRequest",
+    json!({ "requestId": request_id }),
+    |_| {},
+  );
+}
+
+/// Calls the DevTools protocol `method`, `callback` receives its result or `None` on error.
+pub(crate) fn call_method<F>(
+  webview: &ICoreWebView2,
+  method: &str,
+  params: Value,
+  callback: F,
+) -> webview2_com::Result<()>
+where
+  F: FnOnce(Option<Value>) + 'static,
+{
+  let handler =
+    CallDevToolsProtocolMethodCompletedHandler::create(Box::new(move |error, result| {
+      callback(error.ok().and_then(|_| serde_json::from_str(&result).ok()));
+      Ok(())
+    }));
+
+  unsafe {
+    webview.CallDevToolsProtocolMethod(
+      PCWSTR::from_raw(encode_wide(method).as_ptr()),
+      PCWSTR::from_raw(encode_wide(params.to_string()).as_ptr()),
+      &handler,
+    )
+  }
+  .map_err(webview2_com::Error::WindowsError)
+}
@@ -0,0 +1,179 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// Applies the user stylesheets of `WebView::inject_css`, WebView2 has no user stylesheets so they
+// are created in every frame with the DevTools `CSS` domain when it is navigated.
+
+use std::{
+  cell::RefCell,
+  collections::HashMap,
+  rc::{Rc, Weak},
+};
+
+use serde_json::{json, Value};
+use webview2_com::{Microsoft::Web::WebView2::Win32::*, *};
+use windows::{
+  core::{PCWSTR, PWSTR},
+  Win32::System::WinRT::EventRegistrationToken,
+};
+
+use super::{encode_wide, response_transform::call_method};
+use crate::{Result, StyleId};
+
+#[derive(Default)]
+struct State {
+  styles: Vec<(StyleId, String)>,
+  // the stylesheets created in the current documents, by style
+  sheets: HashMap<StyleId, Vec<String>>,
+  listening: bool,
+}
+
+pub(crate) struct UserStyles {
+  webview: ICoreWebView2,
+  state: Rc<RefCell<State>>,
+}
+
+impl UserStyles {
+  pub(crate) fn new(webview: ICoreWebView2) -> Self {
+    Self {
+      webview,
+      state: Default::default(),
+    }
+  }
+
+  /// Adds the stylesheet `css` to the frames of the current page and of the pages loaded
+  /// afterwards.
+  pub(crate) fn add(&self, id: StyleId, css: &str) -> Result<()> {
+    self.listen()?;
+    self.state.borrow_mut().styles.push((id, css.to_string()));
+
+    let webview = self.webview.clone();
+    let state = Rc::downgrade(&self.state);
+    let css = css.to_string();
+    call_method(&self.webview, "Page.getFrameTree", json!({}), move |tree| {
+      let mut frames = Vec::new();
+      if let Some(tree) = tree {
+        frame_ids(&tree["frameTree"], &mut frames);
+      }
+      for frame_id in frames {
+        create_style_sheet(&webview, state.clone(), frame_id, id, css.clone());
+      }
+    })?;
+    Ok(())
+  }
+
+  /// Removes the stylesheet `id` from the current page and from the pages loaded afterwards.
+  pub(crate) fn remove(&self, id: StyleId) -> Result<()> {
+    let mut state = self.state.borrow_mut();
+    state.styles.retain(|(style, _)| *style != id);
+    for sheet in state.sheets.remove(&id).unwrap_or_default() {
+      call_method(
+        &self.webview,
+        "CSS.setStyleSheetText",
+        json!({ "styleSheetId": sheet, "text": "" }),
+        |_| {},
+      )?;
+    }
+    Ok(())
+  }
+
+  /// Creates the stylesheets in the frames as soon as they are navigated.
+  fn listen(&self) -> Result<()> {
+    if self.state.borrow().listening {
+      return Ok(());
+    }
+
+    // the `CSS` domain requires the `DOM` one
+    for method in ["DOM.enable", "CSS.enable", "Page.enable"] {
+      call_method(&self.webview, method, json!({}), |_| {})?;
+    }
+
+    let mut token = EventRegistrationToken::default();
+    let webview = self.webview.clone();
+    let state = Rc::downgrade(&self.state);
+    unsafe {
+      let receiver = self
+        .webview
+        .GetDevToolsProtocolEventReceiver(PCWSTR::from_raw(
+          encode_wide("Page.frameNavigated").as_ptr(),
+        ))
+        .map_err(webview2_com::Error::WindowsError)?;
+      receiver
+        .add_DevToolsProtocolEventReceived(
+          &DevToolsProtocolEventReceivedEventHandler::create(Box::new(move |_, args| {
+            if let Some(args) = args {
+              let mut json = PWSTR::null();
+              args.ParameterObjectAsJson(&mut json)?;
+              if let Ok(navigated) = serde_json::from_str::<Value>(&take_pwstr(json)) {
+                frame_navigated(&webview, &state, &navigated["frame"]);
+              }
+            }
+            Ok(())
+          })),
+          &mut token,
+        )
+        .map_err(webview2_com::Error::WindowsError)?;
+    }
+
+    self.state.borrow_mut().listening = true;
+    Ok(())
+  }
+}
+
+fn frame_navigated(webview: &ICoreWebView2, state: &Weak<RefCell<State>>, frame: &Value) {
+  let (Some(state_), Some(frame_id)) = (state.upgrade(), frame["id"].as_str()) else {
+    return;
+  };
+  // the documents of the previous page are gone with their stylesheets
+  if frame["parentId"].is_null() {
+    state_.borrow_mut().sheets.clear();
+  }
+  let styles = state_.borrow().styles.clone();
+  for (id, css) in styles {
+    create_style_sheet(webview, state.clone(), frame_id.to_string(), id, css);
+  }
+}
+
+fn create_style_sheet(
+  webview: &ICoreWebView2,
+  state: Weak<RefCell<State>>,
+  frame_id: String,
+  id: StyleId,
+  css: String,
+) {
+  let webview_ = webview.clone();
+  let _ = call_method(
+    webview,
+    "CSS.createStyleSheet",
+    json!({ "frameId": frame_id }),
+    move |result| {
+      let sheet =
+        result.and_then(|result| result["styleSheetId"].as_str().map(ToString::to_string));
+      let (Some(state), Some(sheet)) = (state.upgrade(), sheet) else {
+        return;
+      };
+      // removed in the meantime
+      if !state.borrow().styles.iter().any(|(style, _)| *style == id) {
+        return;
+      }
+      let _ = call_method(
+        &webview_,
+        "CSS.setStyleSheetText",
+        json!({ "styleSheetId": &sheet, "text": css }),
+        |_| {},
+      );
+      state.borrow_mut().sheets.entry(id).or_default().push(sheet);
+    },
+  );
+}
+
+/// Collects the ids of the frames of a `Page.FrameTree`.
+fn frame_ids(tree: &Value, ids: &mut Vec<String>) {
+  if let Some(id) = tree["frame"]["id"].as_str() {
+    ids.push(id.to_string());
+  }
+  for child in tree["childFrames"].as_array().into_iter().flatten() {
+    frame_ids(child, ids);
+  }
+}
@@ -0,0 +1,237 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{cell::Cell, ffi::c_void, ptr::null_mut, rc::Rc};
+
+use cocoa::{
+  base::{id, nil, NO},
+  foundation::NSUInteger,
+};
+use objc::{
+  declare::ClassDecl,
+  runtime::{class_getInstanceMethod, method_getImplementation, Object, Sel, BOOL},
+};
+use once_cell::sync::Lazy;
+
+use crate::{AcceleratorKeyEvent, KeyCode};
+
+const NS_EVENT_MODIFIER_FLAG_SHIFT: NSUInteger = 1 << 17;
+const NS_EVENT_MODIFIER_FLAG_CONTROL: NSUInteger = 1 << 18;
+const NS_EVENT_MODIFIER_FLAG_OPTION: NSUInteger = 1 << 19;
+const NS_EVENT_MODIFIER_FLAG_COMMAND: NSUInteger = 1 << 20;
+
+// the virtual key codes of the zoom in and zoom out keys, missing from `KeyCode`
+const KVK_ANSI_EQUAL: u16 = 0x18;
+const KVK_ANSI_MINUS: u16 = 0x1B;
+const KVK_ANSI_KEYPAD_PLUS: u16 = 0x45;
+const KVK_ANSI_KEYPAD_MINUS: u16 = 0x4E;
+
+/// The zoom hotkeys of the webview, `Cmd` with `=`, `-` and the reset key.
+pub(crate) struct ZoomHotkeys {
+  pub(crate) enabled: Cell<bool>,
+  pub(crate) step: f64,
+  pub(crate) reset_key: Option<KeyCode>,
+  pub(crate) initial_zoom: f64,
+  pub(crate) zoom_limits: Rc<Cell<Option<(f64, f64)>>>,
+}
+
+static OBJC_KEY_DOWN: Lazy<extern "C" fn(*const Object, Sel, id)> = Lazy::new(|| unsafe {
+  std::mem::transmute(method_getImplementation(class_getInstanceMethod(
+    class!(WKWebView),
+    sel!(keyDown:),
+  )))
+});
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn set_accelerator_key_handler(
+  webview: *mut Object,
+  handler: Option<Box<dyn Fn(AcceleratorKeyEvent) -> bool>>,
+) -> *mut Box<dyn Fn(AcceleratorKeyEvent) -> bool> {
+  let handler = match handler {
+    Some(handler) => Box::into_raw(Box::new(handler)),
+    None => null_mut(),
+  };
+  (*webview).set_ivar("AcceleratorKeyHandler", handler as *mut c_void);
+  handler
+}
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn set_zoom_hotkeys(
+  webview: *mut Object,
+  hotkeys: ZoomHotkeys,
+) -> *mut ZoomHotkeys {
+  let hotkeys = Box::into_raw(Box::new(hotkeys));
+  (*webview).set_ivar("ZoomHotkeys", hotkeys as *mut c_void);
+  hotkeys
+}
+
+// whether the keyboard focus is in the webview
+unsafe fn is_focused(this: &Object) -> bool {
+  let window: id = msg_send![this, window];
+  if window == nil {
+    return false;
+  }
+  let responder: id = msg_send![window, firstResponder];
+  let is_view: BOOL = msg_send![responder, isKindOfClass: class!(NSView)];
+  if is_view == NO {
+    return false;
+  }
+  let is_focused: BOOL = msg_send![responder, isDescendantOf: this];
+  is_focused != NO
+}
+
+/// Zooms the page when the key `event` is one of its enabled zoom hotkeys, returns whether the key is consumed.
+pub(crate) unsafe fn handle_zoom_key(this: &Object, event: id) -> bool {
+  let hotkeys: *mut c_void = *this.get_ivar("ZoomHotkeys");
+  if hotkeys.is_null() {
+    return false;
+  }
+  let hotkeys = &*(hotkeys as *mut ZoomHotkeys);
+
+  let flags: NSUInteger = msg_send![event, modifierFlags];
+  if !hotkeys.enabled.get() || flags & NS_EVENT_MODIFIER_FLAG_COMMAND == 0 || !is_focused(this) {
+    return false;
+  }
+
+  let key_code: u16 = msg_send![event, keyCode];
+  let zoom: f64 = msg_send![this, pageZoom];
+  let zoom = match key_code {
+    KVK_ANSI_EQUAL | KVK_ANSI_KEYPAD_PLUS => zoom + hotkeys.step,
+    KVK_ANSI_MINUS | KVK_ANSI_KEYPAD_MINUS => zoom - hotkeys.step,
+    _ if hotkeys.reset_key.map(virtual_key_code) == Some(key_code) => hotkeys.initial_zoom,
+    _ => return false,
+  };
+  let zoom = match hotkeys.zoom_limits.get() {
+    Some((min, max)) => zoom.clamp(min, max),
+    // the zoom factors supported by the zoom hotkeys of WebView2
+    None => zoom.clamp(0.25, 5.0),
+  };
+  let () = msg_send![this, setPageZoom: zoom];
+  true
+}
+
+/// Calls the accelerator key handler of the webview with the key `event`, returns whether the key is consumed.
+///
+/// `performKeyEquivalent:` is sent to every view of the window, so the key is only reported
+/// when the webview has the keyboard focus.
+pub(crate) unsafe fn handle_accelerator_key(this: &Object, event: id) -> bool {
+  let handler: *mut c_void = *this.get_ivar("AcceleratorKeyHandler");
+  if handler.is_null() {
+    return false;
+  }
+  let handler = &*(handler as *mut Box<dyn Fn(AcceleratorKeyEvent) -> bool>);
+
+  if !is_focused(this) {
+    return false;
+  }
+
+  let key_code: u16 = msg_send![event, keyCode];
+  let Some(key) = KeyCode::ALL
+    .into_iter()
+    .find(|key| virtual_key_code(*key) == key_code)
+  else {
+    return false;
+  };
+
+  let flags: NSUInteger = msg_send![event, modifierFlags];
+  handler(AcceleratorKeyEvent {
+    key,
+    ctrl: flags & NS_EVENT_MODIFIER_FLAG_CONTROL != 0,
+    shift: flags & NS_EVENT_MODIFIER_FLAG_SHIFT != 0,
+    alt: flags & NS_EVENT_MODIFIER_FLAG_OPTION != 0,
+    meta: flags & NS_EVENT_MODIFIER_FLAG_COMMAND != 0,
+  })
+}
+
+extern "C" fn key_down(this: &mut Object, sel: Sel, event: id) {
+  unsafe {
+    if !handle_accelerator_key(this, event) && !handle_zoom_key(this, event) {
+      OBJC_KEY_DOWN(this, sel, event);
+    }
+  }
+}
+
+pub(crate) unsafe fn add_accelerator_key_methods(decl: &mut ClassDecl) {
+  decl.add_ivar::<*mut c_void>("AcceleratorKeyHandler");
+  decl.add_ivar::<*mut c_void>("ZoomHotkeys");
+
+  decl.add_method(
+    sel!(keyDown:),
+    key_down as extern "C" fn(&mut Object, Sel, id),
+  );
+}
+
+/// Maps a [`KeyCode`] to its macOS virtual key code, see `Events.h` of the Carbon framework.
+fn virtual_key_code(key: KeyCode) -> u16 {
+  match key {
+    KeyCode::Digit0 => 0x1D,
+    KeyCode::Digit1 => 0x12,
+    KeyCode::Digit2 => 0x13,
+    KeyCode::Digit3 => 0x14,
+    KeyCode::Digit4 => 0x15,
+    KeyCode::Digit5 => 0x17,
+    KeyCode::Digit6 => 0x16,
+    KeyCode::Digit7 => 0x1A,
+    KeyCode::Digit8 => 0x1C,
+    KeyCode::Digit9 => 0x19,
+    KeyCode::Numpad0 => 0x52,
+    KeyCode::Numpad1 => 0x53,
+    KeyCode::Numpad2 => 0x54,
+    KeyCode::Numpad3 => 0x55,
+    KeyCode::Numpad4 => 0x56,
+    KeyCode::Numpad5 => 0x57,
+    KeyCode::Numpad6 => 0x58,
+    KeyCode::Numpad7 => 0x59,
+    KeyCode::Numpad8 => 0x5B,
+    KeyCode::Numpad9 => 0x5C,
+    KeyCode::KeyA => 0x00,
+    KeyCode::KeyB => 0x0B,
+    KeyCode::KeyC => 0x08,
+    KeyCode::KeyD => 0x02,
+    KeyCode::KeyE => 0x0E,
+    KeyCode::KeyF => 0x03,
+    KeyCode::KeyG => 0x05,
+    KeyCode::KeyH => 0x04,
+    KeyCode::KeyI => 0x22,
+    KeyCode::KeyJ => 0x26,
+    KeyCode::KeyK => 0x28,
+    KeyCode::KeyL => 0x25,
+    KeyCode::KeyM => 0x2E,
+    KeyCode::KeyN => 0x2D,
+    KeyCode::KeyO => 0x1F,
+    KeyCode::KeyP => 0x23,
+    KeyCode::KeyQ => 0x0C,
+    KeyCode::KeyR => 0x0F,
+    KeyCode::KeyS => 0x01,
+    KeyCode::KeyT => 0x11,
+    KeyCode::KeyU => 0x20,
+    KeyCode::KeyV => 0x09,
+    KeyCode::KeyW => 0x0D,
+    KeyCode::KeyX => 0x07,
+    KeyCode::KeyY => 0x10,
+    KeyCode::KeyZ => 0x06,
+    KeyCode::Backspace => 0x33,
+    KeyCode::Delete => 0x75,
+    KeyCode::Escape => 0x35,
+    KeyCode::Tab => 0x30,
+    KeyCode::Enter => 0x24,
+    KeyCode::Space => 0x31,
+    KeyCode::ArrowLeft => 0x7B,
+    KeyCode::ArrowUp => 0x7E,
+    KeyCode::ArrowRight => 0x7C,
+    KeyCode::ArrowDown => 0x7D,
+    KeyCode::F1 => 0x7A,
+    KeyCode::F2 => 0x78,
+    KeyCode::F3 => 0x63,
+    KeyCode::F4 => 0x76,
+    KeyCode::F5 => 0x60,
+    KeyCode::F6 => 0x61,
+    KeyCode::F7 => 0x62,
+    KeyCode::F8 => 0x64,
+    KeyCode::F9 => 0x65,
+    KeyCode::F10 => 0x6D,
+    KeyCode::F11 => 0x67,
+    KeyCode::F12 => 0x6F,
+  }
+}
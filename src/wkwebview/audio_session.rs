@@ -0,0 +1,84 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use block::ConcreteBlock;
+use cocoa::{
+  base::{id, nil},
+  foundation::NSUInteger,
+};
+
+use super::NSString;
+use crate::{AudioFocusEvent, LossDuration};
+
+#[link(name = "AVFoundation", kind = "framework")]
+extern "C" {
+  static AVAudioSessionInterruptionNotification: id;
+  static AVAudioSessionInterruptionTypeKey: id;
+  static AVAudioSessionInterruptionOptionKey: id;
+}
+
+// AVAudioSessionInterruptionType
+const INTERRUPTION_TYPE_BEGAN: NSUInteger = 1;
+// AVAudioSessionInterruptionOptions
+const INTERRUPTION_OPTION_SHOULD_RESUME: NSUInteger = 1;
+// AVAudioSessionInterruptionReason
+const INTERRUPTION_REASON_DEFAULT: NSUInteger = 0;
+
+unsafe fn user_info_value(user_info: id, key: id) -> Option<NSUInteger> {
+  let value: id = msg_send![user_info, objectForKey: key];
+  if value == nil {
+    None
+  } else {
+    Some(msg_send![value, unsignedIntegerValue])
+  }
+}
+
+/// Calls `handler` on the audio session interruptions, returns the observer to remove with
+/// [`remove_audio_session_observer`].
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn add_audio_session_observer(handler: Box<dyn Fn(AudioFocusEvent)>) -> id {
+  let block = ConcreteBlock::new(move |notification: id| {
+    let user_info: id = msg_send![notification, userInfo];
+    if user_info == nil {
+      return;
+    }
+
+    let event = if user_info_value(user_info, AVAudioSessionInterruptionTypeKey)
+      == Some(INTERRUPTION_TYPE_BEGAN)
+    {
+      // `AVAudioSessionInterruptionReasonKey` is only available since iOS 14.5,
+      // look it up by its value so older systems fall back to the default reason
+      let reason = NSString::new("AVAudioSessionInterruptionReasonKey");
+      match user_info_value(user_info, reason.0) {
+        None | Some(INTERRUPTION_REASON_DEFAULT) => AudioFocusEvent::LostTransient,
+        // the app was suspended, the built-in microphone was muted or the route was disconnected,
+        // the system may never notify the end of the interruption
+        Some(_) => AudioFocusEvent::Lost(LossDuration::Unknown),
+      }
+    } else {
+      let options = user_info_value(user_info, AVAudioSessionInterruptionOptionKey).unwrap_or(0);
+      if options & INTERRUPTION_OPTION_SHOULD_RESUME != 0 {
+        AudioFocusEvent::Gained
+      } else {
+        AudioFocusEvent::Lost(LossDuration::Permanent)
+      }
+    };
+
+    handler(event);
+  })
+  .copy();
+
+  let center: id = msg_send![class!(NSNotificationCenter), defaultCenter];
+  let session: id = msg_send![class!(AVAudioSession), sharedInstance];
+  let observer: id = msg_send![center, addObserverForName: AVAudioSessionInterruptionNotification object: session queue: nil usingBlock: &*block];
+  let _: id = msg_send![observer, retain];
+  observer
+}
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn remove_audio_session_observer(observer: id) {
+  let center: id = msg_send![class!(NSNotificationCenter), defaultCenter];
+  let () = msg_send![center, removeObserver: observer];
+  let () = msg_send![observer, release];
+}
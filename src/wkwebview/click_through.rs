@@ -0,0 +1,83 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{cell::RefCell, ffi::c_void, rc::Rc};
+
+use cocoa::{
+  appkit::NSView,
+  base::{id, nil},
+  foundation::NSPoint,
+};
+use objc::{
+  declare::ClassDecl,
+  runtime::{class_getInstanceMethod, method_getImplementation, Object, Sel},
+};
+use once_cell::sync::Lazy;
+
+use crate::Rect;
+
+/// The click-through regions of a webview, in logical pixels.
+pub(crate) type ClickThroughRegions = Rc<RefCell<Vec<Rect>>>;
+
+static OBJC_HIT_TEST: Lazy<extern "C" fn(*const Object, Sel, NSPoint) -> id> =
+  Lazy::new(|| unsafe {
+    std::mem::transmute(method_getImplementation(class_getInstanceMethod(
+      class!(WKWebView),
+      sel!(hitTest:),
+    )))
+  });
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn set_click_through_regions_ivar(
+  webview: *mut Object,
+  regions: &ClickThroughRegions,
+) {
+  (*webview).set_ivar(
+    "ClickThroughRegions",
+    Rc::into_raw(regions.clone()) as *mut c_void,
+  );
+}
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn drop_click_through_regions_ivar(webview: *mut Object) {
+  let regions: *mut c_void = *(*webview).get_ivar("ClickThroughRegions");
+  if !regions.is_null() {
+    drop(Rc::from_raw(regions as *const RefCell<Vec<Rect>>));
+    (*webview).set_ivar("ClickThroughRegions", std::ptr::null_mut::<c_void>());
+  }
+}
+
+extern "C" fn hit_test(this: &Object, sel: Sel, point: NSPoint) -> id {
+  unsafe {
+    let regions: *mut c_void = *this.get_ivar("ClickThroughRegions");
+    if !regions.is_null() {
+      let regions = &*(regions as *const RefCell<Vec<Rect>>);
+      // the point is in the coordinates of the superview, WKWebView is flipped so the converted
+      // point is relative to its top-left corner
+      let superview: id = msg_send![this, superview];
+      let view_point = (this as *const Object as id).convertPoint_fromView_(point, superview);
+      let (x, y) = (view_point.x, view_point.y);
+      let is_click_through = regions.borrow().iter().any(|rect| {
+        x >= rect.x as f64
+          && y >= rect.y as f64
+          && x < rect.x as f64 + rect.width as f64
+          && y < rect.y as f64 + rect.height as f64
+      });
+      if is_click_through {
+        return nil;
+      }
+    }
+
+    OBJC_HIT_TEST(this, sel, point)
+  }
+}
+
+pub(crate) unsafe fn add_click_through_methods(decl: &mut ClassDecl) {
+  decl.add_ivar::<*mut c_void>("ClickThroughRegions");
+
+  decl.add_method(
+    sel!(hitTest:),
+    hit_test as extern "C" fn(&Object, Sel, NSPoint) -> id,
+  );
+}
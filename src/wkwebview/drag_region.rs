@@ -0,0 +1,68 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{ffi::c_void, ptr::null_mut};
+
+use cocoa::{
+  appkit::{NSEvent, NSEventType, NSView},
+  base::{id, nil},
+};
+use objc::{
+  declare::ClassDecl,
+  runtime::{class_getInstanceMethod, method_getImplementation, Object, Sel},
+};
+use once_cell::sync::Lazy;
+
+use crate::DragRegionQuery;
+
+static OBJC_MOUSE_DOWN: Lazy<extern "C" fn(*const Object, Sel, id)> = Lazy::new(|| unsafe {
+  std::mem::transmute(method_getImplementation(class_getInstanceMethod(
+    class!(WKWebView),
+    sel!(mouseDown:),
+  )))
+});
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn set_drag_region_handler(
+  webview: *mut Object,
+  handler: Option<Box<dyn Fn(DragRegionQuery) -> bool>>,
+) -> *mut Box<dyn Fn(DragRegionQuery) -> bool> {
+  let handler = match handler {
+    Some(handler) => Box::into_raw(Box::new(handler)),
+    None => null_mut(),
+  };
+  (*webview).set_ivar("DragRegionHandler", handler as *mut c_void);
+  handler
+}
+
+extern "C" fn mouse_down(this: &mut Object, sel: Sel, event: id) {
+  unsafe {
+    let handler: *mut c_void = *this.get_ivar("DragRegionHandler");
+    // `otherMouseDown:` is forwarded here too, only the primary button drags the window
+    if !handler.is_null() && event.eventType() == NSEventType::NSLeftMouseDown {
+      let handler = &*(handler as *mut Box<dyn Fn(DragRegionQuery) -> bool>);
+      // WKWebView is flipped, so the point is relative to its top-left corner
+      let view_point = (this as id).convertPoint_fromView_(event.locationInWindow(), nil);
+      if handler(DragRegionQuery {
+        x: view_point.x,
+        y: view_point.y,
+      }) {
+        let window: id = msg_send![this, window];
+        let () = msg_send![window, performWindowDragWithEvent: event];
+        return;
+      }
+    }
+
+    OBJC_MOUSE_DOWN(this, sel, event);
+  }
+}
+
+pub(crate) unsafe fn add_drag_region_methods(decl: &mut ClassDecl) {
+  decl.add_ivar::<*mut c_void>("DragRegionHandler");
+
+  decl.add_method(
+    sel!(mouseDown:),
+    mouse_down as extern "C" fn(&mut Object, Sel, id),
+  );
+}
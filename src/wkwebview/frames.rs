@@ -0,0 +1,122 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{cell::RefCell, ffi::c_void};
+
+use cocoa::base::{id, nil, NO, YES};
+use objc::{
+  declare::ClassDecl,
+  runtime::{Object, Sel, BOOL},
+};
+use url::Url;
+
+use super::NSString;
+use crate::FrameInfo;
+
+const FRAMES_MESSAGE_HANDLER_NAME: &str = "wryFrames";
+
+/// Initialization script reporting its frame to the frames message handler when the
+/// document of the frame is shown and hidden.
+///
+/// WebKit doesn't identify the frames, so every document draws its own id.
+pub(crate) const FRAMES_SCRIPT: &str = r#"(function () {
+  var id = Math.floor(Math.random() * Number.MAX_SAFE_INTEGER);
+  function post(event) {
+    window.webkit.messageHandlers.wryFrames.postMessage({
+      event: event,
+      id: id,
+      url: window.location.href,
+      name: window.name
+    });
+  }
+  post('show');
+  window.addEventListener('pageshow', function (event) {
+    if (event.persisted) post('show');
+  });
+  window.addEventListener('pagehide', function () {
+    post('hide');
+  });
+})();"#;
+
+/// The shown frames, with their retained `WKFrameInfo`.
+pub(crate) type Frames = RefCell<Vec<(FrameInfo, id)>>;
+
+extern "C" fn did_receive(this: &Object, _: Sel, _: id, message: id) {
+  // Safety: objc runtime calls are unsafe
+  unsafe {
+    let frames = this.get_ivar::<*mut c_void>("frames");
+    if frames.is_null() {
+      return;
+    }
+    let frames = &*(*frames as *const Frames);
+
+    let body: id = msg_send![message, body];
+    let is_dictionary: BOOL = msg_send![body, isKindOfClass: class!(NSDictionary)];
+    if is_dictionary == NO {
+      return;
+    }
+    let event: id = msg_send![body, objectForKey: NSString::new("event")];
+    let frame_id: id = msg_send![body, objectForKey: NSString::new("id")];
+    let url: id = msg_send![body, objectForKey: NSString::new("url")];
+    let name: id = msg_send![body, objectForKey: NSString::new("name")];
+    if event == nil || frame_id == nil || url == nil || name == nil {
+      return;
+    }
+    let frame_id: f64 = msg_send![frame_id, doubleValue];
+    let frame_id = frame_id as u64;
+
+    let mut frames = frames.borrow_mut();
+    if let Some(index) = frames.iter().position(|(frame, _)| frame.id == frame_id) {
+      let (_, frame_info) = frames.remove(index);
+      let () = msg_send![frame_info, release];
+    }
+
+    if NSString(event).to_str() == "show" {
+      let frame_info: id = msg_send![message, frameInfo];
+      let is_main_frame: BOOL = msg_send![frame_info, isMainFrame];
+      let frame_info: id = msg_send![frame_info, retain];
+      frames.push((
+        FrameInfo {
+          id: frame_id,
+          url: Url::parse(NSString(url).to_str()).ok(),
+          name: NSString(name).to_str().to_string(),
+          is_main_frame: is_main_frame == YES,
+        },
+        frame_info,
+      ));
+    }
+  }
+}
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn add_frames_handler(manager: id) -> *mut Frames {
+  let cls = match ClassDecl::new("WryFramesDelegate", class!(NSObject)) {
+    Some(mut cls) => {
+      cls.add_ivar::<*mut c_void>("frames");
+      cls.add_method(
+        sel!(userContentController:didReceiveScriptMessage:),
+        did_receive as extern "C" fn(&Object, Sel, id, id),
+      );
+      cls.register()
+    }
+    None => class!(WryFramesDelegate),
+  };
+
+  let frames = Box::into_raw(Box::new(Frames::default()));
+  let handler: id = msg_send![cls, new];
+  (*handler).set_ivar("frames", frames as *mut c_void);
+  let name = NSString::new(FRAMES_MESSAGE_HANDLER_NAME);
+  let _: () = msg_send![manager, addScriptMessageHandler:handler name:name];
+  frames
+}
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn drop_frames_handler(manager: id, frames: *mut Frames) {
+  let name = NSString::new(FRAMES_MESSAGE_HANDLER_NAME);
+  let _: () = msg_send![manager, removeScriptMessageHandlerForName: name];
+
+  for (_, frame_info) in Box::from_raw(frames).into_inner() {
+    let () = msg_send![frame_info, release];
+  }
+}
@@ -0,0 +1,125 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{ffi::c_void, ptr::null_mut};
+
+use block::Block;
+use cocoa::{
+  base::{id, nil},
+  foundation::NSInteger,
+};
+use objc::{
+  declare::ClassDecl,
+  runtime::{Object, Sel},
+};
+use url::Url;
+
+use super::{url_from_webview, NSString};
+use crate::{HttpsError, HttpsErrorDecision, HttpsErrorKind};
+
+type HttpsErrorHandler = Box<dyn Fn(HttpsError) -> HttpsErrorDecision>;
+
+// NSURLSessionAuthChallengeDisposition
+const USE_CREDENTIAL: NSInteger = 0;
+const PERFORM_DEFAULT_HANDLING: NSInteger = 1;
+
+// Security framework result codes
+const ERR_SEC_CERTIFICATE_EXPIRED: NSInteger = -67818;
+const ERR_SEC_CERTIFICATE_NOT_VALID_YET: NSInteger = -67819;
+const ERR_SEC_HOST_NAME_MISMATCH: NSInteger = -67602;
+const ERR_SEC_NOT_TRUSTED: NSInteger = -67843;
+const ERR_SEC_CERTIFICATE_REVOKED: NSInteger = -67820;
+
+#[link(name = "Security", kind = "framework")]
+extern "C" {
+  fn SecTrustEvaluateWithError(trust: *mut c_void, error: *mut id) -> bool;
+}
+
+extern "C" fn did_receive_authentication_challenge(
+  this: &Object,
+  _: Sel,
+  webview: id,
+  challenge: id,
+  completion_handler: id,
+) {
+  unsafe {
+    let completion_handler = &*(completion_handler as *const Block<(NSInteger, id), ()>);
+
+    let function = this.get_ivar::<*mut c_void>("https_error_function");
+    let protection_space: id = msg_send![challenge, protectionSpace];
+    let method: id = msg_send![protection_space, authenticationMethod];
+    if function.is_null() || NSString(method).to_str() != "NSURLAuthenticationMethodServerTrust" {
+      completion_handler.call((PERFORM_DEFAULT_HANDLING, nil));
+      return;
+    }
+
+    let trust: *mut c_void = msg_send![protection_space, serverTrust];
+    let mut error: id = nil;
+    if trust.is_null() || SecTrustEvaluateWithError(trust, &mut error) {
+      completion_handler.call((PERFORM_DEFAULT_HANDLING, nil));
+      return;
+    }
+
+    let code: NSInteger = if error != nil {
+      msg_send![error, code]
+    } else {
+      0
+    };
+    let kind = match code {
+      ERR_SEC_CERTIFICATE_EXPIRED | ERR_SEC_CERTIFICATE_NOT_VALID_YET => {
+        HttpsErrorKind::CertExpired
+      }
+      ERR_SEC_HOST_NAME_MISMATCH => HttpsErrorKind::HostnameMismatch,
+      ERR_SEC_NOT_TRUSTED | ERR_SEC_CERTIFICATE_REVOKED => HttpsErrorKind::CertUntrusted,
+      _ => HttpsErrorKind::Other,
+    };
+    if error != nil {
+      let () = msg_send![error, release];
+    }
+
+    // the challenge is received before the webview URL is updated, use the protection space host
+    let host: id = msg_send![protection_space, host];
+    let url = Url::parse(&format!("https://{}/", NSString(host).to_str()))
+      .or_else(|_| Url::parse(&url_from_webview(webview)));
+    let Ok(url) = url else {
+      completion_handler.call((PERFORM_DEFAULT_HANDLING, nil));
+      return;
+    };
+
+    let function = &*(*function as *mut HttpsErrorHandler);
+    match function(HttpsError { url, kind }) {
+      HttpsErrorDecision::Block => completion_handler.call((PERFORM_DEFAULT_HANDLING, nil)),
+      HttpsErrorDecision::Continue(reason) => {
+        log::warn!(
+          "ignoring the TLS errors of {}: {reason:?}",
+          NSString(host).to_str()
+        );
+        let credential: id = msg_send![class!(NSURLCredential), credentialForTrust: trust];
+        completion_handler.call((USE_CREDENTIAL, credential));
+      }
+    }
+  }
+}
+
+pub(crate) unsafe fn add_https_error_methods(cls: &mut ClassDecl) {
+  cls.add_ivar::<*mut c_void>("https_error_function");
+
+  cls.add_method(
+    sel!(webView:didReceiveAuthenticationChallenge:completionHandler:),
+    did_receive_authentication_challenge as extern "C" fn(&Object, Sel, id, id, id),
+  );
+}
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn set_https_error_handler(
+  navigation_delegate: id,
+  handler: Option<HttpsErrorHandler>,
+) -> *mut HttpsErrorHandler {
+  let handler = match handler {
+    Some(handler) => Box::into_raw(Box::new(handler)),
+    None => null_mut(),
+  };
+  (*navigation_delegate).set_ivar("https_error_function", handler as *mut c_void);
+  handler
+}
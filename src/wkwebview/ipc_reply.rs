@@ -0,0 +1,119 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  ffi::{c_char, c_void, CStr},
+  ptr::null_mut,
+};
+
+use cocoa::base::{id, nil};
+use objc::{
+  declare::ClassDecl,
+  runtime::{Object, Sel, BOOL, NO},
+};
+
+use super::{message_source, NSString};
+use crate::IpcReplier;
+
+pub(crate) const IPC_REPLY_MESSAGE_HANDLER_NAME: &str = "ipcReply";
+
+/// Script defining `window.ipc.postMessageWithReply`, the promise of WebKit rejects when
+/// the handler is not registered.
+pub(crate) const POST_MESSAGE_WITH_REPLY_FUNCTION: &str = r#"(function (message) {
+  var handler = window.webkit.messageHandlers.ipcReply;
+  if (!handler) return Promise.reject(new Error('window.ipc.postMessageWithReply is unsupported'));
+  return handler.postMessage(String(message));
+})"#;
+
+extern "C" fn did_receive_with_reply(this: &Object, _: Sel, _: id, msg: id, reply_handler: id) {
+  // Safety: objc runtime calls are unsafe
+  unsafe {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("wry::ipc::handle").entered();
+
+    // the reply handler may be called after this method returns, the block is copied to keep it alive
+    let reply_handler: id = msg_send![reply_handler, copy];
+    let reply = move |result: Result<String, String>| {
+      let block = reply_handler as *mut block::Block<(id, id), ()>;
+      match result {
+        Ok(value) => (*block).call((NSString::new(&value).as_ptr(), nil)),
+        Err(error) => (*block).call((nil, NSString::new(&error).as_ptr())),
+      };
+      let _: () = msg_send![reply_handler, release];
+    };
+
+    let function = this.get_ivar::<*mut c_void>("function");
+    if !function.is_null() {
+      let function = &*(*function as *mut Box<dyn Fn(String, IpcReplier)>);
+      let body: id = msg_send![msg, body];
+      let is_string: bool = msg_send![body, isKindOfClass: class!(NSString)];
+      if is_string {
+        let utf8: *const c_char = msg_send![body, UTF8String];
+        if let Ok(message) = CStr::from_ptr(utf8).to_str() {
+          crate::ipc::with_message_source(message_source(msg), || {
+            function(message.to_string(), IpcReplier::new(reply))
+          });
+          return;
+        }
+      }
+    }
+
+    log::warn!("WebView received invalid IPC call.");
+    reply(Err("Invalid IPC call".to_string()));
+  }
+}
+
+/// Registers `handler` as the `WKScriptMessageHandlerWithReply` of `window.ipc.postMessageWithReply`,
+/// returns a null pointer when it is unsupported.
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn add_ipc_reply_handler(
+  manager: id,
+  handler: Box<dyn Fn(String, IpcReplier)>,
+) -> *mut Box<dyn Fn(String, IpcReplier)> {
+  // macOS 11+ and iOS 14+
+  let is_supported: BOOL = msg_send![
+    manager,
+    respondsToSelector: sel!(addScriptMessageHandlerWithReply:contentWorld:name:)
+  ];
+  if is_supported == NO {
+    return null_mut();
+  }
+
+  let cls = match ClassDecl::new("WebViewReplyDelegate", class!(NSObject)) {
+    Some(mut cls) => {
+      cls.add_ivar::<*mut c_void>("function");
+      cls.add_method(
+        sel!(userContentController:didReceiveScriptMessage:replyHandler:),
+        did_receive_with_reply as extern "C" fn(&Object, Sel, id, id, id),
+      );
+      cls.register()
+    }
+    None => class!(WebViewReplyDelegate),
+  };
+  let delegate: id = msg_send![cls, new];
+  let handler = Box::into_raw(Box::new(handler));
+  (*delegate).set_ivar("function", handler as *mut c_void);
+
+  let world: id = msg_send![class!(WKContentWorld), pageWorld];
+  let name = NSString::new(IPC_REPLY_MESSAGE_HANDLER_NAME);
+  let _: () =
+    msg_send![manager, addScriptMessageHandlerWithReply: delegate contentWorld: world name: name];
+  let _: () = msg_send![delegate, release];
+  handler
+}
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn remove_ipc_reply_handler(
+  manager: id,
+  handler: *mut Box<dyn Fn(String, IpcReplier)>,
+) {
+  if handler.is_null() {
+    return;
+  }
+
+  let world: id = msg_send![class!(WKContentWorld), pageWorld];
+  let name = NSString::new(IPC_REPLY_MESSAGE_HANDLER_NAME);
+  let _: () = msg_send![manager, removeScriptMessageHandlerForName: name contentWorld: world];
+  drop(Box::from_raw(handler));
+}
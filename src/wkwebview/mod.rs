@@ -2,12 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+#[cfg(target_os = "macos")]
+mod accelerator;
+#[cfg(target_os = "ios")]
+mod audio_session;
+#[cfg(target_os = "macos")]
+mod click_through;
 mod download;
 #[cfg(target_os = "macos")]
+mod drag_region;
+#[cfg(target_os = "macos")]
 mod file_drop;
+mod frames;
+mod https_error;
+mod ipc_reply;
 mod navigation;
 #[cfg(feature = "mac-proxy")]
 mod proxy;
+mod stream;
 #[cfg(target_os = "macos")]
 mod synthetic_mouse_events;
 
@@ -15,16 +27,20 @@ mod synthetic_mouse_events;
 use cocoa::appkit::{NSView, NSViewHeightSizable, NSViewMinYMargin, NSViewWidthSizable};
 use cocoa::{
   base::{id, nil, NO, YES},
-  foundation::{NSDictionary, NSFastEnumeration, NSInteger},
+  foundation::{NSDictionary, NSFastEnumeration, NSInteger, NSUInteger},
 };
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
 use url::Url;
 
 use std::{
   borrow::Cow,
+  cell::{Cell, RefCell},
+  collections::HashMap,
   ffi::{c_void, CStr},
+  io::Read,
   os::raw::c_char,
   ptr::{null, null_mut},
+  rc::{Rc, Weak},
   slice, str,
   sync::{Arc, Mutex},
 };
@@ -36,14 +52,31 @@ use objc::{
 };
 use objc_id::Id;
 
+#[cfg(target_os = "macos")]
+use accelerator::{
+  add_accelerator_key_methods, handle_accelerator_key, handle_zoom_key,
+  set_accelerator_key_handler, set_zoom_hotkeys, ZoomHotkeys,
+};
+#[cfg(target_os = "macos")]
+use click_through::{
+  add_click_through_methods, drop_click_through_regions_ivar, set_click_through_regions_ivar,
+  ClickThroughRegions,
+};
+#[cfg(target_os = "macos")]
+use drag_region::{add_drag_region_methods, set_drag_region_handler};
 #[cfg(target_os = "macos")]
 use file_drop::{add_file_drop_methods, set_file_drop_handler};
+use https_error::{add_https_error_methods, set_https_error_handler};
+use ipc_reply::{
+  add_ipc_reply_handler, remove_ipc_reply_handler, POST_MESSAGE_WITH_REPLY_FUNCTION,
+};
 
 #[cfg(feature = "mac-proxy")]
 use crate::{
   proxy::ProxyConfig,
   wkwebview::proxy::{
-    nw_endpoint_t, nw_proxy_config_create_http_connect, nw_proxy_config_create_socksv5,
+    nw_endpoint_t, nw_proxy_config_add_excluded_domain, nw_proxy_config_create_http_connect,
+    nw_proxy_config_create_socksv5,
   },
 };
 
@@ -53,9 +86,15 @@ use crate::{
       add_download_methods, download_did_fail, download_did_finish, download_policy,
       set_download_delegate,
     },
-    navigation::{add_navigation_mathods, drop_navigation_methods, set_navigation_methods},
+    frames::{add_frames_handler, drop_frames_handler, Frames, FRAMES_SCRIPT},
+    navigation::{
+      add_navigation_mathods, drop_navigation_methods, set_load_error_handler,
+      set_navigation_methods, set_redirect_handler,
+    },
   },
-  Error, PageLoadEvent, Rect, RequestAsyncResponder, Result, WebContext, WebViewAttributes, RGBA,
+  EditCommand, Error, FrameInfo, HistoryEntry, InjectionTime, PageLoadEvent, PrintDialogDecision,
+  PrintDialogRequest, Rect, RequestAsyncResponder, Result, ScriptId, StyleId, WebContext,
+  WebViewAttributes, WindowLevel, ZOrder, RGBA,
 };
 
 use http::{
@@ -67,27 +106,109 @@ use http::{
 
 const IPC_MESSAGE_HANDLER_NAME: &str = "ipc";
 #[cfg(target_os = "macos")]
+const NS_NORMAL_WINDOW_LEVEL: NSInteger = 0;
+#[cfg(target_os = "macos")]
+const NS_FLOATING_WINDOW_LEVEL: NSInteger = 3;
+#[cfg(target_os = "macos")]
+const CG_DESKTOP_ICON_WINDOW_LEVEL_KEY: i32 = 18;
+#[cfg(target_os = "macos")]
 const ACCEPT_FIRST_MOUSE: &str = "accept_first_mouse";
 
 const NS_JSON_WRITING_FRAGMENTS_ALLOWED: u64 = 4;
 
+/// The scripts evaluated before the page is committed, with the callback of their result.
+pub(crate) type PendingScripts = Arc<
+  Mutex<
+    Option<
+      Vec<(
+        String,
+        Option<Box<dyn Fn(std::result::Result<String, String>) + Send>>,
+      )>,
+    >,
+  >,
+>;
+
+type CustomProtocolHandler = Rc<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)>;
+
+/// The handlers of the custom protocols, by scheme. The schemes are part of the configuration
+/// of the `WKWebView` so only their handlers can change after it is built.
+type CustomProtocols = Rc<RefCell<HashMap<String, CustomProtocolHandler>>>;
+
+/// The handler of the scheme `name` set on the configuration, it doesn't keep the `protocols`
+/// of the webview alive.
+fn custom_protocol_dispatcher(
+  protocols: Weak<RefCell<HashMap<String, CustomProtocolHandler>>>,
+  name: String,
+) -> Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)> {
+  Box::new(move |request, responder| {
+    let handler = protocols
+      .upgrade()
+      .and_then(|protocols| protocols.borrow().get(&name).cloned());
+    match handler {
+      Some(handler) => handler(request, responder),
+      None => responder.respond(
+        HttpResponse::builder()
+          .status(StatusCode::NOT_FOUND)
+          .body(Vec::new())
+          .unwrap(),
+      ),
+    }
+  })
+}
+
 pub(crate) struct InnerWebView {
   pub webview: id,
   #[cfg(target_os = "macos")]
-  pub ns_window: id,
+  pub ns_window: Cell<id>,
   pub manager: id,
   is_child: bool,
-  pending_scripts: Arc<Mutex<Option<Vec<String>>>>,
+  zoom_limits: Rc<Cell<Option<(f64, f64)>>>,
+  frames: *mut Frames,
+  // the `WKUserScript`s added with `add_initialization_script`
+  initialization_scripts: RefCell<HashMap<ScriptId, id>>,
+  // the initialization scripts adding the stylesheets of `inject_css`
+  user_styles: RefCell<HashMap<StyleId, ScriptId>>,
+  // the text and options of the last `find`
+  find_search: RefCell<Option<(String, crate::FindOptions)>>,
+  // the image view of `restore_dom`, retained by the webview it is added to
+  frozen_overlay: Cell<id>,
+  pending_scripts: PendingScripts,
   // Note that if following functions signatures are changed in the future,
   // all functions pointer declarations in objc callbacks below all need to get updated.
   ipc_handler_ptr: *mut Box<dyn Fn(String)>,
+  ipc_reply_handler_ptr: *mut Box<dyn Fn(String, crate::IpcReplier)>,
   document_title_changed_handler: *mut Box<dyn Fn(String)>,
+  page_zoom_changed_handler: *mut Box<dyn Fn(f64)>,
+  url_changed_handler: *mut Box<dyn Fn(Url)>,
+  load_progress_handler: *mut Box<dyn Fn(f64)>,
+  media_capture_state_changed_handler: *mut Box<dyn Fn(crate::MediaCaptureState)>,
+  // the observers of the key paths of the webview, removed before their handlers are dropped
+  key_value_observers: Vec<(id, &'static [&'static str])>,
   navigation_decide_policy_ptr: *mut Box<dyn Fn(String, bool) -> bool>,
   page_load_handler: *mut Box<dyn Fn(PageLoadEvent)>,
+  load_error_handler: *mut Box<dyn Fn(crate::LoadError)>,
+  redirect_handler: *mut Box<dyn Fn(String, bool)>,
   #[cfg(target_os = "macos")]
   file_drop_ptr: *mut Box<dyn Fn(crate::FileDropEvent) -> bool>,
+  #[cfg(target_os = "macos")]
+  drag_region_ptr: *mut Box<dyn Fn(crate::DragRegionQuery) -> bool>,
+  #[cfg(target_os = "macos")]
+  accelerator_key_ptr: *mut Box<dyn Fn(crate::AcceleratorKeyEvent) -> bool>,
+  #[cfg(target_os = "macos")]
+  zoom_hotkeys_ptr: *mut ZoomHotkeys,
+  #[cfg(target_os = "macos")]
+  click_through_regions: ClickThroughRegions,
+  #[cfg(target_os = "ios")]
+  audio_session_observer: id,
+  audio_muted: Cell<bool>,
+  on_audio_muted_change: Option<Box<dyn Fn(bool)>>,
+  https_error_ptr: *mut Box<dyn Fn(crate::HttpsError) -> crate::HttpsErrorDecision>,
+  default_headers: id,
+  // the last navigation loaded again with the default headers
+  reloaded_url_ptr: *mut RefCell<Option<String>>,
   download_delegate: id,
   protocol_ptrs: Vec<*mut Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)>>,
+  custom_protocols: CustomProtocols,
 }
 
 impl InnerWebView {
@@ -147,7 +268,7 @@ impl InnerWebView {
           if is_string {
             let utf8: *const c_char = msg_send![body, UTF8String];
             if let Ok(js) = CStr::from_ptr(utf8).to_str() {
-              (function)(js.to_string());
+              crate::ipc::with_message_source(message_source(msg), || (function)(js.to_string()));
               return;
             }
           }
@@ -235,9 +356,9 @@ impl InnerWebView {
           // send response
           match http_request.body(sent_form_body) {
             Ok(final_request) => {
-              let responder: Box<dyn FnOnce(HttpResponse<Cow<'static, [u8]>>)> = Box::new(
-                move |sent_response| {
-                  let content = sent_response.body();
+              // sends the status and the headers of the response, with the length of the body when it is known
+              let receive_response =
+                move |sent_response: &HttpResponse<()>, content_length: Option<usize>| {
                   // default: application/octet-stream, but should be provided by the client
                   let wanted_mime = sent_response.headers().get(CONTENT_TYPE);
                   // default to 200
@@ -250,7 +371,9 @@ impl InnerWebView {
                   if let Some(mime) = wanted_mime {
                     let () = msg_send![headers, setObject:NSString::new(mime.to_str().unwrap()) forKey: NSString::new(CONTENT_TYPE.as_str())];
                   }
-                  let () = msg_send![headers, setObject:NSString::new(&content.len().to_string()) forKey: NSString::new(CONTENT_LENGTH.as_str())];
+                  if let Some(content_length) = content_length {
+                    let () = msg_send![headers, setObject:NSString::new(&content_length.to_string()) forKey: NSString::new(CONTENT_LENGTH.as_str())];
+                  }
 
                   // add headers
                   for (name, value) in sent_response.headers().iter() {
@@ -263,6 +386,12 @@ impl InnerWebView {
                   let urlresponse: id = msg_send![class!(NSHTTPURLResponse), alloc];
                   let response: id = msg_send![urlresponse, initWithURL:url statusCode: wanted_status_code HTTPVersion:NSString::new(&wanted_version) headerFields:headers];
                   let () = msg_send![task, didReceiveResponse: response];
+                };
+
+              let responder: Box<dyn FnOnce(HttpResponse<Cow<'static, [u8]>>)> = Box::new(
+                move |sent_response| {
+                  let (parts, content) = sent_response.into_parts();
+                  receive_response(&HttpResponse::from_parts(parts, ()), Some(content.len()));
 
                   // Send data
                   let bytes = content.as_ptr() as *mut c_void;
@@ -273,10 +402,22 @@ impl InnerWebView {
                   let () = msg_send![task, didFinish];
                 },
               );
+              let stream_responder: Box<dyn FnOnce(HttpResponse<Box<dyn Read + Send>>)> =
+                Box::new(move |sent_response| {
+                  let (parts, body) = sent_response.into_parts();
+                  receive_response(&HttpResponse::from_parts(parts, ()), None);
+                  stream::stream_body(task, body);
+                });
 
               #[cfg(feature = "tracing")]
               let _span = tracing::info_span!("wry::custom_protocol::call_handler").entered();
-              function(final_request, RequestAsyncResponder { responder });
+              function(
+                final_request,
+                RequestAsyncResponder {
+                  responder,
+                  stream_responder: Some(stream_responder),
+                },
+              );
             }
             Err(_) => respond_with_404(),
           };
@@ -287,13 +428,16 @@ impl InnerWebView {
         }
       }
     }
-    extern "C" fn stop_task(_: &Object, _: Sel, _webview: id, _task: id) {}
+    extern "C" fn stop_task(_: &Object, _: Sel, _webview: id, task: id) {
+      stream::stop_task(task);
+    }
 
     // Safety: objc runtime calls are unsafe
     unsafe {
       // Config and custom protocol
       let config: id = msg_send![class!(WKWebViewConfiguration), new];
       let mut protocol_ptrs = Vec::new();
+      let custom_protocols = CustomProtocols::default();
 
       // Incognito mode
       let data_store: id = if attributes.incognito {
@@ -321,6 +465,10 @@ impl InnerWebView {
           None => Class::get(&scheme_name).expect("Failed to get the class definition"),
         };
         let handler: id = msg_send![cls, new];
+        custom_protocols
+          .borrow_mut()
+          .insert(name.clone(), Rc::from(function));
+        let function = custom_protocol_dispatcher(Rc::downgrade(&custom_protocols), name.clone());
         let function = Box::into_raw(Box::new(function));
         protocol_ptrs.push(function);
 
@@ -330,12 +478,16 @@ impl InnerWebView {
 
       // WebView and manager
       let manager: id = msg_send![config, userContentController];
+      let frames = add_frames_handler(manager);
       let cls = match ClassDecl::new("WryWebView", class!(WKWebView)) {
         #[allow(unused_mut)]
         Some(mut decl) => {
           #[cfg(target_os = "macos")]
           {
             add_file_drop_methods(&mut decl);
+            add_drag_region_methods(&mut decl);
+            add_accelerator_key_methods(&mut decl);
+            add_click_through_methods(&mut decl);
             synthetic_mouse_events::setup(&mut decl);
             decl.add_ivar::<bool>(ACCEPT_FIRST_MOUSE);
             decl.add_method(
@@ -358,8 +510,11 @@ impl InnerWebView {
               }
             }
 
-            extern "C" fn key_equivalent(_this: &mut Object, _sel: Sel, event: id) -> BOOL {
+            extern "C" fn key_equivalent(this: &mut Object, _sel: Sel, event: id) -> BOOL {
               unsafe {
+                if handle_accelerator_key(this, event) || handle_zoom_key(this, event) {
+                  return YES;
+                }
                 let app = cocoa::appkit::NSApp();
                 let menu: id = msg_send![app, mainMenu];
                 let () = msg_send![menu, performKeyEquivalent: event];
@@ -390,6 +545,9 @@ impl InnerWebView {
             nw_proxy_config_create_socksv5(nw_endpoint)
           }
         };
+        for host in &attributes.http_proxy_exclude_hosts {
+          nw_proxy_config_add_excluded_domain(proxy_config, NSString::new(host).to_cstr());
+        }
 
         let proxies: id = msg_send![class!(NSArray), arrayWithObject: proxy_config];
         let () = msg_send![data_store, setProxyConfigurations: proxies];
@@ -398,12 +556,28 @@ impl InnerWebView {
       #[cfg(target_os = "macos")]
       (*webview).set_ivar(ACCEPT_FIRST_MOUSE, attributes.accept_first_mouse);
 
+      #[cfg(target_os = "macos")]
+      let click_through_regions = ClickThroughRegions::default();
+      #[cfg(target_os = "macos")]
+      set_click_through_regions_ivar(webview, &click_through_regions);
+
       let _: id = msg_send![_preference, setValue:_yes forKey:NSString::new("allowsPictureInPictureMediaPlayback")];
 
       if attributes.autoplay {
         let _: id = msg_send![config, setMediaTypesRequiringUserActionForPlayback:0];
       }
 
+      // shouldPrintBackgrounds is only available on macOS 13.3+ and iOS 16.4+
+      let can_print_backgrounds: BOOL =
+        msg_send![_preference, respondsToSelector: sel!(setShouldPrintBackgrounds:)];
+      if can_print_backgrounds == YES {
+        let () = msg_send![_preference, setShouldPrintBackgrounds: if attributes.print_background { YES } else { NO }];
+      }
+
+      if !attributes.background_throttling {
+        set_background_throttling(_preference, false);
+      }
+
       #[cfg(target_os = "macos")]
       let _: id = msg_send![_preference, setValue:_yes forKey:NSString::new("tabFocusesLinks")];
 
@@ -422,8 +596,14 @@ impl InnerWebView {
 
       #[cfg(target_os = "macos")]
       {
-        let (x, y) = attributes.bounds.map(|b| (b.x, b.y)).unwrap_or((0, 0));
-        let (w, h) = if is_child {
+        let auto_resize = is_child && attributes.auto_resize;
+        let (x, y) = if auto_resize {
+          None
+        } else {
+          attributes.bounds.map(|b| (b.x, b.y))
+        }
+        .unwrap_or((0, 0));
+        let (w, h) = if is_child && !auto_resize {
           attributes.bounds.map(|b| (b.width, b.height))
         } else {
           None
@@ -443,7 +623,7 @@ impl InnerWebView {
         };
 
         let _: () = msg_send![webview, initWithFrame:frame configuration:config];
-        if is_child {
+        if is_child && !auto_resize {
           // fixed element
           webview.setAutoresizingMask_(NSViewMinYMargin);
         } else {
@@ -488,7 +668,23 @@ impl InnerWebView {
       }
 
       // Message handler
-      let ipc_handler_ptr = if let Some(ipc_handler) = attributes.ipc_handler {
+      // Print dialog handler, the overridden `window.print()` posts to the IPC channel
+      let intercept_print = attributes.on_print_dialog.is_some();
+      let ipc_handler = match attributes.on_print_dialog {
+        Some(on_print_dialog) => {
+          let ipc_handler = attributes.ipc_handler;
+          Some(Box::new(move |message: String| {
+            if crate::is_print_dialog_message(&message) {
+              handle_print_dialog(webview, &on_print_dialog);
+            } else if let Some(ipc_handler) = &ipc_handler {
+              ipc_handler(message);
+            }
+          }) as Box<dyn Fn(String)>)
+        }
+        None => attributes.ipc_handler,
+      };
+
+      let ipc_handler_ptr = if let Some(ipc_handler) = ipc_handler {
         let cls = ClassDecl::new("WebViewDelegate", class!(NSObject));
         let cls = match cls {
           Some(mut cls) => {
@@ -507,11 +703,25 @@ impl InnerWebView {
         (*handler).set_ivar("function", ipc_handler_ptr as *mut _ as *mut c_void);
         let ipc = NSString::new(IPC_MESSAGE_HANDLER_NAME);
         let _: () = msg_send![manager, addScriptMessageHandler:handler name:ipc];
+        if !attributes.isolated_initialization_scripts.is_empty() {
+          if let Some(world) = isolated_world() {
+            let ipc = NSString::new(IPC_MESSAGE_HANDLER_NAME);
+            let _: () =
+              msg_send![manager, addScriptMessageHandler:handler contentWorld:world name:ipc];
+          }
+        }
         ipc_handler_ptr
       } else {
         null_mut()
       };
 
+      let ipc_reply_handler_ptr = match attributes.ipc_reply_handler {
+        Some(ipc_reply_handler) => add_ipc_reply_handler(manager, ipc_reply_handler),
+        None => null_mut(),
+      };
+
+      let mut key_value_observers = Vec::new();
+
       // Document title changed handler
       let document_title_changed_handler = if let Some(document_title_changed_handler) =
         attributes.document_title_changed_handler
@@ -559,14 +769,236 @@ impl InnerWebView {
         );
 
         let _: () = msg_send![webview, addObserver:handler forKeyPath:NSString::new("title") options:0x01 context:nil ];
+        key_value_observers.push((handler, &["title"] as &[_]));
 
         document_title_changed_handler
       } else {
         null_mut()
       };
 
+      // URL changed handler
+      let url_changed_handler = if let Some(on_url_changed) = attributes.on_url_changed {
+        let cls = ClassDecl::new("UrlChangedDelegate", class!(NSObject));
+        let cls = match cls {
+          Some(mut cls) => {
+            cls.add_ivar::<*mut c_void>("function");
+            cls.add_method(
+              sel!(observeValueForKeyPath:ofObject:change:context:),
+              observe_value_for_key_path as extern "C" fn(&Object, Sel, id, id, id, id),
+            );
+            extern "C" fn observe_value_for_key_path(
+              this: &Object,
+              _sel: Sel,
+              key_path: id,
+              of_object: id,
+              _change: id,
+              _context: id,
+            ) {
+              let key = NSString(key_path);
+              if key.to_str() == "URL" {
+                unsafe {
+                  let function = this.get_ivar::<*mut c_void>("function");
+                  let url: id = msg_send![of_object, URL];
+                  if !function.is_null() && url != nil {
+                    let function = &mut *(*function as *mut Box<dyn Fn(Url)>);
+                    if let Ok(url) = Url::parse(&url_from_webview(of_object)) {
+                      (function)(url);
+                    }
+                  }
+                }
+              }
+            }
+            cls.register()
+          }
+          None => class!(UrlChangedDelegate),
+        };
+
+        let handler: id = msg_send![cls, new];
+        let url_changed_handler = Box::into_raw(Box::new(on_url_changed));
+
+        (*handler).set_ivar("function", url_changed_handler as *mut _ as *mut c_void);
+
+        let _: () = msg_send![webview, addObserver:handler forKeyPath:NSString::new("URL") options:0x01 context:nil ];
+        key_value_observers.push((handler, &["URL"] as &[_]));
+
+        url_changed_handler
+      } else {
+        null_mut()
+      };
+
+      // Load progress handler
+      let load_progress_handler = if let Some(on_load_progress) = attributes.on_load_progress {
+        let cls = ClassDecl::new("LoadProgressDelegate", class!(NSObject));
+        let cls = match cls {
+          Some(mut cls) => {
+            cls.add_ivar::<*mut c_void>("function");
+            cls.add_method(
+              sel!(observeValueForKeyPath:ofObject:change:context:),
+              observe_value_for_key_path as extern "C" fn(&Object, Sel, id, id, id, id),
+            );
+            extern "C" fn observe_value_for_key_path(
+              this: &Object,
+              _sel: Sel,
+              key_path: id,
+              of_object: id,
+              _change: id,
+              _context: id,
+            ) {
+              let key = NSString(key_path);
+              if key.to_str() == "estimatedProgress" {
+                unsafe {
+                  let function = this.get_ivar::<*mut c_void>("function");
+                  if !function.is_null() {
+                    let function = &mut *(*function as *mut Box<dyn Fn(f64)>);
+                    let progress: f64 = msg_send![of_object, estimatedProgress];
+                    (function)(progress);
+                  }
+                }
+              }
+            }
+            cls.register()
+          }
+          None => class!(LoadProgressDelegate),
+        };
+
+        let handler: id = msg_send![cls, new];
+        let load_progress_handler = Box::into_raw(Box::new(on_load_progress));
+
+        (*handler).set_ivar("function", load_progress_handler as *mut _ as *mut c_void);
+
+        let _: () = msg_send![webview, addObserver:handler forKeyPath:NSString::new("estimatedProgress") options:0x01 context:nil ];
+        key_value_observers.push((handler, &["estimatedProgress"] as &[_]));
+
+        load_progress_handler
+      } else {
+        null_mut()
+      };
+
+      // Page zoom changed handler
+      let page_zoom_changed_handler = if let Some(on_page_zoom_change) =
+        attributes.on_page_zoom_change
+      {
+        let cls = ClassDecl::new("PageZoomChangedDelegate", class!(NSObject));
+        let cls = match cls {
+          Some(mut cls) => {
+            cls.add_ivar::<*mut c_void>("function");
+            cls.add_method(
+              sel!(observeValueForKeyPath:ofObject:change:context:),
+              observe_value_for_key_path as extern "C" fn(&Object, Sel, id, id, id, id),
+            );
+            extern "C" fn observe_value_for_key_path(
+              this: &Object,
+              _sel: Sel,
+              key_path: id,
+              of_object: id,
+              _change: id,
+              _context: id,
+            ) {
+              let key = NSString(key_path);
+              if key.to_str() == "pageZoom" {
+                unsafe {
+                  let function = this.get_ivar::<*mut c_void>("function");
+                  if !function.is_null() {
+                    let function = &mut *(*function as *mut Box<dyn Fn(f64)>);
+                    let zoom: f64 = msg_send![of_object, pageZoom];
+                    (function)(zoom);
+                  }
+                }
+              }
+            }
+            cls.register()
+          }
+          None => class!(PageZoomChangedDelegate),
+        };
+
+        let handler: id = msg_send![cls, new];
+        let page_zoom_changed_handler = Box::into_raw(Box::new(on_page_zoom_change));
+
+        (*handler).set_ivar(
+          "function",
+          page_zoom_changed_handler as *mut _ as *mut c_void,
+        );
+
+        let _: () = msg_send![webview, addObserver:handler forKeyPath:NSString::new("pageZoom") options:0x01 context:nil ];
+        key_value_observers.push((handler, &["pageZoom"] as &[_]));
+
+        page_zoom_changed_handler
+      } else {
+        null_mut()
+      };
+
+      // Media capture state changed handler
+      let can_observe_capture_state: BOOL =
+        msg_send![webview, respondsToSelector: sel!(cameraCaptureState)];
+      let media_capture_state_changed_handler = match attributes.on_media_capture_state_change {
+        Some(on_media_capture_state_change) if can_observe_capture_state == YES => {
+          let cls = ClassDecl::new("MediaCaptureStateChangedDelegate", class!(NSObject));
+          let cls = match cls {
+            Some(mut cls) => {
+              cls.add_ivar::<*mut c_void>("function");
+              cls.add_method(
+                sel!(observeValueForKeyPath:ofObject:change:context:),
+                observe_value_for_key_path as extern "C" fn(&Object, Sel, id, id, id, id),
+              );
+              extern "C" fn observe_value_for_key_path(
+                this: &Object,
+                _sel: Sel,
+                _key_path: id,
+                of_object: id,
+                _change: id,
+                _context: id,
+              ) {
+                // WKMediaCaptureState
+                fn capture_state(state: NSInteger) -> crate::CaptureState {
+                  match state {
+                    1 => crate::CaptureState::Active,
+                    2 => crate::CaptureState::Muted,
+                    _ => crate::CaptureState::Off,
+                  }
+                }
+
+                unsafe {
+                  let function = this.get_ivar::<*mut c_void>("function");
+                  if !function.is_null() {
+                    let function = &mut *(*function as *mut Box<dyn Fn(crate::MediaCaptureState)>);
+                    let camera: NSInteger = msg_send![of_object, cameraCaptureState];
+                    let microphone: NSInteger = msg_send![of_object, microphoneCaptureState];
+                    (function)(crate::MediaCaptureState {
+                      camera: capture_state(camera),
+                      microphone: capture_state(microphone),
+                      screen: crate::CaptureState::Off,
+                    });
+                  }
+                }
+              }
+              cls.register()
+            }
+            None => class!(MediaCaptureStateChangedDelegate),
+          };
+
+          let handler: id = msg_send![cls, new];
+          let media_capture_state_changed_handler =
+            Box::into_raw(Box::new(on_media_capture_state_change));
+
+          (*handler).set_ivar(
+            "function",
+            media_capture_state_changed_handler as *mut _ as *mut c_void,
+          );
+
+          let _: () = msg_send![webview, addObserver:handler forKeyPath:NSString::new("cameraCaptureState") options:0x01 context:nil ];
+          let _: () = msg_send![webview, addObserver:handler forKeyPath:NSString::new("microphoneCaptureState") options:0x01 context:nil ];
+          key_value_observers.push((
+            handler,
+            &["cameraCaptureState", "microphoneCaptureState"] as &[_],
+          ));
+
+          media_capture_state_changed_handler
+        }
+        _ => null_mut(),
+      };
+
       // Navigation handler
-      extern "C" fn navigation_policy(this: &Object, _: Sel, _: id, action: id, handler: id) {
+      extern "C" fn navigation_policy(this: &Object, _: Sel, webview: id, action: id, handler: id) {
         unsafe {
           // shouldPerformDownload is only available on macOS 11.3+
           let can_download: BOOL =
@@ -601,13 +1033,43 @@ impl InnerWebView {
             let function = this.get_ivar::<*mut c_void>("navigation_policy_function");
             if !function.is_null() {
               let function = &mut *(*function as *mut Box<dyn for<'s> Fn(String, bool) -> bool>);
-              match (function)(url.to_str().to_string(), is_main_frame) {
-                true => (*handler).call((1,)),
-                false => (*handler).call((0,)),
-              };
-            } else {
-              (*handler).call((1,));
+              if !(function)(url.to_str().to_string(), is_main_frame) {
+                (*handler).call((0,));
+                return;
+              }
+            }
+
+            // add the default headers to the main frame navigations missing them, by loading them again
+            let default_headers: id = *this.get_ivar("default_headers");
+            if default_headers != nil && is_main_frame {
+              // skip the navigation loaded again, in case WebKit drops one of the headers
+              let reloaded_url = *this.get_ivar::<*mut c_void>("reloaded_url");
+              let reloaded_url = &*(reloaded_url as *mut RefCell<Option<String>>);
+              let is_reloaded = reloaded_url.take().as_deref() == Some(url.to_str());
+              let method: id = msg_send![request, HTTPMethod];
+              let keys: id = msg_send![default_headers, allKeys];
+              let missing_header = keys.iter().any(|key| {
+                let value: id = msg_send![request, valueForHTTPHeaderField: key];
+                value == nil
+              });
+              if !is_reloaded && NSString(method).to_str() == "GET" && missing_header {
+                (*handler).call((0,));
+                let request: id = msg_send![request, mutableCopy];
+                for key in keys.iter() {
+                  let value: id = msg_send![request, valueForHTTPHeaderField: key];
+                  if value == nil {
+                    let value: id = msg_send![default_headers, objectForKey: key];
+                    let () = msg_send![request, setValue:value forHTTPHeaderField:key];
+                  }
+                }
+                reloaded_url.replace(Some(url.to_str().to_string()));
+                let _: id = msg_send![webview, loadRequest: request];
+                let () = msg_send![request, release];
+                return;
+              }
             }
+
+            (*handler).call((1,));
           }
         }
       }
@@ -646,6 +1108,8 @@ impl InnerWebView {
         Some(mut cls) => {
           cls.add_ivar::<*mut c_void>("pending_scripts");
           cls.add_ivar::<*mut c_void>("HasDownloadHandler");
+          cls.add_ivar::<id>("default_headers");
+          cls.add_ivar::<*mut c_void>("reloaded_url");
           cls.add_method(
             sel!(webView:decidePolicyForNavigationAction:decisionHandler:),
             navigation_policy as extern "C" fn(&Object, Sel, id, id, id),
@@ -656,6 +1120,7 @@ impl InnerWebView {
           );
           add_download_methods(&mut cls);
           add_navigation_mathods(&mut cls);
+          add_https_error_methods(&mut cls);
           cls.register()
         }
         None => class!(WryNavigationDelegate),
@@ -663,6 +1128,23 @@ impl InnerWebView {
 
       let navigation_policy_handler: id = msg_send![navigation_delegate_cls, new];
 
+      let default_headers = match &attributes.default_headers {
+        Some(headers) => {
+          let dictionary: id = msg_send![class!(NSMutableDictionary), new];
+          for (name, value) in headers.iter() {
+            let key = NSString::new(name.as_str());
+            let value = NSString::new(value.to_str().unwrap_or_default());
+            let () = msg_send![dictionary, setObject:value.as_ptr() forKey:key.as_ptr()];
+          }
+          (*navigation_policy_handler).set_ivar("default_headers", dictionary);
+          dictionary
+        }
+        None => nil,
+      };
+
+      let reloaded_url_ptr = Box::into_raw(Box::new(RefCell::new(None::<String>)));
+      (*navigation_policy_handler).set_ivar("reloaded_url", reloaded_url_ptr as *mut c_void);
+
       (*navigation_policy_handler).set_ivar(
         "pending_scripts",
         Box::into_raw(Box::new(pending_scripts.clone())) as *mut c_void,
@@ -758,6 +1240,14 @@ impl InnerWebView {
         attributes.on_page_load_handler,
       );
 
+      let load_error_handler =
+        set_load_error_handler(navigation_policy_handler, attributes.on_load_error);
+      let redirect_handler =
+        set_redirect_handler(navigation_policy_handler, attributes.on_redirect);
+
+      let https_error_ptr =
+        set_https_error_handler(navigation_policy_handler, attributes.on_https_error);
+
       let _: () = msg_send![webview, setNavigationDelegate: navigation_policy_handler];
 
       // File upload panel handler
@@ -838,6 +1328,35 @@ impl InnerWebView {
         None => set_file_drop_handler(webview, Box::new(|_| false)),
       };
 
+      // Drag region handling
+      #[cfg(target_os = "macos")]
+      let drag_region_ptr = set_drag_region_handler(webview, attributes.drag_region_handler);
+
+      // Accelerator key handling
+      #[cfg(target_os = "macos")]
+      let accelerator_key_ptr = set_accelerator_key_handler(webview, attributes.on_accelerator_key);
+
+      // Zoom hotkeys
+      let zoom_limits = Rc::new(Cell::new(attributes.zoom_limits));
+      #[cfg(target_os = "macos")]
+      let zoom_hotkeys_ptr = set_zoom_hotkeys(
+        webview,
+        ZoomHotkeys {
+          enabled: Cell::new(attributes.zoom_hotkeys_enabled),
+          step: attributes.zoom_hotkeys_custom_step,
+          reset_key: attributes.zoom_reset_key,
+          initial_zoom: attributes.initial_zoom,
+          zoom_limits: zoom_limits.clone(),
+        },
+      );
+
+      // Audio session interruptions
+      #[cfg(target_os = "ios")]
+      let audio_session_observer = match attributes.on_audio_focus_change {
+        Some(handler) => audio_session::add_audio_session_observer(handler),
+        None => nil,
+      };
+
       // ns window is required for the print operation
       #[cfg(target_os = "macos")]
       let ns_window = {
@@ -858,33 +1377,101 @@ impl InnerWebView {
       let w = Self {
         webview,
         #[cfg(target_os = "macos")]
-        ns_window,
+        ns_window: Cell::new(ns_window),
         manager,
         pending_scripts,
         ipc_handler_ptr,
+        ipc_reply_handler_ptr,
         document_title_changed_handler,
+        page_zoom_changed_handler,
+        url_changed_handler,
+        load_progress_handler,
+        media_capture_state_changed_handler,
+        key_value_observers,
         navigation_decide_policy_ptr,
         #[cfg(target_os = "macos")]
         file_drop_ptr,
+        #[cfg(target_os = "macos")]
+        drag_region_ptr,
+        #[cfg(target_os = "macos")]
+        accelerator_key_ptr,
+        #[cfg(target_os = "macos")]
+        zoom_hotkeys_ptr,
+        #[cfg(target_os = "macos")]
+        click_through_regions,
+        #[cfg(target_os = "ios")]
+        audio_session_observer,
+        audio_muted: Cell::new(false),
+        on_audio_muted_change: attributes.on_audio_muted_change,
+        https_error_ptr,
+        default_headers,
+        reloaded_url_ptr,
         page_load_handler,
+        load_error_handler,
+        redirect_handler,
         download_delegate,
         protocol_ptrs,
+        custom_protocols,
         is_child,
+        zoom_limits,
+        frames,
+        initialization_scripts: Default::default(),
+        user_styles: Default::default(),
+        find_search: Default::default(),
+        frozen_overlay: Cell::new(nil),
       };
 
       // Initialize scripts
+      let post_chunked = crate::ipc::post_chunked_function(attributes.max_ipc_message_size_bytes);
       w.init(
-r#"Object.defineProperty(window, 'ipc', {
-  value: Object.freeze({postMessage: function(s) {window.webkit.messageHandlers.ipc.postMessage(s);}})
-});"#,
+        &format!(
+          r#"(function () {{
+  var post = {};
+  Object.defineProperty(window, 'ipc', {{
+    value: Object.freeze({{postMessage: function(s) {{post(function (m) {{window.webkit.messageHandlers.ipc.postMessage(m);}}, {}(s));}}, postMessageWithReply: {}, invoke: {}, channel: {}}})
+  }});
+}})();"#,
+          post_chunked,
+          crate::ipc::encode_message_function(),
+          POST_MESSAGE_WITH_REPLY_FUNCTION,
+          crate::ipc::invoke_function(),
+          crate::ipc::channel_function(),
+        ),
+        false,
       );
       for js in attributes.initialization_scripts {
-        w.init(&js);
+        w.init(&js, false);
+      }
+      for js in attributes.main_frame_initialization_scripts {
+        w.init(&js, true);
+      }
+      for (js, main_only) in attributes.document_end_scripts {
+        w.add_user_script(&js, main_only, InjectionTime::DocumentEnd);
       }
+      if !attributes.isolated_initialization_scripts.is_empty() {
+        w.init_isolated(&format!(
+          r#"(function () {{
+  var post = {};
+  Object.defineProperty(window, 'ipc', {{
+    value: Object.freeze({{postMessage: function(s) {{post(function (m) {{window.webkit.messageHandlers.ipc.postMessage(m);}}, {}(s));}}}})
+  }});
+}})();"#,
+          post_chunked,
+          crate::ipc::encode_message_function(),
+        ));
+        for js in attributes.isolated_initialization_scripts {
+          w.init_isolated(&js);
+        }
+      }
+      w.init(FRAMES_SCRIPT, false);
+      if intercept_print {
+        w.init(&crate::override_print_script(), false);
+      }
+      w.init(crate::media::MUTE_MEDIA_SCRIPT, false);
 
       // Set user agent
       if let Some(user_agent) = attributes.user_agent {
-        w.set_user_agent(user_agent.as_str())
+        let _ = w.set_user_agent(user_agent.as_str());
       }
 
       // Navigation
@@ -960,9 +1547,20 @@ r#"Object.defineProperty(window, 'ipc', {
     Url::parse(&url_from_webview(self.webview)).unwrap()
   }
 
+  pub fn title(&self) -> String {
+    unsafe {
+      let title: id = msg_send![self.webview, title];
+      if title == nil {
+        String::new()
+      } else {
+        NSString(title).to_str().to_string()
+      }
+    }
+  }
+
   pub fn eval(&self, js: &str, callback: Option<impl Fn(String) + Send + 'static>) -> Result<()> {
     if let Some(scripts) = &mut *self.pending_scripts.lock().unwrap() {
-      scripts.push(js.into());
+      scripts.push((js.into(), None));
     } else {
       // Safety: objc runtime calls are unsafe
       unsafe {
@@ -1008,30 +1606,101 @@ r#"Object.defineProperty(window, 'ipc', {
     Ok(())
   }
 
-  fn init(&self, js: &str) {
+  pub fn eval_with_result(
+    &self,
+    js: &str,
+    callback: impl Fn(std::result::Result<String, String>) + Send + 'static,
+  ) -> Result<()> {
+    if let Some(scripts) = &mut *self.pending_scripts.lock().unwrap() {
+      // evaluated with the callback once the page is committed
+      scripts.push((js.into(), Some(Box::new(callback))));
+      return Ok(());
+    }
+
+    // Safety: objc runtime calls are unsafe
+    unsafe { evaluate_with_result(self.webview, js, callback) };
+
+    Ok(())
+  }
+
+  fn init(&self, js: &str, main_only: bool) {
+    self.add_user_script(js, main_only, InjectionTime::DocumentStart);
+  }
+
+  fn add_user_script(&self, js: &str, main_only: bool, injection_time: InjectionTime) {
     // Safety: objc runtime calls are unsafe
     // Equivalent Obj-C:
-    // [manager addUserScript:[[WKUserScript alloc] initWithSource:[NSString stringWithUTF8String:js.c_str()] injectionTime:WKUserScriptInjectionTimeAtDocumentStart forMainFrameOnly:YES]]
+    // [manager addUserScript:[[WKUserScript alloc] initWithSource:[NSString stringWithUTF8String:js.c_str()] injectionTime:injection_time forMainFrameOnly:main_only]]
     unsafe {
       let userscript: id = msg_send![class!(WKUserScript), alloc];
-      let script: id =
-      // FIXME: We allow subframe injection because webview2 does and cannot be disabled (currently).
-      // once webview2 allows disabling all-frame script injection, forMainFrameOnly should be enabled
-      // if it does not break anything. (originally added for isolation pattern).
-        msg_send![userscript, initWithSource:NSString::new(js) injectionTime:0 forMainFrameOnly:0];
+      let main_only = if main_only { YES } else { NO };
+      // https://developer.apple.com/documentation/webkit/wkuserscriptinjectiontime
+      let injection_time: NSInteger = match injection_time {
+        InjectionTime::DocumentStart => 0,
+        InjectionTime::DocumentEnd => 1,
+      };
+      let script: id = msg_send![userscript, initWithSource:NSString::new(js) injectionTime:injection_time forMainFrameOnly:main_only];
       let _: () = msg_send![self.manager, addUserScript: script];
     }
   }
 
-  pub fn load_url(&self, url: &str) {
-    self.navigate_to_url(url, None)
-  }
-
-  pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) {
-    self.navigate_to_url(url, Some(headers))
+  pub fn add_initialization_script(&self, js: &str) -> Result<ScriptId> {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let userscript: id = msg_send![class!(WKUserScript), alloc];
+      let script: id =
+        msg_send![userscript, initWithSource:NSString::new(js) injectionTime:0 forMainFrameOnly:NO];
+      let _: () = msg_send![self.manager, addUserScript: script];
+      let id = ScriptId::next();
+      self.initialization_scripts.borrow_mut().insert(id, script);
+      Ok(id)
+    }
   }
 
-  pub fn clear_all_browsing_data(&self) -> Result<()> {
+  pub fn remove_initialization_script(&self, id: ScriptId) -> Result<()> {
+    let Some(script) = self.initialization_scripts.borrow_mut().remove(&id) else {
+      return Ok(());
+    };
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      // WKUserContentController can only remove all the scripts, the other ones are added back
+      let scripts: id = msg_send![self.manager, userScripts];
+      let scripts: id = msg_send![scripts, copy];
+      let _: () = msg_send![self.manager, removeAllUserScripts];
+      let count: NSUInteger = msg_send![scripts, count];
+      for i in 0..count {
+        let user_script: id = msg_send![scripts, objectAtIndex: i];
+        if user_script != script {
+          let _: () = msg_send![self.manager, addUserScript: user_script];
+        }
+      }
+      let _: () = msg_send![scripts, release];
+      let _: () = msg_send![script, release];
+    }
+    Ok(())
+  }
+
+  fn init_isolated(&self, js: &str) {
+    let Some(world) = isolated_world() else {
+      return;
+    };
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let userscript: id = msg_send![class!(WKUserScript), alloc];
+      let script: id = msg_send![userscript, initWithSource:NSString::new(js) injectionTime:0 forMainFrameOnly:NO inContentWorld:world];
+      let _: () = msg_send![self.manager, addUserScript: script];
+    }
+  }
+
+  pub fn load_url(&self, url: &str) {
+    self.navigate_to_url(url, None)
+  }
+
+  pub fn load_url_with_headers(&self, url: &str, headers: http::HeaderMap) {
+    self.navigate_to_url(url, Some(headers))
+  }
+
+  pub fn clear_all_browsing_data(&self) -> Result<()> {
     unsafe {
       let config: id = msg_send![self.webview, configuration];
       let store: id = msg_send![config, websiteDataStore];
@@ -1059,39 +1728,177 @@ r#"Object.defineProperty(window, 'ipc', {
     }
   }
 
+  pub fn load_request(&self, request: Request<Vec<u8>>) -> Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let url: id =
+        msg_send![class!(NSURL), URLWithString: NSString::new(&request.uri().to_string())];
+      let ns_request: id = msg_send![class!(NSMutableURLRequest), requestWithURL: url];
+      let () = msg_send![ns_request, setHTTPMethod: NSString::new(request.method().as_str())];
+      for (name, value) in request.headers().iter() {
+        let key = NSString::new(name.as_str());
+        let value = NSString::new(value.to_str().unwrap_or_default());
+        let _: () = msg_send![ns_request, addValue:value.as_ptr() forHTTPHeaderField:key.as_ptr()];
+      }
+      let body = request.body();
+      if !body.is_empty() {
+        let data: id = msg_send![class!(NSData), dataWithBytes:body.as_ptr() length:body.len()];
+        let () = msg_send![ns_request, setHTTPBody: data];
+      }
+      let () = msg_send![self.webview, loadRequest: ns_request];
+    }
+    Ok(())
+  }
+
   fn navigate_to_string(&self, html: &str) {
+    self.load_html(html, None)
+  }
+
+  pub fn load_html(&self, html: &str, base_url: Option<&str>) {
     // Safety: objc runtime calls are unsafe
     unsafe {
-      let () = msg_send![self.webview, loadHTMLString:NSString::new(html) baseURL:nil];
+      let base_url: id = match base_url {
+        Some(base_url) => msg_send![class!(NSURL), URLWithString: NSString::new(base_url)],
+        None => nil,
+      };
+      let () = msg_send![self.webview, loadHTMLString:NSString::new(html) baseURL:base_url];
     }
   }
 
-  fn set_user_agent(&self, user_agent: &str) {
+  pub fn set_user_agent(&self, user_agent: &str) -> Result<()> {
     unsafe {
       let () = msg_send![self.webview, setCustomUserAgent: NSString::new(user_agent)];
     }
+    Ok(())
   }
 
   pub fn print(&self) {
     // Safety: objc runtime calls are unsafe
     #[cfg(target_os = "macos")]
     unsafe {
-      let can_print: BOOL = msg_send![
-        self.webview,
-        respondsToSelector: sel!(printOperationWithPrintInfo:)
-      ];
-      if can_print == YES {
-        // Create a shared print info
-        let print_info: id = msg_send![class!(NSPrintInfo), sharedPrintInfo];
-        let print_info: id = msg_send![print_info, init];
-        // Create new print operation from the webview content
-        let print_operation: id = msg_send![self.webview, printOperationWithPrintInfo: print_info];
-        // Allow the modal to detach from the current thread and be non-blocker
-        let () = msg_send![print_operation, setCanSpawnSeparateThread: YES];
-        // Launch the modal
-        let () = msg_send![print_operation, runOperationModalForWindow: self.ns_window delegate: null::<*const c_void>() didRunSelector: null::<*const c_void>() contextInfo: null::<*const c_void>()];
+      run_print_operation(self.webview, self.ns_window.get())
+    }
+  }
+
+  pub fn restore_dom(&self, png: Vec<u8>) -> Result<()> {
+    self.resume_rendering()?;
+
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let data: id =
+        msg_send![class!(NSData), dataWithBytes: png.as_ptr() as *const c_void length: png.len()];
+      let bounds: CGRect = msg_send![self.webview, bounds];
+
+      #[cfg(target_os = "macos")]
+      let overlay: id = {
+        let image: id = msg_send![class!(NSImage), alloc];
+        let image: id = msg_send![image, initWithData: data];
+        if image == nil {
+          return Err(Error::SnapshotError("the frame is not a PNG image".into()));
+        }
+        let overlay: id = msg_send![class!(NSImageView), alloc];
+        let overlay: id = msg_send![overlay, initWithFrame: bounds];
+        let () = msg_send![overlay, setImage: image];
+        let () = msg_send![image, release];
+        // NSImageScaleAxesIndependently
+        let () = msg_send![overlay, setImageScaling: 1 as NSUInteger];
+        overlay.setAutoresizingMask_(NSViewHeightSizable | NSViewWidthSizable);
+        overlay
+      };
+
+      #[cfg(target_os = "ios")]
+      let overlay: id = {
+        let image: id = msg_send![class!(UIImage), imageWithData: data];
+        if image == nil {
+          return Err(Error::SnapshotError("the frame is not a PNG image".into()));
+        }
+        let overlay: id = msg_send![class!(UIImageView), alloc];
+        let overlay: id = msg_send![overlay, initWithImage: image];
+        let () = msg_send![overlay, setFrame: bounds];
+        // UIViewContentModeScaleToFill
+        let () = msg_send![overlay, setContentMode: 0 as NSInteger];
+        // UIViewAutoresizingFlexibleWidth | UIViewAutoresizingFlexibleHeight
+        let () = msg_send![overlay, setAutoresizingMask: (1 << 1 | 1 << 4) as NSUInteger];
+        overlay
+      };
+
+      let () = msg_send![self.webview, addSubview: overlay];
+      let () = msg_send![overlay, release];
+      self.frozen_overlay.set(overlay);
+    }
+    Ok(())
+  }
+
+  pub fn resume_rendering(&self) -> Result<()> {
+    let overlay = self.frozen_overlay.replace(nil);
+    if overlay != nil {
+      // Safety: objc runtime calls are unsafe
+      unsafe {
+        let () = msg_send![overlay, removeFromSuperview];
       }
     }
+    Ok(())
+  }
+
+  pub fn snapshot(
+    &self,
+    rect_script: &str,
+    target: &str,
+    callback: Box<dyn FnOnce(Result<Vec<u8>>)>,
+  ) {
+    let webview = self.webview;
+    let js = rect_script;
+    let target = target.to_string();
+    // blocks are `Fn`, so the callback needs to be taken out of a cell
+    let callback = Cell::new(Some(callback));
+
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let handler = block::ConcreteBlock::new(move |val: id, _err: id| {
+        let Some(callback) = callback.take() else {
+          return;
+        };
+
+        let mut json = String::new();
+        if val != nil {
+          let serializer = class!(NSJSONSerialization);
+          let json_ns_data: NSData = msg_send![serializer, dataWithJSONObject:val options:NS_JSON_WRITING_FRAGMENTS_ALLOWED error:nil];
+          json = NSString::from(json_ns_data).to_str().to_string();
+        }
+
+        let rect = match crate::parse_element_rect(&json, &target) {
+          Ok(rect) => rect,
+          Err(e) => return callback(Err(e)),
+        };
+
+        let callback = Cell::new(Some(callback));
+        let snapshot_handler = block::ConcreteBlock::new(move |image: id, _err: id| {
+          let Some(callback) = callback.take() else {
+            return;
+          };
+
+          if image == nil {
+            callback(Err(Error::SnapshotError("failed to take the snapshot".into())));
+          } else {
+            callback(image_to_png(image));
+          }
+        })
+        .copy();
+
+        let config: id = msg_send![class!(WKSnapshotConfiguration), new];
+        let rect = CGRect::new(
+          &CGPoint::new(rect.x, rect.y),
+          &CGSize::new(rect.width, rect.height),
+        );
+        let () = msg_send![config, setRect: rect];
+        let () = msg_send![webview, takeSnapshotWithConfiguration:config completionHandler:snapshot_handler];
+        let () = msg_send![config, release];
+      })
+      .copy();
+
+      let () =
+        msg_send![self.webview, evaluateJavaScript:NSString::new(js) completionHandler:handler];
+    }
   }
 
   #[cfg(any(debug_assertions, feature = "devtools"))]
@@ -1128,11 +1935,24 @@ r#"Object.defineProperty(window, 'ipc', {
   }
 
   pub fn zoom(&self, scale_factor: f64) {
+    let scale_factor = match self.zoom_limits.get() {
+      Some((min, max)) => scale_factor.clamp(min, max),
+      None => scale_factor,
+    };
     unsafe {
       let _: () = msg_send![self.webview, setPageZoom: scale_factor];
     }
   }
 
+  pub fn zoom_factor(&self) -> f64 {
+    unsafe { msg_send![self.webview, pageZoom] }
+  }
+
+  pub fn set_zoom_limits(&self, min: f64, max: f64) {
+    self.zoom_limits.set(Some((min, max)));
+    self.zoom(self.zoom_factor());
+  }
+
   pub fn set_background_color(&self, _background_color: RGBA) -> Result<()> {
     Ok(())
   }
@@ -1152,6 +1972,48 @@ r#"Object.defineProperty(window, 'ipc', {
     }
   }
 
+  pub fn scale_factor(&self) -> f64 {
+    unsafe {
+      #[cfg(target_os = "macos")]
+      {
+        let window: id = msg_send![self.webview, window];
+        if window == nil {
+          let screen: id = msg_send![class!(NSScreen), mainScreen];
+          msg_send![screen, backingScaleFactor]
+        } else {
+          msg_send![window, backingScaleFactor]
+        }
+      }
+      #[cfg(target_os = "ios")]
+      {
+        msg_send![self.webview, contentScaleFactor]
+      }
+    }
+  }
+
+  pub fn set_click_through_regions(&self, regions: &[Rect]) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+      *self.click_through_regions.borrow_mut() = regions.to_vec();
+      Ok(())
+    }
+    #[cfg(target_os = "ios")]
+    {
+      let _ = regions;
+      Err(Error::NotSupported("setting the click-through regions"))
+    }
+  }
+
+  pub fn click_through_regions_setter(&self) -> Box<dyn Fn(&[Rect])> {
+    #[cfg(target_os = "macos")]
+    {
+      let click_through_regions = self.click_through_regions.clone();
+      Box::new(move |regions| *click_through_regions.borrow_mut() = regions.to_vec())
+    }
+    #[cfg(target_os = "ios")]
+    Box::new(|_| ())
+  }
+
   pub fn set_bounds(&self, bounds: Rect) {
     if self.is_child {
       unsafe {
@@ -1181,6 +2043,665 @@ r#"Object.defineProperty(window, 'ipc', {
       let _: () = msg_send![window, makeFirstResponder: self.webview];
     }
   }
+
+  #[allow(unused_variables)]
+  pub fn set_touch_adjust_radius(&self, radius_css_px: f64) {
+    #[cfg(target_os = "ios")]
+    {
+      let script = crate::touch_adjust_script(radius_css_px);
+      // apply to the pages loaded later and to the current one
+      self.init(&script, false);
+      let _ = self.eval(&script, None::<Box<dyn Fn(String) + Send + 'static>>);
+    }
+  }
+
+  pub fn reload(&self) {
+    unsafe {
+      let _: id = msg_send![self.webview, reload];
+    }
+  }
+
+  pub fn stop(&self) {
+    unsafe {
+      let () = msg_send![self.webview, stopLoading];
+    }
+  }
+
+  pub fn reload_ignoring_local_cache(&self) {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let config: id = msg_send![self.webview, configuration];
+      let store: id = msg_send![config, websiteDataStore];
+      let data_types: id = msg_send![class!(NSMutableSet), set];
+      for data_type in ["WKWebsiteDataTypeDiskCache", "WKWebsiteDataTypeMemoryCache"] {
+        let _: () = msg_send![data_types, addObject: NSString::new(data_type)];
+      }
+      let date: id = msg_send![class!(NSDate), distantPast];
+
+      // released once reloaded
+      let webview: id = msg_send![self.webview, retain];
+      let handler = block::ConcreteBlock::new(move || {
+        let _: id = msg_send![webview, reloadFromOrigin];
+        let _: () = msg_send![webview, release];
+      })
+      .copy();
+      let _: () =
+        msg_send![store, removeDataOfTypes:data_types modifiedSince:date completionHandler:handler];
+    }
+  }
+
+  pub fn set_window_level(&self, level: WindowLevel) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    unsafe {
+      let level: NSInteger = match level {
+        WindowLevel::Normal => NS_NORMAL_WINDOW_LEVEL,
+        WindowLevel::Floating => NS_FLOATING_WINDOW_LEVEL,
+        WindowLevel::Desktop => CGWindowLevelForKey(CG_DESKTOP_ICON_WINDOW_LEVEL_KEY) as NSInteger,
+      };
+      let window: id = msg_send![self.webview, window];
+      if window == nil {
+        return Err(Error::NotSupported(
+          "setting the level of a detached webview",
+        ));
+      }
+      let () = msg_send![window, setLevel: level];
+      Ok(())
+    }
+
+    #[cfg(target_os = "ios")]
+    {
+      let _ = level;
+      Err(Error::NotSupported("setting the window level"))
+    }
+  }
+
+  pub fn register_protocol(
+    &self,
+    name: String,
+    handler: Box<dyn Fn(Request<Vec<u8>>, RequestAsyncResponder)>,
+  ) -> Result<()> {
+    let mut protocols = self.custom_protocols.borrow_mut();
+    match protocols.get_mut(&name) {
+      Some(registered) => {
+        *registered = Rc::from(handler);
+        Ok(())
+      }
+      // the scheme handlers are part of the configuration the `WKWebView` was created with
+      None => Err(Error::NotSupported(
+        "registering custom protocols after build",
+      )),
+    }
+  }
+
+  pub fn unregister_protocol(&self, name: &str) -> Result<()> {
+    // the scheme stays set on the configuration, its requests get a 404 response
+    self.custom_protocols.borrow_mut().remove(name);
+    Ok(())
+  }
+
+  pub fn set_audio_muted(&self, muted: bool) -> Result<()> {
+    if self.audio_muted.replace(muted) == muted {
+      return Ok(());
+    }
+
+    let script = crate::media::set_audio_muted_script(muted);
+    // apply to the pages loaded later and to the current one
+    self.init(&script, false);
+    self.eval(&script, None::<Box<dyn Fn(String) + Send + 'static>>)?;
+
+    if let Some(on_audio_muted_change) = &self.on_audio_muted_change {
+      on_audio_muted_change(muted);
+    }
+    Ok(())
+  }
+
+  pub fn is_audio_muted(&self) -> bool {
+    self.audio_muted.get()
+  }
+
+  pub fn is_playing_audio(&self) -> Result<bool> {
+    Err(crate::Error::NotSupported("querying the audio playback"))
+  }
+
+  pub fn history(&self, callback: Box<dyn FnOnce(Vec<HistoryEntry>)>) {
+    callback(self.history_entries())
+  }
+
+  fn history_entries(&self) -> Vec<HistoryEntry> {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let list: id = msg_send![self.webview, backForwardList];
+      let back_list: id = msg_send![list, backList];
+      let forward_list: id = msg_send![list, forwardList];
+      let back: NSInteger = msg_send![back_list, count];
+      let forward: NSInteger = msg_send![forward_list, count];
+
+      (-back..=forward)
+        .filter_map(|offset| {
+          let item: id = msg_send![list, itemAtIndex: offset];
+          if item == nil {
+            return None;
+          }
+          let url: id = msg_send![item, URL];
+          let url: id = msg_send![url, absoluteString];
+          let url = Url::parse(NSString(url).to_str()).ok()?;
+          let title: id = msg_send![item, title];
+          let title = (title != nil).then(|| NSString(title).to_str().to_string());
+          Some(HistoryEntry::new(url, title, offset as i32))
+        })
+        .collect()
+    }
+  }
+
+  pub fn go_to_history_index(&self, index: usize, callback: Box<dyn FnOnce(Result<()>)>) {
+    let result = match self.history_entries().get(index) {
+      Some(entry) => self.navigate_to_history_offset(entry.offset),
+      None => Err(Error::HistoryIndexOutOfBounds(index)),
+    };
+    callback(result)
+  }
+
+  pub fn navigate_to_history_offset(&self, offset: i32) -> Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let list: id = msg_send![self.webview, backForwardList];
+      let item: id = msg_send![list, itemAtIndex: offset as NSInteger];
+      if item != nil {
+        let _: id = msg_send![self.webview, goToBackForwardListItem: item];
+      }
+    }
+    Ok(())
+  }
+
+  pub fn is_visible(&self) -> bool {
+    unsafe {
+      let hidden: BOOL = msg_send![self.webview, isHidden];
+      hidden == NO
+    }
+  }
+
+  pub fn set_parent(&self, parent: &impl HasWindowHandle) -> Result<()> {
+    let ns_view = match parent.window_handle()?.as_raw() {
+      #[cfg(target_os = "macos")]
+      RawWindowHandle::AppKit(w) => w.ns_view.as_ptr(),
+      #[cfg(target_os = "ios")]
+      RawWindowHandle::UiKit(w) => w.ui_view.as_ptr(),
+      _ => return Err(Error::UnsupportedWindowHandle),
+    } as id;
+
+    #[cfg(target_os = "macos")]
+    if !self.is_child {
+      return Err(Error::NotAChildWebView);
+    }
+
+    unsafe {
+      // keep the webview alive while it is detached from its superview
+      let _: id = msg_send![self.webview, retain];
+      let () = msg_send![self.webview, removeFromSuperview];
+      let () = msg_send![ns_view, addSubview: self.webview];
+      let _: () = msg_send![self.webview, release];
+
+      #[cfg(target_os = "macos")]
+      self.ns_window.set(msg_send![ns_view, window]);
+    }
+
+    Ok(())
+  }
+
+  pub fn set_z_order(&self, order: ZOrder) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    if !self.is_child {
+      return Err(Error::NotAChildWebView);
+    }
+
+    unsafe {
+      let superview: id = msg_send![self.webview, superview];
+
+      #[cfg(target_os = "macos")]
+      {
+        // `NSWindowAbove` and `NSWindowBelow`
+        let (position, relative_to): (NSInteger, id) = match order {
+          ZOrder::Top => (1, nil),
+          ZOrder::Bottom => (-1, nil),
+          ZOrder::Above(other) => (1, other.webview.webview),
+        };
+        let () = msg_send![superview, addSubview: self.webview positioned: position relativeTo: relative_to];
+      }
+
+      #[cfg(target_os = "ios")]
+      match order {
+        ZOrder::Top => {
+          let () = msg_send![superview, bringSubviewToFront: self.webview];
+        }
+        ZOrder::Bottom => {
+          let () = msg_send![superview, sendSubviewToBack: self.webview];
+        }
+        ZOrder::Above(other) => {
+          let () =
+            msg_send![superview, insertSubview: self.webview aboveSubview: other.webview.webview];
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn go_back(&self) {
+    unsafe {
+      let _: id = msg_send![self.webview, goBack];
+    }
+  }
+
+  pub fn go_forward(&self) {
+    unsafe {
+      let _: id = msg_send![self.webview, goForward];
+    }
+  }
+
+  pub fn can_go_back(&self) -> bool {
+    let can_go_back: BOOL = unsafe { msg_send![self.webview, canGoBack] };
+    can_go_back == YES
+  }
+
+  pub fn can_go_forward(&self) -> bool {
+    let can_go_forward: BOOL = unsafe { msg_send![self.webview, canGoForward] };
+    can_go_forward == YES
+  }
+
+  pub fn execute_edit_command(&self, command: EditCommand) -> Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let _: () = match command {
+        EditCommand::Cut => msg_send![self.webview, cut: nil],
+        EditCommand::Copy => msg_send![self.webview, copy: nil],
+        EditCommand::Paste => msg_send![self.webview, paste: nil],
+        EditCommand::SelectAll => msg_send![self.webview, selectAll: nil],
+        EditCommand::Undo => {
+          let undo_manager: id = msg_send![self.webview, undoManager];
+          msg_send![undo_manager, undo]
+        }
+        EditCommand::Redo => {
+          let undo_manager: id = msg_send![self.webview, undoManager];
+          msg_send![undo_manager, redo]
+        }
+      };
+    }
+    Ok(())
+  }
+
+  #[cfg(target_os = "macos")]
+  pub fn set_zoom_hotkeys_enabled(&self, enabled: bool) -> Result<()> {
+    // Safety: the hotkeys are dropped with the webview
+    unsafe { (*self.zoom_hotkeys_ptr).enabled.set(enabled) };
+    Ok(())
+  }
+
+  #[cfg(target_os = "ios")]
+  pub fn set_zoom_hotkeys_enabled(&self, _enabled: bool) -> Result<()> {
+    Err(Error::NotSupported("toggling the zoom hotkeys"))
+  }
+
+  pub fn set_background_throttling(&self, enabled: bool) -> Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let config: id = msg_send![self.webview, configuration];
+      let preferences: id = msg_send![config, preferences];
+      if set_background_throttling(preferences, enabled) {
+        Ok(())
+      } else {
+        Err(Error::NotSupported("changing the background throttling"))
+      }
+    }
+  }
+
+  pub fn save_state(&self, callback: Box<dyn FnOnce(Result<Vec<u8>>)>) {
+    callback(self.interaction_state())
+  }
+
+  fn interaction_state(&self) -> Result<Vec<u8>> {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      // interactionState is only available on macOS 12+ and iOS 15+
+      let supported: BOOL = msg_send![self.webview, respondsToSelector: sel!(interactionState)];
+      if supported == NO {
+        return Err(Error::NotSupported("saving the session state"));
+      }
+
+      let state: id = msg_send![self.webview, interactionState];
+      let mut error: id = nil;
+      let data: id = msg_send![class!(NSKeyedArchiver), archivedDataWithRootObject:state requiringSecureCoding:NO error:&mut error];
+      if state == nil || data == nil {
+        return Err(Error::InvalidSessionState);
+      }
+
+      let length: usize = msg_send![data, length];
+      let bytes: *const u8 = msg_send![data, bytes];
+      Ok(slice::from_raw_parts(bytes, length).to_vec())
+    }
+  }
+
+  pub fn restore_state(&self, state: &[u8]) -> Result<()> {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let supported: BOOL = msg_send![self.webview, respondsToSelector: sel!(setInteractionState:)];
+      if supported == NO {
+        return Err(Error::NotSupported("restoring the session state"));
+      }
+
+      let data: id = msg_send![class!(NSData), dataWithBytes:state.as_ptr() length:state.len()];
+      let mut error: id = nil;
+      let state: id =
+        msg_send![class!(NSKeyedUnarchiver), unarchiveTopLevelObjectWithData:data error:&mut error];
+      if state == nil {
+        return Err(Error::InvalidSessionState);
+      }
+
+      let () = msg_send![self.webview, setInteractionState: state];
+    }
+    Ok(())
+  }
+
+  pub fn frames(&self) -> Vec<FrameInfo> {
+    // Safety: the frames are dropped with the webview
+    let frames = unsafe { &*self.frames }.borrow();
+    let mut frames = frames
+      .iter()
+      .map(|(frame, _)| frame.clone())
+      .collect::<Vec<_>>();
+    frames.sort_by_key(|frame| !frame.is_main_frame);
+    frames
+  }
+
+  pub fn find(
+    &self,
+    text: &str,
+    options: crate::FindOptions,
+    callback: impl Fn(usize) + Send + 'static,
+  ) -> Result<()> {
+    if !self.supports_find_string() {
+      return self.eval(
+        &crate::find::find_script(text, options),
+        Some(move |result: String| {
+          if let Ok(count) = result.parse() {
+            callback(count);
+          }
+        }),
+      );
+    }
+
+    *self.find_search.borrow_mut() = Some((text.to_string(), options));
+    if text.is_empty() {
+      callback(0);
+      return self.clear_selection();
+    }
+    // WebKit doesn't count the matches
+    self.find_string(text, options, options.backwards, move |found| {
+      callback(found as usize)
+    });
+    Ok(())
+  }
+
+  pub fn find_next(&self) -> Result<()> {
+    self.find_again(false)
+  }
+
+  pub fn find_previous(&self) -> Result<()> {
+    self.find_again(true)
+  }
+
+  pub fn clear_matches(&self) -> Result<()> {
+    self.find_search.borrow_mut().take();
+    self.clear_selection()
+  }
+
+  fn find_again(&self, backwards: bool) -> Result<()> {
+    if !self.supports_find_string() {
+      return self.eval(
+        &crate::find::find_next_script(backwards),
+        None::<Box<dyn Fn(String) + Send + 'static>>,
+      );
+    }
+    if let Some((text, options)) = self.find_search.borrow().clone() {
+      if !text.is_empty() {
+        self.find_string(&text, options, backwards, |_| ());
+      }
+    }
+    Ok(())
+  }
+
+  fn clear_selection(&self) -> Result<()> {
+    self.eval(
+      crate::find::CLEAR_MATCHES_SCRIPT,
+      None::<Box<dyn Fn(String) + Send + 'static>>,
+    )
+  }
+
+  fn supports_find_string(&self) -> bool {
+    // findString:withConfiguration:completionHandler: is only available on macOS 11+ and iOS 14+
+    // Safety: objc runtime calls are unsafe
+    let supported: BOOL = unsafe {
+      msg_send![self.webview, respondsToSelector: sel!(findString:withConfiguration:completionHandler:)]
+    };
+    supported == YES
+  }
+
+  /// Selects the next match of `text`, `callback` is called with whether there is one.
+  fn find_string(
+    &self,
+    text: &str,
+    options: crate::FindOptions,
+    backwards: bool,
+    callback: impl Fn(bool) + 'static,
+  ) {
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      let config: id = msg_send![class!(WKFindConfiguration), new];
+      let () = msg_send![config, setBackwards: if backwards { YES } else { NO }];
+      let () = msg_send![config, setCaseSensitive: if options.case_sensitive { YES } else { NO }];
+      let () = msg_send![config, setWraps: if options.wrap_around { YES } else { NO }];
+      let handler = block::ConcreteBlock::new(move |result: id| {
+        let found: BOOL = msg_send![result, matchFound];
+        callback(found == YES);
+      })
+      .copy();
+      let () = msg_send![self.webview, findString:NSString::new(text) withConfiguration:config completionHandler:handler];
+      let () = msg_send![config, release];
+    }
+  }
+
+  pub fn inject_css(&self, css: &str) -> Result<StyleId> {
+    // WebKit only has private API for the user stylesheets, a script adds it to every frame
+    let id = StyleId::next();
+    let script = crate::user_style_script(id, css);
+    let script_id = self.add_initialization_script(&script)?;
+    self.user_styles.borrow_mut().insert(id, script_id);
+    self.eval_in_every_frame(&script)?;
+    Ok(id)
+  }
+
+  pub fn remove_css(&self, id: StyleId) -> Result<()> {
+    let Some(script_id) = self.user_styles.borrow_mut().remove(&id) else {
+      return Ok(());
+    };
+    self.remove_initialization_script(script_id)?;
+    self.eval_in_every_frame(&crate::remove_user_style_script(id))
+  }
+
+  /// Evaluates `js` in the main frame and in the iframes, which requires macOS 11+ or iOS 14+.
+  fn eval_in_every_frame(&self, js: &str) -> Result<()> {
+    self.eval(js, None::<Box<dyn Fn(String) + Send + 'static>>)?;
+    for frame in self.frames() {
+      if !frame.is_main_frame {
+        let _ = self.eval_in_frame(frame.id, js);
+      }
+    }
+    Ok(())
+  }
+
+  pub fn eval_in_frame(&self, frame_id: u64, js: &str) -> Result<()> {
+    // Safety: the frames are dropped with the webview
+    let frames = unsafe { &*self.frames }.borrow();
+    let (frame, frame_info) = frames
+      .iter()
+      .find(|(frame, _)| frame.id == frame_id)
+      .ok_or(Error::FrameNotFound(frame_id))?;
+    if frame.is_main_frame {
+      return self.eval(js, None::<Box<dyn Fn(String) + Send + 'static>>);
+    }
+
+    // Safety: objc runtime calls are unsafe
+    unsafe {
+      // evaluateJavaScript:inFrame:inContentWorld:completionHandler: is only available on macOS 11+ and iOS 14+
+      let supported: BOOL = msg_send![self.webview, respondsToSelector: sel!(evaluateJavaScript:inFrame:inContentWorld:completionHandler:)];
+      if supported == NO {
+        return Err(Error::NotSupported("evaluating scripts in iframes"));
+      }
+
+      let world: id = msg_send![class!(WKContentWorld), pageWorld];
+      let _: () = msg_send![self.webview, evaluateJavaScript:NSString::new(js) inFrame:*frame_info inContentWorld:world completionHandler:null::<*const c_void>()];
+    }
+    Ok(())
+  }
+}
+
+/// Evaluates `js` in `webview`, `callback` is called with the JSON string of its result or with
+/// the message of the exception it threw.
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn evaluate_with_result(
+  webview: id,
+  js: &str,
+  callback: impl Fn(std::result::Result<String, String>) + 'static,
+) {
+  let handler = block::ConcreteBlock::new(move |val: id, err: id| {
+    if err != nil {
+      // the message of the exception, or the description of the other errors
+      let user_info: id = msg_send![err, userInfo];
+      let message: id =
+        msg_send![user_info, objectForKey: NSString::new("WKJavaScriptExceptionMessage")];
+      let message = if message != nil {
+        message
+      } else {
+        msg_send![err, localizedDescription]
+      };
+      callback(Err(NSString(message).to_str().to_string()));
+      return;
+    }
+
+    let mut result = String::new();
+    if val != nil {
+      let serializer = class!(NSJSONSerialization);
+      let json_ns_data: NSData = msg_send![serializer, dataWithJSONObject:val options:NS_JSON_WRITING_FRAGMENTS_ALLOWED error:nil];
+      result = NSString::from(json_ns_data).to_str().to_string();
+    }
+    callback(Ok(result));
+  })
+  .copy();
+
+  let _: () = msg_send![webview, evaluateJavaScript:NSString::new(js) completionHandler:handler];
+}
+
+/// The URL of the frame which posted the script message `msg`.
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn message_source(msg: id) -> Option<String> {
+  let frame: id = msg_send![msg, frameInfo];
+  let request: id = msg_send![frame, request];
+  let url: id = msg_send![request, URL];
+  if url == nil {
+    return None;
+  }
+  let url: id = msg_send![url, absoluteString];
+  Some(NSString(url).to_str().to_string())
+}
+
+/// Sets the `WKInactiveSchedulingPolicy` of the preferences, returns `false` when unsupported.
+unsafe fn set_background_throttling(preferences: id, enabled: bool) -> bool {
+  // inactiveSchedulingPolicy is only available on macOS 14+ and iOS 17+
+  let supported: BOOL =
+    msg_send![preferences, respondsToSelector: sel!(setInactiveSchedulingPolicy:)];
+  if supported == NO {
+    return false;
+  }
+  // WKInactiveSchedulingPolicySuspend = 0, WKInactiveSchedulingPolicyThrottle = 1,
+  // WKInactiveSchedulingPolicyNone = 2, restore the default policy of the platform when enabled
+  let policy: NSInteger = if !enabled {
+    2
+  } else if cfg!(target_os = "ios") {
+    0
+  } else {
+    1
+  };
+  let () = msg_send![preferences, setInactiveSchedulingPolicy: policy];
+  true
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn run_print_operation(webview: id, ns_window: id) {
+  let can_print: BOOL = msg_send![
+    webview,
+    respondsToSelector: sel!(printOperationWithPrintInfo:)
+  ];
+  if can_print == YES {
+    // Create a shared print info
+    let print_info: id = msg_send![class!(NSPrintInfo), sharedPrintInfo];
+    let print_info: id = msg_send![print_info, init];
+    // Create new print operation from the webview content
+    let print_operation: id = msg_send![webview, printOperationWithPrintInfo: print_info];
+    // Allow the modal to detach from the current thread and be non-blocker
+    let () = msg_send![print_operation, setCanSpawnSeparateThread: YES];
+    // Launch the modal
+    let () = msg_send![print_operation, runOperationModalForWindow: ns_window delegate: null::<*const c_void>() didRunSelector: null::<*const c_void>() contextInfo: null::<*const c_void>()];
+  }
+}
+
+fn handle_print_dialog(
+  webview: id,
+  on_print_dialog: &dyn Fn(PrintDialogRequest) -> PrintDialogDecision,
+) {
+  let Ok(url) = Url::parse(&url_from_webview(webview)) else {
+    return;
+  };
+
+  let decision = on_print_dialog(PrintDialogRequest {
+    url,
+    initiated_by_js: true,
+  });
+
+  // Safety: objc runtime calls are unsafe
+  unsafe {
+    match decision {
+      #[cfg(target_os = "macos")]
+      PrintDialogDecision::Allow => {
+        let ns_window: id = msg_send![webview, window];
+        run_print_operation(webview, ns_window);
+      }
+      PrintDialogDecision::RedirectToPdf(path) => {
+        let can_create_pdf: BOOL = msg_send![
+          webview,
+          respondsToSelector: sel!(createPDFWithConfiguration:completionHandler:)
+        ];
+        if can_create_pdf == YES {
+          let path = path.to_string_lossy().into_owned();
+          let handler = block::ConcreteBlock::new(move |pdf: id, _err: id| {
+            if pdf != nil {
+              let _: BOOL = msg_send![pdf, writeToFile: NSString::new(&path) atomically: YES];
+            }
+          })
+          .copy();
+          let () = msg_send![webview, createPDFWithConfiguration: nil completionHandler: handler];
+        }
+      }
+      _ => (),
+    }
+  }
+}
+
+/// The `WKContentWorld` of the isolated initialization scripts, `None` before macOS 11 and iOS 14.
+fn isolated_world() -> Option<id> {
+  let cls = Class::get("WKContentWorld")?;
+  // Safety: objc runtime calls are unsafe
+  let world: id =
+    unsafe { msg_send![cls, worldWithName: NSString::new(crate::ISOLATED_WORLD_NAME)] };
+  (world != nil).then_some(world)
 }
 
 pub fn url_from_webview(webview: id) -> String {
@@ -1233,23 +2754,90 @@ impl Drop for InnerWebView {
 
         let ipc = NSString::new(IPC_MESSAGE_HANDLER_NAME);
         let _: () = msg_send![self.manager, removeScriptMessageHandlerForName: ipc];
+        if let Some(world) = isolated_world() {
+          let ipc = NSString::new(IPC_MESSAGE_HANDLER_NAME);
+          let _: () =
+            msg_send![self.manager, removeScriptMessageHandlerForName: ipc contentWorld: world];
+        }
+      }
+
+      remove_ipc_reply_handler(self.manager, self.ipc_reply_handler_ptr);
+
+      for (observer, key_paths) in &self.key_value_observers {
+        for key_path in *key_paths {
+          let _: () =
+            msg_send![self.webview, removeObserver: *observer forKeyPath: NSString::new(key_path)];
+        }
+        let _: () = msg_send![*observer, release];
       }
 
       if !self.document_title_changed_handler.is_null() {
         drop(Box::from_raw(self.document_title_changed_handler));
       }
 
+      if !self.page_zoom_changed_handler.is_null() {
+        drop(Box::from_raw(self.page_zoom_changed_handler));
+      }
+
+      if !self.url_changed_handler.is_null() {
+        drop(Box::from_raw(self.url_changed_handler));
+      }
+
+      if !self.load_progress_handler.is_null() {
+        drop(Box::from_raw(self.load_progress_handler));
+      }
+
+      if !self.media_capture_state_changed_handler.is_null() {
+        drop(Box::from_raw(self.media_capture_state_changed_handler));
+      }
+
       if !self.navigation_decide_policy_ptr.is_null() {
         drop(Box::from_raw(self.navigation_decide_policy_ptr));
       }
 
       drop_navigation_methods(self);
+      drop_frames_handler(self.manager, self.frames);
+
+      if !self.https_error_ptr.is_null() {
+        drop(Box::from_raw(self.https_error_ptr));
+      }
+
+      if self.default_headers != nil {
+        let () = msg_send![self.default_headers, release];
+      }
+
+      drop(Box::from_raw(self.reloaded_url_ptr));
 
       #[cfg(target_os = "macos")]
       if !self.file_drop_ptr.is_null() {
         drop(Box::from_raw(self.file_drop_ptr));
       }
 
+      #[cfg(target_os = "macos")]
+      if !self.drag_region_ptr.is_null() {
+        drop(Box::from_raw(self.drag_region_ptr));
+      }
+
+      #[cfg(target_os = "macos")]
+      if !self.accelerator_key_ptr.is_null() {
+        drop(Box::from_raw(self.accelerator_key_ptr));
+      }
+
+      #[cfg(target_os = "macos")]
+      drop(Box::from_raw(self.zoom_hotkeys_ptr));
+
+      #[cfg(target_os = "macos")]
+      drop_click_through_regions_ivar(self.webview);
+
+      for script in self.initialization_scripts.borrow().values() {
+        let _: () = msg_send![*script, release];
+      }
+
+      #[cfg(target_os = "ios")]
+      if self.audio_session_observer != nil {
+        audio_session::remove_audio_session_observer(self.audio_session_observer);
+      }
+
       if !self.download_delegate.is_null() {
         self.download_delegate.drop_in_place();
       }
@@ -1326,6 +2914,44 @@ impl From<NSData> for NSString {
 
 struct NSData(id);
 
+#[cfg(target_os = "ios")]
+#[link(name = "UIKit", kind = "framework")]
+extern "C" {
+  fn UIImagePNGRepresentation(image: id) -> id;
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+  fn CGWindowLevelForKey(key: i32) -> i32;
+}
+
+/// Encodes a `NSImage` (macOS) or `UIImage` (iOS) as PNG.
+unsafe fn image_to_png(image: id) -> Result<Vec<u8>> {
+  #[cfg(target_os = "macos")]
+  let png: id = {
+    // NSBitmapImageFileTypePNG
+    const PNG_FILE_TYPE: usize = 4;
+    let tiff: id = msg_send![image, TIFFRepresentation];
+    let bitmap: id = msg_send![class!(NSBitmapImageRep), imageRepWithData: tiff];
+    if bitmap == nil {
+      return Err(Error::SnapshotError("failed to encode the snapshot".into()));
+    }
+    let properties: id = msg_send![class!(NSDictionary), dictionary];
+    msg_send![bitmap, representationUsingType:PNG_FILE_TYPE properties:properties]
+  };
+  #[cfg(target_os = "ios")]
+  let png: id = UIImagePNGRepresentation(image);
+
+  if png == nil {
+    return Err(Error::SnapshotError("failed to encode the snapshot".into()));
+  }
+
+  let length: usize = msg_send![png, length];
+  let bytes: *const u8 = msg_send![png, bytes];
+  Ok(slice::from_raw_parts(bytes, length).to_vec())
+}
+
 /// Converts from wry screen-coordinates to macOS screen-coordinates.
 /// wry: top-left is (0, 0) and y increasing downwards
 /// macOS: bottom-left is (0, 0) and y increasing upwards
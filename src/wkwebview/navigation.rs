@@ -1,17 +1,25 @@
 use std::{
+  cell::RefCell,
   ffi::c_void,
   ptr::{null, null_mut},
-  sync::{Arc, Mutex},
 };
 
-use cocoa::base::id;
+use cocoa::{
+  base::{id, nil},
+  foundation::NSInteger,
+};
 use objc::{
   declare::ClassDecl,
   runtime::{Object, Sel},
 };
+use url::Url;
+
+use super::{evaluate_with_result, url_from_webview, InnerWebView, NSString, PendingScripts};
+use crate::{LoadError, LoadErrorCode, PageLoadEvent};
 
-use super::{url_from_webview, InnerWebView, NSString};
-use crate::PageLoadEvent;
+type LoadErrorHandler = Box<dyn Fn(LoadError)>;
+/// Called with the provisional URL of the webview and whether it is the target of a redirect.
+type RedirectHandler = Box<dyn Fn(String, bool)>;
 
 extern "C" fn did_commit_navigation(this: &Object, _: Sel, webview: id, _navigation: id) {
   unsafe {
@@ -24,13 +32,15 @@ extern "C" fn did_commit_navigation(this: &Object, _: Sel, webview: id, _navigat
 
     // Inject scripts
     let pending_scripts_ptr: *mut c_void = *this.get_ivar("pending_scripts");
-    let pending_scripts = &(*(pending_scripts_ptr as *mut Arc<Mutex<Option<Vec<String>>>>));
-    let mut pending_scripts_ = pending_scripts.lock().unwrap();
-    if let Some(pending_scripts) = &*pending_scripts_ {
-      for script in pending_scripts {
-        let _: id = msg_send![webview, evaluateJavaScript:NSString::new(script) completionHandler:null::<*const c_void>()];
+    let pending_scripts = &(*(pending_scripts_ptr as *mut PendingScripts));
+    let pending_scripts = pending_scripts.lock().unwrap().take();
+    for (script, callback) in pending_scripts.into_iter().flatten() {
+      match callback {
+        Some(callback) => evaluate_with_result(webview, &script, callback),
+        None => {
+          let _: id = msg_send![webview, evaluateJavaScript:NSString::new(&script) completionHandler:null::<*const c_void>()];
+        }
       }
-      *pending_scripts_ = None;
     }
   }
 }
@@ -46,9 +56,90 @@ extern "C" fn did_finish_navigation(this: &Object, _: Sel, _webview: id, _naviga
   }
 }
 
+extern "C" fn did_fail_navigation(this: &Object, _: Sel, webview: id, _navigation: id, error: id) {
+  unsafe {
+    let function = this.get_ivar::<*mut c_void>("on_load_error_function");
+    if function.is_null() {
+      return;
+    }
+    let function = &*(*function as *mut LoadErrorHandler);
+
+    let domain: id = msg_send![error, domain];
+    let code: NSInteger = msg_send![error, code];
+    let code = if NSString(domain).to_str() == "NSURLErrorDomain" {
+      load_error_code(code)
+    } else {
+      LoadErrorCode::Other
+    };
+
+    let description: id = msg_send![error, localizedDescription];
+    let description = NSString(description).to_str().to_string();
+
+    let user_info: id = msg_send![error, userInfo];
+    let failing_url: id =
+      msg_send![user_info, objectForKey: NSString::new("NSErrorFailingURLStringKey")];
+    let url = if failing_url == nil {
+      url_from_webview(webview)
+    } else {
+      NSString(failing_url).to_str().to_string()
+    };
+
+    if let Ok(url) = Url::parse(&url) {
+      function(LoadError {
+        url,
+        code,
+        description,
+      });
+    }
+  }
+}
+
+// NSURLError codes
+fn load_error_code(code: NSInteger) -> LoadErrorCode {
+  match code {
+    -1003 => LoadErrorCode::HostNotFound,
+    -1004 => LoadErrorCode::CannotConnect,
+    -1005 => LoadErrorCode::ConnectionLost,
+    -1009 => LoadErrorCode::Offline,
+    -1001 => LoadErrorCode::Timeout,
+    -999 => LoadErrorCode::Cancelled,
+    -1206..=-1200 => LoadErrorCode::Certificate,
+    -1007 => LoadErrorCode::TooManyRedirects,
+    -1002 => LoadErrorCode::UnsupportedUrl,
+    _ => LoadErrorCode::Other,
+  }
+}
+
+extern "C" fn did_start_provisional_navigation(
+  this: &Object,
+  _: Sel,
+  webview: id,
+  _navigation: id,
+) {
+  unsafe {
+    let function = this.get_ivar::<*mut c_void>("on_redirect_function");
+    if !function.is_null() {
+      let function = &*(*function as *mut RedirectHandler);
+      function(url_from_webview(webview), false);
+    }
+  }
+}
+
+extern "C" fn did_receive_server_redirect(this: &Object, _: Sel, webview: id, _navigation: id) {
+  unsafe {
+    let function = this.get_ivar::<*mut c_void>("on_redirect_function");
+    if !function.is_null() {
+      let function = &*(*function as *mut RedirectHandler);
+      function(url_from_webview(webview), true);
+    }
+  }
+}
+
 pub(crate) unsafe fn add_navigation_mathods(cls: &mut ClassDecl) {
   cls.add_ivar::<*mut c_void>("navigation_policy_function");
   cls.add_ivar::<*mut c_void>("on_page_load_function");
+  cls.add_ivar::<*mut c_void>("on_load_error_function");
+  cls.add_ivar::<*mut c_void>("on_redirect_function");
 
   cls.add_method(
     sel!(webView:didFinishNavigation:),
@@ -58,12 +149,34 @@ pub(crate) unsafe fn add_navigation_mathods(cls: &mut ClassDecl) {
     sel!(webView:didCommitNavigation:),
     did_commit_navigation as extern "C" fn(&Object, Sel, id, id),
   );
+  cls.add_method(
+    sel!(webView:didStartProvisionalNavigation:),
+    did_start_provisional_navigation as extern "C" fn(&Object, Sel, id, id),
+  );
+  cls.add_method(
+    sel!(webView:didReceiveServerRedirectForProvisionalNavigation:),
+    did_receive_server_redirect as extern "C" fn(&Object, Sel, id, id),
+  );
+  cls.add_method(
+    sel!(webView:didFailProvisionalNavigation:withError:),
+    did_fail_navigation as extern "C" fn(&Object, Sel, id, id, id),
+  );
+  cls.add_method(
+    sel!(webView:didFailNavigation:withError:),
+    did_fail_navigation as extern "C" fn(&Object, Sel, id, id, id),
+  );
 }
 
 pub(crate) unsafe fn drop_navigation_methods(inner: &mut InnerWebView) {
   if !inner.page_load_handler.is_null() {
     drop(Box::from_raw(inner.page_load_handler))
   }
+  if !inner.load_error_handler.is_null() {
+    drop(Box::from_raw(inner.load_error_handler))
+  }
+  if !inner.redirect_handler.is_null() {
+    drop(Box::from_raw(inner.redirect_handler))
+  }
 }
 
 pub(crate) unsafe fn set_navigation_methods(
@@ -84,3 +197,42 @@ pub(crate) unsafe fn set_navigation_methods(
     null_mut()
   }
 }
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn set_load_error_handler(
+  navigation_delegate: id,
+  handler: Option<LoadErrorHandler>,
+) -> *mut LoadErrorHandler {
+  let handler = match handler {
+    Some(handler) => Box::into_raw(Box::new(handler)),
+    None => null_mut(),
+  };
+  (*navigation_delegate).set_ivar("on_load_error_function", handler as *mut c_void);
+  handler
+}
+
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn set_redirect_handler(
+  navigation_delegate: id,
+  handler: Option<Box<dyn Fn(Url, Url)>>,
+) -> *mut RedirectHandler {
+  let handler = match handler {
+    Some(handler) => {
+      let current = RefCell::new(None::<Url>);
+      Box::into_raw(Box::new(Box::new(move |url: String, is_redirect: bool| {
+        let Ok(url) = Url::parse(&url) else {
+          return;
+        };
+        let previous = current.replace(Some(url.clone()));
+        if is_redirect {
+          if let Some(from) = previous {
+            handler(from, url);
+          }
+        }
+      }) as RedirectHandler))
+    }
+    None => null_mut(),
+  };
+  (*navigation_delegate).set_ivar("on_redirect_function", handler as *mut c_void);
+  handler
+}
@@ -42,6 +42,10 @@ extern "C" {
     first_hop: nw_relay_hop_t,
     second_hop: nw_relay_hop_t,
   ) -> nw_proxy_config_t;
+  pub fn nw_proxy_config_add_excluded_domain(
+    proxy_config: nw_proxy_config_t,
+    excluded_domain: *const c_char,
+  );
   pub fn nw_proxy_config_create_socksv5(proxy_endpoint: nw_endpoint_t) -> nw_proxy_config_t;
   pub fn nw_proxy_config_create_http_connect(
     proxy_endpoint: nw_endpoint_t,
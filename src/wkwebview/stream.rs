@@ -0,0 +1,133 @@
+// Copyright 2020-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  collections::HashMap,
+  ffi::c_void,
+  io::{ErrorKind, Read},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
+};
+
+use cocoa::base::{id, nil};
+use once_cell::sync::Lazy;
+
+use super::NSString;
+
+/// The `WKURLSchemeTask`s whose body is being streamed, with whether they were stopped by the
+/// webview. Calling the methods of a stopped task raises an exception.
+static STREAMED_TASKS: Lazy<Mutex<HashMap<usize, Arc<AtomicBool>>>> = Lazy::new(Default::default);
+
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// `NSURLErrorUnknown`
+const UNKNOWN_ERROR_CODE: isize = -1;
+
+extern "C" {
+  /// The main queue, returned by the `dispatch_get_main_queue` macro.
+  static _dispatch_main_q: c_void;
+  fn dispatch_sync_f(queue: *const c_void, context: *mut c_void, work: extern "C" fn(*mut c_void));
+}
+
+/// Runs `f` on the main thread and waits for it, the tasks are stopped on the main thread so
+/// checking whether they are stopped and calling their methods there doesn't race.
+fn run_on_main_thread<F: FnOnce() + Send>(f: F) {
+  extern "C" fn work<F: FnOnce()>(context: *mut c_void) {
+    // Safety: `context` is the `Option<F>` of `run_on_main_thread`, alive until it returns
+    if let Some(f) = unsafe { &mut *(context as *mut Option<F>) }.take() {
+      f();
+    }
+  }
+
+  let mut f = Some(f);
+  // Safety: the main queue is a static of libdispatch
+  unsafe {
+    dispatch_sync_f(
+      &_dispatch_main_q,
+      &mut f as *mut Option<F> as *mut c_void,
+      work::<F>,
+    )
+  };
+}
+
+/// Marks `task` as stopped, the remaining chunks of its body are discarded.
+///
+/// Called on the main thread.
+pub(crate) fn stop_task(task: id) {
+  if let Some(stopped) = STREAMED_TASKS.lock().unwrap().get(&(task as usize)) {
+    stopped.store(true, Ordering::SeqCst);
+  }
+}
+
+/// Sends the chunks of `body` to `task` as they are read on a background thread, then finishes it,
+/// or fails it when `body` can't be read.
+///
+/// The response of the task must have been received already.
+// Safety: objc runtime calls are unsafe
+pub(crate) unsafe fn stream_body(task: id, mut body: Box<dyn Read + Send>) {
+  // the task is retained until its body is fully sent
+  let task: id = msg_send![task, retain];
+  let stopped = Arc::new(AtomicBool::new(false));
+  STREAMED_TASKS
+    .lock()
+    .unwrap()
+    .insert(task as usize, stopped.clone());
+
+  let task = task as usize;
+  std::thread::spawn(move || {
+    let mut buffer = vec![0; CHUNK_LEN];
+    loop {
+      let read = match body.read(&mut buffer) {
+        Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+        read => read,
+      };
+      let done = !matches!(read, Ok(count) if count > 0);
+
+      // waiting for each chunk to be sent also keeps the reads at the pace of the webview
+      let (buffer, stopped) = (&buffer, &stopped);
+      run_on_main_thread(move || {
+        if !stopped.load(Ordering::SeqCst) {
+          // Safety: objc runtime calls are unsafe
+          unsafe { send_chunk(task as id, read, buffer) };
+        }
+      });
+      if done || stopped.load(Ordering::SeqCst) {
+        break;
+      }
+    }
+
+    run_on_main_thread(move || {
+      STREAMED_TASKS.lock().unwrap().remove(&task);
+      // Safety: objc runtime calls are unsafe
+      let () = unsafe { msg_send![task as id, release] };
+    });
+  });
+}
+
+/// Sends the result of a read of the body of `task`, `Ok(0)` finishes it.
+unsafe fn send_chunk(task: id, read: std::io::Result<usize>, buffer: &[u8]) {
+  match read {
+    Ok(0) => {
+      let () = msg_send![task, didFinish];
+    }
+    Ok(count) => {
+      let data: id = msg_send![class!(NSData), alloc];
+      let data: id = msg_send![data, initWithBytes: buffer.as_ptr() as *const c_void length: count];
+      let () = msg_send![task, didReceiveData: data];
+      let () = msg_send![data, release];
+    }
+    Err(e) => {
+      log::warn!("Failed to read the body of a custom protocol response: {e}");
+      let error: id = msg_send![
+        class!(NSError),
+        errorWithDomain: NSString::new("NSURLErrorDomain")
+        code: UNKNOWN_ERROR_CODE
+        userInfo: nil
+      ];
+      let () = msg_send![task, didFailWithError: error];
+    }
+  }
+}